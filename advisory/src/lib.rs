@@ -0,0 +1,309 @@
+#![allow(clippy::or_fun_call)]
+use std::ffi::OsString;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+use radicle_common as common;
+use radicle_common::args::{Args, Error, Help};
+use radicle_common::cobs::advisory::*;
+use radicle_common::{cobs, keys, project};
+use radicle_terminal as term;
+
+pub const HELP: Help = Help {
+    name: "advisory",
+    description: env!("CARGO_PKG_DESCRIPTION"),
+    version: env!("CARGO_PKG_VERSION"),
+    usage: r#"
+Usage
+
+    rad advisory new --title <title> --severity <severity> [--affected <version>]... [--patched <version>]... [--description <text>]
+    rad advisory show <id>
+    rad advisory list [--all-projects] [--unsolicited]
+
+    Severity is one of `low`, `medium`, `high` or `critical`.
+
+    Anyone can publish an advisory, so only advisories published by a
+    delegate of the project are listed, unless `--unsolicited` is given.
+    Advisories from delegates are also reported by `rad events`.
+
+Options
+
+    --all-projects      List advisories of all local projects
+    --unsolicited       Also list advisories from peers who aren't delegates
+    --help              Print help
+"#,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum OperationName {
+    Create,
+    Show,
+    List,
+}
+
+impl Default for OperationName {
+    fn default() -> Self {
+        Self::List
+    }
+}
+
+#[derive(Debug)]
+pub enum Operation {
+    Create {
+        title: String,
+        description: Option<String>,
+        severity: Severity,
+        affected: Vec<String>,
+        patched: Vec<String>,
+    },
+    Show {
+        id: AdvisoryId,
+    },
+    List {
+        all_projects: bool,
+        unsolicited: bool,
+    },
+}
+
+/// Tool options.
+#[derive(Debug)]
+pub struct Options {
+    pub op: Operation,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut op: Option<OperationName> = None;
+        let mut id: Option<AdvisoryId> = None;
+        let mut title: Option<String> = None;
+        let mut description: Option<String> = None;
+        let mut severity: Option<Severity> = None;
+        let mut affected = Vec::new();
+        let mut patched = Vec::new();
+        let mut all_projects = false;
+        let mut unsolicited = false;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("help") => {
+                    return Err(Error::Help.into());
+                }
+                Long("title") if op == Some(OperationName::Create) => {
+                    let val = parser.value()?.to_string_lossy().trim().to_owned();
+                    if val.is_empty() {
+                        anyhow::bail!("the advisory title cannot be empty");
+                    }
+                    title = Some(val);
+                }
+                Long("description") if op == Some(OperationName::Create) => {
+                    description = Some(parser.value()?.to_string_lossy().into());
+                }
+                Long("severity") if op == Some(OperationName::Create) => {
+                    let val = parser.value()?;
+                    let val = val.to_string_lossy();
+
+                    severity = Some(Severity::from_str(&val).map_err(|_| Error::WithHint {
+                        err: anyhow!("invalid severity '{}'", val),
+                        hint: "hint: severity must be one of `low`, `medium`, `high` or `critical`",
+                    })?);
+                }
+                Long("affected") if op == Some(OperationName::Create) => {
+                    affected.push(parser.value()?.to_string_lossy().into());
+                }
+                Long("patched") if op == Some(OperationName::Create) => {
+                    patched.push(parser.value()?.to_string_lossy().into());
+                }
+                Long("all-projects") if op.is_none() || op == Some(OperationName::List) => {
+                    all_projects = true;
+                }
+                Long("unsolicited") if op.is_none() || op == Some(OperationName::List) => {
+                    unsolicited = true;
+                }
+                Value(val) if op.is_none() => match val.to_string_lossy().as_ref() {
+                    "n" | "new" => op = Some(OperationName::Create),
+                    "s" | "show" => op = Some(OperationName::Show),
+                    "l" | "list" => op = Some(OperationName::List),
+
+                    unknown => anyhow::bail!("unknown operation '{}'", unknown),
+                },
+                Value(val) if op == Some(OperationName::Show) => {
+                    let val = val
+                        .to_str()
+                        .ok_or_else(|| anyhow!("advisory id specified is not UTF-8"))?;
+
+                    id = Some(
+                        AdvisoryId::from_str(val)
+                            .map_err(|_| anyhow!("invalid advisory id '{}'", val))?,
+                    );
+                }
+                _ => {
+                    return Err(anyhow!(arg.unexpected()));
+                }
+            }
+        }
+
+        let op = match op.unwrap_or_default() {
+            OperationName::Create => Operation::Create {
+                title: title.ok_or_else(|| anyhow!("an advisory title must be provided"))?,
+                description,
+                severity: severity
+                    .ok_or_else(|| anyhow!("an advisory severity must be provided"))?,
+                affected,
+                patched,
+            },
+            OperationName::Show => Operation::Show {
+                id: id.ok_or_else(|| anyhow!("an advisory id must be provided"))?,
+            },
+            OperationName::List => Operation::List {
+                all_projects,
+                unsolicited,
+            },
+        };
+
+        Ok((Options { op }, vec![]))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let profile = ctx.profile()?;
+    let signer = term::signer(&profile)?;
+    let storage = keys::storage(&profile, signer)?;
+    let cobs = cobs::store(&profile, &storage)?;
+    let advisories = cobs.advisories();
+
+    match options.op {
+        Operation::Create {
+            title,
+            description,
+            severity,
+            affected,
+            patched,
+        } => {
+            let (urn, _) = project::cwd()?;
+            let project = project::get(&storage, &urn)?
+                .ok_or_else(|| anyhow!("couldn't load project {} from local state", urn))?;
+
+            if !project.remotes.contains(storage.peer_id()) {
+                term::warning(
+                    "You are not a delegate of this project: the advisory won't be listed by default",
+                );
+            }
+            let description = match description {
+                Some(description) => description,
+                None => term::Editor::new()
+                    .edit("Describe the vulnerability...")?
+                    .unwrap_or_default(),
+            };
+            let id =
+                advisories.create(&urn, &title, &description, severity, &affected, &patched)?;
+
+            term::success!(
+                "Advisory {} published for {}",
+                term::format::highlight(id),
+                term::format::highlight(&project.name)
+            );
+        }
+        Operation::Show { id } => {
+            let (urn, _) = project::cwd()?;
+            let project = project::get(&storage, &urn)?
+                .ok_or_else(|| anyhow!("couldn't load project {} from local state", urn))?;
+            let mut advisory = advisories
+                .get(&urn, &id)?
+                .ok_or_else(|| anyhow!("couldn't find advisory {}", id))?;
+
+            advisory.resolve(&storage).ok();
+
+            term::info!(
+                "{} {}",
+                pretty_severity(advisory.severity),
+                term::format::bold(&advisory.title)
+            );
+            term::info!(
+                "{} {}",
+                author(&advisory, &project),
+                term::format::dim(advisory.timestamp)
+            );
+            if !project.remotes.contains(&advisory.author.peer) {
+                term::blank();
+                term::warning("This advisory wasn't published by a delegate of the project");
+            }
+            term::blank();
+            term::info!("Affected: {}", advisory.affected().join(", "));
+            term::info!("Patched:  {}", advisory.patched().join(", "));
+            term::blank();
+            term::markdown(&advisory.description);
+        }
+        Operation::List {
+            all_projects,
+            unsolicited,
+        } => {
+            let projects = if all_projects {
+                project::list(&storage)?
+                    .into_iter()
+                    .map(|(_, meta, _)| meta)
+                    .collect()
+            } else {
+                let (urn, _) = project::cwd()?;
+                let project = project::get(&storage, &urn)?
+                    .ok_or_else(|| anyhow!("couldn't load project {} from local state", urn))?;
+
+                vec![project]
+            };
+
+            let mut table = term::Table::default();
+            let mut hidden = 0;
+            for project in projects {
+                for (id, mut advisory) in advisories.all(&project.urn)? {
+                    if !unsolicited && !project.remotes.contains(&advisory.author.peer) {
+                        hidden += 1;
+                        continue;
+                    }
+                    advisory.resolve(&storage).ok();
+
+                    table.push([
+                        term::format::tertiary(common::fmt::cob(&id)),
+                        pretty_severity(advisory.severity),
+                        term::format::bold(project.name.clone()),
+                        advisory.title.clone(),
+                        author(&advisory, &project),
+                        term::format::dim(advisory.affected().join(", ")),
+                        term::format::dim(advisory.timestamp),
+                    ]);
+                }
+            }
+            table.render();
+
+            if hidden > 0 {
+                term::tip!(
+                    "Hid {} advisory(s) from peers who aren't delegates, use `--unsolicited` to list them",
+                    hidden
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The advisory author, marked if they aren't a delegate of the project.
+fn author(advisory: &Advisory, project: &project::Metadata) -> String {
+    let name = term::format::tertiary(advisory.author.name());
+
+    if project.remotes.contains(&advisory.author.peer) {
+        format!("{} {}", name, term::format::dim("(delegate)"))
+    } else {
+        format!("{} {}", name, term::format::negative("(not a delegate)"))
+    }
+}
+
+fn pretty_severity(severity: Severity) -> String {
+    match severity {
+        Severity::Critical | Severity::High => term::format::negative(severity),
+        Severity::Medium => term::format::yellow(severity),
+        Severity::Low => term::format::dim(severity),
+    }
+}
@@ -0,0 +1,145 @@
+use std::ffi::OsString;
+
+use anyhow::anyhow;
+
+use radicle_common::archive::{self, Archive};
+use radicle_common::args::{Args, Error, Help};
+use radicle_common::keys;
+use radicle_common::store::Store;
+use radicle_terminal as term;
+
+pub const HELP: Help = Help {
+    name: "archive",
+    description: env!("CARGO_PKG_DESCRIPTION"),
+    version: env!("CARGO_PKG_VERSION"),
+    usage: r#"
+Usage
+
+    rad archive [list]
+    rad archive restore <entry> [--force]
+
+    Before `rad` force-updates branches in storage, eg. with `rad push --force`,
+    the old branch tips are recorded in the archive. Use `restore` to reset a
+    branch in storage to an archive entry.
+
+Options
+
+    --force     Overwrite the ref if it already exists, when restoring
+    --help      Print help
+"#,
+};
+
+#[derive(Debug)]
+pub enum Operation {
+    List,
+    Restore { entry: u64, force: bool },
+}
+
+impl Default for Operation {
+    fn default() -> Self {
+        Self::List
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Options {
+    pub op: Operation,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut op: Option<String> = None;
+        let mut entry: Option<u64> = None;
+        let mut force = false;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("force") | Short('f') => {
+                    force = true;
+                }
+                Long("help") => {
+                    return Err(Error::Help.into());
+                }
+                Value(val) if op.is_none() => match val.to_string_lossy().as_ref() {
+                    "list" | "l" => op = Some(String::from("list")),
+                    "restore" | "r" => op = Some(String::from("restore")),
+                    unknown => anyhow::bail!("unknown operation '{}'", unknown),
+                },
+                Value(val) if op.as_deref() == Some("restore") && entry.is_none() => {
+                    entry = Some(radicle_common::args::parse_value("entry", val)?);
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+
+        let op = match op.as_deref() {
+            Some("restore") => Operation::Restore {
+                entry: entry.ok_or_else(|| anyhow!("an archive entry must be provided"))?,
+                force,
+            },
+            _ => Operation::List,
+        };
+
+        Ok((Options { op }, vec![]))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let profile = ctx.profile()?;
+    let archive = Archive::load(&profile)?;
+
+    match options.op {
+        Operation::List => {
+            if archive.entries.is_empty() {
+                term::print(term::format::italic("The archive is empty."));
+                return Ok(());
+            }
+            let mut table = term::Table::default();
+
+            for entry in archive.entries.iter().rev() {
+                table.push([
+                    term::format::bold(entry.id),
+                    term::format::highlight(&entry.name),
+                    term::format::secondary(format!("{:.7}", entry.oid)),
+                    term::format::tertiary(&entry.reason),
+                    term::format::dim(&entry.urn),
+                    term::format::italic(entry.timestamp),
+                ]);
+            }
+            table.render();
+        }
+        Operation::Restore { entry, force } => {
+            let entry = archive
+                .get(entry)
+                .ok_or_else(|| anyhow!("archive entry {} not found", entry))?;
+
+            let signer = term::signer(&profile)?;
+            let storage = keys::storage(&profile, signer)?;
+
+            archive::restore(entry, &storage, force).map_err(|err| {
+                if force {
+                    err
+                } else {
+                    Error::WithHint {
+                        err,
+                        hint: "hint: use `--force` to overwrite the existing ref",
+                    }
+                    .into()
+                }
+            })?;
+
+            term::success!(
+                "Restored {} of {} to {}",
+                term::format::highlight(&entry.name),
+                term::format::tertiary(&entry.urn),
+                term::format::secondary(format!("{:.7}", entry.oid))
+            );
+            term::tip!("Run `rad sync` to publish the restored branch to seeds.");
+        }
+    }
+
+    Ok(())
+}
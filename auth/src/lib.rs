@@ -1,14 +1,17 @@
 #![allow(clippy::or_fun_call)]
+use std::collections::BTreeSet;
 use std::ffi::OsString;
 use std::str::FromStr;
 
 use anyhow::Context as _;
 use radicle_common::signer::ToSigner;
 
+use librad::crypto::keystore::pinentry::SecUtf8;
 use librad::profile::ProfileId;
+use librad::PeerId;
 
 use radicle_common::args::{Args, Error, Help};
-use radicle_common::{config, git, keys, person, profile};
+use radicle_common::{config, git, keychain, keys, person, profile, project, rotation};
 use radicle_terminal as term;
 
 pub const HELP: Help = Help {
@@ -19,20 +22,50 @@ pub const HELP: Help = Help {
 Usage
 
     rad auth [--init | --active] [<options>...] [<profile>]
+    rad auth [--keychain | --no-keychain] [<profile>]
+    rad auth rotate [--stdin]
 
     A passphrase may be given via the environment variable `RAD_PASSPHRASE` or
     via the standard input stream if `--stdin` is used. Using one of these
     methods disables the passphrase prompt.
 
+    Once the key is added to ssh-agent, commands sign with it without asking
+    for a passphrase. The `RAD_SIGNER` environment variable selects where
+    commands get the key from: `agent` only uses ssh-agent, `key` only uses
+    the key file, and `auto`, the default, uses ssh-agent if it holds the
+    key, or else the key file. Without a terminal, the key file's passphrase
+    must be given with `RAD_PASSPHRASE`.
+
+    Hardware tokens are supported through ssh-agent: any agent that holds
+    the profile's ed25519 key can sign, eg. one backed by a PKCS#11 provider
+    loaded with `ssh-add -s <provider>`.
+
+    With `--keychain`, the passphrase is stored in the platform keychain (macOS
+    Keychain, Secret Service on Linux, or Windows Credential Locker), and
+    commands that need it take it from there instead of prompting. The
+    Secret Service is accessed with `secret-tool`, which must be installed.
+    `--no-keychain` removes it from the keychain.
+
     If `--init` is used, a name may be given via the `--name` option. Using
     this disables the input prompt.
 
+    `rotate` replaces this device's key with a new one. As device keys are
+    bound to their storage, a new profile is created for the new key. Your
+    personal identity is updated to include the new key, signed by both keys,
+    and your projects are copied over to the new profile, which tracks the old
+    key's branches. After confirmation, the old key is retired from your
+    identity. The new profile is then activated, and the old one is kept until
+    you remove it with `rad profile remove`. Sync the updated identity to
+    seeds with `rad sync --identity`, so that others learn about the new key.
+
 Options
 
     --init                  Initialize a new identity
     --active                Authenticate with the currently active profile
     --stdin                 Read passphrase from stdin (default: false)
     --name <name>           Use given name (default: none)
+    --keychain              Store the passphrase in the platform keychain
+    --no-keychain           Remove the passphrase from the platform keychain
     --help                  Print help
 "#,
 };
@@ -44,6 +77,10 @@ pub struct Options {
     pub stdin: bool,
     pub name: Option<String>,
     pub profile: Option<ProfileId>,
+    /// Store the passphrase in, or remove it from the keychain.
+    pub keychain: Option<bool>,
+    /// Rotate the device key.
+    pub rotate: bool,
 }
 
 impl Args for Options {
@@ -55,6 +92,8 @@ impl Args for Options {
         let mut stdin = false;
         let mut name = None;
         let mut profile = None;
+        let mut keychain = None;
+        let mut rotate = false;
         let mut parser = lexopt::Parser::from_args(args);
 
         while let Some(arg) = parser.next()? {
@@ -68,6 +107,12 @@ impl Args for Options {
                 Long("stdin") => {
                     stdin = true;
                 }
+                Long("keychain") => {
+                    keychain = Some(true);
+                }
+                Long("no-keychain") => {
+                    keychain = Some(false);
+                }
                 Long("name") if init && name.is_none() => {
                     let val = parser
                         .value()?
@@ -80,7 +125,10 @@ impl Args for Options {
                 Long("help") => {
                     return Err(Error::Help.into());
                 }
-                Value(val) => {
+                Value(val) if val == *"rotate" && !rotate && profile.is_none() && !init => {
+                    rotate = true;
+                }
+                Value(val) if !rotate => {
                     let string = val.to_str().ok_or_else(|| {
                         anyhow::anyhow!("invalid UTF-8 string specified for profile")
                     })?;
@@ -99,6 +147,8 @@ impl Args for Options {
                 stdin,
                 name,
                 profile,
+                keychain,
+                rotate,
             },
             vec![],
         ))
@@ -111,6 +161,9 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
         _ => vec![],
     };
 
+    if options.rotate {
+        return rotate(options, ctx);
+    }
     if options.init || profiles.is_empty() {
         if options.profile.is_some() {
             anyhow::bail!("you may not specify a profile id when initializing a new identity");
@@ -157,7 +210,7 @@ pub fn init(options: Options) -> anyhow::Result<()> {
         spinner.finish();
         signer
     } else {
-        let signer = keys::load_secret_key(&profile, passphrase)?.to_signer(&profile)?;
+        let signer = keys::load_secret_key(&profile, passphrase.clone())?.to_signer(&profile)?;
 
         spinner.finish();
         signer
@@ -167,6 +220,10 @@ pub fn init(options: Options) -> anyhow::Result<()> {
     config::Config::init(&profile)?;
     spinner.finish();
 
+    if options.keychain == Some(true) {
+        set_keychain(&profile, &passphrase)?;
+    }
+
     let storage = keys::storage(&profile, signer.clone())?;
     let person = person::create(&profile, &name, signer, &storage)
         .context("could not create identity document")?;
@@ -196,6 +253,43 @@ pub fn init(options: Options) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Guided setup for first-time users: create an identity, and choose which seed to use.
+pub fn wizard() -> anyhow::Result<profile::Profile> {
+    term::headline("Welcome to 🌱 radicle! Let's get you set up.");
+    term::blank();
+
+    init(Options {
+        init: true,
+        active: false,
+        stdin: false,
+        name: None,
+        profile: None,
+        keychain: None,
+        rotate: false,
+    })?;
+    term::blank();
+
+    let profile = profile::default()?;
+    let mut config = config::Config::profile(&profile)?;
+    let all = String::from("All of the above");
+    let mut choices = config
+        .seeds()
+        .filter_map(|s| s.name.clone())
+        .collect::<Vec<_>>();
+    choices.push(all.clone());
+
+    if let Some(choice) = term::select_with_prompt("Which seed should be used?", &choices, &all) {
+        if *choice != all {
+            config.seed.retain(|s| s.name.as_ref() == Some(choice));
+        }
+    }
+    config.write(config::Config::path(&profile))?;
+
+    term::success!("Seed configuration saved");
+
+    Ok(profile)
+}
+
 pub fn authenticate(
     profiles: &[profile::Profile],
     options: Options,
@@ -254,14 +348,16 @@ pub fn authenticate(
     }
 
     let profile = selection;
+    let mut passphrase = None;
     if let Ok(sock) = keys::ssh_auth_sock() {
         if !keys::is_ready(profile, sock.clone())? {
             term::warning("Adding your radicle key to ssh-agent...");
 
             // TODO: We should show the spinner on the passphrase prompt,
             // otherwise it seems like the passphrase is valid even if it isn't.
-            let passphrase = term::read_passphrase(options.stdin, false)?;
-            let secret = keys::pwhash(passphrase);
+            let input = term::read_passphrase(options.stdin, false)?;
+            let secret = keys::pwhash(input.clone());
+            passphrase = Some(input);
 
             let spinner = term::spinner("Unlocking...");
             keys::add(profile, secret, sock).context("invalid passphrase supplied")?;
@@ -273,6 +369,169 @@ pub fn authenticate(
         }
     }
 
+    match options.keychain {
+        Some(true) => {
+            let passphrase = match passphrase {
+                Some(passphrase) => passphrase,
+                None => term::read_passphrase(options.stdin, false)?,
+            };
+            let spinner = term::spinner("Unlocking...");
+            keys::load_secret_key(profile, passphrase.clone())
+                .context("invalid passphrase supplied")?;
+            spinner.finish();
+
+            set_keychain(profile, &passphrase)?;
+        }
+        Some(false) => {
+            if keychain::remove(profile.id())? {
+                term::success!("Passphrase removed from keychain");
+            } else {
+                term::info!("Passphrase was not in keychain");
+            }
+            let mut config = config::Config::profile(profile)?;
+            config.keychain = false;
+            config.write(config::Config::path(profile))?;
+        }
+        None => {}
+    }
+
+    Ok(())
+}
+
+/// Rotate the device key: see [`rotation`].
+pub fn rotate(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let old = ctx.profile()?;
+    let old_storage = keys::storage(&old, term::signer(&old)?)?;
+    let old_peer = *old_storage.peer_id();
+    let whoami = person::local(&old_storage).map_err(|err| Error::WithHint {
+        err: err.into(),
+        hint: "hint: a personal identity is needed to rotate keys, see `rad auth --init`",
+    })?;
+    let urn = whoami.urn();
+    let projects = project::list(&old_storage)?
+        .into_iter()
+        .filter(|(_, meta, _)| {
+            meta.delegates.iter().any(|d| match d {
+                project::Delegate::Direct { id } => *id == old_peer,
+                project::Delegate::Indirect { urn: u, .. } => *u == urn,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    term::headline(&format!(
+        "🌱 Rotating the key of profile {}",
+        term::format::highlight(old.id())
+    ));
+    term::info!(
+        "A new profile and key will be created, and added to your identity {}, along with {} project(s).",
+        term::format::tertiary(&urn),
+        projects.len()
+    );
+    if !term::confirm("Continue?") {
+        anyhow::bail!("rotation aborted by user");
+    }
+
+    let passphrase = term::read_passphrase(options.stdin, true)?;
+    let mut spinner = term::spinner("Creating your new 🌱 Ed25519 keypair...");
+    let (new, new_peer) = profile::create(profile::home(), keys::pwhash(passphrase.clone()))?;
+    // Nb. The new profile only becomes active once it's ready.
+    profile::set(old.id())?;
+    config::Config::profile(&old)
+        .or_else(|_| config::Config::init(&old))?
+        .write(config::Config::path(&new))?;
+
+    let new_storage = keys::storage(
+        &new,
+        keys::load_secret_key(&new, passphrase.clone())?.to_signer(&new)?,
+    )?;
+    spinner.finish();
+
+    spinner = term::spinner("Adding the new key to your identity...");
+    let mut delegations = whoami
+        .delegations()
+        .iter()
+        .cloned()
+        .collect::<BTreeSet<_>>();
+    delegations.insert(*new_peer.as_public_key());
+    person::set_keys(&old_storage, delegations)?;
+
+    rotation::replicate(&old, &new, &urn, &old_peer)?;
+    let me = rotation::cosign_person(&new_storage, &urn, old_peer)?;
+    person::set_local(&new_storage, &me)?;
+
+    rotation::replicate(&new, &old, &urn, &new_peer)?;
+    rotation::cosign_person(&old_storage, &urn, new_peer)?;
+    spinner.finish();
+
+    for (project, meta, _) in &projects {
+        let spinner = term::spinner(format!(
+            "Copying project {}...",
+            term::format::highlight(&meta.name)
+        ));
+        rotation::replicate(&old, &new, project, &old_peer)?;
+        rotation::cosign_project(&new_storage, project, old_peer)?;
+        spinner.finish();
+    }
+
+    if let Ok(sock) = keys::ssh_auth_sock() {
+        let spinner = term::spinner("Adding the new key to ssh-agent...");
+        keys::add(&new, keys::pwhash(passphrase), sock)?;
+        spinner.finish();
+    }
+
+    if term::confirm(format!(
+        "Retire the old key {} from your identity? Only do this once you no longer need it.",
+        term::format::tertiary(&old_peer)
+    )) {
+        let spinner = term::spinner("Retiring the old key...");
+        let delegations = me
+            .delegations()
+            .iter()
+            .filter(|k| PeerId::from(**k) != old_peer)
+            .cloned()
+            .collect::<BTreeSet<_>>();
+        person::set_keys(&new_storage, delegations)?;
+
+        rotation::replicate(&new, &old, &urn, &new_peer)?;
+        rotation::cosign_person(&old_storage, &urn, new_peer)?;
+        rotation::replicate(&old, &new, &urn, &old_peer)?;
+        rotation::cosign_person(&new_storage, &urn, old_peer)?;
+        spinner.finish();
+    }
+
+    profile::set(new.id())?;
+
+    term::success!(
+        "Profile {} with key {} activated",
+        term::format::highlight(new.id()),
+        term::format::tertiary(&new_peer)
+    );
+    term::blank();
+    term::tip!(
+        "To publish the updated identity, run {} in each project.",
+        term::format::secondary("`rad sync --identity`")
+    );
+    term::tip!(
+        "Once synced, the old profile can be removed with {}.",
+        term::format::secondary(format!("`rad profile remove {}`", old.id()))
+    );
+
+    Ok(())
+}
+
+/// Store a profile's passphrase in the keychain, and have commands take it from there.
+fn set_keychain(profile: &profile::Profile, passphrase: &SecUtf8) -> anyhow::Result<()> {
+    keychain::set(profile.id(), passphrase).map_err(|err| Error::WithHint {
+        err,
+        hint: "hint: on Linux, the keychain is accessed with `secret-tool`, from libsecret",
+    })?;
+
+    let mut config = config::Config::profile(profile)?;
+    config.keychain = true;
+    config.write(config::Config::path(profile))?;
+
+    term::success!("Passphrase stored in keychain");
+
     Ok(())
 }
 
@@ -299,6 +558,8 @@ mod tests {
             stdin: false,
             name: Some(name.to_owned()),
             profile: None,
+            keychain: None,
+            rotate: false,
         }
     }
 
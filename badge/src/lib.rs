@@ -0,0 +1,232 @@
+use std::ffi::OsString;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+use librad::git::Urn;
+
+use radicle_common::args::{Args, Error, Help};
+use radicle_common::badge::{self, Badge, Color};
+use radicle_common::cobs::issue;
+use radicle_common::patch::TAG_PREFIX;
+use radicle_common::{cobs, git, keys, project, schema};
+use radicle_terminal as term;
+
+/// Version of the JSON output schema.
+pub const SCHEMA_VERSION: u32 = 1;
+
+pub const HELP: Help = Help {
+    name: "badge",
+    description: env!("CARGO_PKG_DESCRIPTION"),
+    version: env!("CARGO_PKG_VERSION"),
+    usage: r#"
+Usage
+
+    rad badge <kind> [<urn>] [--format <format>]
+    rad badge --schema
+
+    Prints a status badge for the current project, or the given one, computed
+    from local storage. Badges can be committed, or served by a gateway that
+    regenerates them, eg. `rad badge issues > issues.svg`.
+
+    The JSON format is a shields.io endpoint badge, for use with
+    `https://img.shields.io/endpoint?url=<url>`.
+
+Kinds
+
+    patches           Number of open patches
+    issues            Number of open issues
+    latest-release    Latest version tag, eg. `v1.2.0`
+
+Options
+
+    --format <format>   Output format: `svg` or `json` (default: svg)
+    --schema            Print the JSON Schema of the `json` format
+    --help              Print help
+"#,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Patches,
+    Issues,
+    LatestRelease,
+}
+
+impl FromStr for Kind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "patches" => Ok(Self::Patches),
+            "issues" => Ok(Self::Issues),
+            "latest-release" => Ok(Self::LatestRelease),
+            _ => Err(anyhow!("unknown badge '{}'", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Svg,
+    Json,
+}
+
+#[derive(Debug)]
+pub struct Options {
+    pub kind: Kind,
+    pub urn: Option<Urn>,
+    pub format: Format,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut kind: Option<Kind> = None;
+        let mut urn: Option<Urn> = None;
+        let mut format = Format::Svg;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("format") => {
+                    let val = parser.value()?;
+
+                    format = match val.to_string_lossy().as_ref() {
+                        "svg" => Format::Svg,
+                        "json" => Format::Json,
+                        other => {
+                            return Err(Error::WithHint {
+                                err: anyhow!("invalid format '{}'", other),
+                                hint: "hint: the format must be `svg` or `json`",
+                            }
+                            .into())
+                        }
+                    };
+                }
+                Long("schema") => {
+                    let schema = schema::document("badge", SCHEMA_VERSION, Badge::json_schema());
+
+                    return Err(Error::Schema(schema).into());
+                }
+                Long("help") => {
+                    return Err(Error::Help.into());
+                }
+                Value(val) if kind.is_none() => {
+                    let val = val.to_string_lossy();
+
+                    kind = Some(Kind::from_str(&val).map_err(|err| Error::WithHint {
+                        err,
+                        hint: "hint: the badge must be `patches`, `issues` or `latest-release`",
+                    })?);
+                }
+                Value(val) if urn.is_none() => {
+                    let val = val.to_string_lossy();
+
+                    urn = Some(Urn::from_str(&val).map_err(|_| anyhow!("invalid URN '{}'", val))?);
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+
+        Ok((
+            Options {
+                kind: kind.ok_or_else(|| anyhow!("a badge kind must be provided"))?,
+                urn,
+                format,
+            },
+            vec![],
+        ))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let urn = match options.urn {
+        Some(urn) => urn,
+        None => {
+            project::cwd()
+                .map_err(|_| anyhow!("a URN must be provided outside of a project"))?
+                .0
+        }
+    };
+    let profile = ctx.profile()?;
+    let signer = term::signer(&profile)?;
+    let storage = keys::storage(&profile, signer)?;
+
+    if project::get(&storage, &urn)?.is_none() {
+        anyhow::bail!("project {} not found in local storage", urn);
+    }
+
+    let badge = match options.kind {
+        Kind::Patches => {
+            let cobs = cobs::store(&profile, &storage)?;
+            let open = cobs.patches().proposed(&urn)?.count();
+
+            count("patches", open)
+        }
+        Kind::Issues => {
+            let cobs = cobs::store(&profile, &storage)?;
+            let open = cobs
+                .issues()
+                .all(&urn)?
+                .into_iter()
+                .filter(|(_, i)| i.state() == issue::State::Open)
+                .count();
+
+            count("issues", open)
+        }
+        Kind::LatestRelease => {
+            let repo = git::Repository::open_bare(profile.paths().git_dir())?;
+            let tags = tags(&repo, &urn)?;
+
+            match badge::latest_release(tags.iter().map(|t| t.as_str())) {
+                Some(tag) => Badge::new("release", tag, Color::Blue),
+                None => Badge::new("release", "none", Color::LightGrey),
+            }
+        }
+    };
+
+    match options.format {
+        Format::Svg => print!("{}", badge.to_svg()),
+        Format::Json => println!("{}", badge.to_json()?),
+    }
+
+    Ok(())
+}
+
+fn count(label: &str, open: usize) -> Badge {
+    let color = if open == 0 {
+        Color::BrightGreen
+    } else {
+        Color::Yellow
+    };
+    Badge::new(label, format!("{} open", open), color)
+}
+
+/// Tag names of a project, from your refs and those of tracked peers. Patch tags
+/// are skipped.
+fn tags(repo: &git::Repository, urn: &Urn) -> anyhow::Result<Vec<String>> {
+    let namespace = format!("refs/namespaces/{}/refs/", urn.encode_id());
+    let mut tags = Vec::new();
+
+    for r in repo.references_glob(&format!("{}*", namespace))? {
+        let r = r?;
+        let name = match r.name().and_then(|n| n.strip_prefix(&namespace)) {
+            Some(name) => name,
+            None => continue,
+        };
+        // Either `tags/<tag>` or `remotes/<peer>/tags/<tag>`.
+        let tag = name.strip_prefix("tags/").or_else(|| {
+            name.strip_prefix("remotes/")
+                .and_then(|n| n.splitn(2, '/').nth(1))
+                .and_then(|n| n.strip_prefix("tags/"))
+        });
+        if let Some(tag) = tag {
+            if !tag.starts_with(TAG_PREFIX) && !tags.iter().any(|t| t == tag) {
+                tags.push(tag.to_owned());
+            }
+        }
+    }
+    Ok(tags)
+}
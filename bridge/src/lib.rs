@@ -0,0 +1,725 @@
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::{env, fs};
+
+use anyhow::anyhow;
+
+use librad::git::{Storage, Urn};
+use librad::profile::Profile;
+
+use radicle_common as common;
+use radicle_common::args::{Args, Error, Help};
+use radicle_common::bridge::{self, Bridges, Email, EmailMessage, EmailThread, Github, GithubLink};
+use radicle_common::cobs::patch::{Patch, PatchId, PatchStore};
+use radicle_common::mail;
+use radicle_common::store::Store;
+use radicle_common::{cobs, keys, project};
+use radicle_terminal as term;
+
+pub const HELP: Help = Help {
+    name: "bridge",
+    description: env!("CARGO_PKG_DESCRIPTION"),
+    version: env!("CARGO_PKG_VERSION"),
+    usage: r#"
+Usage
+
+    rad bridge github link <patch-id> <owner>/<repo>#<number>
+    rad bridge github unlink <patch-id>
+    rad bridge github list
+    rad bridge github sync [--token <token>] [--dry-run]
+
+    rad bridge email setup <list-address> [--maildir <path>]
+    rad bridge email send [<patch-id>] [--dry-run]
+    rad bridge email list
+    rad bridge email sync [--maildir <path>] [--dry-run]
+
+    Bridges patches of the current project with GitHub pull requests, eg. for
+    projects mirrored to GitHub, or with a mailing list.
+
+    Once a patch is linked to a pull request, `sync` copies new pull request
+    comments and reviews to the patch, and patch comments to the pull request.
+    What was copied is remembered, so syncing again only copies new comments.
+
+    The GitHub token is read from the `GITHUB_TOKEN` environment variable,
+    unless `--token` is given.
+
+    `rad bridge email send` sends the latest revision of a patch to the list as
+    a `git send-email` thread, or of all proposed patches if no patch is given.
+    Revisions that were already sent are skipped; new revisions are sent as
+    replies to the previous thread. Sending uses your `sendemail.*` git
+    configuration.
+
+    `rad bridge email sync` reads replies to these threads from a maildir and
+    copies them to the patch as comments. To bridge an IMAP mailbox, sync it to
+    a maildir first, eg. with `mbsync` or `offlineimap`.
+
+Options
+
+    --token <token>    GitHub API token
+    --maildir <path>   Maildir to read replies from
+    --dry-run          Show what would be copied or sent, without doing it
+    --help             Print help
+"#,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    Github,
+    Email,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum OperationName {
+    Link,
+    Unlink,
+    List,
+    Sync,
+    Setup,
+    Send,
+}
+
+#[derive(Debug)]
+pub enum Operation {
+    Link {
+        id: cobs::Identifier,
+        repo: String,
+        pr: u64,
+    },
+    Unlink {
+        id: cobs::Identifier,
+    },
+    List,
+    Sync {
+        token: Option<String>,
+        maildir: Option<PathBuf>,
+        dry_run: bool,
+    },
+    Setup {
+        list: String,
+        maildir: Option<PathBuf>,
+    },
+    Send {
+        id: Option<cobs::Identifier>,
+        dry_run: bool,
+    },
+}
+
+#[derive(Debug)]
+pub struct Options {
+    pub platform: Platform,
+    pub op: Operation,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut platform: Option<Platform> = None;
+        let mut op: Option<OperationName> = None;
+        let mut id: Option<cobs::Identifier> = None;
+        let mut target: Option<(String, u64)> = None;
+        let mut token: Option<String> = None;
+        let mut list: Option<String> = None;
+        let mut maildir: Option<PathBuf> = None;
+        let mut dry_run = false;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("help") => {
+                    return Err(Error::Help.into());
+                }
+                Long("token")
+                    if platform == Some(Platform::Github) && op == Some(OperationName::Sync) =>
+                {
+                    token = Some(parser.value()?.to_string_lossy().into());
+                }
+                Long("maildir")
+                    if platform == Some(Platform::Email)
+                        && (op == Some(OperationName::Setup)
+                            || op == Some(OperationName::Sync)) =>
+                {
+                    maildir = Some(parser.value()?.into());
+                }
+                Long("dry-run")
+                    if op == Some(OperationName::Sync) || op == Some(OperationName::Send) =>
+                {
+                    dry_run = true;
+                }
+                Value(val) if platform.is_none() => match val.to_string_lossy().as_ref() {
+                    "github" => platform = Some(Platform::Github),
+                    "email" => platform = Some(Platform::Email),
+                    unknown => anyhow::bail!("unknown platform '{}'", unknown),
+                },
+                Value(val) if op.is_none() => match (platform, val.to_string_lossy().as_ref()) {
+                    (Some(Platform::Github), "link") => op = Some(OperationName::Link),
+                    (Some(Platform::Github), "unlink") => op = Some(OperationName::Unlink),
+                    (Some(Platform::Email), "setup") => op = Some(OperationName::Setup),
+                    (Some(Platform::Email), "send") => op = Some(OperationName::Send),
+                    (_, "l" | "list") => op = Some(OperationName::List),
+                    (_, "s" | "sync") => op = Some(OperationName::Sync),
+
+                    (_, unknown) => anyhow::bail!("unknown operation '{}'", unknown),
+                },
+                Value(val) if list.is_none() && op == Some(OperationName::Setup) => {
+                    let val = val.to_string_lossy();
+                    if !val.contains('@') {
+                        anyhow::bail!("invalid list address '{}'", val);
+                    }
+                    list = Some(val.into_owned());
+                }
+                Value(val)
+                    if id.is_none()
+                        && (op == Some(OperationName::Link)
+                            || op == Some(OperationName::Unlink)
+                            || op == Some(OperationName::Send)) =>
+                {
+                    let val = val
+                        .to_str()
+                        .ok_or_else(|| anyhow!("patch id specified is not UTF-8"))?;
+
+                    id = Some(
+                        cobs::Identifier::from_str(val)
+                            .map_err(|_| anyhow!("invalid patch id '{}'", val))?,
+                    );
+                }
+                Value(val) if target.is_none() && op == Some(OperationName::Link) => {
+                    let val = val.to_string_lossy();
+                    let (repo, pr) = val
+                        .split_once('#')
+                        .and_then(|(repo, pr)| Some((repo, pr.parse::<u64>().ok()?)))
+                        .filter(|(repo, _)| repo.split('/').count() == 2)
+                        .ok_or_else(|| {
+                            anyhow!(
+                                "invalid pull request '{}', expected <owner>/<repo>#<number>",
+                                val
+                            )
+                        })?;
+
+                    target = Some((repo.to_owned(), pr));
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+
+        let platform = platform
+            .ok_or_else(|| anyhow!("a platform must be provided, eg. `rad bridge github`"))?;
+        let require_id = || {
+            id.clone()
+                .ok_or_else(|| anyhow!("a patch id must be provided"))
+        };
+        let op = match op.ok_or_else(|| anyhow!("an operation must be provided"))? {
+            OperationName::Link => {
+                let (repo, pr) = target.ok_or_else(|| {
+                    anyhow!("a pull request must be provided, eg. `acme/tools#42`")
+                })?;
+                Operation::Link {
+                    id: require_id()?,
+                    repo,
+                    pr,
+                }
+            }
+            OperationName::Unlink => Operation::Unlink { id: require_id()? },
+            OperationName::List => Operation::List,
+            OperationName::Sync => Operation::Sync {
+                token,
+                maildir,
+                dry_run,
+            },
+            OperationName::Setup => Operation::Setup {
+                list: list.ok_or_else(|| anyhow!("a mailing list address must be provided"))?,
+                maildir,
+            },
+            OperationName::Send => Operation::Send { id, dry_run },
+        };
+
+        Ok((Options { platform, op }, vec![]))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let (urn, _) = project::cwd()
+        .map_err(|_| anyhow!("this command must be run in the context of a project"))?;
+    let profile = ctx.profile()?;
+    let signer = term::signer(&profile)?;
+    let storage = keys::storage(&profile, signer)?;
+    let cobs = cobs::store(&profile, &storage)?;
+    let patches = cobs.patches();
+    let mut bridges = Bridges::load(&profile)?;
+    let project = urn.to_string();
+
+    if options.platform == Platform::Email {
+        return email(options.op, &urn, &profile, &storage, &patches, &mut bridges);
+    }
+
+    match options.op {
+        Operation::Link { id, repo, pr } => {
+            let (patch_id, _) = patches
+                .resolve::<Patch>(&urn, &id)?
+                .ok_or_else(|| anyhow!("couldn't find patch {} locally", id))?;
+
+            bridges.link_github(&project, &patch_id.to_string(), &repo, pr);
+            bridges.save(&profile)?;
+
+            term::success!(
+                "Patch {} linked to {}",
+                term::format::highlight(common::fmt::cob(&patch_id)),
+                term::format::tertiary(format!("{}#{}", repo, pr))
+            );
+            term::tip!("Run `rad bridge github sync` to copy comments across.");
+        }
+        Operation::Unlink { id } => {
+            let (patch_id, _) = patches
+                .resolve::<Patch>(&urn, &id)?
+                .ok_or_else(|| anyhow!("couldn't find patch {} locally", id))?;
+            let patch_id = patch_id.to_string();
+            let before = bridges.github.len();
+
+            bridges
+                .github
+                .retain(|l| !(l.urn == project && l.patch == patch_id));
+            if bridges.github.len() == before {
+                anyhow::bail!("patch {} is not linked to a pull request", id);
+            }
+            bridges.save(&profile)?;
+
+            term::success!("Patch {} unlinked", term::format::highlight(id));
+        }
+        Operation::List => {
+            let mut table = term::Table::default();
+            let mut count = 0;
+
+            for link in bridges.github_links(&project) {
+                count += 1;
+                table.push([
+                    term::format::tertiary(format!("{:.11}", link.patch)),
+                    term::format::highlight(format!("{}#{}", link.repo, link.pr)),
+                    term::format::dim(format!(
+                        "{} imported, {} exported",
+                        link.imported.len(),
+                        link.exported.len()
+                    )),
+                ]);
+            }
+            if count == 0 {
+                term::print(term::format::italic("No patches are linked."));
+            } else {
+                table.render();
+            }
+        }
+        Operation::Sync { token, dry_run, .. } => {
+            let token = token
+                .or_else(|| std::env::var("GITHUB_TOKEN").ok())
+                .ok_or_else(|| Error::WithHint {
+                    err: anyhow!("a GitHub token is required"),
+                    hint: "hint: set `GITHUB_TOKEN` or use `--token`",
+                })?;
+            let github = Github::new(token)?;
+            let mut links = bridges
+                .github_links(&project)
+                .map(|l| l.clone())
+                .collect::<Vec<_>>();
+
+            if links.is_empty() {
+                term::info!("No patches are linked, nothing to do.");
+                return Ok(());
+            }
+            for link in &mut links {
+                let id = cobs::Identifier::from_str(&link.patch)?;
+                let (patch_id, patch) = match patches.resolve::<Patch>(&urn, &id)? {
+                    Some(patch) => patch,
+                    None => {
+                        term::warning(&format!("Patch {} was not found, skipping", link.patch));
+                        continue;
+                    }
+                };
+                let pr = format!("{}#{}", link.repo, link.pr);
+                let mut spinner = term::spinner(format!(
+                    "Syncing {} with {}...",
+                    term::format::highlight(common::fmt::cob(&patch_id)),
+                    term::format::tertiary(&pr)
+                ));
+
+                // Pull request to patch.
+                let comments = match github.comments(&link.repo, link.pr) {
+                    Ok(comments) => comments,
+                    Err(err) => {
+                        spinner.failed();
+                        return Err(err);
+                    }
+                };
+                let mut imported = 0;
+                for comment in comments {
+                    if link.imported.contains(&comment.id) {
+                        continue;
+                    }
+                    // Comments that were copied from the patch aren't copied back.
+                    if !comment.body.contains(bridge::RADICLE_MARKER) {
+                        if !dry_run {
+                            patches.comment(
+                                &urn,
+                                &patch_id,
+                                patch.version(),
+                                &bridge::from_github(&comment),
+                            )?;
+                        }
+                        imported += 1;
+                    }
+                    link.imported.push(comment.id);
+
+                    if !dry_run {
+                        save_github_link(&mut bridges, link, &profile)?;
+                    }
+                }
+
+                // Patch to pull request.
+                let mut exported = 0;
+                for (rev, revision) in patch.revisions.iter().enumerate() {
+                    for (ix, comment) in revision.discussion.iter().enumerate() {
+                        let key = format!("{}/{}", rev, ix);
+
+                        if link.exported.contains(&key)
+                            || comment.is_redacted()
+                            || comment.body.contains(bridge::GITHUB_MARKER)
+                        {
+                            continue;
+                        }
+                        let mut author = comment.author.clone();
+                        author.resolve(&storage).ok();
+
+                        if !dry_run {
+                            github.comment(
+                                &link.repo,
+                                link.pr,
+                                &bridge::to_github(&author.name(), &comment.body, &key),
+                            )?;
+                        }
+                        exported += 1;
+                        link.exported.push(key);
+
+                        if !dry_run {
+                            save_github_link(&mut bridges, link, &profile)?;
+                        }
+                    }
+                }
+                spinner.message(format!(
+                    "{} {} {} comment(s) imported, {} exported",
+                    term::format::highlight(common::fmt::cob(&patch_id)),
+                    term::format::dim("↔"),
+                    imported,
+                    exported
+                ));
+                spinner.finish();
+            }
+            if dry_run {
+                term::info!("Dry run: nothing was copied.");
+            }
+        }
+        Operation::Setup { .. } | Operation::Send { .. } => {
+            unreachable!("email operations are handled separately")
+        }
+    }
+
+    Ok(())
+}
+
+/// Record what was copied for a GitHub link. This is done after each comment, so that
+/// comments aren't copied twice if syncing fails halfway.
+fn save_github_link(
+    bridges: &mut Bridges,
+    link: &GithubLink,
+    profile: &Profile,
+) -> anyhow::Result<()> {
+    if let Some(l) = bridges
+        .github
+        .iter_mut()
+        .find(|l| l.urn == link.urn && l.patch == link.patch)
+    {
+        *l = link.clone();
+    }
+    bridges.save(profile)
+}
+
+fn email(
+    op: Operation,
+    urn: &Urn,
+    profile: &Profile,
+    storage: &Storage,
+    patches: &PatchStore,
+    bridges: &mut Bridges,
+) -> anyhow::Result<()> {
+    let project = urn.to_string();
+
+    match op {
+        Operation::Setup { list, maildir } => {
+            bridges.email.list = Some(list.clone());
+            if maildir.is_some() {
+                bridges.email.maildir = maildir;
+            }
+            bridges.save(profile)?;
+
+            term::success!("Patches will be sent to {}", term::format::highlight(&list));
+            if let Some(maildir) = &bridges.email.maildir {
+                term::success!(
+                    "Replies will be read from {}",
+                    term::format::tertiary(maildir.display())
+                );
+            }
+        }
+        Operation::Send { id, dry_run } => {
+            let list = bridges.email.list.clone().ok_or_else(|| Error::WithHint {
+                err: anyhow!("no mailing list is configured"),
+                hint: "hint: run `rad bridge email setup <list-address>` first",
+            })?;
+            let name = project::get(storage, urn)?
+                .ok_or_else(|| anyhow!("project {} was not found", urn))?
+                .name;
+            let to_send = match id {
+                Some(id) => vec![patches
+                    .resolve::<Patch>(urn, &id)?
+                    .ok_or_else(|| anyhow!("couldn't find patch {} locally", id))?],
+                None => patches.proposed(urn)?.collect(),
+            };
+            let mut sent = 0;
+
+            for (patch_id, mut patch) in to_send {
+                patch.author.resolve(storage).ok();
+
+                let key = patch_id.to_string();
+                let (revision, _) = patch.latest();
+
+                if bridges.email.thread(&project, &key, revision).is_some() {
+                    continue;
+                }
+                let mut spinner = term::spinner(format!(
+                    "Sending patch {} to {}...",
+                    term::format::highlight(common::fmt::cob(&patch_id)),
+                    term::format::tertiary(&list)
+                ));
+                let thread = match send(
+                    &project,
+                    &patch_id,
+                    &patch,
+                    &name,
+                    &list,
+                    &bridges.email,
+                    profile,
+                    dry_run,
+                ) {
+                    Ok(thread) => thread,
+                    Err(err) => {
+                        spinner.failed();
+                        return Err(err);
+                    }
+                };
+                spinner.message(format!(
+                    "Patch {} sent to {} ({} message(s))",
+                    term::format::highlight(common::fmt::cob(&patch_id)),
+                    term::format::tertiary(&list),
+                    thread.messages.len()
+                ));
+                spinner.finish();
+                sent += 1;
+
+                if !dry_run {
+                    bridges.email.threads.push(thread);
+                    bridges.save(profile)?;
+                }
+            }
+            if sent == 0 {
+                term::info!("No new patch revisions to send.");
+            } else if dry_run {
+                term::info!("Dry run: nothing was sent.");
+            }
+        }
+        Operation::List => {
+            let mut table = term::Table::default();
+            let mut count = 0;
+
+            if let Some(list) = &bridges.email.list {
+                term::info!("Mailing list: {}", term::format::highlight(list));
+            }
+            for thread in bridges.email.threads.iter().filter(|t| t.urn == project) {
+                count += 1;
+                table.push([
+                    term::format::tertiary(format!("{:.11}", thread.patch)),
+                    term::format::dim(format!("R{}", thread.revision)),
+                    term::format::highlight(thread.cover().unwrap_or_default()),
+                ]);
+            }
+            if count == 0 {
+                term::print(term::format::italic("No patches were sent."));
+            } else {
+                table.render();
+            }
+        }
+        Operation::Sync {
+            maildir, dry_run, ..
+        } => {
+            let maildir = maildir
+                .or_else(|| bridges.email.maildir.clone())
+                .ok_or_else(|| Error::WithHint {
+                    err: anyhow!("no maildir is configured"),
+                    hint: "hint: use `--maildir` or run `rad bridge email setup` with `--maildir`",
+                })?;
+            let mut spinner = term::spinner(format!(
+                "Reading replies from {}...",
+                term::format::tertiary(maildir.display())
+            ));
+            let messages = match bridge::maildir(&maildir) {
+                Ok(messages) => messages,
+                Err(err) => {
+                    spinner.failed();
+                    return Err(err.into());
+                }
+            };
+            let mut imported = 0;
+
+            for msg in messages {
+                if bridges.email.ingested.contains(&msg.message_id) {
+                    continue;
+                }
+                let (patch, revision) = match bridges.email.thread_of(&msg) {
+                    Some(t) if t.urn == project && !t.messages.contains(&msg.message_id) => {
+                        (t.patch.clone(), t.revision)
+                    }
+                    _ => continue,
+                };
+                let id = cobs::Identifier::from_str(&patch)?;
+                let (patch_id, _) = match patches.resolve::<Patch>(urn, &id)? {
+                    Some(patch) => patch,
+                    None => continue,
+                };
+                if !dry_run {
+                    patches.comment(urn, &patch_id, revision, &bridge::from_email(&msg))?;
+                    bridges.email.ingested.push(msg.message_id.clone());
+                }
+                imported += 1;
+            }
+            if !dry_run {
+                bridges.save(profile)?;
+            }
+            spinner.message(format!("{} reply(s) copied to patches", imported));
+            spinner.finish();
+
+            if dry_run {
+                term::info!("Dry run: nothing was copied.");
+            }
+        }
+        Operation::Link { .. } | Operation::Unlink { .. } => {
+            unreachable!("link operations are only available for GitHub")
+        }
+    }
+
+    Ok(())
+}
+
+/// Send the latest revision of a patch to a mailing list, with `git send-email`.
+fn send(
+    project: &str,
+    patch_id: &PatchId,
+    patch: &Patch,
+    name: &str,
+    list: &str,
+    email: &Email,
+    profile: &Profile,
+    dry_run: bool,
+) -> anyhow::Result<EmailThread> {
+    let (revision, latest) = patch.latest();
+    let dir = env::temp_dir().join(format!("rad-bridge-{}", patch_id));
+    let prefix = if revision > 0 {
+        format!("PATCH {} v{}", name, revision + 1)
+    } else {
+        format!("PATCH {}", name)
+    };
+    let mut args: Vec<OsString> = vec![
+        "format-patch".into(),
+        "--cover-letter".into(),
+        "--thread=shallow".into(),
+        format!("--subject-prefix={}", prefix).into(),
+        "-o".into(),
+        dir.clone().into(),
+    ];
+    // New revisions are sent as replies to the previous revision's thread.
+    if let Some(cover) = email
+        .latest(project, &patch_id.to_string())
+        .and_then(|t| t.cover())
+    {
+        args.push(format!("--in-reply-to={}", cover).into());
+    }
+    args.push(format!("{}..{}", latest.base, latest.oid).into());
+
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir)?;
+    // The revision commits may not be in the working copy, but they are in storage.
+    common::git::git(profile.paths().git_dir(), args)?;
+
+    let result = send_dir(&dir, patch_id, patch, list, dry_run);
+    fs::remove_dir_all(&dir).ok();
+
+    let messages = result?;
+
+    Ok(EmailThread {
+        urn: project.to_owned(),
+        patch: patch_id.to_string(),
+        revision,
+        messages,
+    })
+}
+
+/// Fill in the cover letter of a `git format-patch` output directory and send it.
+/// Returns the message ids of the thread, cover letter first.
+fn send_dir(
+    dir: &Path,
+    patch_id: &PatchId,
+    patch: &Patch,
+    list: &str,
+    dry_run: bool,
+) -> anyhow::Result<Vec<String>> {
+    let mut files = fs::read_dir(dir)?
+        .map(|e| e.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    files.sort();
+
+    let cover = files
+        .first()
+        .ok_or_else(|| anyhow!("`git format-patch` produced no output"))?;
+    let description = format!(
+        "{}\n\nThis patch was proposed on Radicle. To check it out, run:\n\n    rad patch checkout {}",
+        patch.description().trim(),
+        patch_id
+    );
+    // Nb. The letter is sent by the local user, who may not be the patch author.
+    let signature = common::git::repository()?.signature()?;
+    let sender = String::from_utf8_lossy(signature.name_bytes());
+    let blurb = mail::cover_blurb(&sender, &patch.author.name(), description.trim_start());
+
+    fs::write(
+        cover,
+        mail::fill_cover_letter(&fs::read_to_string(cover)?, &patch.title, &blurb),
+    )?;
+
+    let mut messages = Vec::new();
+    for file in &files {
+        let msg = EmailMessage::read(file)?
+            .ok_or_else(|| anyhow!("{} has no message id", file.display()))?;
+        messages.push(msg.message_id);
+    }
+
+    let mut args: Vec<OsString> = vec![
+        "send-email".into(),
+        format!("--to={}", list).into(),
+        "--confirm=never".into(),
+        "--quiet".into(),
+    ];
+    if dry_run {
+        args.push("--dry-run".into());
+    }
+    args.extend(files.into_iter().map(OsString::from));
+
+    // Run from the working copy, so that its `sendemail.*` configuration applies.
+    common::git::git(&env::current_dir()?, args)?;
+
+    Ok(messages)
+}
@@ -5,10 +5,11 @@ use anyhow::anyhow;
 use anyhow::Context as _;
 
 use librad::git::Urn;
+use librad::PeerId;
 
 use radicle_common::args::{Args, Error, Help};
 use radicle_common::Interactive;
-use radicle_common::{fmt, keys, profile, project};
+use radicle_common::{fmt, git, keys, profile, project, registry};
 use radicle_terminal as term;
 
 pub const HELP: Help = Help {
@@ -18,17 +19,27 @@ pub const HELP: Help = Help {
     usage: r#"
 Usage
 
-    rad checkout <urn> [<option>...]
+    rad checkout <urn> [--peer <peer-id>] [<option>...]
+
+    Creates a working copy of a project from local storage, under a directory
+    named after the project. The working copy's `rad` remote and the upstream of
+    its default branch are set up, as well as a remote for each delegate.
+
+    Your own copy of the project is checked out if you have one, otherwise the
+    delegate's, if the project has only one. With `--peer`, the given peer's
+    copy is checked out instead.
 
 Options
 
-    --no-confirm    Don't ask for confirmation during checkout
-    --help          Print help
+    --peer <peer-id>    Checkout the project from the given peer
+    --no-confirm        Don't ask for confirmation during checkout
+    --help              Print help
 "#,
 };
 
 pub struct Options {
     pub urn: Urn,
+    pub peer: Option<PeerId>,
     pub interactive: Interactive,
 }
 
@@ -39,10 +50,20 @@ impl Args for Options {
 
         let mut parser = lexopt::Parser::from_args(args);
         let mut urn = None;
+        let mut peer = None;
         let mut interactive = Interactive::Yes;
 
         while let Some(arg) = parser.next()? {
             match arg {
+                Long("peer") => {
+                    let val = parser.value()?;
+                    let val = val.to_string_lossy();
+
+                    peer = Some(
+                        PeerId::from_str(&val)
+                            .map_err(|_| anyhow!("invalid <peer-id> '{}'", val))?,
+                    );
+                }
                 Long("no-confirm") => {
                     interactive = Interactive::No;
                 }
@@ -60,6 +81,7 @@ impl Args for Options {
         Ok((
             Options {
                 urn: urn.ok_or_else(|| anyhow!("a project URN to checkout must be provided"))?,
+                peer,
                 interactive,
             },
             vec![],
@@ -101,9 +123,33 @@ pub fn execute(options: Options, profile: &profile::Profile) -> anyhow::Result<P
     // a peer.
     // If we *don't* have a local head, we have to checkout a delegate's head. If there is
     // only one delegate, the choice is easy.
-    let peer = if project::get_local_head(&storage, &options.urn, &project.default_branch)?
-        .is_some()
-    {
+    let peer = if let Some(peer) = options.peer {
+        if peer == *storage.peer_id() {
+            if project::get_local_head(&storage, &options.urn, &project.default_branch)?.is_none() {
+                anyhow::bail!("you don't have a '{}' branch", project.default_branch);
+            }
+            None
+        } else if project::get_remote_head(&storage, &options.urn, &peer, &project.default_branch)?
+            .is_some()
+        {
+            term::success!(
+                "Remote {} branch found via {}...",
+                project.default_branch,
+                term::format::highlight(peer)
+            );
+            Some(peer)
+        } else {
+            return Err(Error::WithHint {
+                err: anyhow!(
+                    "peer {} has no '{}' branch in local storage",
+                    peer,
+                    project.default_branch
+                ),
+                hint: "hint: track the peer with `rad track <peer-id>` and fetch it with `rad sync --fetch`",
+            }
+            .into());
+        }
+    } else if project::get_local_head(&storage, &options.urn, &project.default_branch)?.is_some() {
         term::success!("Local {} branch found...", project.default_branch);
         None
     } else {
@@ -118,7 +164,13 @@ pub fn execute(options: Options, profile: &profile::Profile) -> anyhow::Result<P
                 );
                 Some(*d)
             }
-            [_,_,..] => anyhow::bail!("project has more than one delegate, please specify which one you would like to checkout"),
+            [_, _, ..] => {
+                return Err(Error::WithHint {
+                    err: anyhow!("project has more than one delegate"),
+                    hint: "hint: specify which delegate to checkout with `--peer <peer-id>`",
+                }
+                .into())
+            }
         }
     };
 
@@ -141,6 +193,20 @@ pub fn execute(options: Options, profile: &profile::Profile) -> anyhow::Result<P
     };
     spinner.finish();
 
+    // Nb. Only used to list working copies with `rad ls`.
+    if let Err(err) = registry::register(profile, &options.urn, &path) {
+        term::warning(&format!("Could not register working copy: {:#}", err));
+    }
+
+    // Make sure the default branch tracks the `rad` remote, when checking out your own copy.
+    if peer.is_none() && git::set_upstream_if_unset(&repo, "rad", &project.default_branch)? {
+        term::success!(
+            "Branch {} set up to track {}",
+            term::format::highlight(&project.default_branch),
+            term::format::tertiary(format!("rad/{}", project.default_branch))
+        );
+    }
+
     // Setup signing.
     if let Err(err) = rad_init::setup_signing(storage.peer_id(), &repo, interactive) {
         term::warning(&format!("Could not setup signing: {:#}", err));
@@ -6,7 +6,8 @@ use librad::profile::{LnkHome, LNK_HOME};
 use link_identities::git::Urn;
 use radicle_git_helpers::remote_helper;
 
-use radicle_common::{keys, profile, signer::ToSigner as _};
+use radicle_common::signer::{self, ToSigner as _};
+use radicle_common::{keychain, keys, profile};
 
 use anyhow::anyhow;
 #[cfg(feature = "ethereum")]
@@ -142,12 +143,21 @@ fn run(remote: Remote) -> anyhow::Result<()> {
         }
         Remote::Project { urn: _urn } => {
             let profile = profile::default()?;
-            let signer = if let Ok(sock) = keys::ssh_auth_sock() {
-                sock.to_signer(&profile)?
-            } else if let Ok(pass) = env::var(keys::RAD_PASSPHRASE) {
-                keys::load_secret_key(&profile, SecUtf8::from(pass))?.to_signer(&profile)?
-            } else {
-                fatal(anyhow!("no signers found: ssh-agent is not running"));
+            let backend = signer::Backend::from_env()?;
+            let signer = match keys::source(&profile, backend)? {
+                keys::Source::Agent => keys::ssh_auth_sock()?.to_signer(&profile)?,
+                keys::Source::KeyFile => {
+                    let pass = match env::var(keys::RAD_PASSPHRASE) {
+                        Ok(pass) => SecUtf8::from(pass),
+                        Err(_) => keychain::passphrase(&profile).unwrap_or_else(|| {
+                            fatal(anyhow!(
+                                "no signers found: your key is not in ssh-agent, and `{}` is not set",
+                                keys::RAD_PASSPHRASE
+                            ))
+                        }),
+                    };
+                    keys::load_secret_key(&profile, pass)?.to_signer(&profile)?
+                }
             };
             let config = remote_helper::Config {
                 signer: Some(signer),
@@ -1,15 +1,64 @@
 use std::ffi::OsString;
-use std::{io::ErrorKind, iter, process};
+use std::path::PathBuf;
+use std::{env, io::ErrorKind, iter, process};
 
 use anyhow::anyhow;
 use rad_help::*;
-use radicle_common::profile;
+use radicle_common::{args, git, profile, proxy};
 use radicle_terminal as term;
 
 pub const NAME: &str = "rad";
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const DESCRIPTION: &str = env!("CARGO_PKG_DESCRIPTION");
 
+/// Built-in commands. Nb. Keep in sync with [`run_other`].
+const COMMANDS: &[&str] = &[
+    #[cfg(feature = "ethereum")]
+    "account",
+    "advisory",
+    "archive",
+    "auth",
+    "badge",
+    "bridge",
+    "checkout",
+    "clone",
+    "comment",
+    "compare",
+    "delegate",
+    "doctor",
+    "edit",
+    "env",
+    "events",
+    #[cfg(feature = "ethereum")]
+    "ens",
+    #[cfg(feature = "ethereum")]
+    "gov",
+    "help",
+    "id",
+    "import-repo",
+    "init",
+    "inspect",
+    "issue",
+    "ls",
+    "merge",
+    "patch",
+    "path",
+    "profile",
+    "pull",
+    "push",
+    "remote",
+    "review",
+    "rm",
+    "seed",
+    "self",
+    "setup-hooks",
+    "show",
+    "sync",
+    "track",
+    "tutorial",
+    "untrack",
+];
+
 #[derive(Debug)]
 enum Command {
     Other(Vec<OsString>),
@@ -19,12 +68,12 @@ enum Command {
 
 fn main() {
     match parse_args().map_err(Some).and_then(run) {
-        Ok(_) => process::exit(0),
+        Ok(_) => radicle_terminal::exit(0),
         Err(err) => {
             if let Some(err) = err {
                 radicle_terminal::error(&format!("Error: rad: {}", err));
             }
-            process::exit(1);
+            radicle_terminal::exit(1);
         }
     }
 }
@@ -43,6 +92,37 @@ fn parse_args() -> anyhow::Result<Command> {
             Long("version") => {
                 command = Some(Command::Version);
             }
+            Long("storage") if command.is_none() => {
+                let path = PathBuf::from(parser.value()?);
+                let path = if path.is_absolute() {
+                    path
+                } else {
+                    env::current_dir()?.join(path)
+                };
+                // Nb. This is picked up by `profile::home`, as well as by child processes
+                // such as the git remote helper.
+                env::set_var(profile::RAD_HOME, path);
+            }
+            Long("profile") if command.is_none() => {
+                // Nb. This is picked up by `profile::default`, as well as by child processes.
+                env::set_var(profile::RAD_PROFILE, parser.value()?);
+            }
+            Long("repo") if command.is_none() => {
+                let path = PathBuf::from(parser.value()?);
+
+                // Nb. Commands find the repository from the working directory, as do the
+                // `git` processes they spawn, so this is all that is needed to target it.
+                env::set_current_dir(&path).map_err(|err| {
+                    anyhow!("cannot use repository '{}': {}", path.display(), err)
+                })?;
+            }
+            Long("no-confirm") | Long("yes") | Short('y') if command.is_none() => {
+                // Nb. This is picked up by `term::ask`, as well as by child processes.
+                args::set_assume_yes();
+            }
+            Long("git-trace") if command.is_none() => {
+                git::enable_trace();
+            }
             Value(val) if command.is_none() => {
                 if val == *"." {
                     command = Some(Command::Other(vec![OsString::from("inspect")]));
@@ -85,6 +165,12 @@ fn run(command: Command) -> Result<(), Option<anyhow::Error>> {
             let exe = args.first();
 
             if let Some(Some(exe)) = exe.map(|s| s.to_str()) {
+                if is_first_run(exe) {
+                    setup()?;
+                }
+                if let Ok(profile) = profile::default() {
+                    proxy::init(&profile);
+                }
                 run_other(exe, &args[1..])?;
             } else {
                 print_help()?;
@@ -95,6 +181,49 @@ fn run(command: Command) -> Result<(), Option<anyhow::Error>> {
     Ok(())
 }
 
+/// Whether this is the first time `rad` is used, ie. there are no profiles, and
+/// the command requires one.
+fn is_first_run(exe: &str) -> bool {
+    let interactive = atty::is(atty::Stream::Stdin) && atty::is(atty::Stream::Stdout);
+
+    interactive
+        && !matches!(
+            exe,
+            "auth" | "help" | "init" | "path" | "profile" | "tutorial"
+        )
+        && profile::list().map_or(false, |profiles| profiles.is_empty())
+}
+
+/// Guide the user through setting up radicle.
+fn setup() -> anyhow::Result<()> {
+    term::info!(
+        "{}",
+        term::format::highlight("It looks like this is your first time using radicle.")
+    );
+    if !term::confirm("Would you like to set it up now?") {
+        term::tip!("To get started, use `rad auth` to authenticate.");
+        return Ok(());
+    }
+    term::blank();
+
+    let profile = rad_auth::wizard()?;
+
+    // Offer to initialize the current repository, if it isn't already.
+    if let Ok(repo) = git::repository() {
+        if git::rad_remote(&repo).is_err() {
+            term::blank();
+
+            if term::confirm("Initialize the current repository as a radicle project?") {
+                term::blank();
+                rad_init::init(Default::default(), &profile)?;
+            }
+        }
+    }
+    term::blank();
+
+    Ok(())
+}
+
 fn run_other(exe: &str, args: &[OsString]) -> Result<(), Option<anyhow::Error>> {
     match exe {
         #[cfg(feature = "ethereum")]
@@ -106,6 +235,22 @@ fn run_other(exe: &str, args: &[OsString]) -> Result<(), Option<anyhow::Error>>
                 args.to_vec(),
             );
         }
+        "advisory" => {
+            term::run_command_args::<rad_advisory::Options, _>(
+                rad_advisory::HELP,
+                "Command",
+                rad_advisory::run,
+                args.to_vec(),
+            );
+        }
+        "archive" => {
+            term::run_command_args::<rad_archive::Options, _>(
+                rad_archive::HELP,
+                "Command",
+                rad_archive::run,
+                args.to_vec(),
+            );
+        }
         "auth" => {
             term::run_command_args::<rad_auth::Options, _>(
                 rad_auth::HELP,
@@ -114,6 +259,22 @@ fn run_other(exe: &str, args: &[OsString]) -> Result<(), Option<anyhow::Error>>
                 args.to_vec(),
             );
         }
+        "badge" => {
+            term::run_command_args::<rad_badge::Options, _>(
+                rad_badge::HELP,
+                "Command",
+                rad_badge::run,
+                args.to_vec(),
+            );
+        }
+        "bridge" => {
+            term::run_command_args::<rad_bridge::Options, _>(
+                rad_bridge::HELP,
+                "Command",
+                rad_bridge::run,
+                args.to_vec(),
+            );
+        }
         "checkout" => {
             term::run_command_args::<rad_checkout::Options, _>(
                 rad_checkout::HELP,
@@ -138,6 +299,30 @@ fn run_other(exe: &str, args: &[OsString]) -> Result<(), Option<anyhow::Error>>
                 args.to_vec(),
             );
         }
+        "compare" => {
+            term::run_command_args::<rad_compare::Options, _>(
+                rad_compare::HELP,
+                "Command",
+                rad_compare::run,
+                args.to_vec(),
+            );
+        }
+        "delegate" => {
+            term::run_command_args::<rad_delegate::Options, _>(
+                rad_delegate::HELP,
+                "Command",
+                rad_delegate::run,
+                args.to_vec(),
+            );
+        }
+        "doctor" => {
+            term::run_command_args::<rad_doctor::Options, _>(
+                rad_doctor::HELP,
+                "Command",
+                rad_doctor::run,
+                args.to_vec(),
+            );
+        }
         "edit" => {
             term::run_command_args::<rad_edit::Options, _>(
                 rad_edit::HELP,
@@ -146,6 +331,22 @@ fn run_other(exe: &str, args: &[OsString]) -> Result<(), Option<anyhow::Error>>
                 args.to_vec(),
             );
         }
+        "env" => {
+            term::run_command_args::<rad_env::Options, _>(
+                rad_env::HELP,
+                "Command",
+                rad_env::run,
+                args.to_vec(),
+            );
+        }
+        "events" => {
+            term::run_command_args::<rad_events::Options, _>(
+                rad_events::HELP,
+                "Command",
+                rad_events::run,
+                args.to_vec(),
+            );
+        }
         #[cfg(feature = "ethereum")]
         "ens" => {
             term::run_command_args::<rad_ens::Options, _>(
@@ -172,6 +373,22 @@ fn run_other(exe: &str, args: &[OsString]) -> Result<(), Option<anyhow::Error>>
                 args.to_vec(),
             );
         }
+        "id" => {
+            term::run_command_args::<rad_id::Options, _>(
+                rad_id::HELP,
+                "Command",
+                rad_id::run,
+                args.to_vec(),
+            );
+        }
+        "import-repo" => {
+            term::run_command_args::<rad_import_repo::Options, _>(
+                rad_import_repo::HELP,
+                "Import",
+                rad_import_repo::run,
+                args.to_vec(),
+            );
+        }
         "init" => {
             term::run_command_args::<rad_init::Options, _>(
                 rad_init::HELP,
@@ -228,6 +445,14 @@ fn run_other(exe: &str, args: &[OsString]) -> Result<(), Option<anyhow::Error>>
                 args.to_vec(),
             );
         }
+        "profile" => {
+            term::run_command_args::<rad_profile::Options, _>(
+                rad_profile::HELP,
+                "Command",
+                rad_profile::run,
+                args.to_vec(),
+            );
+        }
         "pull" => {
             term::run_command_args::<rad_pull::Options, _>(
                 rad_pull::HELP,
@@ -268,6 +493,14 @@ fn run_other(exe: &str, args: &[OsString]) -> Result<(), Option<anyhow::Error>>
                 args.to_vec(),
             );
         }
+        "seed" => {
+            term::run_command_args::<rad_seed::Options, _>(
+                rad_seed::HELP,
+                "Command",
+                rad_seed::run,
+                args.to_vec(),
+            );
+        }
         "self" => {
             term::run_command_args::<rad_self::Options, _>(
                 rad_self::HELP,
@@ -276,6 +509,22 @@ fn run_other(exe: &str, args: &[OsString]) -> Result<(), Option<anyhow::Error>>
                 args.to_vec(),
             );
         }
+        "setup-hooks" => {
+            term::run_command_args::<rad_setup_hooks::Options, _>(
+                rad_setup_hooks::HELP,
+                "Command",
+                rad_setup_hooks::run,
+                args.to_vec(),
+            );
+        }
+        "show" => {
+            term::run_command_args::<rad_show::Options, _>(
+                rad_show::HELP,
+                "Command",
+                rad_show::run,
+                args.to_vec(),
+            );
+        }
         "sync" => {
             term::run_command_args::<rad_sync::Options, _>(
                 rad_sync::HELP,
@@ -292,6 +541,14 @@ fn run_other(exe: &str, args: &[OsString]) -> Result<(), Option<anyhow::Error>>
                 args.to_vec(),
             );
         }
+        "tutorial" => {
+            term::run_command_args::<rad_tutorial::Options, _>(
+                rad_tutorial::HELP,
+                "Tutorial",
+                rad_tutorial::run,
+                args.to_vec(),
+            );
+        }
         "untrack" => {
             term::run_command_args::<rad_untrack::Options, _>(
                 rad_untrack::HELP,
@@ -301,6 +558,7 @@ fn run_other(exe: &str, args: &[OsString]) -> Result<(), Option<anyhow::Error>>
             );
         }
         _ => {
+            let name = exe;
             let exe = format!("{}-{}", NAME, exe);
             let status = process::Command::new(exe.clone()).args(args).status();
 
@@ -312,6 +570,15 @@ fn run_other(exe: &str, args: &[OsString]) -> Result<(), Option<anyhow::Error>>
                 }
                 Err(err) => {
                     if let ErrorKind::NotFound = err.kind() {
+                        let suggestion =
+                            radicle_common::args::suggest(name, COMMANDS.iter().copied());
+
+                        if let Some(suggestion) = suggestion {
+                            term::error(format!("Error: rad: command `{}` not found", exe));
+                            term::tip!("Did you mean `rad {}`?", suggestion);
+
+                            return Err(None);
+                        }
                         return Err(Some(anyhow!("command `{}` not found", exe)));
                     } else {
                         return Err(Some(err.into()));
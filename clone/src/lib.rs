@@ -6,6 +6,7 @@ use std::str::FromStr;
 use anyhow::anyhow;
 use librad::git::tracking;
 use librad::git::Urn;
+use librad::PeerId;
 use url::Url;
 
 use radicle_common::args::{Args, Error, Help};
@@ -21,13 +22,23 @@ pub const HELP: Help = Help {
     usage: r#"
 Usage
 
-    rad clone <urn | url> [--seed <addr>] [<option>...]
+    rad clone <urn | url> [--seed <addr>] [--peer <peer-id>] [<option>...]
+
+    Clones a project in one step: the project is fetched from a seed into local
+    storage, its delegates are tracked, and a working copy is created under a
+    directory named after the project. The seed is given as `<peer-id>@<addr>`
+    or as a URL, eg. `rad://<peer-id>@<addr>`. When a seed is given, it is also
+    set as the working copy's seed, for `rad sync` and other commands.
+
+    Git URLs, eg. `https://github.com/acme/project.git`, are cloned with git,
+    and can then be initialized as projects.
 
 Options
 
-    --no-confirm    Don't ask for confirmation during clone
-    --seed <addr>   Seed to clone from
-    --help          Print help
+    --no-confirm        Don't ask for confirmation during clone
+    --seed <addr>       Seed to clone from
+    --peer <peer-id>    Checkout the project from the given peer (see `rad checkout`)
+    --help              Print help
 
 "#,
 };
@@ -41,6 +52,7 @@ enum Origin {
 #[derive(Debug)]
 pub struct Options {
     origin: Origin,
+    peer: Option<PeerId>,
     interactive: Interactive,
 }
 
@@ -52,12 +64,22 @@ impl Args for Options {
         let mut origin: Option<Origin> = None;
         let mut interactive = Interactive::Yes;
         let mut seed = None;
+        let mut peer = None;
 
         while let Some(arg) = parser.next()? {
             match arg {
                 Long("seed") if seed.is_none() => {
                     seed = Some(seed::parse_value(&mut parser)?);
                 }
+                Long("peer") => {
+                    let val = parser.value()?;
+                    let val = val.to_string_lossy();
+
+                    peer = Some(
+                        PeerId::from_str(&val)
+                            .map_err(|_| anyhow!("invalid <peer-id> '{}'", val))?,
+                    );
+                }
                 Long("no-confirm") => {
                     interactive = Interactive::No;
                 }
@@ -96,6 +118,9 @@ impl Args for Options {
             anyhow!("to clone, a URN or URL must be provided; see `rad clone --help`")
         })?;
 
+        if peer.is_some() && matches!(origin, Origin::Git(_)) {
+            anyhow::bail!("`--peer` cannot be specified when a git URL is given as origin");
+        }
         let origin = if let Origin::Radicle(identity::Origin { urn, seed: None }) = origin {
             Origin::Radicle(identity::Origin { urn, seed })
         } else {
@@ -105,6 +130,7 @@ impl Args for Options {
         Ok((
             Options {
                 origin,
+                peer,
                 interactive,
             },
             vec![],
@@ -115,7 +141,13 @@ impl Args for Options {
 pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
     match options.origin {
         Origin::Radicle(origin) => {
-            clone_project(origin.urn, origin.seed, options.interactive, ctx)?;
+            clone_project(
+                origin.urn,
+                origin.seed,
+                options.peer,
+                options.interactive,
+                ctx,
+            )?;
         }
         Origin::Git(url) => {
             let profile = ctx.profile()?;
@@ -128,6 +160,7 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
 pub fn clone_project(
     urn: Urn,
     seed: Option<sync::Seed<String>>,
+    peer: Option<PeerId>,
     interactive: Interactive,
     ctx: impl term::Context,
 ) -> anyhow::Result<()> {
@@ -137,20 +170,13 @@ pub fn clone_project(
         rad_sync::Options {
             origin: Some(identity::Origin {
                 urn: urn.clone(),
-                seed,
+                seed: seed.clone(),
             }),
             verbose: true,
             ..rad_sync::Options::default()
         },
         profile.clone(),
     )?;
-    let path = rad_checkout::execute(
-        rad_checkout::Options {
-            urn: urn.clone(),
-            interactive,
-        },
-        &profile,
-    )?;
 
     let signer = term::signer(&profile)?;
     let storage = keys::storage(&profile, signer)?;
@@ -158,18 +184,37 @@ pub fn clone_project(
     let project = project::get(&storage, &urn)?
         .ok_or_else(|| anyhow!("couldn't load project {} from local state", urn))?;
 
-    // Track all project delegates.
-    for peer in project.remotes {
+    // Track all project delegates, before the checkout sets up their remotes.
+    for peer in &project.remotes {
         tracking::track(
             &storage,
             &urn,
-            Some(peer),
+            Some(*peer),
             cfg.clone(),
             tracking::policy::Track::Any,
         )??;
     }
     term::success!("Tracking for project delegates configured");
 
+    let path = rad_checkout::execute(
+        rad_checkout::Options {
+            urn: urn.clone(),
+            peer,
+            interactive,
+        },
+        &profile,
+    )?;
+
+    // Remember the seed the project was cloned from, so that it's synced with from now on.
+    if let Some(seed) = seed {
+        let addr =
+            seed::Address::try_from(Url::parse(&format!("rad://{}@{}", seed.peer, seed.addrs))?)?;
+        let repo = git::Repository::open(&path)?;
+
+        seed::set_seeds(&repo, &[addr.clone()])?;
+        term::success!("Seed {} set", term::format::highlight(addr));
+    }
+
     term::headline(&format!(
         "🌱 Project clone successful under ./{}",
         term::format::highlight(path.file_name().unwrap_or_default().to_string_lossy())
@@ -221,6 +266,11 @@ mod test {
                 "--seed",
                 "hyb5to4rshftx4apgmu9s6wnsp4ddmp1mz6ijh4qqey7fb8wrpawxa@pine.radicle.garden:8776",
             ],
+            vec![
+                "rad:git:hnrkfbrd7y9674d8ow8uioki16fniwcyoz67y",
+                "--seed",
+                "rad://hyb5to4rshftx4apgmu9s6wnsp4ddmp1mz6ijh4qqey7fb8wrpawxa@pine.radicle.garden",
+            ],
         ];
 
         for args in tests {
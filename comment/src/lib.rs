@@ -10,7 +10,7 @@ use radicle_common::{
     keys, project,
 };
 use radicle_terminal as term;
-use radicle_terminal::patch::Comment;
+use radicle_terminal::patch::{Comment, Validation};
 
 pub const HELP: Help = Help {
     name: "comment",
@@ -97,7 +97,9 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
     let cobs = cobs::store(&profile, &storage)?;
     let cob_id = options.id;
 
-    let message = options.message.get("Enter a comment message...");
+    let message = options
+        .message
+        .get("Enter a comment message...", Validation::optional())?;
     if message.is_empty() {
         return Ok(());
     }
@@ -0,0 +1,121 @@
+//! Per-project collaborator allowlists.
+//!
+//! When a project has an allowlist, only patches and issues from delegates and listed
+//! peers are shown by default. Contributions from other peers are considered unsolicited.
+//!
+//! The allowlist is also enforced when fetching: the tracking entries of peers that
+//! aren't allowed are restricted to their identity, so that their branches and
+//! collaborative objects are no longer replicated, see [`Allowlist::enforce`].
+use std::collections::{BTreeMap, BTreeSet};
+
+use librad::git::storage::Storage;
+use librad::git::tracking;
+use librad::git::Urn;
+use librad::PeerId;
+use serde::{Deserialize, Serialize};
+
+use crate::project;
+use crate::store::Store;
+
+/// File name of the allowlist file, in the profile scope.
+pub const FILE_NAME: &str = "allowlist.toml";
+
+/// Collaborator allowlists, keyed by project URN.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Allowlist {
+    #[serde(default)]
+    pub projects: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl Store for Allowlist {
+    const FILE_NAME: &'static str = FILE_NAME;
+}
+
+impl Allowlist {
+    /// Whether the project has an allowlist.
+    pub fn is_enabled(&self, urn: &Urn) -> bool {
+        self.projects.contains_key(&urn.to_string())
+    }
+
+    /// Add a peer to a project's allowlist. Returns whether the peer was added.
+    pub fn allow(&mut self, urn: &Urn, peer: &PeerId) -> bool {
+        self.projects
+            .entry(urn.to_string())
+            .or_default()
+            .insert(peer.default_encoding())
+    }
+
+    /// Remove a peer from a project's allowlist. Returns whether the peer was listed.
+    /// The project keeps its allowlist, even if it becomes empty.
+    pub fn disallow(&mut self, urn: &Urn, peer: &PeerId) -> bool {
+        self.projects
+            .get_mut(&urn.to_string())
+            .map_or(false, |peers| peers.remove(&peer.default_encoding()))
+    }
+
+    /// Whether contributions from the given peer should be shown for a project.
+    /// This is always the case for projects without an allowlist, and for delegates.
+    pub fn allows(&self, project: &project::Metadata, peer: &PeerId) -> bool {
+        match self.projects.get(&project.urn.to_string()) {
+            Some(peers) => {
+                project.remotes.contains(peer) || peers.contains(&peer.default_encoding())
+            }
+            None => true,
+        }
+    }
+
+    /// Apply a project's allowlist to its tracking entries: delegates and listed peers
+    /// are replicated in full, while other tracked peers, including the entry tracking
+    /// any peer, only have their identity replicated. Returns the number of peers that
+    /// are restricted. Projects without an allowlist are left untouched.
+    pub fn enforce(&self, storage: &Storage, project: &project::Metadata) -> anyhow::Result<usize> {
+        if !self.is_enabled(&project.urn) {
+            return Ok(0);
+        }
+        let mut restricted = 0;
+
+        for tracked in tracking::tracked(storage, Some(&project.urn))? {
+            let peer = tracked?.peer_id();
+            let config = match peer {
+                Some(peer) if self.allows(project, &peer) => tracking::config::Config::default(),
+                _ => {
+                    restricted += 1;
+                    tracking::config::Config {
+                        data: false,
+                        cobs: tracking::config::cobs::Cobs::deny_all(),
+                    }
+                }
+            };
+            tracking::track(
+                storage,
+                &project.urn,
+                peer,
+                config,
+                tracking::policy::Track::Any,
+            )??;
+        }
+        Ok(restricted)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use librad::SecretKey;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_allow_disallow() {
+        let urn = Urn::from_str("rad:git:hnrkmg77m8tfzj4gi4pa4mbhgysfgzwntjpao").unwrap();
+        let alice = PeerId::from(SecretKey::new());
+        let mut allowlist = Allowlist::default();
+
+        assert!(!allowlist.is_enabled(&urn));
+        assert!(allowlist.allow(&urn, &alice));
+        assert!(!allowlist.allow(&urn, &alice));
+        assert!(allowlist.is_enabled(&urn));
+        assert!(allowlist.disallow(&urn, &alice));
+        assert!(!allowlist.disallow(&urn, &alice));
+        assert!(allowlist.is_enabled(&urn));
+    }
+}
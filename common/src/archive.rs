@@ -0,0 +1,146 @@
+//! Archive of storage ref tips overwritten by the CLI.
+//!
+//! Before a command force-updates refs in storage, eg. `rad push --force`, it records
+//! their old tips here, so that they can be listed and restored later, eg. with
+//! `rad archive restore`.
+use anyhow::anyhow;
+use librad::git::refs::Refs;
+use librad::git::storage::Storage;
+use librad::git::Urn;
+use serde::{Deserialize, Serialize};
+
+use crate::cobs::Timestamp;
+use crate::git;
+use crate::store::Store;
+
+/// File name of the archive file, in the profile scope.
+pub const FILE_NAME: &str = "archive.toml";
+
+/// An archived ref tip.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    /// Entry number, unique within the archive.
+    pub id: u64,
+    /// When the ref tip was archived.
+    pub timestamp: Timestamp,
+    /// Project the ref belongs to.
+    pub urn: Urn,
+    /// Ref name, relative to the project namespace, eg. `refs/heads/master`.
+    pub name: String,
+    /// Commit the ref pointed to.
+    pub oid: String,
+    /// Operation that overwrote the ref, eg. `push --force`.
+    pub reason: String,
+}
+
+/// Archived ref tips, oldest first.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Archive {
+    #[serde(default)]
+    pub entries: Vec<Entry>,
+}
+
+impl Store for Archive {
+    const FILE_NAME: &'static str = FILE_NAME;
+}
+
+impl Archive {
+    /// Record a ref tip. Returns the new entry.
+    pub fn record(&mut self, urn: &Urn, name: &str, oid: git::Oid, reason: &str) -> &Entry {
+        let id = self.entries.iter().map(|e| e.id).max().unwrap_or(0) + 1;
+
+        self.entries.push(Entry {
+            id,
+            timestamp: Timestamp::now(),
+            urn: urn.clone(),
+            name: name.to_owned(),
+            oid: oid.to_string(),
+            reason: reason.to_owned(),
+        });
+        &self.entries[self.entries.len() - 1]
+    }
+
+    /// Get an entry by number.
+    pub fn get(&self, id: u64) -> Option<&Entry> {
+        self.entries.iter().find(|e| e.id == id)
+    }
+
+    /// Remove an entry by number. Returns the entry, if it existed.
+    pub fn remove(&mut self, id: u64) -> Option<Entry> {
+        let ix = self.entries.iter().position(|e| e.id == id)?;
+
+        Some(self.entries.remove(ix))
+    }
+}
+
+impl Entry {
+    /// Full name of the ref in storage.
+    pub fn refname(&self) -> String {
+        format!("refs/namespaces/{}/{}", self.urn.encode_id(), self.name)
+    }
+}
+
+/// Restore an archived ref tip in storage, and sign the project refs. Fails if the ref
+/// exists and points elsewhere, unless `force` is set.
+pub fn restore(entry: &Entry, storage: &Storage, force: bool) -> Result<(), anyhow::Error> {
+    let repo = storage.as_raw();
+    let name = entry.refname();
+    let oid = git::Oid::from_str(&entry.oid)?;
+
+    if repo.find_commit(oid).is_err() {
+        anyhow::bail!(
+            "commit {} is no longer in storage, it may have been garbage collected",
+            entry.oid
+        );
+    }
+    if let Ok(existing) = repo.find_reference(&name) {
+        if existing.target() == Some(oid) {
+            return Ok(());
+        }
+        if !force {
+            return Err(anyhow!("ref '{}' already exists", entry.name));
+        }
+    }
+    repo.reference(
+        &name,
+        oid,
+        force,
+        &format!("rad archive: restore entry {}", entry.id),
+    )?;
+    Refs::update(storage, &entry.urn)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_record_remove() {
+        let urn = Urn::from_str("rad:git:hnrkmg77m8tfzj4gi4pa4mbhgysfgzwntjpao").unwrap();
+        let oid = git::Oid::from_str("a8a1a2cc55e8c3e0b6c2c4e6b8ae7fcc7c0ce7d8").unwrap();
+        let mut archive = Archive::default();
+
+        let a = archive.record(&urn, "refs/heads/a", oid, "test").id;
+        let b = archive.record(&urn, "refs/heads/b", oid, "test").id;
+        assert_eq!((a, b), (1, 2));
+        assert_eq!(archive.get(b).unwrap().name, "refs/heads/b");
+
+        assert!(archive.remove(a).is_some());
+        assert!(archive.remove(a).is_none());
+
+        let c = archive.record(&urn, "refs/heads/c", oid, "test").id;
+        assert_eq!(c, 3);
+
+        let archive: Archive = toml::from_str(&toml::to_string_pretty(&archive).unwrap()).unwrap();
+        assert_eq!(archive.entries.len(), 2);
+        assert_eq!(archive.get(c).unwrap().oid, oid.to_string());
+        assert_eq!(
+            archive.get(c).unwrap().refname(),
+            format!("refs/namespaces/{}/refs/heads/c", urn.encode_id())
+        );
+    }
+}
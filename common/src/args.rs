@@ -1,3 +1,4 @@
+use std::env;
 use std::ffi::OsString;
 use std::str::FromStr;
 
@@ -11,6 +12,9 @@ pub enum Error {
     /// If this error is returned from argument parsing, usage is displayed.
     #[error("usage invoked")]
     Usage,
+    /// If this error is returned from argument parsing, the JSON schema is displayed.
+    #[error("schema invoked")]
+    Schema(serde_json::Value),
     /// An error with a hint.
     #[error("{err}")]
     WithHint {
@@ -26,6 +30,40 @@ pub struct Help {
     pub usage: &'static str,
 }
 
+impl Help {
+    /// Long options mentioned in the usage text, eg. `list` for `--list`.
+    pub fn options(&self) -> Vec<String> {
+        let mut options = Vec::new();
+
+        for word in self.usage.split_whitespace() {
+            let word = word.trim_matches(|c| matches!(c, '[' | ']' | '|' | ',' | '`' | '.'));
+            let option = match word.strip_prefix("--") {
+                Some(option) => option,
+                None => continue,
+            };
+            let (negatable, option) = match option.strip_prefix("[no-]") {
+                Some(option) => (true, option),
+                None => (false, option),
+            };
+            let option = option
+                .split(|c| matches!(c, '=' | '<' | '['))
+                .next()
+                .unwrap_or_default();
+
+            if option.is_empty() {
+                continue;
+            }
+            if negatable {
+                options.push(format!("no-{}", option));
+            }
+            options.push(option.to_owned());
+        }
+        options.sort_unstable();
+        options.dedup();
+        options
+    }
+}
+
 pub trait Args: Sized {
     fn from_env() -> anyhow::Result<Self> {
         let args: Vec<_> = std::env::args_os().into_iter().skip(1).collect();
@@ -43,6 +81,25 @@ pub trait Args: Sized {
     fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)>;
 }
 
+/// Environment variable that makes confirmation prompts auto-accept, when set to `1`,
+/// `true` or `yes`. Set by the global `--no-confirm` flag.
+pub const RAD_YES: &str = "RAD_YES";
+
+/// Make confirmation prompts auto-accept, in this process and the processes it spawns,
+/// eg. the git remote helper.
+pub fn set_assume_yes() {
+    env::set_var(RAD_YES, "1");
+}
+
+/// Whether confirmation prompts should auto-accept, see [`RAD_YES`].
+pub fn assume_yes() -> bool {
+    env::var(RAD_YES).map_or(false, |val| is_yes(&val))
+}
+
+fn is_yes(val: &str) -> bool {
+    matches!(val.to_lowercase().as_str(), "1" | "true" | "yes")
+}
+
 pub fn parse_value<T: FromStr>(flag: &str, value: OsString) -> anyhow::Result<T>
 where
     <T as FromStr>::Err: std::error::Error,
@@ -71,3 +128,73 @@ pub fn finish(unparsed: Vec<OsString>) -> anyhow::Result<()> {
     }
     Ok(())
 }
+
+/// Suggest the candidate closest to a mistyped input, if any is close enough.
+pub fn suggest<'a>(input: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let max = std::cmp::max(1, input.chars().count() / 3);
+
+    candidates
+        .into_iter()
+        .map(|c| (distance(input, c), c))
+        .filter(|(d, _)| *d <= max)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, c)| c)
+}
+
+/// Levenshtein distance between two strings.
+fn distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { prev } else { prev + 1 };
+            prev = row[j + 1];
+            row[j + 1] = std::cmp::min(cost, std::cmp::min(row[j], prev) + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_suggest() {
+        let candidates = ["list", "sync", "no-sync", "message"];
+
+        assert_eq!(suggest("lsit", candidates), Some("list"));
+        assert_eq!(suggest("lst", candidates), Some("list"));
+        assert_eq!(suggest("nosync", candidates), Some("no-sync"));
+        assert_eq!(suggest("mesage", candidates), Some("message"));
+        assert_eq!(suggest("frobnicate", candidates), None);
+    }
+
+    #[test]
+    fn test_is_yes() {
+        assert!(is_yes("1"));
+        assert!(is_yes("true"));
+        assert!(is_yes("YES"));
+        assert!(!is_yes("0"));
+        assert!(!is_yes("no"));
+        assert!(!is_yes(""));
+    }
+
+    #[test]
+    fn test_help_options() {
+        let help = Help {
+            name: "test",
+            description: "",
+            version: "",
+            usage: "rad test [--list] --[no-]sync\n    -m, --message <string>  Message\n    --help",
+        };
+        assert_eq!(
+            help.options(),
+            vec!["help", "list", "message", "no-sync", "sync"]
+        );
+    }
+}
@@ -0,0 +1,240 @@
+//! Status badges, eg. for embedding in a project's README.
+//!
+//! Badges render as flat SVG, or as [shields.io endpoint](https://shields.io/endpoint)
+//! JSON, for gateways that serve badges through shields.
+use std::fmt::Write as _;
+
+use serde::Serialize;
+
+use crate::json;
+use crate::schema;
+
+/// Badge color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    BrightGreen,
+    Yellow,
+    Blue,
+    LightGrey,
+}
+
+impl Color {
+    /// Color name, as understood by shields.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::BrightGreen => "brightgreen",
+            Self::Yellow => "yellow",
+            Self::Blue => "blue",
+            Self::LightGrey => "lightgrey",
+        }
+    }
+
+    pub fn hex(&self) -> &'static str {
+        match self {
+            Self::BrightGreen => "#4c1",
+            Self::Yellow => "#dfb317",
+            Self::Blue => "#007ec6",
+            Self::LightGrey => "#9f9f9f",
+        }
+    }
+}
+
+/// A badge, eg. "issues | 3 open".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Badge {
+    pub label: String,
+    pub message: String,
+    pub color: Color,
+}
+
+/// Shields endpoint badge.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Endpoint<'a> {
+    schema_version: u32,
+    label: &'a str,
+    message: &'a str,
+    color: &'a str,
+}
+
+impl Badge {
+    pub fn new(label: impl Into<String>, message: impl Into<String>, color: Color) -> Self {
+        Self {
+            label: label.into(),
+            message: message.into(),
+            color,
+        }
+    }
+
+    /// Render as shields endpoint JSON.
+    pub fn to_json(&self) -> Result<String, json::Error> {
+        json::to_string_pretty(&Endpoint {
+            schema_version: 1,
+            label: &self.label,
+            message: &self.message,
+            color: self.color.name(),
+        })
+    }
+
+    /// JSON Schema of the shields endpoint JSON.
+    pub fn json_schema() -> json::Value {
+        let colors = [
+            Color::BrightGreen,
+            Color::Yellow,
+            Color::Blue,
+            Color::LightGrey,
+        ]
+        .iter()
+        .map(|c| c.name())
+        .collect::<Vec<_>>();
+
+        schema::object(&[
+            (
+                "schemaVersion",
+                json::json!({ "const": 1, "description": "Version of the shields endpoint format" }),
+            ),
+            ("label", schema::string("Badge label")),
+            ("message", schema::string("Badge message")),
+            ("color", json::json!({ "type": "string", "enum": colors })),
+        ])
+    }
+
+    /// Render as a flat SVG badge.
+    pub fn to_svg(&self) -> String {
+        let (label, message) = (escape(&self.label), escape(&self.message));
+        let (lw, mw) = (text_width(&self.label), text_width(&self.message));
+        let width = lw + mw;
+        let mut svg = String::new();
+
+        // Nb. Writing to a string can't fail.
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="20" role="img" aria-label="{l}: {m}">"#,
+            w = width,
+            l = label,
+            m = message
+        )
+        .ok();
+        writeln!(svg, "<title>{}: {}</title>", label, message).ok();
+        writeln!(
+            svg,
+            r##"<linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient>"##
+        )
+        .ok();
+        writeln!(
+            svg,
+            r##"<clipPath id="r"><rect width="{}" height="20" rx="3" fill="#fff"/></clipPath>"##,
+            width
+        )
+        .ok();
+        writeln!(
+            svg,
+            r##"<g clip-path="url(#r)"><rect width="{lw}" height="20" fill="#555"/><rect x="{lw}" width="{mw}" height="20" fill="{c}"/><rect width="{w}" height="20" fill="url(#s)"/></g>"##,
+            lw = lw,
+            mw = mw,
+            w = width,
+            c = self.color.hex()
+        )
+        .ok();
+        writeln!(
+            svg,
+            r##"<g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11"><text x="{lx}" y="14">{l}</text><text x="{mx}" y="14">{m}</text></g>"##,
+            lx = lw as f32 / 2.,
+            mx = lw as f32 + mw as f32 / 2.,
+            l = label,
+            m = message
+        )
+        .ok();
+        svg.push_str("</svg>\n");
+
+        svg
+    }
+}
+
+/// Approximate width of a badge section, in pixels, including padding.
+fn text_width(text: &str) -> usize {
+    text.chars().count() * 7 + 10
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Get the latest release among the given tag names, eg. `v1.2.0`. Tags that aren't
+/// versions are ignored.
+pub fn latest_release<'a>(tags: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    tags.into_iter()
+        .filter_map(|tag| version(tag).map(|v| (v, tag)))
+        .max()
+        .map(|(_, tag)| tag)
+}
+
+/// Parse a version tag, eg. `v1.2.0` or `1.2`.
+fn version(tag: &str) -> Option<Vec<u64>> {
+    let tag = tag.strip_prefix('v').unwrap_or(tag);
+    let version = tag
+        .split('.')
+        .map(|n| n.parse().ok())
+        .collect::<Option<Vec<u64>>>()?;
+
+    if version.len() < 2 {
+        return None;
+    }
+    Some(version)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_badge() {
+        let badge = Badge::new("issues", "3 open", Color::Yellow);
+        let svg = badge.to_svg();
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(">issues</text>"));
+        assert!(svg.contains(">3 open</text>"));
+        assert!(svg.contains(Color::Yellow.hex()));
+        assert!(Badge::new("a<b", "c", Color::Blue)
+            .to_svg()
+            .contains("a&lt;b"));
+
+        let value: json::Value = json::from_str(&badge.to_json().unwrap()).unwrap();
+        assert_eq!(
+            value,
+            json::json!({
+                "schemaVersion": 1,
+                "label": "issues",
+                "message": "3 open",
+                "color": "yellow",
+            })
+        );
+
+        let schema = Badge::json_schema();
+        let mut keys = value.as_object().unwrap().keys().collect::<Vec<_>>();
+        let mut required = schema["required"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|k| k.as_str().unwrap())
+            .collect::<Vec<_>>();
+        keys.sort();
+        required.sort_unstable();
+        assert_eq!(keys, required);
+    }
+
+    #[test]
+    fn test_latest_release() {
+        assert_eq!(
+            latest_release(vec!["v0.9.0", "v0.10.0", "v0.2.1", "nightly"]),
+            Some("v0.10.0")
+        );
+        assert_eq!(latest_release(vec!["1.0", "0.9.9"]), Some("1.0"));
+        assert_eq!(latest_release(vec!["nightly", "v1", "1.x"]), None);
+        assert_eq!(latest_release(vec![]), None);
+    }
+}
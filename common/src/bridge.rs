@@ -0,0 +1,504 @@
+//! Bridges between patches and discussions hosted outside of radicle.
+//!
+//! Links between patches and their external counterparts, eg. GitHub pull requests
+//! or mailing list threads, are stored in the profile, along with which comments were
+//! already copied across, so that syncing a bridge is idempotent.
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+
+use crate::mail::{self, parse_headers};
+use crate::proxy;
+use crate::store::Store;
+
+/// File name of the bridge file, in the profile scope.
+pub const FILE_NAME: &str = "bridges.toml";
+
+/// Marker added to comments copied from GitHub, so that they aren't copied back.
+pub const GITHUB_MARKER: &str = "<!-- rad-bridge:github";
+/// Marker added to comments copied to GitHub, so that they aren't copied back.
+pub const RADICLE_MARKER: &str = "<!-- rad-bridge:radicle";
+/// Marker added to comments copied from email replies.
+pub const EMAIL_MARKER: &str = "<!-- rad-bridge:email";
+
+/// A link between a patch and a GitHub pull request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GithubLink {
+    /// Project URN.
+    pub urn: String,
+    /// Patch id.
+    pub patch: String,
+    /// GitHub repository, eg. `radicle-dev/radicle-cli`.
+    pub repo: String,
+    /// Pull request number.
+    pub pr: u64,
+    /// GitHub comment and review ids copied to the patch.
+    #[serde(default)]
+    pub imported: Vec<u64>,
+    /// Patch comments copied to the pull request, as `<revision>/<comment>`.
+    #[serde(default)]
+    pub exported: Vec<String>,
+}
+
+/// A patch revision sent to a mailing list.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmailThread {
+    /// Project URN.
+    pub urn: String,
+    /// Patch id.
+    pub patch: String,
+    /// Revision sent.
+    pub revision: usize,
+    /// Message ids of the thread, cover letter first.
+    pub messages: Vec<String>,
+}
+
+impl EmailThread {
+    /// Message id of the cover letter.
+    pub fn cover(&self) -> Option<&str> {
+        self.messages.first().map(|m| m.as_str())
+    }
+}
+
+/// Mailing list bridge.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Email {
+    /// Mailing list address patches are sent to.
+    pub list: Option<String>,
+    /// Maildir replies are read from.
+    pub maildir: Option<PathBuf>,
+    /// Message ids of replies copied to patches.
+    #[serde(default)]
+    pub ingested: Vec<String>,
+    /// Patch revisions sent to the list.
+    #[serde(default)]
+    pub threads: Vec<EmailThread>,
+}
+
+impl Email {
+    /// Thread sent for a patch revision, if any.
+    pub fn thread(&self, urn: &str, patch: &str, revision: usize) -> Option<&EmailThread> {
+        self.threads
+            .iter()
+            .find(|t| t.urn == urn && t.patch == patch && t.revision == revision)
+    }
+
+    /// Latest thread sent for a patch, if any.
+    pub fn latest(&self, urn: &str, patch: &str) -> Option<&EmailThread> {
+        self.threads
+            .iter()
+            .filter(|t| t.urn == urn && t.patch == patch)
+            .max_by_key(|t| t.revision)
+    }
+
+    /// Thread a reply belongs to, if any.
+    pub fn thread_of(&self, reply: &EmailMessage) -> Option<&EmailThread> {
+        self.threads.iter().find(|t| {
+            reply
+                .references
+                .iter()
+                .any(|r| t.messages.iter().any(|m| m == r))
+        })
+    }
+}
+
+/// Bridge links of a profile.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Bridges {
+    #[serde(default)]
+    pub github: Vec<GithubLink>,
+    #[serde(default)]
+    pub email: Email,
+}
+
+impl Store for Bridges {
+    const FILE_NAME: &'static str = FILE_NAME;
+}
+
+impl Bridges {
+    /// Link a patch to a pull request. Replaces any existing link of the patch.
+    pub fn link_github(&mut self, urn: &str, patch: &str, repo: &str, pr: u64) -> &GithubLink {
+        self.github.retain(|l| !(l.urn == urn && l.patch == patch));
+        self.github.push(GithubLink {
+            urn: urn.to_owned(),
+            patch: patch.to_owned(),
+            repo: repo.to_owned(),
+            pr,
+            imported: Vec::new(),
+            exported: Vec::new(),
+        });
+        &self.github[self.github.len() - 1]
+    }
+
+    /// Links of a project.
+    pub fn github_links(&mut self, urn: &str) -> impl Iterator<Item = &mut GithubLink> {
+        let urn = urn.to_owned();
+        self.github.iter_mut().filter(move |l| l.urn == urn)
+    }
+}
+
+/// A comment on a GitHub pull request, or a review with a body.
+#[derive(Debug, Clone)]
+pub struct GithubComment {
+    pub id: u64,
+    pub author: String,
+    pub body: String,
+    /// Review state, eg. `APPROVED`, if the comment is a review.
+    pub review: Option<String>,
+}
+
+/// Minimal GitHub REST API client.
+pub struct Github {
+    agent: ureq::Agent,
+    token: String,
+    api: String,
+}
+
+impl Github {
+    /// Default API endpoint.
+    pub const API: &'static str = "https://api.github.com";
+
+    pub fn new(token: impl Into<String>) -> Result<Self, anyhow::Error> {
+        let api = url::Url::parse(Self::API)?;
+
+        Ok(Self {
+            agent: proxy::agent(&api)?,
+            token: token.into(),
+            api: Self::API.to_owned(),
+        })
+    }
+
+    fn get(&self, path: &str) -> Result<serde_json::Value, anyhow::Error> {
+        let val = self
+            .agent
+            .get(&format!("{}{}", self.api, path))
+            .set("Authorization", &format!("Bearer {}", self.token))
+            .set("Accept", "application/vnd.github+json")
+            .call()?
+            .into_json()?;
+
+        Ok(val)
+    }
+
+    /// Get the comments and reviews of a pull request, oldest first.
+    pub fn comments(&self, repo: &str, pr: u64) -> Result<Vec<GithubComment>, anyhow::Error> {
+        let mut comments = Vec::new();
+        let issue_comments = self.get(&format!(
+            "/repos/{}/issues/{}/comments?per_page=100",
+            repo, pr
+        ))?;
+        let reviews = self.get(&format!(
+            "/repos/{}/pulls/{}/reviews?per_page=100",
+            repo, pr
+        ))?;
+
+        for (val, is_review) in [(issue_comments, false), (reviews, true)] {
+            let items = val
+                .as_array()
+                .ok_or_else(|| anyhow!("unexpected GitHub API response"))?;
+
+            for item in items {
+                let id = item["id"]
+                    .as_u64()
+                    .ok_or_else(|| anyhow!("missing 'id' in GitHub API response"))?;
+                let body = item["body"].as_str().unwrap_or_default().trim().to_owned();
+                let author = item["user"]["login"].as_str().unwrap_or("ghost").to_owned();
+                let review = if is_review {
+                    item["state"].as_str().map(ToOwned::to_owned)
+                } else {
+                    None
+                };
+                // Reviews without a body, eg. plain approvals, are still worth copying.
+                if body.is_empty() && review.is_none() {
+                    continue;
+                }
+                comments.push(GithubComment {
+                    id,
+                    author,
+                    body,
+                    review,
+                });
+            }
+        }
+        comments.sort_by_key(|c| c.id);
+
+        Ok(comments)
+    }
+
+    /// Post a comment on a pull request. Returns the comment id.
+    pub fn comment(&self, repo: &str, pr: u64, body: &str) -> Result<u64, anyhow::Error> {
+        let val: serde_json::Value = self
+            .agent
+            .post(&format!(
+                "{}/repos/{}/issues/{}/comments",
+                self.api, repo, pr
+            ))
+            .set("Authorization", &format!("Bearer {}", self.token))
+            .set("Accept", "application/vnd.github+json")
+            .send_json(ureq::json!({ "body": body }))?
+            .into_json()?;
+
+        val["id"]
+            .as_u64()
+            .ok_or_else(|| anyhow!("missing 'id' in GitHub API response"))
+    }
+}
+
+/// Format a GitHub comment as a patch comment.
+pub fn from_github(comment: &GithubComment) -> String {
+    let action = match comment.review.as_deref() {
+        Some("APPROVED") => "approved the pull request",
+        Some("CHANGES_REQUESTED") => "requested changes",
+        Some(_) => "reviewed the pull request",
+        None => "commented",
+    };
+    let mut body = format!("**@{}** {} on GitHub", comment.author, action);
+
+    if !comment.body.is_empty() {
+        body.push_str(":\n\n");
+        body.push_str(&comment.body);
+    }
+    format!("{}\n\n{}:{} -->", body, GITHUB_MARKER, comment.id)
+}
+
+/// Format a patch comment as a GitHub comment.
+pub fn to_github(author: &str, body: &str, key: &str) -> String {
+    format!(
+        "**{}** commented on Radicle:\n\n{}\n\n{}:{} -->",
+        author, body, RADICLE_MARKER, key
+    )
+}
+
+/// An email message, as much of it as is needed to bridge replies.
+#[derive(Debug, Clone, Default)]
+pub struct EmailMessage {
+    pub message_id: String,
+    pub from: String,
+    pub subject: String,
+    /// Message ids this message replies to, from `In-Reply-To` and `References`.
+    pub references: Vec<String>,
+    /// Plain text body.
+    pub body: String,
+}
+
+impl EmailMessage {
+    /// Parse a raw RFC 5322 message. Returns `None` if it has no message id.
+    pub fn parse(raw: &str) -> Option<Self> {
+        let (headers, body) = mail::split(raw);
+        let header = |name: &str| mail::header(&headers, name);
+        let message_id = header("Message-Id").and_then(|v| message_ids(v).pop())?;
+        let mut references = Vec::new();
+
+        for name in ["In-Reply-To", "References"] {
+            for id in header(name).map(message_ids).unwrap_or_default() {
+                if !references.contains(&id) {
+                    references.push(id);
+                }
+            }
+        }
+
+        Some(Self {
+            message_id,
+            from: header("From").unwrap_or_default().to_owned(),
+            subject: header("Subject").unwrap_or_default().to_owned(),
+            references,
+            body: text_body(&headers, &body).unwrap_or_default(),
+        })
+    }
+
+    /// Read a message from a file.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Option<Self>, io::Error> {
+        let bytes = fs::read(path)?;
+
+        Ok(Self::parse(&String::from_utf8_lossy(&bytes)))
+    }
+
+    /// Body without the trailing signature.
+    pub fn reply(&self) -> &str {
+        let body = match self.body.find("\n-- \n") {
+            Some(ix) => &self.body[..ix],
+            None => &self.body,
+        };
+        body.trim()
+    }
+}
+
+/// Messages of a maildir, from its `new` and `cur` folders.
+pub fn maildir<P: AsRef<Path>>(path: P) -> Result<Vec<EmailMessage>, io::Error> {
+    let mut paths = Vec::new();
+    let mut messages = Vec::new();
+
+    for folder in ["new", "cur"] {
+        let entries = match fs::read_dir(path.as_ref().join(folder)) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err),
+        };
+        for entry in entries {
+            let path = entry?.path();
+            if path.is_file() {
+                paths.push(path);
+            }
+        }
+    }
+    // Maildir file names start with the delivery time.
+    paths.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+
+    for path in paths {
+        if let Some(msg) = EmailMessage::read(&path)? {
+            messages.push(msg);
+        }
+    }
+    Ok(messages)
+}
+
+/// Message ids in a header value, without angle brackets.
+fn message_ids(value: &str) -> Vec<String> {
+    value
+        .split('<')
+        .skip(1)
+        .filter_map(|s| s.split_once('>'))
+        .map(|(id, _)| id.trim().to_owned())
+        .filter(|id| !id.is_empty())
+        .collect()
+}
+
+/// Header parameter, eg. the `boundary` of a `Content-Type`.
+fn header_param(value: &str, param: &str) -> Option<String> {
+    value.split(';').skip(1).find_map(|p| {
+        let (k, v) = p.split_once('=')?;
+        if k.trim().eq_ignore_ascii_case(param) {
+            Some(v.trim().trim_matches('"').to_owned())
+        } else {
+            None
+        }
+    })
+}
+
+/// The plain text part of a body, decoded.
+fn text_body(headers: &[(String, String)], body: &str) -> Option<String> {
+    let header = |name: &str| mail::header(headers, name);
+    let content_type = header("Content-Type").unwrap_or("text/plain");
+    let mime = content_type.split(';').next().unwrap_or_default().trim();
+
+    if mime.to_ascii_lowercase().starts_with("multipart/") {
+        let boundary = format!("--{}", header_param(content_type, "boundary")?);
+
+        return body
+            .split(boundary.as_str())
+            .skip(1)
+            .take_while(|part| !part.starts_with("--"))
+            .find_map(|part| {
+                let part = part.trim_start_matches(|c| c == '\r' || c == '\n');
+                let (head, body) = part.split_once("\n\n").unwrap_or((part, ""));
+
+                text_body(&parse_headers(head), body)
+            });
+    }
+    if !mime.eq_ignore_ascii_case("text/plain") {
+        return None;
+    }
+    let encoding = header("Content-Transfer-Encoding").unwrap_or("7bit");
+
+    if encoding.eq_ignore_ascii_case("base64") {
+        let data: String = body.split_whitespace().collect();
+        let bytes = base64::decode(data).ok()?;
+
+        Some(String::from_utf8_lossy(&bytes).into_owned())
+    } else if encoding.eq_ignore_ascii_case("quoted-printable") {
+        Some(mail::decode_quoted_printable(body))
+    } else {
+        Some(body.to_owned())
+    }
+}
+
+/// Format an email reply as a patch comment.
+pub fn from_email(reply: &EmailMessage) -> String {
+    format!(
+        "**{}** replied by email:\n\n{}\n\n{}:{} -->",
+        reply.from,
+        reply.reply(),
+        EMAIL_MARKER,
+        reply.message_id
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_link_github() {
+        let mut bridges = Bridges::default();
+
+        bridges.link_github("rad:git:a", "patch-1", "acme/tools", 7);
+        bridges.link_github("rad:git:a", "patch-1", "acme/tools", 8);
+        bridges.link_github("rad:git:b", "patch-2", "acme/other", 1);
+
+        assert_eq!(bridges.github.len(), 2);
+        assert_eq!(bridges.github_links("rad:git:a").count(), 1);
+        assert_eq!(bridges.github_links("rad:git:a").next().unwrap().pr, 8);
+
+        let bridges: Bridges = toml::from_str(&toml::to_string_pretty(&bridges).unwrap()).unwrap();
+        assert_eq!(bridges.github.len(), 2);
+    }
+
+    #[test]
+    fn test_markers() {
+        let comment = GithubComment {
+            id: 42,
+            author: "alice".to_owned(),
+            body: String::new(),
+            review: Some("APPROVED".to_owned()),
+        };
+        let body = from_github(&comment);
+
+        assert!(body.starts_with("**@alice** approved the pull request on GitHub"));
+        assert!(body.contains(GITHUB_MARKER));
+        assert!(to_github("bob", "LGTM", "0/1").contains(RADICLE_MARKER));
+    }
+
+    #[test]
+    fn test_parse_email() {
+        let raw = "From: Alice <alice@example.com>\r\n\
+                   Subject: Re: [PATCH acme 0/2] Add widgets\r\n\
+                   Message-ID: <reply-1@example.com>\r\n\
+                   In-Reply-To: <cover@radicle>\r\n\
+                   References: <cover@radicle>\r\n\
+                   \t<patch-1@radicle>\r\n\
+                   Content-Type: multipart/alternative; boundary=\"b1\"\r\n\
+                   \r\n\
+                   --b1\r\n\
+                   Content-Type: text/plain; charset=utf-8\r\n\
+                   Content-Transfer-Encoding: quoted-printable\r\n\
+                   \r\n\
+                   Looks good =E2=9C=93, one n=\r\nit.\r\n\
+                   -- \r\n\
+                   Alice\r\n\
+                   --b1\r\n\
+                   Content-Type: text/html\r\n\
+                   \r\n\
+                   <p>Looks good</p>\r\n\
+                   --b1--\r\n";
+        let msg = EmailMessage::parse(raw).unwrap();
+
+        assert_eq!(msg.message_id, "reply-1@example.com");
+        assert_eq!(msg.from, "Alice <alice@example.com>");
+        assert_eq!(msg.references, vec!["cover@radicle", "patch-1@radicle"]);
+        assert_eq!(msg.reply(), "Looks good ✓, one nit.");
+        assert!(from_email(&msg).contains(EMAIL_MARKER));
+
+        let mut email = Email::default();
+        email.threads.push(EmailThread {
+            urn: "rad:git:a".to_owned(),
+            patch: "patch-1".to_owned(),
+            revision: 0,
+            messages: vec!["cover@radicle".to_owned()],
+        });
+        assert_eq!(email.thread_of(&msg).unwrap().patch, "patch-1");
+        assert!(EmailMessage::parse("Subject: no id\n\nbody").is_none());
+    }
+}
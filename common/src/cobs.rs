@@ -1,6 +1,8 @@
+pub mod advisory;
 pub mod issue;
 pub mod label;
 pub mod patch;
+pub mod review;
 pub mod shared;
 pub mod user;
 
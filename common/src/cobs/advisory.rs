@@ -0,0 +1,374 @@
+//! Security advisories.
+//!
+//! Advisories are published by project maintainers to let consumers of a project know
+//! about vulnerabilities, which versions they affect, and which releases fix them.
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::{ControlFlow, Deref};
+use std::str::FromStr;
+
+use automerge::{Automerge, AutomergeError, ObjType, ScalarValue, Value};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+
+use librad::collaborative_objects::{
+    CollaborativeObjects, EntryContents, History, NewObjectSpec, ObjectId, TypeName,
+};
+use librad::git::identities::local::LocalIdentity;
+use librad::git::storage::ReadOnly;
+use librad::git::Urn;
+
+use crate::cobs::shared::*;
+
+lazy_static! {
+    pub static ref TYPENAME: TypeName = FromStr::from_str("xyz.radicle.advisory").unwrap();
+}
+
+/// Identifier for an advisory.
+pub type AdvisoryId = ObjectId;
+
+/// Severity of the vulnerability described by an advisory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Low => write!(f, "low"),
+            Self::Medium => write!(f, "medium"),
+            Self::High => write!(f, "high"),
+            Self::Critical => write!(f, "critical"),
+        }
+    }
+}
+
+impl FromStr for Severity {
+    type Err = ValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "low" => Ok(Self::Low),
+            "medium" => Ok(Self::Medium),
+            "high" => Ok(Self::High),
+            "critical" => Ok(Self::Critical),
+            _ => Err(ValueError::InvalidValue(s.to_owned())),
+        }
+    }
+}
+
+impl From<Severity> for ScalarValue {
+    fn from(severity: Severity) -> Self {
+        ScalarValue::from(severity.to_string())
+    }
+}
+
+impl<'a> FromValue<'a> for Severity {
+    fn from_value(value: Value<'a>) -> Result<Self, ValueError> {
+        let severity = value.to_str().ok_or(ValueError::InvalidType)?;
+
+        Severity::from_str(severity)
+    }
+}
+
+/// A security advisory.
+#[derive(Debug, Clone, Serialize)]
+pub struct Advisory {
+    pub author: Author,
+    pub title: String,
+    pub description: String,
+    pub severity: Severity,
+    /// Affected versions, eg. `< 1.2.3` or `1.0.0`.
+    pub affected: HashSet<String>,
+    /// Releases that fix the vulnerability.
+    pub patched: HashSet<String>,
+    pub timestamp: Timestamp,
+}
+
+impl Advisory {
+    /// Affected versions, sorted.
+    pub fn affected(&self) -> Vec<&str> {
+        let mut affected = self.affected.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+        affected.sort_unstable();
+        affected
+    }
+
+    /// Patched releases, sorted.
+    pub fn patched(&self) -> Vec<&str> {
+        let mut patched = self.patched.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+        patched.sort_unstable();
+        patched
+    }
+
+    pub fn resolve<S: AsRef<ReadOnly>>(&mut self, storage: &S) -> Result<(), ResolveError> {
+        self.author.resolve(storage)?;
+
+        Ok(())
+    }
+}
+
+impl Cob for Advisory {
+    fn type_name() -> &'static TypeName {
+        &TYPENAME
+    }
+
+    fn from_history(history: &History) -> Result<Self, anyhow::Error> {
+        let doc = history.traverse(Automerge::new(), |mut doc, entry| {
+            match entry.contents() {
+                EntryContents::Automerge(bytes) => {
+                    match automerge::Change::from_bytes(bytes.clone()) {
+                        Ok(change) => {
+                            doc.apply_changes([change]).ok();
+                        }
+                        Err(_err) => {
+                            // Ignore
+                        }
+                    }
+                }
+            }
+            ControlFlow::Continue(doc)
+        });
+        let advisory = Advisory::try_from(doc)?;
+
+        Ok(advisory)
+    }
+}
+
+impl TryFrom<&History> for Advisory {
+    type Error = anyhow::Error;
+
+    fn try_from(history: &History) -> Result<Self, Self::Error> {
+        Advisory::from_history(history)
+    }
+}
+
+impl TryFrom<Automerge> for Advisory {
+    type Error = DocumentError;
+
+    fn try_from(doc: Automerge) -> Result<Self, Self::Error> {
+        let doc = Document::new(&doc);
+        let (_obj, obj_id) = doc.get(automerge::ObjId::Root, "advisory")?;
+        let title = doc.val(&obj_id, "title")?;
+        let description = doc.val(&obj_id, "description")?;
+        let severity = doc.val(&obj_id, "severity")?;
+        let peer = doc.val(&obj_id, "peer")?;
+        let author = doc
+            .val(&obj_id, "author")
+            .map(|urn: Urn| Author::new(urn, peer))?;
+        let timestamp = doc.val(&obj_id, "timestamp")?;
+        let affected: HashSet<String> = doc.keys(&obj_id, "affected")?;
+        let patched: HashSet<String> = doc.keys(&obj_id, "patched")?;
+
+        Ok(Self {
+            author,
+            title,
+            description,
+            severity,
+            affected,
+            patched,
+            timestamp,
+        })
+    }
+}
+
+pub struct AdvisoryStore<'a> {
+    store: &'a Store<'a>,
+}
+
+impl<'a> Deref for AdvisoryStore<'a> {
+    type Target = Store<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        self.store
+    }
+}
+
+impl<'a> AdvisoryStore<'a> {
+    pub fn new(store: &'a Store<'a>) -> Self {
+        Self { store }
+    }
+
+    pub fn create(
+        &self,
+        project: &Urn,
+        title: &str,
+        description: &str,
+        severity: Severity,
+        affected: &[String],
+        patched: &[String],
+    ) -> Result<AdvisoryId, Error> {
+        if title.trim().is_empty() {
+            return Err(Error::EmptyTitle);
+        }
+        let author = self.author();
+        let timestamp = Timestamp::now();
+        let history = events::create(
+            &author,
+            title,
+            description,
+            severity,
+            affected,
+            patched,
+            timestamp,
+        )?;
+
+        cobs::create(history, project, &self.whoami, self.store)
+    }
+
+    pub fn get(&self, namespace: &Urn, id: &ObjectId) -> anyhow::Result<Option<Advisory>> {
+        self.store.get::<Advisory>(namespace, id)
+    }
+
+    pub fn all(&self, project: &Urn) -> Result<Vec<(AdvisoryId, Advisory)>, Error> {
+        let cobs = self.store.list(project, &TYPENAME)?;
+
+        let mut advisories = Vec::new();
+        for cob in cobs {
+            // Nb. Advisories come from any peer: one that doesn't parse mustn't prevent
+            // listing the others.
+            match Advisory::try_from(cob.history()) {
+                Ok(advisory) => advisories.push((*cob.id(), advisory)),
+                Err(err) => log::warn!("Skipping invalid advisory {}: {}", cob.id(), err),
+            }
+        }
+        advisories.sort_by_key(|(_, a)| a.timestamp);
+
+        Ok(advisories)
+    }
+
+    pub fn count(&self, project: &Urn) -> Result<usize, Error> {
+        let cobs = self.store.list(project, &TYPENAME)?;
+
+        Ok(cobs.len())
+    }
+}
+
+mod cobs {
+    use super::*;
+
+    pub(super) fn create(
+        history: EntryContents,
+        project: &Urn,
+        whoami: &LocalIdentity,
+        store: &CollaborativeObjects,
+    ) -> Result<AdvisoryId, Error> {
+        let cob = store.create(
+            whoami,
+            project,
+            NewObjectSpec {
+                typename: TYPENAME.clone(),
+                message: Some("Create advisory".to_owned()),
+                history,
+            },
+        )?;
+
+        Ok(*cob.id())
+    }
+}
+
+mod events {
+    use super::*;
+    use automerge::{
+        transaction::{CommitOptions, Transactable},
+        ObjId,
+    };
+
+    pub fn create(
+        author: &Author,
+        title: &str,
+        description: &str,
+        severity: Severity,
+        affected: &[String],
+        patched: &[String],
+        timestamp: Timestamp,
+    ) -> Result<EntryContents, AutomergeError> {
+        let title = title.trim();
+
+        let mut doc = Automerge::new();
+        let _advisory = doc
+            .transact_with::<_, _, AutomergeError, _, ()>(
+                |_| CommitOptions::default().with_message("Create advisory".to_owned()),
+                |tx| {
+                    let advisory = tx.put_object(ObjId::Root, "advisory", ObjType::Map)?;
+
+                    tx.put(&advisory, "title", title)?;
+                    tx.put(&advisory, "description", description.trim())?;
+                    tx.put(&advisory, "severity", severity)?;
+                    tx.put(&advisory, "author", author.urn().to_string())?;
+                    tx.put(&advisory, "peer", author.peer.default_encoding())?;
+                    tx.put(&advisory, "timestamp", timestamp)?;
+
+                    let affected_id = tx.put_object(&advisory, "affected", ObjType::Map)?;
+                    for version in affected {
+                        tx.put(&affected_id, version.trim(), true)?;
+                    }
+                    let patched_id = tx.put_object(&advisory, "patched", ObjType::Map)?;
+                    for version in patched {
+                        tx.put(&patched_id, version.trim(), true)?;
+                    }
+
+                    Ok(advisory)
+                },
+            )
+            .map_err(|failure| failure.error)?
+            .result;
+
+        Ok(EntryContents::Automerge(doc.save_incremental()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test;
+
+    #[test]
+    fn test_advisory_create_and_get() {
+        let (storage, profile, whoami, project) = test::setup::profile();
+        let author = whoami.urn();
+        let cobs = Store::new(whoami, profile.paths(), &storage);
+        let advisories = cobs.advisories();
+        let advisory_id = advisories
+            .create(
+                &project.urn(),
+                "Remote code execution",
+                "Crafted input leads to arbitrary code execution.",
+                Severity::Critical,
+                &["< 1.2.3".to_owned()],
+                &["1.2.3".to_owned(), "1.1.9".to_owned()],
+            )
+            .unwrap();
+
+        let advisory = advisories
+            .get(&project.urn(), &advisory_id)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(advisory.title, "Remote code execution");
+        assert_eq!(
+            advisory.description,
+            "Crafted input leads to arbitrary code execution."
+        );
+        assert_eq!(advisory.severity, Severity::Critical);
+        assert_eq!(advisory.author.urn(), &author);
+        assert_eq!(advisory.affected(), vec!["< 1.2.3"]);
+        assert_eq!(advisory.patched(), vec!["1.1.9", "1.2.3"]);
+        assert_eq!(advisories.count(&project.urn()).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_severity_from_str() {
+        assert_eq!(Severity::from_str("high").unwrap(), Severity::High);
+        assert_eq!(
+            Severity::from_str(&Severity::Low.to_string()).unwrap(),
+            Severity::Low
+        );
+        assert!(Severity::from_str("urgent").is_err());
+    }
+}
@@ -89,6 +89,8 @@ pub struct Issue {
     pub comment: Comment,
     pub discussion: Discussion,
     pub labels: HashSet<Label>,
+    /// Peers the issue is assigned to, in no particular order.
+    pub assignees: Vec<Author>,
     pub timestamp: Timestamp,
 }
 
@@ -121,6 +123,10 @@ impl Issue {
         &self.labels
     }
 
+    pub fn assignees(&self) -> &[Author] {
+        &self.assignees
+    }
+
     pub fn timestamp(&self) -> Timestamp {
         self.timestamp
     }
@@ -129,6 +135,9 @@ impl Issue {
         self.author.resolve(storage)?;
         self.comment.resolve(storage)?;
 
+        for assignee in &mut self.assignees {
+            assignee.resolve(storage)?;
+        }
         for comment in &mut self.discussion {
             comment.resolve(storage)?;
         }
@@ -190,6 +199,20 @@ impl TryFrom<Automerge> for Issue {
         let discussion: Discussion = doc.list(&obj_id, "discussion", shared::lookup::thread)?;
         let labels: HashSet<Label> = doc.keys(&obj_id, "labels")?;
 
+        // Issues created before assignees were supported don't have any.
+        let mut assignees = Vec::new();
+        if let Ok((_, assignees_id)) = doc.get(&obj_id, "assignees") {
+            for key in (*doc).keys(&assignees_id) {
+                let (_, assignee_id) = doc.get(&assignees_id, key)?;
+                let peer = doc.val(&assignee_id, "peer")?;
+                let assignee = doc
+                    .val(&assignee_id, "author")
+                    .map(|urn: Urn| Author::new(urn, peer))?;
+
+                assignees.push(assignee);
+            }
+        }
+
         Ok(Self {
             title,
             state,
@@ -197,6 +220,7 @@ impl TryFrom<Automerge> for Issue {
             comment,
             discussion,
             labels,
+            assignees,
             timestamp,
         })
     }
@@ -301,6 +325,58 @@ impl<'a> IssueStore<'a> {
         Ok(())
     }
 
+    /// Assign the issue to the given peers, in addition to the existing assignees.
+    pub fn assign(
+        &self,
+        project: &Urn,
+        issue_id: &IssueId,
+        assignees: &[Author],
+    ) -> Result<(), Error> {
+        let mut issue = self.get_raw(project, issue_id)?.unwrap();
+        let changes = events::assign(&mut issue, assignees)?;
+        let _cob = self
+            .store
+            .update(
+                &self.whoami,
+                project,
+                UpdateObjectSpec {
+                    object_id: *issue_id,
+                    typename: TYPENAME.clone(),
+                    message: Some("Assign issue".to_owned()),
+                    changes,
+                },
+            )
+            .unwrap();
+
+        Ok(())
+    }
+
+    /// Redact the body of the issue description or one of its comments.
+    pub fn redact(
+        &self,
+        project: &Urn,
+        issue_id: &IssueId,
+        comment_id: CommentId,
+    ) -> Result<(), Error> {
+        let mut issue = self.get_raw(project, issue_id)?.unwrap();
+        let changes = events::redact(&mut issue, comment_id, Timestamp::now())?;
+        let _cob = self
+            .store
+            .update(
+                &self.whoami,
+                project,
+                UpdateObjectSpec {
+                    object_id: *issue_id,
+                    typename: TYPENAME.clone(),
+                    message: Some("Redact comment".to_owned()),
+                    changes,
+                },
+            )
+            .unwrap();
+
+        Ok(())
+    }
+
     pub fn react(
         &self,
         project: &Urn,
@@ -319,7 +395,7 @@ impl<'a> IssueStore<'a> {
                 UpdateObjectSpec {
                     object_id: *issue_id,
                     typename: TYPENAME.clone(),
-                    message: Some("Add comment".to_owned()),
+                    message: Some("React".to_owned()),
                     changes,
                 },
             )
@@ -464,6 +540,7 @@ mod events {
                     for label in labels {
                         tx.put(&labels_id, label.name().trim(), true)?;
                     }
+                    tx.put_object(&issue, "assignees", ObjType::Map)?;
 
                     // Nb. The top-level comment doesn't have a `replies` field.
                     let comment_id = tx.put_object(&issue, "comment", ObjType::Map)?;
@@ -566,6 +643,36 @@ mod events {
         Ok(EntryContents::Automerge(change))
     }
 
+    pub fn assign(
+        issue: &mut Automerge,
+        assignees: &[Author],
+    ) -> Result<EntryContents, AutomergeError> {
+        issue
+            .transact_with::<_, _, AutomergeError, _, ()>(
+                |_| CommitOptions::default().with_message("Assign issue".to_owned()),
+                |tx| {
+                    let (_, obj_id) = tx.get(ObjId::Root, "issue")?.unwrap();
+                    let assignees_id = match tx.get(&obj_id, "assignees")? {
+                        Some((_, id)) => id,
+                        None => tx.put_object(&obj_id, "assignees", ObjType::Map)?,
+                    };
+
+                    for assignee in assignees {
+                        let assignee_id =
+                            tx.put_object(&assignees_id, assignee.urn().to_string(), ObjType::Map)?;
+                        tx.put(&assignee_id, "author", assignee.urn().to_string())?;
+                        tx.put(&assignee_id, "peer", assignee.peer.default_encoding())?;
+                    }
+                    Ok(())
+                },
+            )
+            .map_err(|failure| failure.error)?;
+
+        let change = issue.get_last_local_change().unwrap().raw_bytes().to_vec();
+
+        Ok(EntryContents::Automerge(change))
+    }
+
     pub fn reply(
         issue: &mut Automerge,
         comment_id: CommentId,
@@ -602,6 +709,38 @@ mod events {
         Ok(EntryContents::Automerge(change))
     }
 
+    pub fn redact(
+        issue: &mut Automerge,
+        comment_id: CommentId,
+        timestamp: Timestamp,
+    ) -> Result<EntryContents, AutomergeError> {
+        issue
+            .transact_with::<_, _, AutomergeError, _, ()>(
+                |_| CommitOptions::default().with_message("Redact comment".to_owned()),
+                |tx| {
+                    let (_, obj_id) = tx.get(ObjId::Root, "issue")?.unwrap();
+                    let (_, discussion_id) = tx.get(&obj_id, "discussion")?.unwrap();
+                    let (_, comment_id) = if comment_id == CommentId::root() {
+                        tx.get(&obj_id, "comment")?.unwrap()
+                    } else {
+                        tx.get(&discussion_id, usize::from(comment_id) - 1)?
+                            .unwrap()
+                    };
+                    // The body is replaced with a tombstone, while the history keeps
+                    // a record of the redaction.
+                    tx.put(&comment_id, "body", "")?;
+                    tx.put(&comment_id, "redacted", timestamp)?;
+
+                    Ok(())
+                },
+            )
+            .map_err(|failure| failure.error)?;
+
+        let change = issue.get_last_local_change().unwrap().raw_bytes().to_vec();
+
+        Ok(EntryContents::Automerge(change))
+    }
+
     pub fn react(
         issue: &mut Automerge,
         comment_id: CommentId,
@@ -722,9 +861,58 @@ mod test {
         let issue = issues.get(&project, &issue_id).unwrap().unwrap();
         let count = issue.reactions()[&reaction];
 
-        // TODO: Test multiple reactions from same author and different authors
-
         assert_eq!(count, 1);
+
+        // Reacting twice with the same emoji only counts once per author.
+        let other = Reaction::new('👍').unwrap();
+        issues
+            .react(&project, &issue_id, CommentId::root(), reaction)
+            .unwrap();
+        issues
+            .react(&project, &issue_id, CommentId::root(), other)
+            .unwrap();
+
+        let issue = issues.get(&project, &issue_id).unwrap().unwrap();
+        assert_eq!(issue.reactions()[&reaction], 1);
+        assert_eq!(issue.reactions()[&other], 1);
+
+        // TODO: Test reactions from different authors
+    }
+
+    #[test]
+    fn test_issue_redact() {
+        let (storage, profile, whoami, project) = test::setup::profile();
+        let cobs = Store::new(whoami, profile.paths(), &storage);
+        let issues = cobs.issues();
+        let project = project.urn();
+        let issue_id = issues
+            .create(
+                &project,
+                "My first issue",
+                "My phone number is 555-0100.",
+                &[],
+            )
+            .unwrap();
+        issues
+            .comment(&project, &issue_id, "My email is alice@example.com.")
+            .unwrap();
+
+        let issue = issues.get(&project, &issue_id).unwrap().unwrap();
+        assert!(!issue.comment.is_redacted());
+
+        issues
+            .redact(&project, &issue_id, CommentId::root())
+            .unwrap();
+        issues
+            .redact(&project, &issue_id, CommentId::from(1))
+            .unwrap();
+
+        let issue = issues.get(&project, &issue_id).unwrap().unwrap();
+        assert!(issue.comment.is_redacted());
+        assert_eq!(issue.description(), "");
+        assert!(issue.comments()[0].is_redacted());
+        assert_eq!(issue.comments()[0].body, "");
+        assert_eq!(issue.title(), "My first issue");
     }
 
     #[test]
@@ -780,6 +968,28 @@ mod test {
         assert!(labels.contains(&wontfix_label));
     }
 
+    #[test]
+    fn test_issue_assign() {
+        let (storage, profile, whoami, project) = test::setup::profile();
+        let me = Author::new(whoami.urn(), *storage.peer_id());
+        let cobs = Store::new(whoami, profile.paths(), &storage);
+        let issues = cobs.issues();
+        let project = project.urn();
+        let issue_id = issues
+            .create(&project, "My first issue", "Blah blah blah.", &[])
+            .unwrap();
+
+        let issue = issues.get(&project, &issue_id).unwrap().unwrap();
+        assert!(issue.assignees().is_empty());
+
+        issues.assign(&project, &issue_id, &[me.clone()]).unwrap();
+        // Assigning twice has no effect.
+        issues.assign(&project, &issue_id, &[me.clone()]).unwrap();
+
+        let issue = issues.get(&project, &issue_id).unwrap().unwrap();
+        assert_eq!(issue.assignees(), &[me]);
+    }
+
     #[test]
     fn test_issue_comment() {
         let (storage, profile, whoami, project) = test::setup::profile();
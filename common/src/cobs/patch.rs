@@ -1,16 +1,14 @@
 #![allow(clippy::too_many_arguments)]
 use std::collections::{HashMap, HashSet};
-use std::convert::{TryFrom, TryInto};
-use std::fmt;
-use std::ops::{ControlFlow, Deref, RangeInclusive};
+use std::convert::TryFrom;
+use std::ops::{ControlFlow, Deref};
 use std::str::FromStr;
-use std::sync::Arc;
 
 use automerge::transaction::Transactable;
 use automerge::{Automerge, AutomergeError, ObjType, ScalarValue, Value};
 use lazy_static::lazy_static;
 use nonempty::NonEmpty;
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 
 use librad::collaborative_objects::{
     CollaborativeObjects, EntryContents, History, NewObjectSpec, ObjectId, TypeName,
@@ -26,6 +24,10 @@ use radicle_git_ext as git;
 use crate::cobs::shared;
 use crate::cobs::shared::*;
 
+pub use crate::cobs::review::{
+    ChecklistItem, CodeComment, CodeLocation, Review, ReviewCount, Verdict,
+};
+
 lazy_static! {
     pub static ref TYPENAME: TypeName = FromStr::from_str("xyz.radicle.patch").unwrap();
 }
@@ -39,6 +41,28 @@ pub type RevisionId = uuid::Uuid;
 /// Index of a revision in the revisions list.
 pub type RevisionIx = usize;
 
+/// Trailer used in patch descriptions to reference issues closed by the patch,
+/// eg. `Closes: 2b8a0ec2d3f`.
+pub const CLOSES_TRAILER: &str = "Closes";
+
+/// Issues referenced by `Closes:` trailers in the given text, ie. in its last paragraph.
+/// A trailer may reference several issues, separated by commas or spaces.
+pub fn closes(text: &str) -> Vec<Identifier> {
+    let text = text.replace("\r\n", "\n");
+    // Nb. Text without a trailers paragraph doesn't close anything.
+    let trailers = git_trailers::parse(&text, ":").unwrap_or_default();
+
+    trailers
+        .iter()
+        .filter(|t| t.token.eq_ignore_ascii_case(CLOSES_TRAILER))
+        .flat_map(|t| t.values.iter())
+        .flat_map(|v| v.split(|c: char| c == ',' || c.is_whitespace()))
+        .filter(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric()))
+        // Nb. Parsing identifiers is infallible.
+        .filter_map(|id| Identifier::from_str(id).ok())
+        .collect()
+}
+
 /// Where a patch is intended to be merged.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "lowercase")]
@@ -90,6 +114,12 @@ where
     pub target: MergeTarget,
     /// Labels associated with the patch.
     pub labels: HashSet<Label>,
+    /// Co-authors who signed off on the patch, in order of signing.
+    pub coauthors: Vec<CoAuthor>,
+    /// Results of external checks, eg. CI, in order of recording.
+    pub checks: Vec<Check>,
+    /// Peers assigned to review the patch, in no particular order.
+    pub assignees: Vec<Author>,
     /// List of patch revisions. The initial changeset is part of the
     /// first revision.
     pub revisions: NonEmpty<Revision<T, P>>,
@@ -121,10 +151,36 @@ impl Patch {
         matches!(self.state, State::Archived)
     }
 
+    pub fn is_retracted(&self) -> bool {
+        matches!(self.state, State::Retracted)
+    }
+
+    /// Whether the latest revision of this patch was merged.
+    pub fn is_merged(&self) -> bool {
+        !self.revisions.last().merges.is_empty()
+    }
+
     pub fn description(&self) -> &str {
         self.latest().1.description()
     }
 
+    /// Issues this patch closes when merged, as referenced in its description.
+    pub fn closes(&self) -> Vec<Identifier> {
+        closes(self.description())
+    }
+
+    /// Latest result of each check against the latest revision.
+    pub fn checks(&self) -> Vec<&Check> {
+        let head = self.head();
+        let mut checks: Vec<&Check> = Vec::new();
+
+        for check in self.checks.iter().filter(|c| &c.oid == head) {
+            checks.retain(|c| c.name != check.name);
+            checks.push(check);
+        }
+        checks
+    }
+
     pub fn resolve<S: AsRef<ReadOnly>>(&mut self, storage: &S) -> Result<(), ResolveError> {
         self.author.resolve(storage)?;
 
@@ -144,6 +200,83 @@ impl Cob for Patch {
     fn from_history(history: &History) -> Result<Self, anyhow::Error> {
         Patch::try_from(history)
     }
+
+    fn from_history_moderated(history: &History, delegates: &[Urn]) -> Result<Self, anyhow::Error> {
+        let init = (Automerge::new(), Moderated::default());
+        let (doc, moderated) = history.traverse(init, |(mut doc, mut moderated), entry| {
+            let before = Moderated::read(&doc);
+
+            match entry.contents() {
+                EntryContents::Automerge(bytes) => {
+                    match automerge::Change::from_bytes(bytes.clone()) {
+                        Ok(change) => {
+                            doc.apply_changes([change]).ok();
+                        }
+                        Err(_err) => {
+                            // Ignore
+                        }
+                    }
+                }
+            }
+            let after = Moderated::read(&doc);
+            // The first entry creates the patch, and has no author to check against.
+            let authorized = match (&moderated.author, entry.author()) {
+                (None, _) => true,
+                (Some(author), Some(urn)) => author == urn || delegates.contains(urn),
+                (Some(_), None) => false,
+            };
+            if authorized {
+                moderated.adopt(&before, after);
+            }
+            ControlFlow::Continue((doc, moderated))
+        });
+        let mut patch = Patch::try_from(Document::new(&doc))?;
+
+        if let Some(state) = moderated.state {
+            patch.state = state;
+        }
+        if let (Some(urn), Some(peer)) = (moderated.author, moderated.peer) {
+            patch.author = Author::new(urn, peer);
+        }
+        Ok(patch)
+    }
+}
+
+/// Fields of a patch that only its author and the project delegates may change.
+/// Changes made by other peers are still part of the history, but are ignored.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Moderated {
+    state: Option<State>,
+    author: Option<Urn>,
+    peer: Option<PeerId>,
+}
+
+impl Moderated {
+    fn read(doc: &Automerge) -> Self {
+        let doc = Document::new(doc);
+        let (_, obj_id) = match doc.get(automerge::ObjId::Root, "patch") {
+            Ok(patch) => patch,
+            Err(_) => return Self::default(),
+        };
+
+        Self {
+            state: lookup::state(doc, &obj_id).ok(),
+            author: doc.val(&obj_id, "author").ok(),
+            peer: doc.val(&obj_id, "peer").ok(),
+        }
+    }
+
+    /// Adopt the fields changed by an authorized entry.
+    fn adopt(&mut self, before: &Self, after: Self) {
+        if after.state != before.state {
+            self.state = after.state;
+        }
+        // A transfer changes both the author and their peer.
+        if (&after.author, &after.peer) != (&before.author, &before.peer) {
+            self.author = after.author;
+            self.peer = after.peer;
+        }
+    }
 }
 
 impl TryFrom<Document<'_>> for Patch {
@@ -154,12 +287,45 @@ impl TryFrom<Document<'_>> for Patch {
         let title = doc.val(&obj_id, "title")?;
         let author = doc.val(&obj_id, "author")?;
         let peer = doc.val(&obj_id, "peer")?;
-        let state = doc.val(&obj_id, "state")?;
+        let state = lookup::state(doc, &obj_id)?;
         let target = doc.val(&obj_id, "target")?;
         let timestamp = doc.val(&obj_id, "timestamp")?;
 
         let revisions = doc.list(&obj_id, "revisions", lookup::revision)?;
         let labels: HashSet<Label> = doc.keys(&obj_id, "labels")?;
+        // Patches created before co-authorship was supported don't have co-authors.
+        let mut coauthors = Vec::new();
+        if let Ok((_, coauthors_id)) = doc.get(&obj_id, "coauthors") {
+            for key in (*doc).keys(&coauthors_id) {
+                let (_, coauthor_id) = doc.get(&coauthors_id, key)?;
+                coauthors.push(lookup::coauthor(doc, &coauthor_id)?);
+            }
+        }
+        coauthors.sort_by_key(|c: &CoAuthor| c.timestamp);
+
+        // Likewise for checks.
+        let mut checks = Vec::new();
+        if let Ok((_, checks_id)) = doc.get(&obj_id, "checks") {
+            for key in (*doc).keys(&checks_id) {
+                let (_, check_id) = doc.get(&checks_id, key)?;
+                checks.push(lookup::check(doc, &check_id)?);
+            }
+        }
+        checks.sort_by_key(|c: &Check| c.timestamp);
+
+        // Likewise for assignees.
+        let mut assignees = Vec::new();
+        if let Ok((_, assignees_id)) = doc.get(&obj_id, "assignees") {
+            for key in (*doc).keys(&assignees_id) {
+                let (_, assignee_id) = doc.get(&assignees_id, key)?;
+                let peer = doc.val(&assignee_id, "peer")?;
+                let assignee = doc
+                    .val(&assignee_id, "author")
+                    .map(|urn: Urn| Author::new(urn, peer))?;
+
+                assignees.push(assignee);
+            }
+        }
         let revisions = NonEmpty::from_vec(revisions).ok_or(DocumentError::EmptyList)?;
         let author: Author = Author::new(author, peer);
 
@@ -169,6 +335,9 @@ impl TryFrom<Document<'_>> for Patch {
             state,
             target,
             labels,
+            coauthors,
+            checks,
+            assignees,
             revisions,
             timestamp,
         })
@@ -179,24 +348,8 @@ impl TryFrom<&History> for Patch {
     type Error = anyhow::Error;
 
     fn try_from(history: &History) -> Result<Self, Self::Error> {
-        let doc = history.traverse(Automerge::new(), |mut doc, entry| {
-            match entry.contents() {
-                EntryContents::Automerge(bytes) => {
-                    match automerge::Change::from_bytes(bytes.clone()) {
-                        Ok(change) => {
-                            doc.apply_changes([change]).ok();
-                        }
-                        Err(_err) => {
-                            // Ignore
-                        }
-                    }
-                }
-            }
-            ControlFlow::Continue(doc)
-        });
-        let patch = Patch::try_from(Document::new(&doc))?;
-
-        Ok(patch)
+        // Without the project delegates, only the patch author may moderate it.
+        Patch::from_history_moderated(history, &[])
     }
 }
 
@@ -356,9 +509,13 @@ impl<'a> PatchStore<'a> {
         verdict: Option<Verdict>,
         comment: impl Into<String>,
         inline: Vec<CodeComment>,
+        checklist: Vec<ChecklistItem>,
+        target: Option<git::Oid>,
     ) -> Result<(), Error> {
         let timestamp = Timestamp::now();
-        let review = Review::new(self.author(), verdict, comment, inline, timestamp);
+        let review = Review::new(self.author(), verdict, comment, inline, timestamp)
+            .with_checklist(checklist)
+            .with_target(target);
 
         let mut patch = self.get_raw(project, patch_id)?.unwrap();
         let (_, changes) = events::review(&mut patch, revision_ix, review)?;
@@ -375,6 +532,141 @@ impl<'a> PatchStore<'a> {
         Ok(())
     }
 
+    /// Change the state of a patch, eg. to close or reopen it.
+    pub fn lifecycle(&self, project: &Urn, patch_id: &PatchId, state: State) -> Result<(), Error> {
+        let mut patch = self.get_raw(project, patch_id)?.unwrap();
+        let changes = events::lifecycle(&mut patch, state)?;
+
+        cobs::update(
+            *patch_id,
+            project,
+            "Change patch state",
+            changes,
+            &self.whoami,
+            self.store,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn label(&self, project: &Urn, patch_id: &PatchId, labels: &[Label]) -> Result<(), Error> {
+        let mut patch = self.get_raw(project, patch_id)?.unwrap();
+        let changes = events::label(&mut patch, labels)?;
+
+        cobs::update(
+            *patch_id,
+            project,
+            "Add label",
+            changes,
+            &self.whoami,
+            self.store,
+        )?;
+
+        Ok(())
+    }
+
+    /// Assign the patch to the given peers for review, in addition to the existing assignees.
+    pub fn assign(
+        &self,
+        project: &Urn,
+        patch_id: &PatchId,
+        assignees: &[Author],
+    ) -> Result<(), Error> {
+        let mut patch = self.get_raw(project, patch_id)?.unwrap();
+        let changes = events::assign(&mut patch, assignees)?;
+
+        cobs::update(
+            *patch_id,
+            project,
+            "Assign patch",
+            changes,
+            &self.whoami,
+            self.store,
+        )?;
+
+        Ok(())
+    }
+
+    /// Record the result of an external check, eg. CI, against a commit of a patch.
+    pub fn check(
+        &self,
+        project: &Urn,
+        patch_id: &PatchId,
+        name: &str,
+        oid: impl Into<git::Oid>,
+        state: CheckState,
+        url: Option<&str>,
+    ) -> Result<(), Error> {
+        let check = Check {
+            name: name.to_owned(),
+            oid: oid.into(),
+            state,
+            url: url.map(|u| u.to_owned()),
+            author: self.author(),
+            timestamp: Timestamp::now(),
+        };
+        let mut patch = self.get_raw(project, patch_id)?.unwrap();
+        let changes = events::check(&mut patch, &check)?;
+
+        cobs::update(
+            *patch_id,
+            project,
+            "Record check",
+            changes,
+            &self.whoami,
+            self.store,
+        )?;
+
+        Ok(())
+    }
+
+    /// Transfer the ownership of a patch to another author.
+    pub fn transfer(&self, project: &Urn, patch_id: &PatchId, owner: &Author) -> Result<(), Error> {
+        let mut patch = self.get_raw(project, patch_id)?.unwrap();
+        let changes = events::transfer(&mut patch, owner)?;
+
+        cobs::update(
+            *patch_id,
+            project,
+            "Transfer patch",
+            changes,
+            &self.whoami,
+            self.store,
+        )?;
+
+        Ok(())
+    }
+
+    /// Sign off on a patch as a co-author. The name and email are used in
+    /// `Co-authored-by` trailers.
+    pub fn cosign(
+        &self,
+        project: &Urn,
+        patch_id: &PatchId,
+        name: &str,
+        email: &str,
+    ) -> Result<(), Error> {
+        let coauthor = CoAuthor {
+            author: self.author(),
+            name: name.to_owned(),
+            email: email.to_owned(),
+            timestamp: Timestamp::now(),
+        };
+        let mut patch = self.get_raw(project, patch_id)?.unwrap();
+        let changes = events::cosign(&mut patch, &coauthor)?;
+
+        cobs::update(
+            *patch_id,
+            project,
+            "Co-sign patch",
+            changes,
+            &self.whoami,
+            self.store,
+        )?;
+
+        Ok(())
+    }
+
     pub fn get(&self, namespace: &Urn, id: &ObjectId) -> anyhow::Result<Option<Patch>> {
         self.store.get::<Patch>(namespace, id)
     }
@@ -439,9 +731,12 @@ impl<'a> PatchStore<'a> {
     pub fn all(&self, project: &Urn) -> Result<Vec<(PatchId, Patch)>, Error> {
         let mut patches = Vec::new();
         let cobs = self.store.list(project, &TYPENAME)?;
+        let delegates = self.store.delegates(project);
         for cob in cobs {
-            let patch: Result<Patch, _> = cob.history().try_into();
-            patches.push((*cob.id(), patch.unwrap()));
+            match Patch::from_history_moderated(cob.history(), &delegates) {
+                Ok(patch) => patches.push((*cob.id(), patch)),
+                Err(err) => log::warn!("Skipping invalid patch {}: {}", cob.id(), err),
+            }
         }
         patches.sort_by_key(|(_, p)| p.timestamp);
 
@@ -471,6 +766,19 @@ pub enum State {
     Draft,
     Proposed,
     Archived,
+    /// Withdrawn by its author, eg. because it was created by mistake.
+    Retracted,
+}
+
+impl State {
+    fn lifecycle_message(self) -> String {
+        match self {
+            State::Draft => "Draft patch".to_owned(),
+            State::Proposed => "Propose patch".to_owned(),
+            State::Archived => "Close patch".to_owned(),
+            State::Retracted => "Retract patch".to_owned(),
+        }
+    }
 }
 
 impl From<State> for ScalarValue {
@@ -479,6 +787,7 @@ impl From<State> for ScalarValue {
             State::Proposed => ScalarValue::from("proposed"),
             State::Draft => ScalarValue::from("draft"),
             State::Archived => ScalarValue::from("archived"),
+            State::Retracted => ScalarValue::from("retracted"),
         }
     }
 }
@@ -491,6 +800,7 @@ impl<'a> FromValue<'a> for State {
             "proposed" => Ok(Self::Proposed),
             "draft" => Ok(Self::Draft),
             "archived" => Ok(Self::Archived),
+            "retracted" => Ok(Self::Retracted),
             _ => Err(ValueError::InvalidValue(value.to_string())),
         }
     }
@@ -545,6 +855,11 @@ impl Revision {
         }
     }
 
+    /// Count the reviews of this revision, by verdict.
+    pub fn review_count(&self) -> ReviewCount {
+        ReviewCount::new(self.reviews.values())
+    }
+
     pub fn description(&self) -> &str {
         &self.comment.body
     }
@@ -607,135 +922,92 @@ pub struct Merge<P = PeerId> {
     pub timestamp: Timestamp,
 }
 
-/// A patch review verdict.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Outcome of a check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "lowercase")]
-pub enum Verdict {
-    /// Accept patch.
-    Accept,
-    /// Reject patch.
-    Reject,
+pub enum CheckState {
+    Pass,
+    Fail,
 }
 
-impl fmt::Display for Verdict {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::Accept => write!(f, "accept"),
-            Self::Reject => write!(f, "reject"),
+impl FromStr for CheckState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pass" => Ok(Self::Pass),
+            "fail" => Ok(Self::Fail),
+            _ => Err(format!("invalid check state '{}'", s)),
         }
     }
 }
 
-impl From<Verdict> for ScalarValue {
-    fn from(verdict: Verdict) -> Self {
-        let s = serde_json::to_string(&verdict).unwrap(); // Cannot fail.
-        ScalarValue::from(s)
+impl From<CheckState> for ScalarValue {
+    fn from(state: CheckState) -> Self {
+        match state {
+            CheckState::Pass => ScalarValue::from("pass"),
+            CheckState::Fail => ScalarValue::from("fail"),
+        }
     }
 }
 
-impl<'a> FromValue<'a> for Verdict {
-    fn from_value(value: Value) -> Result<Self, ValueError> {
-        let verdict = value.to_str().ok_or(ValueError::InvalidType)?;
-        serde_json::from_str(verdict).map_err(|e| ValueError::Other(Arc::new(e)))
-    }
-}
+impl<'a> FromValue<'a> for CheckState {
+    fn from_value(value: Value<'a>) -> Result<Self, ValueError> {
+        let state = value.to_str().ok_or(ValueError::InvalidType)?;
 
-/// Code location, used for attaching comments.
-#[derive(Debug, Clone, Serialize)]
-pub struct CodeLocation {
-    /// Line number commented on.
-    pub lines: RangeInclusive<usize>,
-    /// Commit commented on.
-    pub commit: git::Oid,
-    /// File being commented on.
-    pub blob: git::Oid,
+        Self::from_str(state).map_err(|_| ValueError::InvalidValue(value.to_string()))
+    }
 }
 
-/// Comment on code.
+/// Result of an external check, eg. CI, against a commit of a patch.
 #[derive(Debug, Clone, Serialize)]
-pub struct CodeComment {
-    /// Code location of the comment.
-    location: CodeLocation,
-    /// Comment.
-    comment: Comment,
+pub struct Check {
+    /// Name of the check, eg. `ci`.
+    pub name: String,
+    /// Commit that was checked.
+    pub oid: git::Oid,
+    /// Outcome of the check.
+    pub state: CheckState,
+    /// Link to the check details, eg. a CI job.
+    pub url: Option<String>,
+    /// Who recorded the check.
+    pub author: Author,
+    /// When the check was recorded.
+    pub timestamp: Timestamp,
 }
 
-/// A patch review on a revision.
+/// A co-author of a patch.
 #[derive(Debug, Clone, Serialize)]
-pub struct Review {
-    /// Review author.
+pub struct CoAuthor {
+    /// Identity of the co-author.
     pub author: Author,
-    /// Review verdict.
-    pub verdict: Option<Verdict>,
-    /// Review general comment.
-    pub comment: Comment<Replies>,
-    /// Review inline code comments.
-    pub inline: Vec<CodeComment>,
-    /// Review timestamp.
+    /// Name used in the `Co-authored-by` trailer.
+    pub name: String,
+    /// Email used in the `Co-authored-by` trailer.
+    pub email: String,
+    /// When the co-author signed off on the patch.
     pub timestamp: Timestamp,
 }
 
-impl Review {
-    pub fn new(
-        author: Author,
-        verdict: Option<Verdict>,
-        comment: impl Into<String>,
-        inline: Vec<CodeComment>,
-        timestamp: Timestamp,
-    ) -> Self {
-        let comment = Comment::new(author.clone(), comment.into(), timestamp);
-
-        Self {
-            author,
-            verdict,
-            comment,
-            inline,
-            timestamp,
-        }
-    }
-
-    /// Put this object into an automerge document.
-    fn put(
-        &self,
-        tx: &mut automerge::transaction::Transaction,
-        id: &automerge::ObjId,
-    ) -> Result<(), AutomergeError> {
-        assert!(
-            self.inline.is_empty(),
-            "Cannot put review with non-empty inline comments"
-        );
-
-        tx.put(&id, "author", self.author.urn().to_string())?;
-        tx.put(&id, "peer", self.author.peer.default_encoding())?;
-        tx.put(
-            &id,
-            "verdict",
-            if let Some(v) = self.verdict {
-                v.into()
-            } else {
-                ScalarValue::Null
-            },
-        )?;
-
-        self.comment.put(tx, id)?;
-
-        tx.put_object(&id, "inline", ObjType::List)?;
-        tx.put(&id, "timestamp", self.timestamp)?;
-
-        Ok(())
-    }
-
-    pub fn resolve<S: AsRef<ReadOnly>>(&mut self, storage: &S) -> Result<(), ResolveError> {
-        self.author.resolve(storage)?;
-        self.comment.resolve(storage)?;
-
-        Ok(())
+impl CoAuthor {
+    /// The `Co-authored-by` commit trailer for this co-author.
+    pub fn trailer(&self) -> String {
+        format!("Co-authored-by: {} <{}>", self.name, self.email)
     }
 }
 
 mod lookup {
     use super::*;
 
+    /// The state of a patch. Retracted patches are stored as archived, with a `retracted`
+    /// flag, which older clients ignore.
+    pub fn state(doc: Document, patch_id: &automerge::ObjId) -> Result<State, DocumentError> {
+        let state = doc.val(patch_id, "state")?;
+        let retracted = doc.val(patch_id, "retracted").unwrap_or(false);
+
+        Ok(if retracted { State::Retracted } else { state })
+    }
+
     pub fn revision(
         doc: Document,
         revision_id: &automerge::ObjId,
@@ -776,6 +1048,40 @@ mod lookup {
         })
     }
 
+    pub fn check(doc: Document, obj_id: &automerge::ObjId) -> Result<Check, DocumentError> {
+        let name = doc.val(&obj_id, "name")?;
+        let oid = doc.val(&obj_id, "oid")?;
+        let state = doc.val(&obj_id, "state")?;
+        let url: String = doc.val(&obj_id, "url")?;
+        let author = doc.val(&obj_id, "author")?;
+        let peer = doc.val(&obj_id, "peer")?;
+        let timestamp = doc.val(&obj_id, "timestamp")?;
+
+        Ok(Check {
+            name,
+            oid,
+            state,
+            url: Some(url).filter(|u| !u.is_empty()),
+            author: Author::new(author, peer),
+            timestamp,
+        })
+    }
+
+    pub fn coauthor(doc: Document, obj_id: &automerge::ObjId) -> Result<CoAuthor, DocumentError> {
+        let author = doc.val(&obj_id, "author")?;
+        let peer = doc.val(&obj_id, "peer")?;
+        let name = doc.val(&obj_id, "name")?;
+        let email = doc.val(&obj_id, "email")?;
+        let timestamp = doc.val(&obj_id, "timestamp")?;
+
+        Ok(CoAuthor {
+            author: Author::new(author, peer),
+            name,
+            email,
+            timestamp,
+        })
+    }
+
     pub fn merge(doc: Document, obj_id: &automerge::ObjId) -> Result<Merge, DocumentError> {
         let peer = doc.val(&obj_id, "peer")?;
         let commit = doc.val(&obj_id, "commit")?;
@@ -795,19 +1101,37 @@ mod lookup {
         let timestamp = doc.val(&obj_id, "timestamp")?;
         let comment = doc.lookup(&obj_id, "comment", shared::lookup::thread)?;
         let inline = vec![];
+        // Reviews made before checklists were supported don't have one.
+        let checklist = doc
+            .list(&obj_id, "checklist", self::checklist_item)
+            .unwrap_or_default();
+        // Likewise for the target branch head.
+        let target = doc.val(&obj_id, "target").ok();
 
         Ok(Review {
             author: Author::new(author, peer),
             comment,
             verdict,
             inline,
+            checklist,
+            target,
             timestamp,
         })
     }
-}
 
-mod cobs {
-    use super::*;
+    pub fn checklist_item(
+        doc: Document,
+        obj_id: &automerge::ObjId,
+    ) -> Result<ChecklistItem, DocumentError> {
+        let text = doc.val(&obj_id, "text")?;
+        let checked = doc.val(&obj_id, "checked")?;
+
+        Ok(ChecklistItem { text, checked })
+    }
+}
+
+mod cobs {
+    use super::*;
 
     pub(super) fn create(
         history: EntryContents,
@@ -890,6 +1214,8 @@ mod events {
                     for label in labels {
                         tx.put(&labels_id, label.name().trim(), true)?;
                     }
+                    tx.put_object(&patch_id, "coauthors", ObjType::Map)?;
+                    tx.put_object(&patch_id, "checks", ObjType::Map)?;
 
                     let revisions_id = tx.put_object(&patch_id, "revisions", ObjType::List)?;
                     let revision_id = tx.insert_object(&revisions_id, 0, ObjType::Map)?;
@@ -969,6 +1295,177 @@ mod events {
         Ok((revision_ix, EntryContents::Automerge(change)))
     }
 
+    pub fn lifecycle(patch: &mut Automerge, state: State) -> Result<EntryContents, AutomergeError> {
+        patch
+            .transact_with::<_, _, AutomergeError, _, ()>(
+                |_| CommitOptions::default().with_message(state.lifecycle_message()),
+                |tx| {
+                    let (_, obj_id) = tx.get(ObjId::Root, "patch")?.unwrap();
+
+                    if state == State::Retracted {
+                        // Clients that don't know about retraction parse the state strictly,
+                        // so a retracted patch is archived for them, see `lookup::state`.
+                        tx.put(&obj_id, "state", State::Archived)?;
+                        tx.put(&obj_id, "retracted", true)?;
+                    } else {
+                        tx.put(&obj_id, "state", state)?;
+                    }
+                    Ok(())
+                },
+            )
+            .map_err(|failure| failure.error)?;
+
+        let change = patch.get_last_local_change().unwrap().raw_bytes().to_vec();
+
+        Ok(EntryContents::Automerge(change))
+    }
+
+    pub fn check(patch: &mut Automerge, check: &Check) -> Result<EntryContents, AutomergeError> {
+        patch
+            .transact_with::<_, _, AutomergeError, _, ()>(
+                |_| CommitOptions::default().with_message("Record check".to_owned()),
+                |tx| {
+                    let (_, obj_id) = tx.get(ObjId::Root, "patch")?.unwrap();
+                    let checks_id = match tx.get(&obj_id, "checks")? {
+                        Some((_, checks_id)) => checks_id,
+                        None => tx.put_object(&obj_id, "checks", ObjType::Map)?,
+                    };
+                    // Each result is recorded under its own key, so that concurrent results
+                    // don't conflict, and earlier results are kept.
+                    let key = format!("{}/{}/{}", check.oid, check.name, check.timestamp.as_secs());
+                    let check_id = tx.put_object(&checks_id, key, ObjType::Map)?;
+
+                    tx.put(&check_id, "name", check.name.as_str())?;
+                    tx.put(&check_id, "oid", check.oid.to_string())?;
+                    tx.put(&check_id, "state", check.state)?;
+                    tx.put(&check_id, "url", check.url.as_deref().unwrap_or_default())?;
+                    tx.put(&check_id, "author", check.author.urn().to_string())?;
+                    tx.put(&check_id, "peer", check.author.peer.default_encoding())?;
+                    tx.put(&check_id, "timestamp", check.timestamp)?;
+
+                    Ok(())
+                },
+            )
+            .map_err(|failure| failure.error)?;
+
+        let change = patch.get_last_local_change().unwrap().raw_bytes().to_vec();
+
+        Ok(EntryContents::Automerge(change))
+    }
+
+    pub fn transfer(
+        patch: &mut Automerge,
+        owner: &Author,
+    ) -> Result<EntryContents, AutomergeError> {
+        patch
+            .transact_with::<_, _, AutomergeError, _, ()>(
+                |_| CommitOptions::default().with_message("Transfer patch".to_owned()),
+                |tx| {
+                    let (_, obj_id) = tx.get(ObjId::Root, "patch")?.unwrap();
+
+                    tx.put(&obj_id, "author", owner.urn().to_string())?;
+                    tx.put(&obj_id, "peer", owner.peer.default_encoding())?;
+
+                    Ok(())
+                },
+            )
+            .map_err(|failure| failure.error)?;
+
+        let change = patch.get_last_local_change().unwrap().raw_bytes().to_vec();
+
+        Ok(EntryContents::Automerge(change))
+    }
+
+    pub fn cosign(
+        patch: &mut Automerge,
+        coauthor: &CoAuthor,
+    ) -> Result<EntryContents, AutomergeError> {
+        patch
+            .transact_with::<_, _, AutomergeError, _, ()>(
+                |_| CommitOptions::default().with_message("Co-sign patch".to_owned()),
+                |tx| {
+                    let (_, obj_id) = tx.get(ObjId::Root, "patch")?.unwrap();
+                    let coauthors_id = match tx.get(&obj_id, "coauthors")? {
+                        Some((_, coauthors_id)) => coauthors_id,
+                        None => tx.put_object(&obj_id, "coauthors", ObjType::Map)?,
+                    };
+                    let coauthor_id = tx.put_object(
+                        &coauthors_id,
+                        coauthor.author.urn().to_string(),
+                        ObjType::Map,
+                    )?;
+
+                    tx.put(&coauthor_id, "author", coauthor.author.urn().to_string())?;
+                    tx.put(
+                        &coauthor_id,
+                        "peer",
+                        coauthor.author.peer.default_encoding(),
+                    )?;
+                    tx.put(&coauthor_id, "name", coauthor.name.as_str())?;
+                    tx.put(&coauthor_id, "email", coauthor.email.as_str())?;
+                    tx.put(&coauthor_id, "timestamp", coauthor.timestamp)?;
+
+                    Ok(())
+                },
+            )
+            .map_err(|failure| failure.error)?;
+
+        let change = patch.get_last_local_change().unwrap().raw_bytes().to_vec();
+
+        Ok(EntryContents::Automerge(change))
+    }
+
+    pub fn label(patch: &mut Automerge, labels: &[Label]) -> Result<EntryContents, AutomergeError> {
+        patch
+            .transact_with::<_, _, AutomergeError, _, ()>(
+                |_| CommitOptions::default().with_message("Label patch".to_owned()),
+                |tx| {
+                    let (_, obj_id) = tx.get(ObjId::Root, "patch")?.unwrap();
+                    let (_, labels_id) = tx.get(&obj_id, "labels")?.unwrap();
+
+                    for label in labels {
+                        tx.put(&labels_id, label.name().trim(), true)?;
+                    }
+                    Ok(())
+                },
+            )
+            .map_err(|failure| failure.error)?;
+
+        let change = patch.get_last_local_change().unwrap().raw_bytes().to_vec();
+
+        Ok(EntryContents::Automerge(change))
+    }
+
+    pub fn assign(
+        patch: &mut Automerge,
+        assignees: &[Author],
+    ) -> Result<EntryContents, AutomergeError> {
+        patch
+            .transact_with::<_, _, AutomergeError, _, ()>(
+                |_| CommitOptions::default().with_message("Assign patch".to_owned()),
+                |tx| {
+                    let (_, obj_id) = tx.get(ObjId::Root, "patch")?.unwrap();
+                    let assignees_id = match tx.get(&obj_id, "assignees")? {
+                        Some((_, id)) => id,
+                        None => tx.put_object(&obj_id, "assignees", ObjType::Map)?,
+                    };
+
+                    for assignee in assignees {
+                        let assignee_id =
+                            tx.put_object(&assignees_id, assignee.urn().to_string(), ObjType::Map)?;
+                        tx.put(&assignee_id, "author", assignee.urn().to_string())?;
+                        tx.put(&assignee_id, "peer", assignee.peer.default_encoding())?;
+                    }
+                    Ok(())
+                },
+            )
+            .map_err(|failure| failure.error)?;
+
+        let change = patch.get_last_local_change().unwrap().raw_bytes().to_vec();
+
+        Ok(EntryContents::Automerge(change))
+    }
+
     pub fn reply(
         patch: &mut Automerge,
         revision_ix: RevisionIx,
@@ -1074,6 +1571,18 @@ mod test {
     use super::*;
     use crate::test;
 
+    #[test]
+    fn test_closes() {
+        let ids =
+            closes("Fix the thing.\n\nCloses: 2b8a0ec, 9f1c\ncloses: ab12\nCloses:\nSee: 77ff");
+        let ids = ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(ids, vec!["2b8a0ec", "9f1c", "ab12"]);
+        assert!(closes("Closes the gap between x and y").is_empty());
+        assert!(closes("Closes: 2b8a0ec\n\nNot a trailer, so nothing is closed.").is_empty());
+        assert!(closes("Fix the thing.\n\nCloses: 2b8a0ec\nas discussed").is_empty());
+    }
+
     #[test]
     fn test_patch_create_and_get() {
         let (storage, profile, whoami, project) = test::setup::profile();
@@ -1134,8 +1643,13 @@ mod test {
             )
             .unwrap();
 
+        let patch = patches.get(&project.urn(), &patch_id).unwrap().unwrap();
+        assert!(!patch.is_merged());
+
         let _merge = patches.merge(&project.urn(), &patch_id, 0, base).unwrap();
         let patch = patches.get(&project.urn(), &patch_id).unwrap().unwrap();
+        assert!(patch.is_merged());
+
         let merges = patch.revisions.head.merges;
 
         assert_eq!(merges.len(), 1);
@@ -1143,6 +1657,244 @@ mod test {
         assert_eq!(merges[0].commit, base);
     }
 
+    #[test]
+    fn test_patch_lifecycle() {
+        let (storage, profile, whoami, project) = test::setup::profile();
+        let cobs = Store::new(whoami, profile.paths(), &storage);
+        let patches = cobs.patches();
+        let oid = git::Oid::from(git2::Oid::zero());
+        let base = git::Oid::from_str("cb18e95ada2bb38aadd8e6cef0963ce37a87add3").unwrap();
+        let patch_id = patches
+            .create(
+                &project.urn(),
+                "My first patch",
+                "Blah blah blah.",
+                MergeTarget::Upstream,
+                base,
+                oid,
+                &[],
+            )
+            .unwrap();
+
+        patches
+            .lifecycle(&project.urn(), &patch_id, State::Archived)
+            .unwrap();
+        let patch = patches.get(&project.urn(), &patch_id).unwrap().unwrap();
+        assert!(patch.is_archived());
+
+        patches
+            .lifecycle(&project.urn(), &patch_id, State::Proposed)
+            .unwrap();
+        let patch = patches.get(&project.urn(), &patch_id).unwrap().unwrap();
+        assert!(patch.is_proposed());
+
+        patches
+            .lifecycle(&project.urn(), &patch_id, State::Retracted)
+            .unwrap();
+        let patch = patches.get(&project.urn(), &patch_id).unwrap().unwrap();
+        assert!(patch.is_retracted());
+        assert!(!patch.is_proposed());
+
+        // Clients that don't know about retraction see an archived patch.
+        let raw = patches.get_raw(&project.urn(), &patch_id).unwrap().unwrap();
+        let doc = Document::new(&raw);
+        let (_, obj_id) = doc.get(automerge::ObjId::Root, "patch").unwrap();
+        let state: State = doc.val(&obj_id, "state").unwrap();
+        assert_eq!(state, State::Archived);
+    }
+
+    #[test]
+    fn test_patch_label() {
+        let (storage, profile, whoami, project) = test::setup::profile();
+        let cobs = Store::new(whoami, profile.paths(), &storage);
+        let patches = cobs.patches();
+        let oid = git::Oid::from(git2::Oid::zero());
+        let base = git::Oid::from_str("cb18e95ada2bb38aadd8e6cef0963ce37a87add3").unwrap();
+        let bugfix = Label::new("bugfix").unwrap();
+        let breaking = Label::new("breaking").unwrap();
+        let patch_id = patches
+            .create(
+                &project.urn(),
+                "My first patch",
+                "Blah blah blah.",
+                MergeTarget::Upstream,
+                base,
+                oid,
+                &[bugfix.clone()],
+            )
+            .unwrap();
+
+        patches
+            .label(&project.urn(), &patch_id, &[breaking.clone()])
+            .unwrap();
+
+        let patch = patches.get(&project.urn(), &patch_id).unwrap().unwrap();
+
+        assert!(patch.labels.contains(&bugfix));
+        assert!(patch.labels.contains(&breaking));
+    }
+
+    #[test]
+    fn test_patch_assign() {
+        let (storage, profile, whoami, project) = test::setup::profile();
+        let cobs = Store::new(whoami, profile.paths(), &storage);
+        let patches = cobs.patches();
+        let oid = git::Oid::from(git2::Oid::zero());
+        let base = git::Oid::from_str("cb18e95ada2bb38aadd8e6cef0963ce37a87add3").unwrap();
+        let patch_id = patches
+            .create(
+                &project.urn(),
+                "My first patch",
+                "Blah blah blah.",
+                MergeTarget::Upstream,
+                base,
+                oid,
+                &[],
+            )
+            .unwrap();
+
+        let patch = patches.get(&project.urn(), &patch_id).unwrap().unwrap();
+        assert!(patch.assignees.is_empty());
+
+        let bob = Author::new(
+            Urn::from_str("rad:git:hnrkmg77m8tfzj4gi4pa4mbhgysfgzwntjpao").unwrap(),
+            PeerId::from(librad::SecretKey::new()),
+        );
+        patches
+            .assign(&project.urn(), &patch_id, &[bob.clone()])
+            .unwrap();
+        patches
+            .assign(&project.urn(), &patch_id, &[bob.clone()])
+            .unwrap();
+
+        let patch = patches.get(&project.urn(), &patch_id).unwrap().unwrap();
+        assert_eq!(patch.assignees, vec![bob]);
+    }
+
+    #[test]
+    fn test_patch_check() {
+        let (storage, profile, whoami, project) = test::setup::profile();
+        let cobs = Store::new(whoami, profile.paths(), &storage);
+        let patches = cobs.patches();
+        let base = git::Oid::from_str("cb18e95ada2bb38aadd8e6cef0963ce37a87add3").unwrap();
+        let rev0 = git::Oid::from_str("518d5069f94c03427f694bb494ac1cd7d1339380").unwrap();
+        let rev1 = git::Oid::from_str("cf5f8a1e1d0e0a8e4b0e18e1a0c7c4a5c2c0e2d1").unwrap();
+        let project = &project.urn();
+        let patch_id = patches
+            .create(
+                project,
+                "My first patch",
+                "Blah blah blah.",
+                MergeTarget::Upstream,
+                base,
+                rev0,
+                &[],
+            )
+            .unwrap();
+
+        patches
+            .check(project, &patch_id, "ci", rev0, CheckState::Fail, None)
+            .unwrap();
+        let patch = patches.get(project, &patch_id).unwrap().unwrap();
+        let checks = patch.checks();
+
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].state, CheckState::Fail);
+        assert_eq!(checks[0].url, None);
+
+        // Checks are against a revision; a new revision has no checks yet.
+        patches
+            .update(project, &patch_id, "Fix CI", base, rev1)
+            .unwrap();
+        let patch = patches.get(project, &patch_id).unwrap().unwrap();
+        assert!(patch.checks().is_empty());
+
+        patches
+            .check(
+                project,
+                &patch_id,
+                "ci",
+                rev1,
+                CheckState::Pass,
+                Some("https://ci.radicle.xyz/1"),
+            )
+            .unwrap();
+        let patch = patches.get(project, &patch_id).unwrap().unwrap();
+        let checks = patch.checks();
+
+        assert_eq!(patch.checks.len(), 2);
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].state, CheckState::Pass);
+        assert_eq!(checks[0].url.as_deref(), Some("https://ci.radicle.xyz/1"));
+    }
+
+    #[test]
+    fn test_patch_transfer() {
+        let (storage, profile, whoami, project) = test::setup::profile();
+        let cobs = Store::new(whoami.clone(), profile.paths(), &storage);
+        let patches = cobs.patches();
+        let oid = git::Oid::from(git2::Oid::zero());
+        let base = git::Oid::from_str("cb18e95ada2bb38aadd8e6cef0963ce37a87add3").unwrap();
+        let patch_id = patches
+            .create(
+                &project.urn(),
+                "My first patch",
+                "Blah blah blah.",
+                MergeTarget::Upstream,
+                base,
+                oid,
+                &[],
+            )
+            .unwrap();
+
+        let bob = Author::new(
+            Urn::from_str("rad:git:hnrkmg77m8tfzj4gi4pa4mbhgysfgzwntjpao").unwrap(),
+            PeerId::from(librad::SecretKey::new()),
+        );
+        patches.transfer(&project.urn(), &patch_id, &bob).unwrap();
+
+        let patch = patches.get(&project.urn(), &patch_id).unwrap().unwrap();
+        assert_eq!(patch.author, bob);
+        assert_eq!(patch.title, "My first patch");
+        assert_eq!(patch.revisions.len(), 1);
+    }
+
+    #[test]
+    fn test_patch_cosign() {
+        let (storage, profile, whoami, project) = test::setup::profile();
+        let cobs = Store::new(whoami.clone(), profile.paths(), &storage);
+        let patches = cobs.patches();
+        let oid = git::Oid::from(git2::Oid::zero());
+        let base = git::Oid::from_str("cb18e95ada2bb38aadd8e6cef0963ce37a87add3").unwrap();
+        let patch_id = patches
+            .create(
+                &project.urn(),
+                "My first patch",
+                "Blah blah blah.",
+                MergeTarget::Upstream,
+                base,
+                oid,
+                &[],
+            )
+            .unwrap();
+
+        let patch = patches.get(&project.urn(), &patch_id).unwrap().unwrap();
+        assert!(patch.coauthors.is_empty());
+
+        patches
+            .cosign(&project.urn(), &patch_id, "Alice", "alice@radicle.xyz")
+            .unwrap();
+        let patch = patches.get(&project.urn(), &patch_id).unwrap().unwrap();
+        let coauthor = &patch.coauthors[0];
+
+        assert_eq!(patch.coauthors.len(), 1);
+        assert_eq!(coauthor.author.urn(), &whoami.urn());
+        assert_eq!(
+            coauthor.trailer(),
+            "Co-authored-by: Alice <alice@radicle.xyz>"
+        );
+    }
+
     #[test]
     fn test_patch_review() {
         let (storage, profile, whoami, project) = test::setup::profile();
@@ -1165,7 +1917,19 @@ mod test {
             .unwrap();
 
         patches
-            .review(project, &patch_id, 0, Some(Verdict::Accept), "LGTM", vec![])
+            .review(
+                project,
+                &patch_id,
+                0,
+                Some(Verdict::Accept),
+                "LGTM",
+                vec![],
+                vec![ChecklistItem {
+                    text: "Tests pass".to_owned(),
+                    checked: true,
+                }],
+                Some(base),
+            )
             .unwrap();
         let patch = patches.get(project, &patch_id).unwrap().unwrap();
         let reviews = patch.revisions.head.reviews;
@@ -1175,6 +1939,16 @@ mod test {
         assert_eq!(review.author.urn(), &whoami.urn());
         assert_eq!(review.verdict, Some(Verdict::Accept));
         assert_eq!(review.comment.body.as_str(), "LGTM");
+        assert_eq!(review.checklist_completion(), Some((1, 1)));
+        assert_eq!(review.target, Some(base));
+        assert_eq!(
+            ReviewCount::new(reviews.values()),
+            ReviewCount {
+                accepted: 1,
+                rejected: 0,
+                total: 1
+            }
+        );
     }
 
     #[test]
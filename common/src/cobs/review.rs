@@ -0,0 +1,319 @@
+//! Patch reviews.
+//!
+//! Reviews are stored as part of the patch they refer to, with one review per author
+//! and revision.
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use automerge::transaction::Transactable;
+use automerge::{AutomergeError, ObjType, ScalarValue, Value};
+use serde::{Deserialize, Serialize};
+
+use librad::git::storage::ReadOnly;
+
+use radicle_git_ext as git;
+
+use crate::cobs::shared::*;
+
+/// A patch review verdict.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    /// Accept patch.
+    Accept,
+    /// Reject patch.
+    Reject,
+    /// Keep the patch open, but ask the author for changes before it can be accepted.
+    /// Only applies to the reviewed revision, so it's cleared by a new revision.
+    #[serde(rename = "request-changes")]
+    RequestChanges,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Accept => write!(f, "accept"),
+            Self::Reject => write!(f, "reject"),
+            Self::RequestChanges => write!(f, "request changes"),
+        }
+    }
+}
+
+impl From<Verdict> for ScalarValue {
+    fn from(verdict: Verdict) -> Self {
+        let s = serde_json::to_string(&verdict).unwrap(); // Cannot fail.
+        ScalarValue::from(s)
+    }
+}
+
+impl<'a> FromValue<'a> for Verdict {
+    fn from_value(value: Value) -> Result<Self, ValueError> {
+        let verdict = value.to_str().ok_or(ValueError::InvalidType)?;
+        serde_json::from_str(verdict).map_err(|e| ValueError::Other(Arc::new(e)))
+    }
+}
+
+/// Path of a project's review checklist template, relative to the repository root.
+pub const CHECKLIST_PATH: &str = ".radicle/review-checklist.md";
+
+/// An item of a review checklist.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChecklistItem {
+    /// What the reviewer should check.
+    pub text: String,
+    /// Whether the reviewer checked it.
+    pub checked: bool,
+}
+
+impl ChecklistItem {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            checked: false,
+        }
+    }
+}
+
+/// Parse a review checklist template. Every markdown list item is a checklist item.
+/// Items written as `- [x] ...` start out checked.
+pub fn parse_checklist(template: &str) -> Vec<ChecklistItem> {
+    template
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let item = line
+                .strip_prefix("- ")
+                .or_else(|| line.strip_prefix("* "))?
+                .trim();
+            let (checked, text) = if let Some(text) = item.strip_prefix("[ ]") {
+                (false, text)
+            } else if let Some(text) = item
+                .strip_prefix("[x]")
+                .or_else(|| item.strip_prefix("[X]"))
+            {
+                (true, text)
+            } else {
+                (false, item)
+            };
+            let text = text.trim();
+
+            if text.is_empty() {
+                return None;
+            }
+            Some(ChecklistItem {
+                text: text.to_owned(),
+                checked,
+            })
+        })
+        .collect()
+}
+
+/// Code location, used for attaching comments.
+#[derive(Debug, Clone, Serialize)]
+pub struct CodeLocation {
+    /// Line number commented on.
+    pub lines: RangeInclusive<usize>,
+    /// Commit commented on.
+    pub commit: git::Oid,
+    /// File being commented on.
+    pub blob: git::Oid,
+}
+
+/// Comment on code.
+#[derive(Debug, Clone, Serialize)]
+pub struct CodeComment {
+    /// Code location of the comment.
+    location: CodeLocation,
+    /// Comment.
+    comment: Comment,
+}
+
+/// A patch review on a revision.
+#[derive(Debug, Clone, Serialize)]
+pub struct Review {
+    /// Review author.
+    pub author: Author,
+    /// Review verdict.
+    pub verdict: Option<Verdict>,
+    /// Review general comment.
+    pub comment: Comment<Replies>,
+    /// Review inline code comments.
+    pub inline: Vec<CodeComment>,
+    /// Review checklist, if the project has one.
+    pub checklist: Vec<ChecklistItem>,
+    /// Head of the target branch the review was made against, if known. Used to tell
+    /// whether the branch moved since an approval.
+    pub target: Option<git::Oid>,
+    /// Review timestamp.
+    pub timestamp: Timestamp,
+}
+
+impl Review {
+    pub fn new(
+        author: Author,
+        verdict: Option<Verdict>,
+        comment: impl Into<String>,
+        inline: Vec<CodeComment>,
+        timestamp: Timestamp,
+    ) -> Self {
+        let comment = Comment::new(author.clone(), comment.into(), timestamp);
+
+        Self {
+            author,
+            verdict,
+            comment,
+            inline,
+            checklist: Vec::new(),
+            target: None,
+            timestamp,
+        }
+    }
+
+    /// Attach a checklist to the review.
+    pub fn with_checklist(mut self, checklist: Vec<ChecklistItem>) -> Self {
+        self.checklist = checklist;
+        self
+    }
+
+    /// Record the head of the target branch the review was made against.
+    pub fn with_target(mut self, target: Option<git::Oid>) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Checklist completion, as checked and total items. Returns `None` if the review
+    /// has no checklist.
+    pub fn checklist_completion(&self) -> Option<(usize, usize)> {
+        if self.checklist.is_empty() {
+            return None;
+        }
+        let checked = self.checklist.iter().filter(|i| i.checked).count();
+
+        Some((checked, self.checklist.len()))
+    }
+
+    /// Put this object into an automerge document.
+    pub(super) fn put(
+        &self,
+        tx: &mut automerge::transaction::Transaction,
+        id: &automerge::ObjId,
+    ) -> Result<(), AutomergeError> {
+        assert!(
+            self.inline.is_empty(),
+            "Cannot put review with non-empty inline comments"
+        );
+
+        tx.put(&id, "author", self.author.urn().to_string())?;
+        tx.put(&id, "peer", self.author.peer.default_encoding())?;
+        tx.put(
+            &id,
+            "verdict",
+            if let Some(v) = self.verdict {
+                v.into()
+            } else {
+                ScalarValue::Null
+            },
+        )?;
+
+        self.comment.put(tx, id)?;
+
+        tx.put_object(&id, "inline", ObjType::List)?;
+        tx.put(&id, "timestamp", self.timestamp)?;
+
+        if let Some(target) = self.target {
+            tx.put(&id, "target", target.to_string())?;
+        }
+
+        let checklist_id = tx.put_object(&id, "checklist", ObjType::List)?;
+        for (ix, item) in self.checklist.iter().enumerate() {
+            let item_id = tx.insert_object(&checklist_id, ix, ObjType::Map)?;
+
+            tx.put(&item_id, "text", item.text.as_str())?;
+            tx.put(&item_id, "checked", item.checked)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn resolve<S: AsRef<ReadOnly>>(&mut self, storage: &S) -> Result<(), ResolveError> {
+        self.author.resolve(storage)?;
+        self.comment.resolve(storage)?;
+
+        Ok(())
+    }
+}
+
+/// Tally of the reviews on a revision.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize)]
+pub struct ReviewCount {
+    /// Reviews with an accept verdict.
+    pub accepted: usize,
+    /// Reviews with a reject verdict.
+    pub rejected: usize,
+    /// Reviews requesting changes.
+    pub changes_requested: usize,
+    /// All reviews, including the ones without a verdict.
+    pub total: usize,
+}
+
+impl ReviewCount {
+    pub fn new<'a>(reviews: impl IntoIterator<Item = &'a Review>) -> Self {
+        reviews
+            .into_iter()
+            .fold(Self::default(), |mut count, review| {
+                match review.verdict {
+                    Some(Verdict::Accept) => count.accepted += 1,
+                    Some(Verdict::Reject) => count.rejected += 1,
+                    Some(Verdict::RequestChanges) => count.changes_requested += 1,
+                    None => {}
+                }
+                count.total += 1;
+                count
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_checklist() {
+        let template = r#"
+# Review checklist
+
+- [ ] Tests cover the change
+- [x] Commits are signed
+* Documentation is updated
+- [ ]
+
+Some other text.
+"#;
+        assert_eq!(
+            parse_checklist(template),
+            vec![
+                ChecklistItem::new("Tests cover the change"),
+                ChecklistItem {
+                    text: "Commits are signed".to_owned(),
+                    checked: true,
+                },
+                ChecklistItem::new("Documentation is updated"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_verdict_value() {
+        for verdict in [Verdict::Accept, Verdict::Reject, Verdict::RequestChanges] {
+            let value = ScalarValue::from(verdict);
+            let value = Value::Scalar(std::borrow::Cow::Owned(value));
+
+            assert_eq!(Verdict::from_value(value).unwrap(), verdict);
+        }
+        assert_eq!(
+            serde_json::to_string(&Verdict::RequestChanges).unwrap(),
+            "\"request-changes\""
+        );
+    }
+}
@@ -26,7 +26,7 @@ use librad::profile::Profile;
 use librad::PeerId;
 use radicle_git_ext as git;
 
-use crate::cobs::{issue, patch, user};
+use crate::cobs::{advisory, issue, patch, user};
 use crate::{person, project};
 
 #[derive(Debug, thiserror::Error)]
@@ -39,6 +39,8 @@ pub enum Error {
     Retrieve(#[from] collaborative_objects::error::Retrieve),
     #[error(transparent)]
     Automerge(#[from] AutomergeError),
+    #[error("title cannot be empty")]
+    EmptyTitle,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -96,6 +98,14 @@ pub trait Cob: Sized {
     fn type_name() -> &'static TypeName;
     /// Create an object from a history.
     fn from_history(history: &History) -> Result<Self, anyhow::Error>;
+    /// Create an object from a history, given the personal identities of the project
+    /// delegates, who may moderate it. By default, delegates have no special rights.
+    fn from_history_moderated(
+        history: &History,
+        _delegates: &[Urn],
+    ) -> Result<Self, anyhow::Error> {
+        Self::from_history(history)
+    }
 }
 
 pub struct Store<'a> {
@@ -103,6 +113,7 @@ pub struct Store<'a> {
     pub peer_id: PeerId,
 
     store: CollaborativeObjects<'a>,
+    storage: &'a Storage,
 }
 
 impl<'a> Deref for Store<'a> {
@@ -122,6 +133,7 @@ impl<'a> Store<'a> {
             store,
             whoami,
             peer_id,
+            storage,
         }
     }
 
@@ -137,16 +149,39 @@ impl<'a> Store<'a> {
         issue::IssueStore::new(self)
     }
 
+    pub fn advisories(&self) -> advisory::AdvisoryStore<'_> {
+        advisory::AdvisoryStore::new(self)
+    }
+
     pub fn users(&self) -> user::UserStore<'_> {
         user::UserStore::new(self)
     }
 
+    /// Personal identities of the delegates of a project, who may moderate its objects.
+    /// Delegates that are only keys can't be matched with an object's authors, and aren't
+    /// included. Empty if the namespace isn't a project.
+    pub fn delegates(&self, project: &Urn) -> Vec<Urn> {
+        project::get(self.storage, project)
+            .ok()
+            .flatten()
+            .map(|p| {
+                p.delegates
+                    .into_iter()
+                    .filter_map(|d| match d {
+                        project::Delegate::Indirect { urn, .. } => Some(urn),
+                        project::Delegate::Direct { .. } => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     pub fn get<T: Cob>(&self, namespace: &Urn, id: &ObjectId) -> anyhow::Result<Option<T>> {
         let cob = self.store.retrieve(namespace, T::type_name(), id)?;
 
         if let Some(cob) = cob {
             let history = cob.history();
-            let obj = T::from_history(history)?;
+            let obj = T::from_history_moderated(history, &self.delegates(namespace))?;
 
             Ok(Some(obj))
         } else {
@@ -422,6 +457,9 @@ pub struct Comment<R = ()> {
     pub reactions: HashMap<Reaction, usize>,
     pub replies: R,
     pub timestamp: Timestamp,
+    /// When the comment body was redacted, if it was.
+    #[serde(default)]
+    pub redacted: Option<Timestamp>,
 }
 
 impl<R: Default> Comment<R> {
@@ -432,8 +470,14 @@ impl<R: Default> Comment<R> {
             reactions: HashMap::default(),
             replies: R::default(),
             timestamp,
+            redacted: None,
         }
     }
+
+    /// Whether the comment body was redacted.
+    pub fn is_redacted(&self) -> bool {
+        self.redacted.is_some()
+    }
 }
 
 impl Comment<()> {
@@ -533,7 +577,8 @@ impl fmt::Display for Timestamp {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let fmt = timeago::Formatter::new();
         let now = Timestamp::now();
-        let duration = time::Duration::from_secs(now.seconds - self.seconds);
+        // Timestamps from peers with a clock ahead of ours can be in the future.
+        let duration = time::Duration::from_secs(now.seconds.saturating_sub(self.seconds));
 
         write!(f, "{}", fmt.convert(duration))
     }
@@ -621,6 +666,17 @@ impl<'a> FromValue<'a> for git::OneLevel {
     }
 }
 
+impl<'a> FromValue<'a> for bool {
+    fn from_value(val: Value<'a>) -> Result<bool, ValueError> {
+        if let Value::Scalar(scalar) = &val {
+            if let ScalarValue::Boolean(b) = scalar.borrow() {
+                return Ok(*b);
+            }
+        }
+        Err(ValueError::InvalidType)
+    }
+}
+
 impl<'a> FromValue<'a> for String {
     fn from_value(val: Value) -> Result<String, ValueError> {
         val.into_string().map_err(|_| ValueError::InvalidType)
@@ -826,6 +882,8 @@ pub mod lookup {
         let body = doc.val(&obj_id, "body")?;
         let timestamp = doc.val(&obj_id, "timestamp")?;
         let reactions: HashMap<Reaction, usize> = doc.map(&obj_id, "reactions", |v| *v += 1)?;
+        // Only redacted comments have this property.
+        let redacted = doc.val(&obj_id, "redacted").ok();
 
         Ok(Comment {
             author,
@@ -833,6 +891,7 @@ pub mod lookup {
             reactions,
             replies: (),
             timestamp,
+            redacted,
         })
     }
 
@@ -849,6 +908,7 @@ pub mod lookup {
             reactions: comment.reactions,
             replies,
             timestamp: comment.timestamp,
+            redacted: comment.redacted,
         })
     }
 }
@@ -864,6 +924,13 @@ pub fn store<'a>(profile: &Profile, storage: &'a Storage) -> anyhow::Result<Stor
 mod test {
     use super::*;
 
+    #[test]
+    fn test_timestamp_future() {
+        let future = Timestamp::new(Timestamp::now().as_secs() + 60);
+
+        assert_eq!(future.to_string(), "now");
+    }
+
     #[test]
     fn test_color() {
         let c = Color::from_str("#ffccaa").unwrap();
@@ -10,6 +10,7 @@ use librad::PeerId;
 use serde::{Deserialize, Serialize};
 use url::{Host, Url};
 
+use crate::git;
 use crate::seed::{
     Address, Protocol, DEFAULT_SEED_API_PORT, DEFAULT_SEED_GIT_PORT, DEFAULT_SEED_P2P_PORT,
 };
@@ -45,6 +46,10 @@ pub struct SeedConfig {
     pub git: Url,
     /// HTTP API URL.
     pub api: Url,
+    /// Proxy for this seed's HTTP API and git, eg. a Tor SOCKS proxy. Onion seeds use
+    /// `network.tor-proxy` if this isn't set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
 }
 
 impl TryFrom<SeedConfig> for Seed<String> {
@@ -58,9 +63,112 @@ impl TryFrom<SeedConfig> for Seed<String> {
     }
 }
 
+/// How `rad push` treats direct pushes to the project's default branch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Protection {
+    /// Allow the push.
+    Off,
+    /// Allow the push, but suggest opening a patch instead.
+    Warn,
+    /// Refuse the push.
+    Block,
+}
+
+impl Default for Protection {
+    fn default() -> Self {
+        Self::Off
+    }
+}
+
+/// What to do when a seed's peer id doesn't match the one pinned on first contact.
+/// See `rad seed fingerprint`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Pinning {
+    /// Don't pin seeds.
+    Off,
+    /// Sync with the seed, but warn about the change.
+    Warn,
+    /// Skip the seed.
+    Block,
+}
+
+impl Default for Pinning {
+    fn default() -> Self {
+        Self::Warn
+    }
+}
+
+/// Project policies enforced locally through git hooks. See `rad setup-hooks`.
+/// Default branch protection is enforced by `rad push`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Policy {
+    /// Require a `Signed-off-by` trailer in commit messages.
+    #[serde(default)]
+    pub signoff: bool,
+    /// Maximum length of the commit message subject line.
+    #[serde(default)]
+    pub max_subject_length: Option<usize>,
+    /// File patterns that may not be pushed, eg. `*.pem`.
+    #[serde(default)]
+    pub forbidden_files: Vec<String>,
+    /// Whether to warn about, or block direct pushes to the default branch.
+    #[serde(default)]
+    pub protect_default_branch: Protection,
+    /// Allow merging a patch that wasn't approved against the current default branch,
+    /// eg. because it moved since, or the patch wasn't approved. Enforced by `rad merge`.
+    #[serde(default)]
+    pub allow_stale_merge: bool,
+    /// Whether to warn about, or skip seeds whose peer id changed since they were
+    /// first synced with. Enforced when syncing.
+    #[serde(default)]
+    pub seed_pinning: Pinning,
+}
+
+impl Policy {
+    /// Whether the policy has no rules.
+    pub fn is_empty(&self) -> bool {
+        !self.signoff && self.max_subject_length.is_none() && self.forbidden_files.is_empty()
+    }
+}
+
+/// Network settings. Only read from the profile config.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Network {
+    /// Proxy for network operations, eg. `socks5h://127.0.0.1:9050` for Tor, or
+    /// `http://proxy.acme.com:3128`. Takes precedence over the `*_PROXY` environment
+    /// variables.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// Connect directly for network operations that can't go through the proxy, ie.
+    /// peer-to-peer sync, instead of refusing them. This reveals our address to seeds.
+    #[serde(default)]
+    pub allow_direct: bool,
+    /// SOCKS proxy of the Tor daemon, used for `.onion` seeds. Defaults to
+    /// `socks5h://127.0.0.1:9050`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tor_proxy: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
+    #[serde(default)]
     pub seed: Vec<SeedConfig>,
+    #[serde(default)]
+    pub policy: Policy,
+    /// Whether the profile passphrase is stored in the platform keychain. See
+    /// `rad auth --keychain`.
+    #[serde(default)]
+    pub keychain: bool,
+    #[serde(default)]
+    pub network: Network,
+    /// Location the profile's storage was moved to, see `rad profile storage`. Only
+    /// read from the profile's config.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage: Option<PathBuf>,
 }
 
 impl Default for Config {
@@ -90,27 +198,64 @@ impl Default for Config {
                         p2p,
                         git,
                         api,
+                        proxy: None,
                     }
                 })
                 .collect(),
+            policy: Policy::default(),
+            keychain: false,
+            network: Network::default(),
+            storage: None,
         }
     }
 }
 
 impl Config {
     pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, io::Error> {
+        let path = path.as_ref();
         let content = fs::read_to_string(path)?;
-        let config = toml::from_str(&content)?;
+        let config = toml::from_str(&content).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid config {}: {}", path.display(), err),
+            )
+        })?;
 
         Ok(config)
     }
 
+    /// Load the config of the working copy the current directory is in, or if there is
+    /// none, the profile's.
     pub fn load(profile: &Profile) -> Result<Self, io::Error> {
-        Self::local().or_else(|_| Self::profile(profile))
+        if let Ok(repo) = git::repository() {
+            if let Some(config) = Self::local(&repo)? {
+                return Ok(config);
+            }
+        }
+        Self::profile(profile)
+    }
+
+    /// Load the config of a working copy, if it has one.
+    pub fn local(repo: &git::Repository) -> Result<Option<Self>, io::Error> {
+        let workdir = match repo.workdir() {
+            Some(workdir) => workdir,
+            None => return Ok(None),
+        };
+        match Self::read(workdir.join(FILE_NAME_LOCAL)) {
+            Ok(config) => Ok(Some(config)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
     }
 
-    pub fn local() -> Result<Self, io::Error> {
-        Self::read(Path::new(FILE_NAME_LOCAL))
+    /// Get the policy that applies to the current working copy, see [`Config::load`].
+    /// Without a config, the default policy applies.
+    pub fn policy(profile: &Profile) -> Result<Policy, io::Error> {
+        match Self::load(profile) {
+            Ok(config) => Ok(config.policy),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Policy::default()),
+            Err(err) => Err(err),
+        }
     }
 
     pub fn profile(profile: &Profile) -> Result<Self, io::Error> {
@@ -146,3 +291,17 @@ impl Config {
         self.seed.iter()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_policy_only() {
+        let config: Config = toml::from_str("[policy]\nsignoff = true\n").unwrap();
+
+        assert!(config.seed.is_empty());
+        assert!(config.policy.signoff);
+        assert_eq!(config.policy.seed_pinning, Pinning::Warn);
+    }
+}
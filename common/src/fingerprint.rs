@@ -0,0 +1,136 @@
+//! Trust-on-first-use pinning of seed identities, like SSH's `known_hosts`.
+//!
+//! The first time we sync with a seed host, the peer id it is configured with is
+//! recorded. If the host later shows up with a different peer id, eg. because the seed
+//! configuration was tampered with or the seed's API was impersonated, the change is
+//! flagged, and depending on the `seed-pinning` policy, the seed is skipped.
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use librad::PeerId;
+use serde::{Deserialize, Serialize};
+
+use crate::cobs::Timestamp;
+use crate::store::Store;
+
+/// File name of the known seeds file, in the profile scope.
+pub const FILE_NAME: &str = "known-seeds.toml";
+
+/// A pinned seed identity.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KnownSeed {
+    /// Encoded peer id of the seed.
+    pub peer: String,
+    /// When the seed was first seen, or pinned.
+    pub added: Timestamp,
+}
+
+impl KnownSeed {
+    pub fn peer(&self) -> Option<PeerId> {
+        PeerId::from_str(&self.peer).ok()
+    }
+}
+
+/// Result of checking a seed against the known seeds.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Check {
+    /// The host wasn't known; it is now pinned to the given peer.
+    New,
+    /// The host is pinned to the given peer.
+    Known,
+    /// The host is pinned to another peer.
+    Changed { pinned: PeerId },
+}
+
+/// Known seeds, by host.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct KnownSeeds {
+    #[serde(default)]
+    pub seeds: BTreeMap<String, KnownSeed>,
+}
+
+impl Store for KnownSeeds {
+    const FILE_NAME: &'static str = FILE_NAME;
+}
+
+impl KnownSeeds {
+    pub fn get(&self, host: &str) -> Option<&KnownSeed> {
+        self.seeds.get(host)
+    }
+
+    /// Check a seed host's peer id, pinning it if the host is new.
+    pub fn check(&mut self, host: &str, peer: &PeerId) -> Check {
+        match self.seeds.get(host).and_then(|s| s.peer()) {
+            Some(pinned) if pinned == *peer => Check::Known,
+            Some(pinned) => Check::Changed { pinned },
+            None => {
+                self.pin(host, peer);
+                Check::New
+            }
+        }
+    }
+
+    /// Pin a host to a peer id, replacing any existing pin.
+    pub fn pin(&mut self, host: &str, peer: &PeerId) {
+        self.seeds.insert(
+            host.to_owned(),
+            KnownSeed {
+                peer: peer.default_encoding(),
+                added: Timestamp::now(),
+            },
+        );
+    }
+
+    /// Forget a host. Returns `false` if it wasn't known.
+    pub fn forget(&mut self, host: &str) -> bool {
+        self.seeds.remove(host).is_some()
+    }
+}
+
+/// Get the host of a seed address, eg. `seed.acme.com` for `seed.acme.com:8776`.
+pub fn host(addr: &str) -> &str {
+    match addr.rsplit_once(':') {
+        // Nb. Don't split bare IPv6 addresses.
+        Some((host, port)) if port.parse::<u16>().is_ok() && !host.ends_with(':') => host,
+        _ => addr,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let (a, b) = (
+            PeerId::from(librad::SecretKey::new()),
+            PeerId::from(librad::SecretKey::new()),
+        );
+        let mut known = KnownSeeds::default();
+
+        assert_eq!(known.check("seed.acme.com", &a), Check::New);
+        assert_eq!(known.check("seed.acme.com", &a), Check::Known);
+        assert_eq!(
+            known.check("seed.acme.com", &b),
+            Check::Changed { pinned: a }
+        );
+
+        // Pinning accepts the new peer id.
+        known.pin("seed.acme.com", &b);
+        assert_eq!(known.check("seed.acme.com", &b), Check::Known);
+
+        let mut known: KnownSeeds =
+            toml::from_str(&toml::to_string_pretty(&known).unwrap()).unwrap();
+        assert_eq!(known.get("seed.acme.com").unwrap().peer(), Some(b));
+        assert!(known.forget("seed.acme.com"));
+        assert!(!known.forget("seed.acme.com"));
+    }
+
+    #[test]
+    fn test_host() {
+        assert_eq!(host("seed.acme.com:8776"), "seed.acme.com");
+        assert_eq!(host("seed.acme.com"), "seed.acme.com");
+        assert_eq!(host("[::1]:8776"), "[::1]");
+        assert_eq!(host("10.0.0.1:8776"), "10.0.0.1");
+    }
+}
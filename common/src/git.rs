@@ -1,5 +1,6 @@
 //! Git-related functions and types.
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::env;
 use std::fs::{File, OpenOptions};
 use std::io;
 use std::io::Write;
@@ -16,8 +17,8 @@ use librad::reflike;
 use librad::{crypto::BoxedSigner, PeerId};
 
 pub use git2::{
-    build::CheckoutBuilder, AnnotatedCommit, Commit, Direction, ErrorCode, MergeAnalysis,
-    MergeOptions, Oid, Reference, Repository, Signature,
+    build::CheckoutBuilder, AnnotatedCommit, BranchType, Commit, DiffFormat, Direction, ErrorCode,
+    MergeAnalysis, MergeOptions, Oid, Reference, Repository, Signature,
 };
 pub use librad::git::local::transport;
 pub use librad::git::types::remote::LocalFetchspec;
@@ -105,20 +106,70 @@ pub fn version() -> Result<Version, anyhow::Error> {
     Err(anyhow!("failed to run `git version`"))
 }
 
-/// Get the git repository in the current directory.
+/// Get the git repository of the current directory. Like `git`, this searches the parent
+/// directories for a repository, and respects `GIT_DIR`, so that it works from within
+/// subdirectories of a working copy, and from bare repositories.
 pub fn repository() -> Result<Repository, anyhow::Error> {
-    match Repository::open(".") {
+    match Repository::open_from_env() {
         Ok(repo) => Ok(repo),
         Err(err) => Err(err).context("the current working directory is not a git repository"),
     }
 }
 
+/// Environment variable set when git tracing is enabled.
+pub const TRACE_ENV: &str = "GIT_TRACE";
+
+/// Enable tracing of git operations, for spawned `git` processes, including the ones
+/// spawned by git itself, eg. `git-remote-rad`, as well as for libgit2.
+pub fn enable_trace() {
+    // Nb. A value of `2` means the trace is written to standard error.
+    env::set_var(TRACE_ENV, "2");
+    env::set_var("GIT_TRACE_PACKET", "2");
+    env::set_var("GIT_TRACE_SETUP", "2");
+
+    git2::trace_set(git2::TraceLevel::Trace, |level, msg| {
+        eprintln!("libgit2 {:?}: {}", level, msg);
+    });
+}
+
+/// Whether git tracing is enabled.
+pub fn is_trace_enabled() -> bool {
+    env::var_os(TRACE_ENV).map_or(false, |v| !v.is_empty() && v != "0")
+}
+
+/// Forward trace lines in a git process's standard error to our own, and return
+/// the remaining output. Trace lines are of the form `HH:MM:SS.uuuuuu <file>:<line> ...`.
+fn untrace(stderr: &[u8]) -> Vec<u8> {
+    let mut rest = Vec::new();
+
+    for line in String::from_utf8_lossy(stderr).lines() {
+        let bytes = line.as_bytes();
+        let is_trace = bytes.len() > 16
+            && bytes[2] == b':'
+            && bytes[5] == b':'
+            && bytes[8] == b'.'
+            && bytes[..2].iter().all(u8::is_ascii_digit);
+
+        if is_trace {
+            eprintln!("{}", line);
+        } else {
+            rest.extend_from_slice(bytes);
+            rest.push(b'\n');
+        }
+    }
+    rest
+}
+
 /// Execute a git command by spawning a child process.
 pub fn git<S: AsRef<std::ffi::OsStr>>(
     repo: &std::path::Path,
     args: impl IntoIterator<Item = S>,
 ) -> Result<String, anyhow::Error> {
-    let output = Command::new("git").current_dir(repo).args(args).output()?;
+    let mut output = Command::new("git").current_dir(repo).args(args).output()?;
+
+    if is_trace_enabled() {
+        output.stderr = untrace(&output.stderr);
+    }
 
     if output.status.success() {
         let out = if output.stdout.is_empty() {
@@ -294,6 +345,50 @@ pub fn branch_remote(repo: &Repository, branch: &str) -> anyhow::Result<String>
     Ok(remote)
 }
 
+/// Set the upstream of a local branch to the same branch on the given remote, eg.
+/// `master` -> `rad/master`, unless the branch already has an upstream.
+///
+/// Returns `true` if the upstream was set.
+pub fn set_upstream_if_unset(
+    repo: &Repository,
+    remote: &str,
+    branch: &str,
+) -> anyhow::Result<bool> {
+    if branch_remote(repo, branch).is_ok() {
+        return Ok(false);
+    }
+    let mut cfg = repo.config()?;
+
+    // Nb. The remote-tracking branch may not exist yet, so the config is written directly.
+    cfg.set_str(&format!("branch.{}.remote", branch), remote)?;
+    cfg.set_str(
+        &format!("branch.{}.merge", branch),
+        &format!("refs/heads/{}", branch),
+    )?;
+
+    Ok(true)
+}
+
+/// Get the number of commits a local branch is ahead and behind its upstream,
+/// or `None` if it has no upstream, or the upstream was not fetched.
+pub fn upstream_ahead_behind(
+    repo: &Repository,
+    branch: &str,
+) -> anyhow::Result<Option<(usize, usize)>> {
+    let local = repo.find_branch(branch, BranchType::Local)?;
+    let upstream = match local.upstream() {
+        Ok(upstream) => upstream,
+        Err(err) if err.code() == ErrorCode::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    let (local, upstream) = match (local.get().target(), upstream.get().target()) {
+        (Some(local), Some(upstream)) => (local, upstream),
+        _ => return Ok(None),
+    };
+
+    Ok(Some(repo.graph_ahead_behind(local, upstream)?))
+}
+
 /// Call `git pull`, optionally with `--force`.
 pub fn pull(repo: &Path, force: bool) -> anyhow::Result<String> {
     let mut args = vec!["-c", "color.diff=always", "pull", "-v"];
@@ -369,22 +464,6 @@ pub fn view_diff(
     Ok(())
 }
 
-pub fn add_tag(
-    repo: &git2::Repository,
-    message: &str,
-    patch_tag_name: &str,
-) -> anyhow::Result<git2::Oid> {
-    let head = repo.head()?;
-    let commit = head.peel(git2::ObjectType::Commit).unwrap();
-    let oid = repo.tag(patch_tag_name, &commit, &repo.signature()?, message, false)?;
-
-    Ok(oid)
-}
-
-pub fn push_tag(tag_name: &str) -> anyhow::Result<String> {
-    git(Path::new("."), vec!["push", "rad", "tag", tag_name])
-}
-
 pub fn push_branch(name: &str) -> anyhow::Result<String> {
     git(Path::new("."), vec!["push", "rad", name])
 }
@@ -425,11 +504,142 @@ pub fn commit_ssh_fingerprint(path: &Path, sha1: &str) -> Result<Option<String>,
     Ok(None)
 }
 
+/// Signature status of a commit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureStatus {
+    /// Good signature, by a trusted key, eg. one in the allowed signers file.
+    Good,
+    /// Good signature, by a key that isn't trusted, or that expired.
+    Untrusted,
+    /// Bad signature, or signature by a revoked key.
+    Bad,
+    /// The signature couldn't be checked, eg. because signing isn't configured.
+    Unverified,
+    /// No signature.
+    Unsigned,
+}
+
+impl SignatureStatus {
+    /// Parse a signature status, as output by git's `%G?` format placeholder.
+    pub fn from_placeholder(s: &str) -> Self {
+        match s.trim() {
+            "G" => Self::Good,
+            "U" | "X" | "Y" => Self::Untrusted,
+            "B" | "R" => Self::Bad,
+            "N" => Self::Unsigned,
+            _ => Self::Unverified,
+        }
+    }
+}
+
+/// Get the signature status of the given commits, with a single git invocation.
+pub fn signature_status(
+    repo: &git2::Repository,
+    commits: &[git2::Oid],
+) -> anyhow::Result<HashMap<git2::Oid, SignatureStatus>> {
+    if commits.is_empty() {
+        return Ok(HashMap::new());
+    }
+    // Run from the working copy if there is one, so that a relative allowed signers
+    // file, eg. `.gitsigners`, is found.
+    let dir = repo.workdir().unwrap_or_else(|| repo.path());
+    let output = git(
+        dir,
+        ["show", "--no-patch", "--format=%H %G?"]
+            .iter()
+            .map(|a| a.to_string())
+            .chain(commits.iter().map(|oid| oid.to_string())),
+    )?;
+    let mut statuses = HashMap::new();
+
+    for line in output.lines() {
+        if let Some((oid, status)) = line.split_once(' ') {
+            if let Ok(oid) = git2::Oid::from_str(oid) {
+                statuses.insert(oid, SignatureStatus::from_placeholder(status));
+            }
+        }
+    }
+    Ok(statuses)
+}
+
+/// Create a linear history of `n` empty commits, and return their ids, from oldest to
+/// newest. Commit `i` is made at `(i + 1) * 1000` seconds since the epoch.
+#[cfg(test)]
+pub(crate) fn linear_history(repo: &Repository, n: usize) -> Vec<Oid> {
+    let tree = repo
+        .find_tree(repo.treebuilder(None).unwrap().write().unwrap())
+        .unwrap();
+    let mut oids: Vec<Oid> = Vec::new();
+
+    for i in 0..n {
+        let time = git2::Time::new((i as i64 + 1) * 1000, 0);
+        let sig = Signature::new("anonymous", "anonymous@radicle.xyz", &time).unwrap();
+        let parents = oids
+            .last()
+            .map(|oid| repo.find_commit(*oid).unwrap())
+            .into_iter()
+            .collect::<Vec<_>>();
+        let parents = parents.iter().collect::<Vec<_>>();
+        let oid = repo
+            .commit(None, &sig, &sig, &format!("Commit {}", i), &tree, &parents)
+            .unwrap();
+
+        oids.push(oid);
+    }
+    oids
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use std::str::FromStr;
 
+    #[test]
+    fn test_signature_status() {
+        assert_eq!(
+            SignatureStatus::from_placeholder("G"),
+            SignatureStatus::Good
+        );
+        assert_eq!(
+            SignatureStatus::from_placeholder("U\n"),
+            SignatureStatus::Untrusted
+        );
+        assert_eq!(SignatureStatus::from_placeholder("B"), SignatureStatus::Bad);
+        assert_eq!(
+            SignatureStatus::from_placeholder("N"),
+            SignatureStatus::Unsigned
+        );
+        assert_eq!(
+            SignatureStatus::from_placeholder("E"),
+            SignatureStatus::Unverified
+        );
+    }
+
+    #[test]
+    fn test_upstream() {
+        let path = std::env::temp_dir().join("rad").join("test-upstream");
+        std::fs::remove_dir_all(&path).ok();
+
+        let repo = Repository::init(&path).unwrap();
+        let oids = linear_history(&repo, 2);
+        let (base, head) = (oids[0], oids[1]);
+
+        repo.reference("refs/heads/feature", head, true, "test")
+            .unwrap();
+        assert_eq!(upstream_ahead_behind(&repo, "feature").unwrap(), None);
+
+        assert!(set_upstream_if_unset(&repo, "rad", "feature").unwrap());
+        assert!(!set_upstream_if_unset(&repo, "origin", "feature").unwrap());
+        assert_eq!(branch_remote(&repo, "feature").unwrap(), "rad");
+
+        repo.reference("refs/remotes/rad/feature", base, true, "test")
+            .unwrap();
+        assert_eq!(
+            upstream_ahead_behind(&repo, "feature").unwrap(),
+            Some((1, 0))
+        );
+    }
+
     #[test]
     fn test_version_ord() {
         assert!(
@@ -1,14 +1,26 @@
+use std::collections::BTreeSet;
 use std::convert::TryFrom;
 use std::str::FromStr;
 
 use anyhow::anyhow;
+use serde_json as json;
 use url::Url;
 
+use librad::git::identities::project;
+use librad::git::storage::Storage;
 use librad::git::Urn;
+use librad::identities::payload::ProjectPayload;
 use librad::PeerId;
 
 use crate::project::URL_SCHEME;
-use crate::{seed, sync};
+use crate::{git, seed, sync};
+
+/// Commit trailer holding an identity document signature.
+pub const SIGNATURE_TRAILER: &str = "X-Rad-Signature";
+/// Payload extension holding the core project fields, eg. `name`.
+pub const PROJECT_PAYLOAD: &str = "https://radicle.xyz/link/identities/project/v1";
+/// Project payload fields that can be edited, see [`set_field`].
+pub const PROJECT_FIELDS: &[&str] = &["name", "description", "default_branch"];
 
 /// Identity origin.
 ///
@@ -80,11 +92,281 @@ impl TryFrom<Url> for Origin {
     }
 }
 
+/// An identity document, as stored in a revision of an identity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document(pub json::Value);
+
+impl Document {
+    /// Get a payload field, eg. `name`, from any of the payload extensions.
+    pub fn field(&self, key: &str) -> Option<&str> {
+        self.0
+            .get("payload")?
+            .as_object()?
+            .values()
+            .find_map(|ext| ext.get(key))
+            .and_then(|v| v.as_str())
+    }
+
+    /// Identity name.
+    pub fn name(&self) -> Option<&str> {
+        self.field("name")
+    }
+
+    /// Identity description.
+    pub fn description(&self) -> Option<&str> {
+        self.field("description")
+    }
+
+    /// Project default branch.
+    pub fn default_branch(&self) -> Option<&str> {
+        self.field("default_branch")
+    }
+
+    /// Identity delegations, as keys or URNs.
+    pub fn delegations(&self) -> BTreeSet<String> {
+        match self.0.get("delegations") {
+            Some(json::Value::Array(ds)) => ds
+                .iter()
+                .filter_map(|d| d.as_str().map(ToOwned::to_owned))
+                .collect(),
+            Some(json::Value::Object(ds)) => ds.keys().cloned().collect(),
+            _ => BTreeSet::new(),
+        }
+    }
+
+    /// Changes from a previous version of this document.
+    pub fn changes(&self, previous: &Document) -> Vec<Change> {
+        let mut changes = Vec::new();
+
+        for (field, old, new) in [
+            ("name", previous.name(), self.name()),
+            ("description", previous.description(), self.description()),
+            (
+                "default_branch",
+                previous.default_branch(),
+                self.default_branch(),
+            ),
+        ] {
+            if old != new {
+                changes.push(Change::Field {
+                    field,
+                    old: old.map(ToOwned::to_owned),
+                    new: new.map(ToOwned::to_owned),
+                });
+            }
+        }
+        let (old, new) = (previous.delegations(), self.delegations());
+        for delegate in new.difference(&old) {
+            changes.push(Change::DelegateAdded(delegate.clone()));
+        }
+        for delegate in old.difference(&new) {
+            changes.push(Change::DelegateRemoved(delegate.clone()));
+        }
+        changes
+    }
+}
+
+/// A change between two revisions of an identity document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// A payload field changed, eg. `name`.
+    Field {
+        field: &'static str,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    /// A delegate was added.
+    DelegateAdded(String),
+    /// A delegate was removed.
+    DelegateRemoved(String),
+}
+
+/// A revision of an identity document.
+#[derive(Debug, Clone)]
+pub struct Revision {
+    /// Commit of the revision.
+    pub commit: git::Oid,
+    /// Commit time, in seconds since epoch.
+    pub timestamp: i64,
+    /// Keys that signed the revision.
+    pub signers: Vec<String>,
+    /// The document at this revision.
+    pub doc: Document,
+}
+
+/// Get all revisions of an identity, oldest first. If a peer is given, the revisions are
+/// read from that peer's view of the identity.
+pub fn history(
+    repo: &git::Repository,
+    urn: &Urn,
+    peer: Option<&PeerId>,
+) -> anyhow::Result<Vec<Revision>> {
+    let name = match peer {
+        Some(peer) => format!(
+            "refs/namespaces/{}/refs/remotes/{}/rad/id",
+            urn.encode_id(),
+            peer
+        ),
+        None => format!("refs/namespaces/{}/refs/rad/id", urn.encode_id()),
+    };
+    let reference = repo
+        .find_reference(&name)
+        .map_err(|_| anyhow!("identity {} was not found in storage", urn))?;
+    let mut tip = Some(reference.peel_to_commit()?);
+    let mut revisions = Vec::new();
+
+    while let Some(commit) = tip {
+        let tree = commit.tree()?;
+        let entry = tree
+            .get(0)
+            .ok_or_else(|| anyhow!("identity revision {} has an empty tree", commit.id()))?;
+        let blob = repo.find_blob(entry.id())?;
+        let doc = Document(json::from_slice(blob.content())?);
+
+        revisions.push(Revision {
+            commit: commit.id(),
+            timestamp: commit.time().seconds(),
+            signers: signers(commit.message().unwrap_or_default()),
+            doc,
+        });
+        tip = commit.parent(0).ok();
+    }
+    revisions.reverse();
+
+    Ok(revisions)
+}
+
+/// Get the latest revisions of an identity proposed by tracked peers, ie. the revisions
+/// that aren't part of our view of the identity yet.
+pub fn proposals(repo: &git::Repository, urn: &Urn) -> anyhow::Result<Vec<(PeerId, Revision)>> {
+    let namespace = format!("refs/namespaces/{}/refs", urn.encode_id());
+    let ours = repo.refname_to_id(&format!("{}/rad/id", namespace))?;
+    let mut proposals = Vec::new();
+
+    for reference in repo.references_glob(&format!("{}/remotes/*/rad/id", namespace))? {
+        let reference = reference?;
+        let peer = reference
+            .name()
+            .and_then(|n| n.strip_prefix(&format!("{}/remotes/", namespace)))
+            .and_then(|n| n.strip_suffix("/rad/id"))
+            .and_then(|p| PeerId::from_str(p).ok());
+        let peer = if let Some(peer) = peer {
+            peer
+        } else {
+            continue;
+        };
+        let theirs = reference.peel_to_commit()?.id();
+
+        if theirs == ours || repo.graph_descendant_of(ours, theirs)? {
+            continue;
+        }
+        if let Some(revision) = history(repo, urn, Some(&peer))?.pop() {
+            proposals.push((peer, revision));
+        }
+    }
+    Ok(proposals)
+}
+
+/// Get the project payload of our latest revision of a project identity, as JSON.
+pub fn payload(storage: &Storage, urn: &Urn) -> anyhow::Result<json::Value> {
+    let project = project::get(storage, urn)?
+        .ok_or_else(|| anyhow!("project {} was not found in storage", urn))?;
+
+    Ok(json::to_value(project.payload())?)
+}
+
+/// Set a core field of a project payload, eg. `name`. The field is removed if no value
+/// is given.
+pub fn set_field(
+    payload: &mut json::Value,
+    field: &str,
+    value: Option<&str>,
+) -> anyhow::Result<()> {
+    if !PROJECT_FIELDS.contains(&field) {
+        anyhow::bail!("unknown project field '{}'", field);
+    }
+    let core = payload
+        .get_mut(PROJECT_PAYLOAD)
+        .and_then(|v| v.as_object_mut())
+        .ok_or_else(|| anyhow!("payload is not a project payload"))?;
+
+    match value {
+        Some(value) => core.insert(field.to_owned(), json::Value::from(value)),
+        None => core.remove(field),
+    };
+    Ok(())
+}
+
+/// Propose a new revision of a project identity with the given payload, signed with the
+/// storage's key. If the project has other delegates, the revision needs their
+/// signatures before it takes effect, see [`sign`].
+pub fn propose(storage: &Storage, urn: &Urn, payload: json::Value) -> anyhow::Result<()> {
+    let payload: ProjectPayload =
+        json::from_value(payload).map_err(|err| anyhow!("invalid project payload: {}", err))?;
+    project::update(storage, urn, None, payload, None)?;
+
+    Ok(())
+}
+
+/// Sign a peer's latest revision of a project identity with the storage's key, and make
+/// it ours.
+pub fn sign(storage: &Storage, urn: &Urn, peer: PeerId) -> anyhow::Result<()> {
+    project::merge(storage, urn, peer)?;
+
+    Ok(())
+}
+
+/// Get the commit of the latest revision of a project identity that has the signatures
+/// of a quorum of its delegates. Later revisions of our view are pending.
+pub fn verified(storage: &Storage, urn: &Urn) -> anyhow::Result<Option<git::Oid>> {
+    let project = match project::verify(storage, urn)? {
+        Some(project) => project,
+        None => return Ok(None),
+    };
+    Ok(Some(git::Oid::from_str(&project.content_id.to_string())?))
+}
+
+/// Get the signing keys from the signature trailers of an identity commit message.
+pub fn signers(message: &str) -> Vec<String> {
+    message
+        .lines()
+        .filter_map(|line| {
+            let (token, value) = line.split_once(':')?;
+            if !token.trim().eq_ignore_ascii_case(SIGNATURE_TRAILER) {
+                return None;
+            }
+            value.split_whitespace().next().map(ToOwned::to_owned)
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use std::str::FromStr;
 
+    #[test]
+    fn test_set_field() {
+        let mut payload = json::json!({
+            PROJECT_PAYLOAD: {
+                "name": "acme",
+                "description": "Acme's project",
+                "default_branch": "master"
+            }
+        });
+
+        set_field(&mut payload, "name", Some("acme-2")).unwrap();
+        set_field(&mut payload, "description", None).unwrap();
+        set_field(&mut payload, "maintainer", Some("alice")).unwrap_err();
+
+        let doc = Document(json::json!({ "payload": payload }));
+        assert_eq!(doc.name(), Some("acme-2"));
+        assert_eq!(doc.description(), None);
+        assert_eq!(doc.default_branch(), Some("master"));
+
+        set_field(&mut json::json!({}), "name", Some("acme")).unwrap_err();
+    }
+
     #[test]
     fn test_origin_from_url() {
         let url = Url::parse("rad://willow.radicle.garden/hnrkbjg7r54q48sqsaho1n4qfxhi4nbmdh51y")
@@ -132,4 +414,60 @@ mod test {
         );
         assert!(origin.seed.is_none());
     }
+
+    #[test]
+    fn test_document_changes() {
+        let old = Document(json::json!({
+            "payload": {
+                "https://radicle.xyz/link/identities/project/v1": {
+                    "name": "acme",
+                    "default_branch": "master"
+                }
+            },
+            "delegations": ["hyb5to4rshftx4apgmu9s6wnsp4ddmp1mz6ijh4qqey7fb8wrpawxa"]
+        }));
+        let new = Document(json::json!({
+            "payload": {
+                "https://radicle.xyz/link/identities/project/v1": {
+                    "name": "acme",
+                    "description": "Acme tools",
+                    "default_branch": "main"
+                }
+            },
+            "delegations": ["hybz9gfgtd9d4pd14a6r66j5hz6f77fed4jdu7pana4fxaxbt369kg"]
+        }));
+
+        assert_eq!(new.name(), Some("acme"));
+        assert!(old.changes(&old).is_empty());
+        assert_eq!(
+            new.changes(&old),
+            vec![
+                Change::Field {
+                    field: "description",
+                    old: None,
+                    new: Some("Acme tools".to_owned()),
+                },
+                Change::Field {
+                    field: "default_branch",
+                    old: Some("master".to_owned()),
+                    new: Some("main".to_owned()),
+                },
+                Change::DelegateAdded(
+                    "hybz9gfgtd9d4pd14a6r66j5hz6f77fed4jdu7pana4fxaxbt369kg".to_owned()
+                ),
+                Change::DelegateRemoved(
+                    "hyb5to4rshftx4apgmu9s6wnsp4ddmp1mz6ijh4qqey7fb8wrpawxa".to_owned()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_signers() {
+        let message = "Update\n\nX-Rad-Signature: hyb5to4rshftx4apgmu9s6wnsp4ddmp1mz6ijh4qqey7fb8wrpawxa z3abc\n";
+        assert_eq!(
+            signers(message),
+            vec!["hyb5to4rshftx4apgmu9s6wnsp4ddmp1mz6ijh4qqey7fb8wrpawxa".to_owned()]
+        );
+    }
 }
@@ -0,0 +1,195 @@
+//! Profile passphrase storage in the platform keychain: the macOS Keychain, the
+//! Secret Service on Linux (eg. GNOME Keyring or KWallet), or the Windows Credential
+//! Locker.
+//!
+//! Storing the passphrase is opt-in, see `rad auth --keychain`. The platform's own
+//! tools are used, ie. `security`, `secret-tool` and PowerShell, and the passphrase is
+//! always passed on stdin, never as an argument, so it doesn't show up in process lists.
+use std::io::Write as _;
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, Context as _};
+use zeroize::Zeroizing;
+
+use librad::crypto::keystore::pinentry::SecUtf8;
+use librad::profile::{Profile, ProfileId};
+
+use crate::config::Config;
+
+/// Service name under which passphrases are stored.
+pub const SERVICE: &str = "radicle";
+
+/// Whether the profile opted into storing its passphrase in the keychain.
+pub fn is_enabled(profile: &Profile) -> bool {
+    Config::profile(profile).map_or(false, |c| c.keychain)
+}
+
+/// Get the passphrase of a profile from the keychain, if the profile opted into it
+/// and the passphrase is stored there.
+pub fn passphrase(profile: &Profile) -> Option<SecUtf8> {
+    if !is_enabled(profile) {
+        return None;
+    }
+    get(profile.id()).ok().flatten()
+}
+
+/// Get the passphrase of a profile from the keychain, if it's stored there.
+pub fn get(id: &ProfileId) -> Result<Option<SecUtf8>, anyhow::Error> {
+    let output = platform::get(&id.to_string())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()
+        .context("keychain is not available")?;
+
+    if !output.status.success() {
+        return Ok(None);
+    }
+    let secret = Zeroizing::new(String::from_utf8(output.stdout)?);
+    let secret = secret.trim_end_matches(&['\r', '\n'][..]);
+
+    if secret.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(SecUtf8::from(secret)))
+}
+
+/// Store the passphrase of a profile in the keychain, replacing any previous one.
+pub fn set(id: &ProfileId, passphrase: &SecUtf8) -> Result<(), anyhow::Error> {
+    let (mut cmd, input) = platform::set(&id.to_string(), passphrase.unsecure());
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("keychain is not available")?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "could not store passphrase in keychain: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Remove the passphrase of a profile from the keychain. Returns `false` if it wasn't
+/// stored there.
+pub fn remove(id: &ProfileId) -> Result<bool, anyhow::Error> {
+    let status = platform::remove(&id.to_string())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .context("keychain is not available")?;
+
+    Ok(status.success())
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+
+    pub fn get(account: &str) -> Command {
+        let mut cmd = Command::new("security");
+        cmd.args(["find-generic-password", "-s", SERVICE, "-a", account, "-w"]);
+
+        cmd
+    }
+
+    pub fn set(account: &str, secret: &str) -> (Command, Zeroizing<String>) {
+        // Commands are read from stdin in interactive mode, keeping the secret out of
+        // the arguments.
+        let mut cmd = Command::new("security");
+        cmd.arg("-i");
+
+        let input = Zeroizing::new(format!(
+            "add-generic-password -U -s {} -a {} -w {}\n",
+            SERVICE,
+            account,
+            quote(secret)
+        ));
+        (cmd, input)
+    }
+
+    pub fn remove(account: &str) -> Command {
+        let mut cmd = Command::new("security");
+        cmd.args(["delete-generic-password", "-s", SERVICE, "-a", account]);
+
+        cmd
+    }
+
+    fn quote(s: &str) -> String {
+        format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use super::*;
+
+    pub fn get(account: &str) -> Command {
+        let mut cmd = Command::new("secret-tool");
+        cmd.args(["lookup", "service", SERVICE, "profile", account]);
+
+        cmd
+    }
+
+    pub fn set(account: &str, secret: &str) -> (Command, Zeroizing<String>) {
+        let mut cmd = Command::new("secret-tool");
+        cmd.args(["store", "--label", "Radicle profile passphrase"])
+            .args(["service", SERVICE, "profile", account]);
+
+        (cmd, Zeroizing::new(secret.to_owned()))
+    }
+
+    pub fn remove(account: &str) -> Command {
+        let mut cmd = Command::new("secret-tool");
+        cmd.args(["clear", "service", SERVICE, "profile", account]);
+
+        cmd
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::*;
+
+    const VAULT: &str = "[void][Windows.Security.Credentials.PasswordVault,Windows.Security.Credentials,ContentType=WindowsRuntime]; \
+        $v = New-Object Windows.Security.Credentials.PasswordVault;";
+
+    fn powershell(script: String) -> Command {
+        let mut cmd = Command::new("powershell");
+        cmd.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+        cmd
+    }
+
+    pub fn get(account: &str) -> Command {
+        powershell(format!(
+            "{} $c = $v.Retrieve('{}', '{}'); $c.RetrievePassword(); $c.Password",
+            VAULT, SERVICE, account
+        ))
+    }
+
+    pub fn set(account: &str, secret: &str) -> (Command, Zeroizing<String>) {
+        let cmd = powershell(format!(
+            "{} try {{ $v.Remove($v.Retrieve('{s}', '{a}')) }} catch {{}}; \
+             $p = [Console]::In.ReadLine(); \
+             $v.Add((New-Object Windows.Security.Credentials.PasswordCredential('{s}', '{a}', $p)))",
+            VAULT,
+            s = SERVICE,
+            a = account
+        ));
+        (cmd, Zeroizing::new(format!("{}\n", secret)))
+    }
+
+    pub fn remove(account: &str) -> Command {
+        powershell(format!(
+            "{} $v.Remove($v.Retrieve('{}', '{}'))",
+            VAULT, SERVICE, account
+        ))
+    }
+}
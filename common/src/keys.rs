@@ -14,7 +14,7 @@ use lnk_clib::keys::ssh::SshAuthSock;
 
 pub use lnk_clib::keys::LIBRAD_KEY_FILE as KEY_FILE;
 
-use crate::signer::{ToSigner, ZeroizingSecretKey};
+use crate::signer::{Backend, ToSigner, ZeroizingSecretKey};
 
 /// Env var used to pass down the passphrase to the git-remote-helper when
 /// ssh-agent isn't present.
@@ -29,6 +29,8 @@ pub fn storage(profile: &Profile, signer: impl ToSigner) -> Result<Storage, Erro
         }
         Err(err) => anyhow::bail!(err),
     };
+    crate::profile::check_storage(profile)?;
+    crate::storage::prepare(profile)?;
     let storage = Storage::open(profile.paths(), signer)?;
 
     Ok(storage)
@@ -72,6 +74,40 @@ pub fn is_ready(profile: &Profile, sock: SshAuthSock) -> Result<bool, Error> {
         .context("could not lookup ssh key, is ssh-agent running?")
 }
 
+/// Where a profile's signing key is taken from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    /// ssh-agent, which holds the key.
+    Agent,
+    /// The profile's key file.
+    KeyFile,
+}
+
+/// Get the source of a profile's signing key, for the given backend.
+///
+/// With [`Backend::Auto`], ssh-agent is only used if it holds the key, so that an
+/// agent without it doesn't stop commands from signing with the key file.
+pub fn source(profile: &Profile, backend: Backend) -> Result<Source, Error> {
+    match backend {
+        Backend::Key => Ok(Source::KeyFile),
+        Backend::Agent => {
+            let sock = ssh_auth_sock()?;
+
+            if !is_ready(profile, sock)? {
+                anyhow::bail!(
+                    "the radicle key of profile {} is not in ssh-agent",
+                    profile.id()
+                );
+            }
+            Ok(Source::Agent)
+        }
+        Backend::Auto => match ssh_auth_sock() {
+            Ok(sock) if is_ready(profile, sock).unwrap_or(false) => Ok(Source::Agent),
+            _ => Ok(Source::KeyFile),
+        },
+    }
+}
+
 /// Get the SSH long key from a peer id.
 /// This is the output of `ssh-add -L`.
 pub fn to_ssh_key(peer_id: &PeerId) -> Result<String, std::io::Error> {
@@ -0,0 +1,243 @@
+//! Replication with peers on the local network, without seeds.
+//!
+//! A peer serves a project by running `git daemon` on a repository holding only the
+//! project's refs, and advertising it over mDNS, see [`crate::mdns`]. Other peers fetch
+//! the serving peer's refs of the project, and update its remote in their storage to
+//! the refs it signed, as they would from a seed.
+use std::fs;
+use std::net::{SocketAddr, TcpListener};
+use std::process::{Child, Command, Stdio};
+
+use anyhow::{anyhow, Context as _};
+use librad::git::refs::Refs;
+use librad::git::storage::Storage;
+use librad::git::Urn;
+use librad::profile::Profile;
+use librad::PeerId;
+
+use crate::{git, mdns};
+
+/// Refs of a peer that are replicated.
+const CATEGORIES: &[&str] = &["rad", "heads", "tags", "cobs"];
+
+/// A project served to the local network.
+pub struct Server {
+    /// The `git daemon` process.
+    pub daemon: Child,
+    /// Address the daemon listens on.
+    pub addr: SocketAddr,
+    urn: Urn,
+    storage: git::Repository,
+    repo: git::Repository,
+}
+
+impl Server {
+    /// Mirror our refs of the project in storage to the served repository, so that
+    /// peers fetch their current state.
+    pub fn refresh(&self) -> anyhow::Result<()> {
+        let namespace = format!("refs/namespaces/{}/", self.urn.encode_id());
+        let mut refs = Vec::new();
+
+        for c in CATEGORIES {
+            for r in self
+                .storage
+                .references_glob(&format!("{}refs/{}/*", namespace, c))?
+            {
+                let r = r?;
+                if let (Some(name), Some(oid)) = (r.name(), r.resolve()?.target()) {
+                    refs.push((name[namespace.len()..].to_owned(), oid));
+                }
+            }
+        }
+
+        let mut stale = Vec::new();
+        for r in self.repo.references()? {
+            let r = r?;
+            match r.name() {
+                Some(name) if refs.iter().any(|(n, _)| n == name) => {}
+                _ => stale.push(r),
+            }
+        }
+        for mut r in stale {
+            r.delete()?;
+        }
+        for (name, oid) in refs {
+            if self.repo.refname_to_id(&name).ok() != Some(oid) {
+                self.repo.reference(&name, oid, true, "rad sync --serve")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.daemon.kill().ok();
+        self.daemon.wait().ok();
+    }
+}
+
+/// Serve a project to the local network, over the git protocol. The daemon listens on
+/// the interface used for mDNS, and only exports a repository holding our refs of the
+/// project, which borrows its objects from storage.
+pub fn serve(profile: &Profile, urn: &Urn) -> anyhow::Result<Server> {
+    let ip = mdns::interface()?;
+    // Nb. The port is free now, but could be taken by the time the daemon binds it.
+    let port = TcpListener::bind((ip, 0))?.local_addr()?.port();
+    let storage = git::Repository::open_bare(profile.paths().git_dir())?;
+    let base = profile.paths().git_dir().with_file_name("lan");
+    let path = base.join(urn.encode_id());
+
+    if path.exists() {
+        fs::remove_dir_all(&path)?;
+    }
+    let repo = git::Repository::init_bare(&path)?;
+    let info = path.join("objects").join("info");
+
+    fs::create_dir_all(&info)?;
+    fs::write(
+        info.join("alternates"),
+        format!("{}\n", storage.path().join("objects").display()),
+    )?;
+    // Only repositories with this file are exported, see git-daemon(1).
+    fs::write(path.join("git-daemon-export-ok"), "")?;
+
+    let daemon = Command::new("git")
+        .arg("daemon")
+        .arg("--reuseaddr")
+        .arg("--strict-paths")
+        .arg(format!("--listen={}", ip))
+        .arg(format!("--port={}", port))
+        .arg(format!("--base-path={}", base.display()))
+        .arg(&path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let server = Server {
+        daemon,
+        addr: SocketAddr::from((ip, port)),
+        urn: urn.clone(),
+        storage,
+        repo,
+    };
+    server.refresh()?;
+
+    Ok(server)
+}
+
+/// Fetch a local network peer's refs of a project, and update its remote in storage to
+/// the refs it signed. Refs are fetched into a staging area first: nothing is updated
+/// unless the peer's `rad/signed_refs` are signed with its key, and descend from the
+/// ones we have. Without `data`, only the peer's identity, ie. its `rad` refs, is
+/// updated, as for peers restricted by an allowlist.
+pub fn replicate(
+    profile: &Profile,
+    storage: &Storage,
+    urn: &Urn,
+    peer: &PeerId,
+    addr: &SocketAddr,
+    data: bool,
+) -> anyhow::Result<()> {
+    let repo = git::Repository::open_bare(profile.paths().git_dir())?;
+    let categories = if data { CATEGORIES } else { &CATEGORIES[..1] };
+    let staging = format!("refs/lan/{}", peer.default_encoding());
+    let refspecs = categories
+        .iter()
+        .map(|c| format!("+refs/{c}/*:{staging}/{c}/*", c = c, staging = staging))
+        .collect::<Vec<_>>();
+    let mut remote = repo.remote_anonymous(&format!("git://{}/{}", addr, urn.encode_id()))?;
+
+    remote.fetch(&refspecs, None, None)?;
+
+    let result = update(&repo, storage, urn, peer, &staging, categories);
+    let staged = repo
+        .references_glob(&format!("{}/*", staging))?
+        .collect::<Result<Vec<_>, _>>()?;
+    for mut r in staged {
+        r.delete()?;
+    }
+    result
+}
+
+/// Update a peer's remote to its staged refs, if they are signed.
+fn update(
+    repo: &git::Repository,
+    storage: &Storage,
+    urn: &Urn,
+    peer: &PeerId,
+    staging: &str,
+    categories: &[&str],
+) -> anyhow::Result<()> {
+    let remote = format!(
+        "refs/namespaces/{}/refs/remotes/{}",
+        urn.encode_id(),
+        peer.default_encoding()
+    );
+    let signed = format!("{}/rad/signed_refs", remote);
+    let fetched = repo
+        .refname_to_id(&format!("{}/rad/signed_refs", staging))
+        .context("peer has no signed refs")?;
+    let previous = repo.refname_to_id(&signed).ok();
+
+    if let Some(previous) = previous {
+        if previous != fetched && !repo.graph_descendant_of(fetched, previous)? {
+            anyhow::bail!("signed refs don't descend from the ones in storage");
+        }
+    }
+    repo.reference(&signed, fetched, true, "rad sync --local")?;
+
+    let updates = match signed_refs(repo, storage, urn, peer, staging, categories) {
+        Ok(updates) => updates,
+        Err(err) => {
+            match previous {
+                Some(previous) => {
+                    repo.reference(&signed, previous, true, "rad sync --local: rollback")?;
+                }
+                None => repo.find_reference(&signed)?.delete()?,
+            }
+            return Err(err);
+        }
+    };
+    for (name, oid) in updates {
+        repo.reference(
+            &format!("{}/{}", remote, name),
+            oid,
+            true,
+            "rad sync --local",
+        )?;
+    }
+    Ok(())
+}
+
+/// The refs signed by a peer, in the given categories, once its signed refs are
+/// verified. Fails if a staged ref differs from the signed one.
+fn signed_refs(
+    repo: &git::Repository,
+    storage: &Storage,
+    urn: &Urn,
+    peer: &PeerId,
+    staging: &str,
+    categories: &[&str],
+) -> anyhow::Result<Vec<(String, git::Oid)>> {
+    // Nb. Loading the signed refs verifies their signature with the peer's key.
+    let refs = Refs::load(storage, urn, Some(*peer))
+        .map_err(|err| anyhow!("invalid signed refs: {}", err))?
+        .ok_or_else(|| anyhow!("signed refs not found"))?;
+    let mut updates = Vec::new();
+
+    for ((name, oid), category) in refs.iter_categorised() {
+        let category = category.to_string();
+        if !categories.contains(&category.as_str()) {
+            continue;
+        }
+        let name = format!("{}/{}", category, name);
+        let oid = git::Oid::from(*oid);
+
+        if repo.refname_to_id(&format!("{}/{}", staging, name)).ok() != Some(oid) {
+            anyhow::bail!("refs/{} doesn't match the signed refs", name);
+        }
+        updates.push((name, oid));
+    }
+    Ok(updates)
+}
@@ -1,18 +1,36 @@
 //! Common radicle utilities.
 #![allow(clippy::or_fun_call)]
+pub mod allowlist;
+pub mod archive;
 pub mod args;
+pub mod badge;
+pub mod bridge;
 pub mod cobs;
 pub mod config;
+pub mod fingerprint;
 pub mod git;
 pub mod identity;
+pub mod keychain;
 pub mod keys;
+pub mod lan;
 pub mod logger;
+pub mod mail;
+pub mod markdown;
+pub mod mdns;
+pub mod merge;
 pub mod patch;
 pub mod person;
+pub mod pin;
 pub mod profile;
 pub mod project;
+pub mod proxy;
+pub mod registry;
+pub mod rotation;
+pub mod schema;
 pub mod seed;
 pub mod signer;
+pub mod storage;
+pub mod store;
 pub mod sync;
 pub mod test;
 
@@ -54,6 +72,41 @@ pub mod fmt {
     pub fn cob(id: &ObjectId) -> String {
         format!("{:.11}", id.to_string())
     }
+
+    /// Columns are not shrunk below this width when fitting them to a width.
+    pub const MIN_COLUMN_WIDTH: usize = 12;
+
+    /// Shrink the widest columns until a row, with one space between columns, fits the
+    /// given width. Columns are not shrunk below [`MIN_COLUMN_WIDTH`].
+    pub fn fit<const W: usize>(mut widths: [usize; W], width: usize) -> [usize; W] {
+        let gaps = W.saturating_sub(1);
+
+        while widths.iter().sum::<usize>() + gaps > width {
+            match widths
+                .iter_mut()
+                .filter(|w| **w > MIN_COLUMN_WIDTH)
+                .max_by_key(|w| **w)
+            {
+                Some(w) => *w -= 1,
+                None => break,
+            }
+        }
+        widths
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn test_fit() {
+            assert_eq!(fit([30, 20, 5], 80), [30, 20, 5]);
+            assert_eq!(fit([30, 20, 5], 40), [17, 16, 5]);
+            // Columns aren't shrunk below the minimum, even if the row doesn't fit.
+            assert_eq!(fit([30, 20, 5], 10), [12, 12, 5]);
+            assert_eq!(fit([], 0), []);
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
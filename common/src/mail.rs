@@ -0,0 +1,229 @@
+//! Patch emails, as produced by `git format-patch`.
+
+/// Header used to record the patch id in the cover letter.
+pub const PATCH_ID_HEADER: &str = "Radicle-Patch";
+
+/// Placeholders of `git format-patch --cover-letter`.
+pub const COVER_SUBJECT: &str = "*** SUBJECT HERE ***";
+pub const COVER_BLURB: &str = "*** BLURB HERE ***";
+
+/// Parse headers, unfolding continuation lines.
+pub fn parse_headers(head: &str) -> Vec<(String, String)> {
+    let mut headers: Vec<(String, String)> = Vec::new();
+
+    for line in head.lines() {
+        if line.starts_with(' ') || line.starts_with('\t') {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_owned(), value.trim().to_owned()));
+        }
+    }
+    headers
+}
+
+/// Split a message into its headers and body.
+pub fn split(message: &str) -> (Vec<(String, String)>, String) {
+    let message = message.replace("\r\n", "\n");
+    let (head, body) = message.split_once("\n\n").unwrap_or((&message, ""));
+
+    (parse_headers(head), body.to_owned())
+}
+
+/// Get the value of a header.
+pub fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.as_str())
+}
+
+/// Split a subject into its `[PATCH n/m]` prefix, if any, and the rest.
+fn split_subject(subject: &str) -> (Option<&str>, &str) {
+    match subject.strip_prefix('[').and_then(|s| s.split_once(']')) {
+        Some((prefix, rest)) => (Some(prefix), rest.trim()),
+        None => (None, subject),
+    }
+}
+
+/// Get the subject of a patch, without the `[PATCH n/m]` prefix.
+pub fn subject(message: &str) -> Option<String> {
+    let (headers, _) = split(message);
+    let (_, subject) = split_subject(header(&headers, "Subject")?.trim());
+
+    Some(subject.to_owned())
+}
+
+/// Parse a cover letter, ie. a message with a `[PATCH 0/<n>]` subject, into a title
+/// and description.
+pub fn cover_letter(message: &str) -> Option<(String, String)> {
+    let (headers, body) = split(message);
+    let title = match split_subject(header(&headers, "Subject")?.trim()) {
+        (Some(prefix), title) if prefix.contains(" 0/") => title,
+        _ => return None,
+    };
+
+    // Skip the in-body header crediting the patch author.
+    let body = match body.strip_prefix("From: ") {
+        Some(rest) => rest.split_once('\n').map_or("", |(_, rest)| rest),
+        None => &body,
+    };
+    let description = body
+        .lines()
+        .take_while(|l| *l != "-- ")
+        .filter(|l| !l.starts_with(PATCH_ID_HEADER))
+        .collect::<Vec<_>>()
+        .join("\n");
+    // Templates generated by `git format-patch --cover-letter` have placeholder content.
+    let title = title.replace(COVER_SUBJECT, "");
+    let description = description.replace(COVER_BLURB, "");
+
+    Some((title.trim().to_owned(), description.trim().to_owned()))
+}
+
+/// Fill in the placeholders of a cover letter, as generated by
+/// `git format-patch --cover-letter`.
+pub fn fill_cover_letter(template: &str, subject: &str, blurb: &str) -> String {
+    template
+        .replacen(COVER_SUBJECT, subject, 1)
+        .replacen(COVER_BLURB, blurb, 1)
+}
+
+/// Cover letter blurb for a patch description. Like `git format-patch`, the patch author
+/// is credited with an in-body header if they aren't the sender.
+pub fn cover_blurb(sender: &str, author: &str, description: &str) -> String {
+    let mut blurb = String::new();
+
+    if author != sender {
+        blurb.push_str(&format!("From: {}\n\n", author));
+    }
+    if !description.is_empty() {
+        blurb.push_str(description);
+        blurb.push('\n');
+    }
+    blurb
+}
+
+/// Decode a quoted-printable body.
+pub fn decode_quoted_printable(input: &str) -> String {
+    let input = input.replace("=\n", "");
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'=' {
+            if let Some(b) = input
+                .get(i + 1..i + 3)
+                .and_then(|h| u8::from_str_radix(h, 16).ok())
+            {
+                out.push(b);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Turn a patch title into a branch name component.
+pub fn slug(title: &str) -> String {
+    title
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_ascii_lowercase())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_subject() {
+        let patch = "From 8f3c Mon Sep 17 00:00:00 2001\n\
+                     From: Alice <alice@example.com>\n\
+                     Subject: [PATCH 2/3] Add a rather long subject, which\n \
+                     `git format-patch` folds\n\
+                     \n\
+                     Body\n";
+
+        assert_eq!(
+            subject(patch).unwrap(),
+            "Add a rather long subject, which `git format-patch` folds"
+        );
+        assert_eq!(subject("Subject: Fix\r\n\r\nBody").unwrap(), "Fix");
+        assert_eq!(subject("From: Alice\n\nSubject: Fix\n"), None);
+    }
+
+    #[test]
+    fn test_cover_letter() {
+        let letter = "From 8f3c Mon Sep 17 00:00:00 2001\n\
+                      From: Bob <bob@example.com>\n\
+                      Subject: [PATCH 0/2] Add widgets\n\
+                      Radicle-Patch: 0123\n\
+                      \n\
+                      From: Alice\n\
+                      \n\
+                      Widgets for everyone.\n\
+                      \n\
+                      -- \n\
+                      2.37.0\n";
+
+        assert_eq!(
+            cover_letter(letter).unwrap(),
+            (
+                String::from("Add widgets"),
+                String::from("Widgets for everyone.")
+            )
+        );
+        assert_eq!(
+            cover_letter("Subject: [PATCH 0/1] *** SUBJECT HERE ***\n\n*** BLURB HERE ***\n"),
+            Some((String::new(), String::new()))
+        );
+        assert_eq!(cover_letter("Subject: [PATCH 1/2] Add widgets\n\n"), None);
+        assert_eq!(cover_letter("Subject: Add widgets\n\n"), None);
+    }
+
+    #[test]
+    fn test_fill_cover_letter() {
+        let template = "Subject: [PATCH 0/1] *** SUBJECT HERE ***\n\n*** BLURB HERE ***\n";
+        let blurb = cover_blurb("Bob", "Alice", "Widgets for everyone.");
+        let letter = fill_cover_letter(template, "Add widgets", &blurb);
+
+        assert_eq!(
+            letter,
+            "Subject: [PATCH 0/1] Add widgets\n\nFrom: Alice\n\nWidgets for everyone.\n\n"
+        );
+        // The in-body header is skipped when parsing the letter back.
+        assert_eq!(
+            cover_letter(&letter).unwrap(),
+            (
+                String::from("Add widgets"),
+                String::from("Widgets for everyone.")
+            )
+        );
+        assert_eq!(cover_blurb("Alice", "Alice", ""), "");
+    }
+
+    #[test]
+    fn test_decode_quoted_printable() {
+        assert_eq!(
+            decode_quoted_printable("Caf=C3=A9 au lait, =\nsoft break =3D kept"),
+            "Café au lait, soft break = kept"
+        );
+        assert_eq!(decode_quoted_printable("100% =ZZ"), "100% =ZZ");
+    }
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(slug("Fix the `rad sync` crash!"), "fix-the-rad-sync-crash");
+        assert_eq!(slug("Überarbeitung"), "berarbeitung");
+        assert_eq!(slug("修正"), "");
+    }
+}
@@ -0,0 +1,123 @@
+//! Markdown written by users, eg. issue and patch descriptions.
+
+/// Checks applied to comments written by the user.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Validation {
+    /// The comment can't be empty.
+    pub required: bool,
+    /// The comment must start with a title line, eg. for patches.
+    pub title: bool,
+}
+
+impl Validation {
+    /// Only check markdown syntax, eg. for optional descriptions.
+    pub fn optional() -> Self {
+        Self::default()
+    }
+
+    /// The comment can't be empty.
+    pub fn required() -> Self {
+        Self {
+            required: true,
+            ..Self::default()
+        }
+    }
+
+    /// The comment must start with a title line.
+    pub fn title() -> Self {
+        Self {
+            required: true,
+            title: true,
+        }
+    }
+
+    /// Check a comment. Returns the problems found, if any.
+    pub fn check(&self, comment: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        if comment.is_empty() {
+            if self.required {
+                errors.push("content can't be empty".to_owned());
+            }
+            return errors;
+        }
+        if self.title {
+            let title = comment.lines().next().unwrap_or_default().trim();
+
+            if title.is_empty() || title.chars().all(|c| c == '#' || c == '`' || c == '~') {
+                errors.push("the first line must be a title".to_owned());
+            }
+        }
+        if let Some(line) = unclosed_fence(comment) {
+            errors.push(format!("code block opened on line {} is not closed", line));
+        }
+        errors
+    }
+}
+
+/// Find a markdown code fence that isn't closed. Returns its line number.
+fn unclosed_fence(text: &str) -> Option<usize> {
+    // Fence character, length and line number of the open fence.
+    let mut open: Option<(char, usize, usize)> = None;
+
+    for (ix, line) in text.lines().enumerate() {
+        let line = line.trim_start();
+        let fence = match line.chars().next() {
+            Some(c @ '`') | Some(c @ '~') => c,
+            _ => continue,
+        };
+        let len = line.chars().take_while(|c| *c == fence).count();
+        if len < 3 {
+            continue;
+        }
+        match open {
+            // A closing fence uses the same character, is at least as long, and has
+            // no info string.
+            Some((c, n, _)) if c == fence && len >= n && line[len..].trim().is_empty() => {
+                open = None;
+            }
+            Some(_) => {}
+            None => open = Some((fence, len, ix + 1)),
+        }
+    }
+    open.map(|(_, _, line)| line)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        assert!(Validation::optional().check("").is_empty());
+        assert_eq!(
+            Validation::required().check(""),
+            vec!["content can't be empty"]
+        );
+        assert!(Validation::title()
+            .check("Fix the thing\n\nDetails.")
+            .is_empty());
+        assert_eq!(
+            Validation::title().check("```\nfn main() {}\n```"),
+            vec!["the first line must be a title"]
+        );
+        assert_eq!(
+            Validation::optional().check("Run:\n\n```sh\nrad sync\n"),
+            vec!["code block opened on line 3 is not closed"]
+        );
+    }
+
+    #[test]
+    fn test_unclosed_fence() {
+        assert_eq!(unclosed_fence("No code."), None);
+        assert_eq!(unclosed_fence("```rust\nfn main() {}\n```"), None);
+        assert_eq!(unclosed_fence("~~~\ncode\n~~~~"), None);
+        assert_eq!(unclosed_fence("  ```\nindented\n  ```"), None);
+        // Closing fences must use the same character, be as long, and have no info string.
+        assert_eq!(unclosed_fence("```\ncode\n~~~"), Some(1));
+        assert_eq!(unclosed_fence("text\n````\ncode\n```"), Some(2));
+        assert_eq!(unclosed_fence("```\ncode\n```rust"), Some(1));
+        // Shorter runs aren't fences.
+        assert_eq!(unclosed_fence("``not a fence``"), None);
+    }
+}
@@ -0,0 +1,402 @@
+//! Discovery of peers on the local network, over multicast DNS.
+//!
+//! Peers serving a project advertise it as a DNS-SD service instance of
+//! [`SERVICE`], named after their peer id, with a TXT record holding the project URN
+//! and the port it is served on. Peers looking for a project send a PTR query for the
+//! service, and pick the instances advertising the project from the answers. The
+//! address of a peer is the source address of its answer, so no SRV or A records are
+//! published.
+//!
+//! Only IPv4 is supported.
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4, UdpSocket};
+use std::str::FromStr;
+use std::time;
+
+use librad::git::Urn;
+use librad::PeerId;
+use socket2::{Domain, Protocol, Socket, Type};
+
+/// mDNS multicast group.
+pub const GROUP: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+/// mDNS port.
+pub const PORT: u16 = 5353;
+/// DNS-SD service type of radicle peers.
+pub const SERVICE: &str = "_radicle._tcp.local";
+/// Time-to-live of advertised records, in seconds.
+pub const TTL: u32 = 120;
+
+const TYPE_PTR: u16 = 12;
+const TYPE_TXT: u16 = 16;
+const TYPE_ANY: u16 = 255;
+const CLASS_IN: u16 = 1;
+/// Cache-flush bit of the record class, for records only we publish.
+const CACHE_FLUSH: u16 = 0x8000;
+
+/// A project advertised by a peer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Announcement {
+    /// Project URN.
+    pub urn: Urn,
+    /// Peer serving the project.
+    pub peer: PeerId,
+    /// TCP port the project is served on.
+    pub port: u16,
+}
+
+/// A received mDNS message, as far as we're concerned.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Message {
+    /// Whether the message queries our service.
+    pub query: bool,
+    /// Projects advertised in the message.
+    pub announcements: Vec<Announcement>,
+}
+
+/// Create a socket joined to the mDNS group. The port is shared with other mDNS
+/// responders on the host, eg. Avahi.
+pub fn socket() -> io::Result<UdpSocket> {
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_reuse_address(true)?;
+    #[cfg(all(unix, not(any(target_os = "solaris", target_os = "illumos"))))]
+    socket.set_reuse_port(true)?;
+    socket.bind(&SocketAddr::from((Ipv4Addr::UNSPECIFIED, PORT)).into())?;
+    socket.join_multicast_v4(&GROUP, &Ipv4Addr::UNSPECIFIED)?;
+    // So that peers on the same host see each other.
+    socket.set_multicast_loop_v4(true)?;
+
+    Ok(socket.into())
+}
+
+/// The address of the interface mDNS messages are sent from, ie. the address peers on
+/// the local network see our answers coming from.
+pub fn interface() -> io::Result<Ipv4Addr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+    // Nb. Nothing is sent, connecting only picks the route to the group.
+    socket.connect(SocketAddrV4::new(GROUP, PORT))?;
+
+    match socket.local_addr()? {
+        SocketAddr::V4(addr) if !addr.ip().is_unspecified() => Ok(*addr.ip()),
+        _ => Err(io::Error::new(
+            io::ErrorKind::AddrNotAvailable,
+            "no network interface to reach the mDNS group",
+        )),
+    }
+}
+
+/// Send a message to the mDNS group.
+pub fn send(socket: &UdpSocket, packet: &[u8]) -> io::Result<()> {
+    socket.send_to(packet, SocketAddrV4::new(GROUP, PORT))?;
+    Ok(())
+}
+
+/// Look for peers advertising a project, for the given amount of time. Returns the
+/// peers found, and the address they serve the project on.
+pub fn discover(urn: &Urn, window: time::Duration) -> io::Result<Vec<(SocketAddr, Announcement)>> {
+    let socket = socket()?;
+    let start = time::Instant::now();
+    let mut found: Vec<(SocketAddr, Announcement)> = Vec::new();
+    let mut buf = [0; 9000];
+
+    socket.set_read_timeout(Some(time::Duration::from_millis(250)))?;
+    // Queries are sent twice, in case the first one is lost.
+    let mut queries = vec![time::Duration::ZERO, window / 3]
+        .into_iter()
+        .peekable();
+
+    while start.elapsed() < window {
+        if queries.peek().map_or(false, |at| start.elapsed() >= *at) {
+            queries.next();
+            send(&socket, &query())?;
+        }
+        let (n, from) = match socket.recv_from(&mut buf) {
+            Ok(received) => received,
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                continue
+            }
+            Err(err) => return Err(err),
+        };
+        let message = match parse(&buf[..n]) {
+            Some(message) => message,
+            None => continue,
+        };
+        for ann in message.announcements {
+            if ann.urn != *urn || found.iter().any(|(_, a)| a.peer == ann.peer) {
+                continue;
+            }
+            found.push((SocketAddr::new(from.ip(), ann.port), ann));
+        }
+    }
+    Ok(found)
+}
+
+/// Answer queries for our service with the given announcement, until an error occurs.
+/// The announcement is also sent once when starting.
+pub fn advertise(announcement: &Announcement) -> io::Result<()> {
+    let socket = socket()?;
+    let packet = response(announcement);
+    let mut buf = [0; 9000];
+
+    send(&socket, &packet)?;
+    loop {
+        let (n, _) = socket.recv_from(&mut buf)?;
+
+        if parse(&buf[..n]).map_or(false, |m| m.query) {
+            send(&socket, &packet)?;
+        }
+    }
+}
+
+/// Encode a query for our service.
+pub fn query() -> Vec<u8> {
+    let mut packet = header(0, 1, 0, 0);
+    write_name(&mut packet, SERVICE);
+    write_u16(&mut packet, TYPE_PTR);
+    write_u16(&mut packet, CLASS_IN);
+
+    packet
+}
+
+/// Encode a response advertising a project.
+pub fn response(announcement: &Announcement) -> Vec<u8> {
+    let instance = format!("{}.{}", announcement.peer.default_encoding(), SERVICE);
+    let mut packet = header(0x8400, 0, 1, 1);
+
+    // PTR record, pointing to our service instance.
+    write_name(&mut packet, SERVICE);
+    write_u16(&mut packet, TYPE_PTR);
+    write_u16(&mut packet, CLASS_IN);
+    write_u32(&mut packet, TTL);
+    let mut rdata = Vec::new();
+    write_name(&mut rdata, &instance);
+    write_u16(&mut packet, rdata.len() as u16);
+    packet.extend(rdata);
+
+    // TXT record of the instance, with the project.
+    write_name(&mut packet, &instance);
+    write_u16(&mut packet, TYPE_TXT);
+    write_u16(&mut packet, CLASS_IN | CACHE_FLUSH);
+    write_u32(&mut packet, TTL);
+    let mut rdata = Vec::new();
+    for entry in [
+        format!("urn={}", announcement.urn),
+        format!("peer={}", announcement.peer.default_encoding()),
+        format!("port={}", announcement.port),
+    ] {
+        rdata.push(entry.len() as u8);
+        rdata.extend(entry.as_bytes());
+    }
+    write_u16(&mut packet, rdata.len() as u16);
+    packet.extend(rdata);
+
+    packet
+}
+
+/// Parse an mDNS message. Returns `None` if the message is malformed.
+pub fn parse(packet: &[u8]) -> Option<Message> {
+    let mut reader = Reader { packet, pos: 0 };
+    let mut message = Message::default();
+
+    let _id = reader.u16()?;
+    let _flags = reader.u16()?;
+    let questions = reader.u16()?;
+    let records = reader.u16()? as usize + reader.u16()? as usize + reader.u16()? as usize;
+
+    for _ in 0..questions {
+        let name = reader.name()?;
+        let kind = reader.u16()?;
+        let _class = reader.u16()?;
+
+        if name.eq_ignore_ascii_case(SERVICE) && (kind == TYPE_PTR || kind == TYPE_ANY) {
+            message.query = true;
+        }
+    }
+    for _ in 0..records {
+        let name = reader.name()?;
+        let kind = reader.u16()?;
+        let _class = reader.u16()?;
+        let _ttl = reader.u32()?;
+        let len = reader.u16()? as usize;
+        let rdata = reader.bytes(len)?;
+
+        if kind != TYPE_TXT
+            || !name
+                .to_ascii_lowercase()
+                .ends_with(&format!(".{}", SERVICE))
+        {
+            continue;
+        }
+        if let Some(ann) = announcement(rdata) {
+            message.announcements.push(ann);
+        }
+    }
+    Some(message)
+}
+
+/// Parse the TXT record data of an announcement.
+fn announcement(mut rdata: &[u8]) -> Option<Announcement> {
+    let (mut urn, mut peer, mut port) = (None, None, None);
+
+    while let Some((len, rest)) = rdata.split_first() {
+        let len = *len as usize;
+        if rest.len() < len {
+            return None;
+        }
+        let entry = std::str::from_utf8(&rest[..len]).ok()?;
+        rdata = &rest[len..];
+
+        match entry.split_once('=') {
+            Some(("urn", val)) => urn = Urn::from_str(val).ok(),
+            Some(("peer", val)) => peer = PeerId::from_str(val).ok(),
+            Some(("port", val)) => port = val.parse().ok(),
+            _ => {}
+        }
+    }
+    Some(Announcement {
+        urn: urn?,
+        peer: peer?,
+        port: port?,
+    })
+}
+
+fn header(flags: u16, questions: u16, answers: u16, additional: u16) -> Vec<u8> {
+    let mut packet = Vec::new();
+    for field in [0, flags, questions, answers, 0, additional] {
+        write_u16(&mut packet, field);
+    }
+    packet
+}
+
+fn write_name(buf: &mut Vec<u8>, name: &str) {
+    for label in name.split('.') {
+        buf.push(label.len() as u8);
+        buf.extend(label.as_bytes());
+    }
+    buf.push(0);
+}
+
+fn write_u16(buf: &mut Vec<u8>, n: u16) {
+    buf.extend(n.to_be_bytes());
+}
+
+fn write_u32(buf: &mut Vec<u8>, n: u32) {
+    buf.extend(n.to_be_bytes());
+}
+
+/// Reader of DNS messages.
+struct Reader<'a> {
+    packet: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.packet.get(self.pos..self.pos + len)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        let bytes = self.bytes(2)?;
+        Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let bytes = self.bytes(4)?;
+        Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Read a domain name, following compression pointers.
+    fn name(&mut self) -> Option<String> {
+        let mut labels = Vec::new();
+        let mut pos = self.pos;
+        let mut jumped = false;
+        // Bound the number of pointers followed, in case of loops.
+        let mut jumps = 0;
+
+        loop {
+            let len = *self.packet.get(pos)? as usize;
+
+            if len == 0 {
+                pos += 1;
+                break;
+            } else if len & 0xc0 == 0xc0 {
+                let next = *self.packet.get(pos + 1)? as usize;
+                if !jumped {
+                    self.pos = pos + 2;
+                    jumped = true;
+                }
+                jumps += 1;
+                if jumps > 16 {
+                    return None;
+                }
+                pos = ((len & 0x3f) << 8) | next;
+            } else {
+                let label = self.packet.get(pos + 1..pos + 1 + len)?;
+                labels.push(String::from_utf8_lossy(label).into_owned());
+                pos += 1 + len;
+            }
+        }
+        if !jumped {
+            self.pos = pos;
+        }
+        Some(labels.join("."))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_query() {
+        let message = parse(&query()).unwrap();
+
+        assert!(message.query);
+        assert!(message.announcements.is_empty());
+    }
+
+    #[test]
+    fn test_response() {
+        let ann = Announcement {
+            urn: Urn::from_str("rad:git:hnrkbjg7r54q48sqsaho1n4qfxhi4nbmdh51y").unwrap(),
+            peer: PeerId::from(librad::SecretKey::new()),
+            port: 9418,
+        };
+        let message = parse(&response(&ann)).unwrap();
+
+        assert!(!message.query);
+        assert_eq!(message.announcements, vec![ann]);
+    }
+
+    #[test]
+    fn test_name_compression() {
+        // A query for `_radicle._tcp.local`, followed by a PTR record whose name points
+        // back to the question name.
+        let mut packet = header(0x8400, 1, 1, 0);
+        write_name(&mut packet, SERVICE);
+        write_u16(&mut packet, TYPE_PTR);
+        write_u16(&mut packet, CLASS_IN);
+        packet.extend([0xc0, 12]);
+        write_u16(&mut packet, TYPE_PTR);
+        write_u16(&mut packet, CLASS_IN);
+        write_u32(&mut packet, TTL);
+        write_u16(&mut packet, 2);
+        packet.extend([0xc0, 12]);
+
+        let message = parse(&packet).unwrap();
+        assert!(message.query);
+
+        // Truncated messages are rejected.
+        assert_eq!(parse(&packet[..packet.len() - 1]), None);
+        // Pointer loops are rejected.
+        let mut looped = header(0, 1, 0, 0);
+        looped.extend([0xc0, 12]);
+        assert_eq!(parse(&looped), None);
+    }
+}
@@ -0,0 +1,338 @@
+//! Merge drivers, for resolving trivial conflicts in generated files when merging patches.
+//!
+//! Drivers are configured in [`CONFIG_PATH`] on the branch patches are merged into, eg.
+//!
+//! ```toml
+//! [[driver]]
+//! path = "CHANGELOG.md"
+//! strategy = "union"
+//!
+//! [[driver]]
+//! path = "Cargo.lock"
+//! command = "cargo generate-lockfile"
+//! ```
+//!
+//! A driver either resolves a conflicted file with a merge strategy, or regenerates it with
+//! a command run from the repository root. Since commands are run on the merger's machine,
+//! the configuration is only ever read from the merger's branch, never from a patch.
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context as _};
+use serde::Deserialize;
+
+use crate::git;
+
+/// Path of a project's merge driver configuration, relative to the repository root.
+pub const CONFIG_PATH: &str = ".radicle/merge-drivers.toml";
+
+/// How a driver resolves a conflicted file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Strategy {
+    /// Keep the lines of both sides, eg. for changelogs.
+    Union,
+    /// Keep our side.
+    Ours,
+    /// Keep their side.
+    Theirs,
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Union => write!(f, "union"),
+            Self::Ours => write!(f, "ours"),
+            Self::Theirs => write!(f, "theirs"),
+        }
+    }
+}
+
+/// A merge driver.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Driver {
+    /// Pathspec of the files the driver applies to, eg. `CHANGELOG.md` or `*.lock`.
+    pub path: String,
+    /// Merge strategy.
+    #[serde(default)]
+    pub strategy: Option<Strategy>,
+    /// Command regenerating the files, run from the repository root.
+    #[serde(default)]
+    pub command: Option<String>,
+}
+
+impl Driver {
+    /// Whether the driver applies to the given path.
+    pub fn matches(&self, path: &str) -> bool {
+        git2::Pathspec::new(std::iter::once(self.path.as_str()))
+            .map(|spec| spec.matches_path(Path::new(path), git2::PathspecFlags::DEFAULT))
+            .unwrap_or(false)
+    }
+}
+
+impl fmt::Display for Driver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.strategy, &self.command) {
+            (Some(strategy), _) => write!(f, "{} merge", strategy),
+            (None, Some(cmd)) => write!(f, "`{}`", cmd),
+            (None, None) => write!(f, "none"),
+        }
+    }
+}
+
+/// A project's merge drivers. The first driver matching a file applies.
+#[derive(Debug, Default, Deserialize)]
+pub struct Drivers {
+    #[serde(default, rename = "driver")]
+    pub drivers: Vec<Driver>,
+}
+
+impl FromStr for Drivers {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let drivers: Self = toml::from_str(s)?;
+
+        for driver in &drivers.drivers {
+            if driver.strategy.is_some() == driver.command.is_some() {
+                anyhow::bail!(
+                    "merge driver for '{}' must have either a `strategy` or a `command`",
+                    driver.path
+                );
+            }
+        }
+        Ok(drivers)
+    }
+}
+
+impl Drivers {
+    /// Load the merge drivers configured in the given commit, if any.
+    pub fn load(repo: &git::Repository, commit: &git::Commit) -> Result<Self, anyhow::Error> {
+        let entry = match commit.tree()?.get_path(Path::new(CONFIG_PATH)) {
+            Ok(entry) => entry,
+            Err(err) if err.code() == git::ErrorCode::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err.into()),
+        };
+        let blob = repo.find_blob(entry.id())?;
+        let config = std::str::from_utf8(blob.content())
+            .with_context(|| format!("{} is not valid UTF-8", CONFIG_PATH))?;
+
+        config
+            .parse()
+            .with_context(|| format!("invalid merge driver configuration in {}", CONFIG_PATH))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.drivers.is_empty()
+    }
+
+    /// Get the driver for a path.
+    pub fn find(&self, path: &str) -> Option<&Driver> {
+        self.drivers.iter().find(|d| d.matches(path))
+    }
+
+    /// Conflicted paths of an index that no driver applies to.
+    pub fn unresolved(&self, index: &git2::Index) -> Result<Vec<String>, git2::Error> {
+        let mut paths = Vec::new();
+
+        for conflict in index.conflicts()? {
+            let path = conflict_path(&conflict?);
+
+            if self.find(&path).is_none() {
+                paths.push(path);
+            }
+        }
+        Ok(paths)
+    }
+
+    /// Resolve the conflicts of an ongoing merge in the repository's index and working
+    /// copy. Returns the resolved paths, with the driver used for each.
+    ///
+    /// Files regenerated by a command start out as our side, and are regenerated once
+    /// all other conflicts are resolved.
+    pub fn resolve(&self, repo: &git::Repository) -> Result<Vec<(String, &Driver)>, anyhow::Error> {
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| anyhow!("cannot resolve conflicts in a bare repository"))?;
+        let mut index = repo.index()?;
+        let conflicts = index.conflicts()?.collect::<Result<Vec<_>, _>>()?;
+        let mut resolved = Vec::new();
+        let mut commands = Vec::new();
+
+        for conflict in conflicts {
+            let path = conflict_path(&conflict);
+            let driver = if let Some(driver) = self.find(&path) {
+                driver
+            } else {
+                continue;
+            };
+            let strategy = driver.strategy.unwrap_or(Strategy::Ours);
+
+            match merge_file(repo, &conflict, strategy)? {
+                Some(content) => {
+                    fs::write(workdir.join(&path), content)?;
+                    index.add_path(Path::new(&path))?;
+                }
+                None => {
+                    fs::remove_file(workdir.join(&path)).ok();
+                    index.remove_path(Path::new(&path))?;
+                }
+            }
+            if let Some(cmd) = &driver.command {
+                if !commands.contains(cmd) {
+                    commands.push(cmd.clone());
+                }
+            }
+            resolved.push((path, driver));
+        }
+
+        for cmd in &commands {
+            let output = Command::new("sh")
+                .arg("-c")
+                .arg(cmd)
+                .current_dir(workdir)
+                .output()
+                .with_context(|| format!("failed to run merge driver `{}`", cmd))?;
+
+            if !output.status.success() {
+                anyhow::bail!(
+                    "merge driver `{}` failed: {}",
+                    cmd,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+            }
+        }
+        for (path, driver) in &resolved {
+            if driver.command.is_none() {
+                continue;
+            }
+            if workdir.join(path).exists() {
+                index.add_path(Path::new(path))?;
+            } else {
+                index.remove_path(Path::new(path))?;
+            }
+        }
+        index.write()?;
+
+        Ok(resolved)
+    }
+}
+
+/// Path of a conflicted file.
+fn conflict_path(conflict: &git2::IndexConflict) -> String {
+    conflict
+        .our
+        .as_ref()
+        .or_else(|| conflict.their.as_ref())
+        .or_else(|| conflict.ancestor.as_ref())
+        .map(|e| String::from_utf8_lossy(&e.path).into_owned())
+        .unwrap_or_default()
+}
+
+/// Merge a conflicted file with the given strategy. Returns `None` if the file should
+/// be deleted.
+fn merge_file(
+    repo: &git::Repository,
+    conflict: &git2::IndexConflict,
+    strategy: Strategy,
+) -> Result<Option<Vec<u8>>, anyhow::Error> {
+    let content = |entry: &Option<git2::IndexEntry>| -> Result<Option<Vec<u8>>, git2::Error> {
+        entry
+            .as_ref()
+            .map(|e| repo.find_blob(e.id).map(|b| b.content().to_vec()))
+            .transpose()
+    };
+    let (ours, theirs) = (content(&conflict.our)?, content(&conflict.their)?);
+
+    match (strategy, ours, theirs) {
+        (Strategy::Ours, ours, _) => Ok(ours),
+        (Strategy::Theirs, _, theirs) => Ok(theirs),
+        (Strategy::Union, Some(ours), Some(theirs)) => {
+            let base = content(&conflict.ancestor)?.unwrap_or_default();
+
+            union(repo, &ours, &base, &theirs).map(Some)
+        }
+        // One side deleted the file: keep the other side's changes.
+        (Strategy::Union, ours, theirs) => Ok(ours.or(theirs)),
+    }
+}
+
+/// Union merge of a file, using `git merge-file`.
+fn union(
+    repo: &git::Repository,
+    ours: &[u8],
+    base: &[u8],
+    theirs: &[u8],
+) -> Result<Vec<u8>, anyhow::Error> {
+    let files = [
+        (repo.path().join("RAD_MERGE_OURS"), ours),
+        (repo.path().join("RAD_MERGE_BASE"), base),
+        (repo.path().join("RAD_MERGE_THEIRS"), theirs),
+    ];
+    for (path, content) in &files {
+        fs::write(path, content)?;
+    }
+    let output = Command::new("git")
+        .arg("merge-file")
+        .arg("-p")
+        .arg("--union")
+        .args(files.iter().map(|(path, _)| path))
+        .output();
+
+    for (path, _) in &files {
+        fs::remove_file(path).ok();
+    }
+    let output = output.context("failed to run `git merge-file`")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "`git merge-file` failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(output.stdout)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_drivers() {
+        let drivers = Drivers::from_str(
+            r#"
+            [[driver]]
+            path = "CHANGELOG.md"
+            strategy = "union"
+
+            [[driver]]
+            path = "*.lock"
+            command = "cargo generate-lockfile"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            drivers.find("CHANGELOG.md").unwrap().strategy,
+            Some(Strategy::Union)
+        );
+        assert_eq!(
+            drivers.find("Cargo.lock").unwrap().command.as_deref(),
+            Some("cargo generate-lockfile")
+        );
+        assert!(drivers.find("crates/a/Cargo.lock").is_some());
+        assert!(drivers.find("src/lib.rs").is_none());
+        assert!(Drivers::from_str("").unwrap().is_empty());
+
+        // Drivers need exactly one of a strategy and a command.
+        assert!(Drivers::from_str("[[driver]]\npath = \"a\"").is_err());
+        assert!(Drivers::from_str(
+            "[[driver]]\npath = \"a\"\nstrategy = \"ours\"\ncommand = \"true\""
+        )
+        .is_err());
+        assert!(Drivers::from_str("[[driver]]\npath = \"a\"\nstrategy = \"rebase\"").is_err());
+    }
+}
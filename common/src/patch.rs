@@ -1,15 +1,12 @@
 //! Patch-related functions and types.
-use std::convert::TryInto;
 use std::fmt;
 
 use librad::git::identities;
 use librad::git::identities::project::heads::DefaultBranchHead;
 use librad::git::refs::Refs;
 use librad::git::storage::{ReadOnly, ReadOnlyStorage};
-use librad::git::{Storage, Urn};
-use librad::PeerId;
+use librad::git::Storage;
 
-use git_trailers as trailers;
 use radicle_git_ext as git;
 use serde::Serialize;
 
@@ -166,53 +163,6 @@ pub fn is_merged(
     }
 }
 
-/// Create a "patch" tag under:
-///
-/// > /refs/namespaces/<project>/refs/tags/patches/<patch>/<remote>/<revision>
-///
-pub fn create_tag(
-    repo: &git2::Repository,
-    author: &Urn,
-    project: &Urn,
-    patch_id: cob::PatchId,
-    peer_id: &PeerId,
-    commit: git2::Oid,
-    revision: usize,
-) -> Result<git2::Oid, Error> {
-    let commit = repo.find_commit(commit)?;
-    let name = format!("{patch_id}/{peer_id}/{revision}");
-    let trailers = [
-        trailers::Trailer {
-            token: "Rad-Cob".try_into().unwrap(),
-            values: vec![patch_id.to_string().into()],
-        },
-        trailers::Trailer {
-            token: "Rad-Author".try_into().unwrap(),
-            values: vec![author.to_string().into()],
-        },
-        trailers::Trailer {
-            token: "Rad-Peer".try_into().unwrap(),
-            values: vec![peer_id.to_string().into()],
-        },
-    ]
-    .iter()
-    .map(|t| t.display(": ").to_string())
-    .collect::<Vec<_>>()
-    .join("\n");
-
-    repo.set_namespace(&project.to_string())?;
-
-    let oid = repo.tag(
-        &name,
-        commit.as_object(),
-        &repo.signature()?,
-        &trailers,
-        false,
-    )?;
-
-    Ok(oid)
-}
-
 #[derive(Debug, Default)]
 pub struct MergeTargets {
     pub merged: Vec<project::PeerInfo>,
@@ -224,6 +174,22 @@ pub fn find_merge_targets<S>(
     storage: &S,
     project: &project::Metadata,
 ) -> anyhow::Result<MergeTargets>
+where
+    S: AsRef<ReadOnly>,
+{
+    let branch = git::RefLike::from(project.default_branch.clone());
+
+    find_merge_targets_on(head, &branch, storage, project)
+}
+
+/// Find merge targets on the given branch of tracked peers. Peers that don't have
+/// the branch are skipped.
+pub fn find_merge_targets_on<S>(
+    head: &git2::Oid,
+    branch: &git::RefLike,
+    storage: &S,
+    project: &project::Metadata,
+) -> anyhow::Result<MergeTargets>
 where
     S: AsRef<ReadOnly>,
 {
@@ -232,8 +198,11 @@ where
     let repo = git2::Repository::open_bare(storage.path())?;
 
     for (peer_id, peer_info) in project::tracked(project, storage)? {
-        let target = project.remote_head(&peer_id);
-        let target_oid = storage.reference_oid(&target)?;
+        let target = project.remote_branch(&peer_id, branch.clone());
+        let target_oid = match storage.reference_oid(&target) {
+            Ok(oid) => oid,
+            Err(_) => continue,
+        };
 
         if is_merged(&repo, target_oid.into(), *head)? {
             targets.merged.push(peer_info);
@@ -267,6 +236,18 @@ pub fn patch_merge_target_oid(
     }
 }
 
+/// Walk the commits between the merge base and a head, newest first.
+fn range_walk<'a>(
+    repo: &'a git2::Repository,
+    base: &git2::Oid,
+    head: &git2::Oid,
+) -> anyhow::Result<git2::Revwalk<'a>> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_range(&format!("{}..{}", base, head))?;
+
+    Ok(revwalk)
+}
+
 /// Return commits between the merge base and a head.
 pub fn patch_commits<'a>(
     repo: &'a git2::Repository,
@@ -274,13 +255,399 @@ pub fn patch_commits<'a>(
     head: &git2::Oid,
 ) -> anyhow::Result<Vec<git2::Commit<'a>>> {
     let mut commits = Vec::new();
-    let mut revwalk = repo.revwalk()?;
-    revwalk.push_range(&format!("{}..{}", base, head))?;
 
-    for rev in revwalk {
+    for rev in range_walk(repo, base, head)? {
         let commit = repo.find_commit(rev?)?;
         commits.push(commit);
     }
 
     Ok(commits)
 }
+
+/// Return up to `limit` commits between the merge base and a head, newest first, along
+/// with the total number of commits. Commits past the limit are only counted.
+pub fn patch_commits_limited<'a>(
+    repo: &'a git2::Repository,
+    base: &git2::Oid,
+    head: &git2::Oid,
+    limit: usize,
+) -> anyhow::Result<(Vec<git2::Commit<'a>>, usize)> {
+    let mut commits = Vec::new();
+    let mut total = 0;
+
+    for rev in range_walk(repo, base, head)? {
+        let rev = rev?;
+
+        if commits.len() < limit {
+            commits.push(repo.find_commit(rev)?);
+        }
+        total += 1;
+    }
+
+    Ok((commits, total))
+}
+
+/// Find the local branches with commits on top of a published patch revision, ie. commits
+/// that are not yet part of the patch, along with the number of such commits.
+pub fn unpublished_commits(
+    repo: &git2::Repository,
+    revision: &git2::Oid,
+) -> anyhow::Result<Vec<(String, usize)>> {
+    let mut branches = Vec::new();
+
+    for branch in repo.branches(Some(git2::BranchType::Local))? {
+        let (branch, _) = branch?;
+        let (name, tip) = match (branch.name()?, branch.get().target()) {
+            (Some(name), Some(tip)) => (name, tip),
+            _ => continue,
+        };
+        if tip == *revision || !repo.graph_descendant_of(tip, *revision)? {
+            continue;
+        }
+        let (ahead, _) = repo.graph_ahead_behind(tip, *revision)?;
+
+        branches.push((name.to_owned(), ahead));
+    }
+    Ok(branches)
+}
+
+/// Count the commits of `head` that aren't in `since`. Used to tell whether a branch moved
+/// since eg. an approval. Fails if `since` isn't in the repository.
+pub fn commits_since(
+    repo: &git2::Repository,
+    since: &git2::Oid,
+    head: &git2::Oid,
+) -> Result<usize, git2::Error> {
+    let mut revwalk = repo.revwalk()?;
+
+    revwalk.push(*head)?;
+    revwalk.hide(*since)?;
+
+    revwalk.try_fold(0, |count, oid| oid.map(|_| count + 1))
+}
+
+/// A commit of a patch revision, compared with its counterpart in another revision.
+pub enum Interdiff<'a> {
+    /// The commit's changes are the same in both revisions.
+    Unchanged {
+        old: git2::Commit<'a>,
+        new: git2::Commit<'a>,
+    },
+    /// The commit's changes differ. The diff is a diff of the commit diffs.
+    Changed {
+        old: git2::Commit<'a>,
+        new: git2::Commit<'a>,
+        diff: Vec<String>,
+    },
+    /// The commit only exists in the newer revision.
+    Added(git2::Commit<'a>),
+    /// The commit only exists in the older revision.
+    Removed(git2::Commit<'a>),
+}
+
+/// Compare the commits of two patch revisions, given as `(base, head)` pairs, in the
+/// style of `git range-diff`. Commits are matched by patch-id, or else by summary, and
+/// are returned in the order of the newer revision, preceded by commits that were
+/// removed.
+pub fn interdiff<'a>(
+    repo: &'a git2::Repository,
+    old: (&git2::Oid, &git2::Oid),
+    new: (&git2::Oid, &git2::Oid),
+) -> anyhow::Result<Vec<Interdiff<'a>>> {
+    let mut old = patch_commits(repo, old.0, old.1)?
+        .into_iter()
+        .rev()
+        .map(Some)
+        .collect::<Vec<_>>();
+    let new = patch_commits(repo, new.0, new.1)?
+        .into_iter()
+        .rev()
+        .collect::<Vec<_>>();
+    let old_ids = old
+        .iter()
+        .flatten()
+        .map(|c| patch_id(repo, c))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Match commits with the same changes first, so that a reworded commit isn't
+    // matched with another one by summary.
+    let mut counterparts = Vec::with_capacity(new.len());
+    for commit in &new {
+        let id = patch_id(repo, commit)?;
+        let counterpart = old_ids
+            .iter()
+            .position(|old_id| *old_id == id)
+            .and_then(|i| old[i].take());
+
+        counterparts.push(counterpart);
+    }
+    for (commit, counterpart) in new.iter().zip(counterparts.iter_mut()) {
+        if counterpart.is_none() {
+            *counterpart = old
+                .iter_mut()
+                .find(|c| matches!(c, Some(c) if c.summary_bytes() == commit.summary_bytes()))
+                .and_then(Option::take);
+        }
+    }
+
+    let mut matched = Vec::new();
+    for (commit, counterpart) in new.into_iter().zip(counterparts) {
+        let item = match counterpart {
+            Some(counterpart) => {
+                let diff = commit_diff_diff(repo, &counterpart, &commit)?;
+
+                if diff.is_empty() {
+                    Interdiff::Unchanged {
+                        old: counterpart,
+                        new: commit,
+                    }
+                } else {
+                    Interdiff::Changed {
+                        old: counterpart,
+                        new: commit,
+                        diff,
+                    }
+                }
+            }
+            None => Interdiff::Added(commit),
+        };
+        matched.push(item);
+    }
+
+    let mut result = old
+        .into_iter()
+        .flatten()
+        .map(Interdiff::Removed)
+        .collect::<Vec<_>>();
+    result.extend(matched);
+
+    Ok(result)
+}
+
+/// Diff the changes introduced by two commits, returning the lines of the resulting diff.
+fn commit_diff_diff(
+    repo: &git2::Repository,
+    old: &git2::Commit,
+    new: &git2::Commit,
+) -> anyhow::Result<Vec<String>> {
+    let old = commit_diff(repo, old)?;
+    let new = commit_diff(repo, new)?;
+    let patch = git2::Patch::from_buffers(old.as_bytes(), None, new.as_bytes(), None, None)?;
+    let mut lines = Vec::new();
+
+    for h in 0..patch.num_hunks() {
+        let (hunk, count) = patch.hunk(h)?;
+
+        lines.push(String::from_utf8_lossy(hunk.header()).trim_end().to_owned());
+        for l in 0..count {
+            let line = patch.line_in_hunk(h, l)?;
+            let content = String::from_utf8_lossy(line.content());
+
+            lines.push(format!(
+                "{}{}",
+                line.origin(),
+                content.trim_end_matches('\n')
+            ));
+        }
+    }
+    Ok(lines)
+}
+
+/// Get the patch-id of a commit, in the style of `git patch-id`: a hash of the
+/// changes it introduces, ignoring whitespace and line numbers.
+fn patch_id(repo: &git2::Repository, commit: &git2::Commit) -> anyhow::Result<git2::Oid> {
+    let diff = tree_diff(repo, commit)?;
+    let mut text = Vec::new();
+
+    diff.print(git2::DiffFormat::Patch, |delta, _, line| {
+        match line.origin() {
+            'F' => {
+                for path in [delta.old_file().path(), delta.new_file().path()]
+                    .iter()
+                    .flatten()
+                {
+                    text.extend_from_slice(path.to_string_lossy().as_bytes());
+                    text.push(b'\n');
+                }
+            }
+            origin @ ('+' | '-' | ' ') => {
+                text.push(origin as u8);
+                text.extend(line.content().iter().filter(|b| !b.is_ascii_whitespace()));
+                text.push(b'\n');
+            }
+            _ => {}
+        }
+        true
+    })?;
+
+    Ok(git2::Oid::hash_object(git2::ObjectType::Blob, &text)?)
+}
+
+/// Diff a commit against its first parent.
+fn tree_diff<'a>(
+    repo: &'a git2::Repository,
+    commit: &git2::Commit,
+) -> Result<git2::Diff<'a>, git2::Error> {
+    let tree = commit.tree()?;
+    let parent = if commit.parent_count() > 0 {
+        Some(commit.parent(0)?.tree()?)
+    } else {
+        None
+    };
+    repo.diff_tree_to_tree(parent.as_ref(), Some(&tree), None)
+}
+
+/// Get the changes introduced by a commit as a patch, without line numbers, so
+/// that the result is stable across rebases.
+fn commit_diff(repo: &git2::Repository, commit: &git2::Commit) -> anyhow::Result<String> {
+    let diff = tree_diff(repo, commit)?;
+    let mut text = String::new();
+
+    diff.print(git2::DiffFormat::Patch, |_, _, line| {
+        match line.origin() {
+            '+' | '-' | ' ' => text.push(line.origin()),
+            // Hunk headers contain line numbers, which change when the base does.
+            'H' => {
+                text.push_str("@@\n");
+                return true;
+            }
+            _ => {}
+        }
+        text.push_str(&String::from_utf8_lossy(line.content()));
+        true
+    })?;
+
+    Ok(text)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_patch_commits_limited() {
+        let path = std::env::temp_dir()
+            .join("rad")
+            .join("test-patch-commits-limited");
+        std::fs::remove_dir_all(&path).ok();
+
+        let repo = git2::Repository::init(&path).unwrap();
+        let oids = crate::git::linear_history(&repo, 5);
+        let (base, head) = (oids[0], oids[4]);
+
+        let (commits, total) = patch_commits_limited(&repo, &base, &head, 2).unwrap();
+        assert_eq!(total, 4);
+        assert_eq!(
+            commits.iter().map(|c| c.id()).collect::<Vec<_>>(),
+            vec![oids[4], oids[3]]
+        );
+
+        let (commits, total) = patch_commits_limited(&repo, &base, &head, 10).unwrap();
+        assert_eq!(total, 4);
+        assert_eq!(commits.len(), 4);
+        assert_eq!(
+            patch_commits(&repo, &base, &head).unwrap().len(),
+            commits.len()
+        );
+    }
+
+    #[test]
+    fn test_interdiff() {
+        let path = std::env::temp_dir().join("rad").join("test-interdiff");
+        std::fs::remove_dir_all(&path).ok();
+
+        let repo = git2::Repository::init(&path).unwrap();
+        let sig = git2::Signature::now("anonymous", "anonymous@radicle.xyz").unwrap();
+        let commit = |parent: git2::Oid, msg: &str, files: &[(&str, &str)]| {
+            let parent = repo.find_commit(parent).unwrap();
+            let mut tree = repo.treebuilder(Some(&parent.tree().unwrap())).unwrap();
+
+            for (name, content) in files {
+                let blob = repo.blob(content.as_bytes()).unwrap();
+                tree.insert(name, blob, 0o100644).unwrap();
+            }
+            let tree = repo.find_tree(tree.write().unwrap()).unwrap();
+
+            repo.commit(None, &sig, &sig, msg, &tree, &[&parent])
+                .unwrap()
+        };
+        let empty = repo
+            .find_tree(repo.treebuilder(None).unwrap().write().unwrap())
+            .unwrap();
+        let root = repo.commit(None, &sig, &sig, "Root", &empty, &[]).unwrap();
+
+        let old_base = commit(root, "Base", &[("README", "Hello\n")]);
+        let old_a = commit(old_base, "Add a", &[("a", "a\n")]);
+        let old_b = commit(old_a, "Add b", &[("b", "b\n")]);
+        let old_c = commit(old_b, "Add c", &[("c", "c\n")]);
+
+        // Rebased, with the first commit reworded, the second one changed, the
+        // third one dropped and a new one added.
+        let new_base = commit(old_base, "Update", &[("README", "Hello, world\n")]);
+        let new_a = commit(new_base, "Add file a", &[("a", "a\n")]);
+        let new_b = commit(new_a, "Add b", &[("b", "bb\n")]);
+        let new_d = commit(new_b, "Add d", &[("d", "d\n")]);
+
+        let interdiff = interdiff(&repo, (&old_base, &old_c), (&new_base, &new_d)).unwrap();
+        let items = interdiff
+            .iter()
+            .map(|item| match item {
+                Interdiff::Unchanged { old, new } => ("unchanged", Some(old.id()), Some(new.id())),
+                Interdiff::Changed { old, new, .. } => ("changed", Some(old.id()), Some(new.id())),
+                Interdiff::Added(new) => ("added", None, Some(new.id())),
+                Interdiff::Removed(old) => ("removed", Some(old.id()), None),
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            items,
+            vec![
+                ("removed", Some(old_c), None),
+                ("unchanged", Some(old_a), Some(new_a)),
+                ("changed", Some(old_b), Some(new_b)),
+                ("added", None, Some(new_d)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_commits_since() {
+        let path = std::env::temp_dir().join("rad").join("test-commits-since");
+        std::fs::remove_dir_all(&path).ok();
+
+        let repo = git2::Repository::init(&path).unwrap();
+        let oids = crate::git::linear_history(&repo, 4);
+        let (base, head) = (oids[0], oids[3]);
+
+        assert_eq!(commits_since(&repo, &base, &head).unwrap(), 3);
+        assert_eq!(commits_since(&repo, &oids[2], &head).unwrap(), 1);
+        assert_eq!(commits_since(&repo, &head, &head).unwrap(), 0);
+        // Commits made after `head` don't count.
+        assert_eq!(commits_since(&repo, &head, &base).unwrap(), 0);
+        // Nor can commits be counted since an unknown commit.
+        assert!(commits_since(&repo, &git2::Oid::zero(), &head).is_err());
+    }
+
+    #[test]
+    fn test_unpublished_commits() {
+        let path = std::env::temp_dir()
+            .join("rad")
+            .join("test-unpublished-commits");
+        std::fs::remove_dir_all(&path).ok();
+
+        let repo = git2::Repository::init(&path).unwrap();
+        let oids = crate::git::linear_history(&repo, 4);
+        repo.reference("refs/heads/published", oids[1], true, "test")
+            .unwrap();
+        repo.reference("refs/heads/feature", oids[3], true, "test")
+            .unwrap();
+        repo.reference("refs/heads/old", oids[0], true, "test")
+            .unwrap();
+
+        assert_eq!(
+            unpublished_commits(&repo, &oids[1]).unwrap(),
+            vec![(String::from("feature"), 2)]
+        );
+        assert!(unpublished_commits(&repo, &oids[3]).unwrap().is_empty());
+    }
+}
@@ -1,4 +1,6 @@
 //! Person-related functionality.
+use std::collections::BTreeSet;
+
 use anyhow::{Context, Error, Result};
 
 use librad::{canonical::Cstring, git::identities::local::LocalIdentity};
@@ -6,7 +8,7 @@ use librad::{canonical::Cstring, git::identities::local::LocalIdentity};
 use librad::git::identities::Person;
 use librad::git::storage::Storage;
 
-use librad::crypto::BoxedSigner;
+use librad::crypto::{BoxedSigner, PublicKey};
 use librad::identities::payload;
 use librad::identities::payload::HasNamespace;
 use librad::profile::Profile;
@@ -76,6 +78,31 @@ pub fn local(storage: &Storage) -> Result<LocalIdentity, local::Error> {
     local::default(storage)
 }
 
+/// Set the key delegations of the local identity, signing the update with the
+/// storage's key. Returns the updated person.
+pub fn set_keys(storage: &Storage, keys: BTreeSet<PublicKey>) -> Result<Person> {
+    let id = local::default(storage)?;
+    let exts = id
+        .payload()
+        .exts()
+        .map(|(namespace, val)| payload::Ext {
+            namespace: namespace.clone(),
+            val: val.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    let new = person::update(
+        storage,
+        &id.urn(),
+        Some(id.urn()),
+        None,
+        exts,
+        Some(keys.into_iter()),
+    )?;
+
+    Ok(new)
+}
+
 /// Set an ENS payload for the local identity.
 /// Returns the updated person.
 pub fn set_ens_payload(ens: Ens, storage: &Storage) -> Result<Person> {
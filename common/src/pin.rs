@@ -0,0 +1,281 @@
+//! Pinning of trusted project delegate sets.
+//!
+//! For projects we consume but don't develop, we can pin the set of delegate keys we
+//! currently accept. When a sync brings in an identity document whose delegates differ
+//! from the pinned set, the update is refused until the user explicitly accepts it.
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+use librad::git::refs::Refs;
+use librad::git::storage::{ReadOnly, Storage};
+use librad::git::types::{Force, Namespace, Reference};
+use librad::git::Urn;
+use librad::PeerId;
+use serde::{Deserialize, Serialize};
+
+use crate::store::Store;
+use crate::{git, project};
+
+/// File name of the pins file, in the profile scope.
+pub const FILE_NAME: &str = "pins.toml";
+
+/// A pinned delegate set.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pin {
+    /// Encoded peer ids of the accepted delegates.
+    pub delegates: BTreeSet<String>,
+}
+
+impl Pin {
+    /// Create a pin from a set of delegates.
+    pub fn new<'a>(delegates: impl IntoIterator<Item = &'a PeerId>) -> Self {
+        Self {
+            delegates: delegates
+                .into_iter()
+                .map(|p| p.default_encoding())
+                .collect(),
+        }
+    }
+
+    /// Get the pinned delegates. Entries that can't be decoded are skipped.
+    pub fn peers(&self) -> HashSet<PeerId> {
+        self.delegates
+            .iter()
+            .filter_map(|p| PeerId::from_str(p).ok())
+            .collect()
+    }
+}
+
+/// Result of verifying a project against its pin.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verification {
+    /// The project has no pinned delegate set.
+    Unpinned,
+    /// The project delegates match the pinned set.
+    Match,
+    /// The project delegates differ from the pinned set.
+    Mismatch {
+        /// Delegates that are not part of the pinned set.
+        added: Vec<PeerId>,
+        /// Pinned delegates that are no longer delegates.
+        removed: Vec<PeerId>,
+    },
+}
+
+impl Verification {
+    pub fn is_mismatch(&self) -> bool {
+        matches!(self, Self::Mismatch { .. })
+    }
+}
+
+/// Pinned delegate sets, keyed by project URN.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Pins {
+    #[serde(default)]
+    pub projects: BTreeMap<String, Pin>,
+}
+
+impl Store for Pins {
+    const FILE_NAME: &'static str = FILE_NAME;
+}
+
+impl Pins {
+    /// Get the pin of a project, if any.
+    pub fn get(&self, urn: &Urn) -> Option<&Pin> {
+        self.projects.get(&urn.to_string())
+    }
+
+    /// Pin the given delegate set for a project, replacing any existing pin.
+    pub fn pin<'a>(&mut self, urn: &Urn, delegates: impl IntoIterator<Item = &'a PeerId>) {
+        self.projects.insert(urn.to_string(), Pin::new(delegates));
+    }
+
+    /// Remove a project's pin. Returns whether the project was pinned.
+    pub fn unpin(&mut self, urn: &Urn) -> bool {
+        self.projects.remove(&urn.to_string()).is_some()
+    }
+
+    /// Verify a set of delegates against a project's pin.
+    pub fn verify(&self, urn: &Urn, delegates: &HashSet<PeerId>) -> Verification {
+        let pinned = if let Some(pin) = self.get(urn) {
+            pin.peers()
+        } else {
+            return Verification::Unpinned;
+        };
+        let mut added = delegates.difference(&pinned).copied().collect::<Vec<_>>();
+        let mut removed = pinned.difference(delegates).copied().collect::<Vec<_>>();
+
+        if added.is_empty() && removed.is_empty() {
+            return Verification::Match;
+        }
+        added.sort_by_key(|p| p.default_encoding());
+        removed.sort_by_key(|p| p.default_encoding());
+
+        Verification::Mismatch { added, removed }
+    }
+
+    /// Verify a project's current delegates against its pin.
+    pub fn verify_project(&self, project: &project::Metadata) -> Verification {
+        self.verify(&project.urn, &project.remotes)
+    }
+}
+
+/// Get the name of a project's identity ref in the monorepo.
+fn identity_ref(urn: &Urn) -> String {
+    format!("refs/namespaces/{}/refs/rad/id", urn.encode_id())
+}
+
+/// Get the current target of a project's identity ref.
+fn identity_head<S>(storage: &S, urn: &Urn) -> anyhow::Result<Option<git::Oid>>
+where
+    S: AsRef<ReadOnly>,
+{
+    let repo = git::Repository::open_bare(storage.as_ref().path())?;
+    let reference = repo.find_reference(&identity_ref(urn)).ok();
+
+    Ok(reference.and_then(|r| r.target()))
+}
+
+/// A project's identity head before a sync, to restore if the sync brings in an identity
+/// update that doesn't match the pinned delegate set.
+#[derive(Debug)]
+pub struct Snapshot {
+    urn: Urn,
+    head: Option<git::Oid>,
+}
+
+impl Snapshot {
+    /// Record the current identity head of a project.
+    pub fn take<S>(storage: &S, urn: &Urn) -> anyhow::Result<Self>
+    where
+        S: AsRef<ReadOnly>,
+    {
+        Ok(Self {
+            urn: urn.clone(),
+            head: identity_head(storage, urn)?,
+        })
+    }
+
+    /// Restore the recorded identity head, and re-sign our refs so that they match it.
+    ///
+    /// The restored identity must verify, and match the pin. Otherwise, or if re-signing
+    /// fails, the identity is left as it was after the sync, and an error is returned. If
+    /// the project was not in storage before the sync, its refs are removed.
+    pub fn restore(&self, storage: &Storage, pins: &Pins) -> anyhow::Result<()> {
+        let head = match self.head {
+            Some(head) => head,
+            None => return remove(storage, &self.urn),
+        };
+        let current = identity_head(storage, &self.urn)?;
+        let reference = Reference::rad_id(Namespace::from(self.urn.clone()));
+
+        reference.create(
+            storage.as_raw(),
+            head,
+            Force::True,
+            "rad: refuse identity update not matching pinned delegates",
+        )?;
+
+        if let Err(err) = self.check(storage, pins) {
+            if let Some(current) = current {
+                reference.create(
+                    storage.as_raw(),
+                    current,
+                    Force::True,
+                    "rad: roll back identity restore",
+                )?;
+            }
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    fn check(&self, storage: &Storage, pins: &Pins) -> anyhow::Result<()> {
+        let project = project::get(storage, &self.urn)?
+            .ok_or_else(|| anyhow!("restored identity of {} was not found", self.urn))?;
+
+        project.verified(storage)?;
+        if pins.verify_project(&project).is_mismatch() {
+            anyhow::bail!(
+                "restored identity of {} does not match the pinned delegate set",
+                self.urn
+            );
+        }
+        Refs::update(storage, &self.urn)?;
+
+        Ok(())
+    }
+}
+
+/// Remove the refs of a project that was first fetched by a refused sync.
+fn remove(storage: &Storage, urn: &Urn) -> anyhow::Result<()> {
+    let repo = storage.as_raw();
+    let glob = format!("refs/namespaces/{}/*", urn.encode_id());
+    let names = repo
+        .references_glob(&glob)?
+        .filter_map(|r| r.ok().and_then(|r| r.name().map(ToOwned::to_owned)))
+        .collect::<Vec<_>>();
+
+    for name in names {
+        repo.find_reference(&name)?.delete()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use librad::SecretKey;
+
+    fn peer() -> PeerId {
+        PeerId::from(SecretKey::new())
+    }
+
+    #[test]
+    fn test_pin_verify() {
+        let urn = Urn::from_str("rad:git:hnrkmg77m8tfzj4gi4pa4mbhgysfgzwntjpao").unwrap();
+        let (alice, bob, eve) = (peer(), peer(), peer());
+        let mut pins = Pins::default();
+
+        assert_eq!(
+            pins.verify(&urn, &vec![alice].into_iter().collect()),
+            Verification::Unpinned
+        );
+
+        pins.pin(&urn, &[alice, bob]);
+
+        assert_eq!(
+            pins.verify(&urn, &vec![bob, alice].into_iter().collect()),
+            Verification::Match
+        );
+        assert_eq!(
+            pins.verify(&urn, &vec![alice, eve].into_iter().collect()),
+            Verification::Mismatch {
+                added: vec![eve],
+                removed: vec![bob],
+            }
+        );
+        assert!(pins.unpin(&urn));
+        assert!(!pins.unpin(&urn));
+    }
+
+    #[test]
+    fn test_pins_roundtrip() {
+        let urn = Urn::from_str("rad:git:hnrkmg77m8tfzj4gi4pa4mbhgysfgzwntjpao").unwrap();
+        let alice = peer();
+        let mut pins = Pins::default();
+
+        pins.pin(&urn, &[alice]);
+
+        let encoded = toml::to_string_pretty(&pins).unwrap();
+        let decoded: Pins = toml::from_str(&encoded).unwrap();
+
+        assert_eq!(decoded.get(&urn), pins.get(&urn));
+        assert_eq!(
+            decoded.get(&urn).unwrap().peers(),
+            vec![alice].into_iter().collect()
+        );
+    }
+}
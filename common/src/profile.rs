@@ -1,7 +1,8 @@
 //! User profile related functions.
-use std::{env, fmt, path};
+use std::str::FromStr;
+use std::{env, fmt, fs, io, path};
 
-use anyhow::{anyhow, Error, Result};
+use anyhow::{anyhow, Context as _, Error, Result};
 use serde::{de::DeserializeOwned, Serialize};
 
 pub use librad::profile::{LnkHome, Profile, ProfileId};
@@ -14,10 +15,13 @@ use librad::PeerId;
 use librad::{git::storage::ReadOnly, git::Storage, keystore::crypto::Crypto};
 
 use crate::args;
+use crate::config::Config;
 use crate::keys;
 
 /// Environment var that sets the radicle home directory.
 pub const RAD_HOME: &str = "RAD_HOME";
+/// Environment var that overrides the active profile.
+pub const RAD_PROFILE: &str = "RAD_PROFILE";
 
 /// Create a new profile.
 pub fn create<C: Crypto>(home: impl Into<LnkHome>, crypto: C) -> Result<(Profile, PeerId)>
@@ -49,8 +53,22 @@ pub fn home() -> LnkHome {
     home.unwrap_or_default()
 }
 
-/// Get the default profile. Fails if there is no profile.
+/// Get the default profile: the one set with [`RAD_PROFILE`], or else the active
+/// profile. Fails if there is no profile.
 pub fn default() -> Result<Profile, Error> {
+    if let Ok(id) = env::var(RAD_PROFILE) {
+        let id = ProfileId::from_str(&id)
+            .map_err(|_| anyhow!("invalid profile id '{}' in {}", id, RAD_PROFILE))?;
+
+        return get(&id)?.ok_or_else(|| {
+            args::Error::WithHint {
+                err: anyhow!("Could not load radicle profile {}", id),
+                hint: "To list your profiles, run `rad profile list`.",
+            }
+            .into()
+        });
+    }
+
     let error = args::Error::WithHint {
         err: anyhow!("Could not load radicle profile"),
         hint: "To setup your radicle profile, run `rad auth`.",
@@ -82,6 +100,130 @@ pub fn list() -> Result<Vec<Profile>, Error> {
     Profile::list(&home()).map_err(|e| e.into())
 }
 
+/// Get a profile by id.
+pub fn get(id: &ProfileId) -> Result<Option<Profile>, Error> {
+    Ok(list()?.into_iter().find(|p| p.id() == id))
+}
+
+/// Get the active profile, ignoring [`RAD_PROFILE`].
+pub fn active() -> Result<Option<Profile>, Error> {
+    Profile::active(&home()).map_err(|e| e.into())
+}
+
+/// Remove a profile's keys and storage. Fails if the profile is active.
+pub fn remove(profile: &Profile) -> Result<(), Error> {
+    if active()?.map_or(false, |p| p.id() == profile.id()) {
+        return Err(anyhow!("profile {} is active", profile.id()));
+    }
+    let id = profile.id().to_string();
+    let paths = profile.paths();
+    // Nb. Depending on the radicle home, keys and storage may be in different base
+    // directories, each in a directory named after the profile.
+    let mut dirs = vec![];
+    for dir in [paths.keys_dir(), paths.git_dir()] {
+        let dir = dir
+            .parent()
+            .filter(|d| d.file_name().map_or(false, |name| name == id.as_str()))
+            .ok_or_else(|| anyhow!("unexpected layout for profile {}", id))?;
+
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    // Nb. Storage that was moved elsewhere is only linked from the profile directory.
+    if let Some(storage) = Config::profile(profile).ok().and_then(|c| c.storage) {
+        if storage.exists() {
+            fs::remove_dir_all(storage)?;
+        }
+    }
+    for dir in dirs {
+        if dir.exists() {
+            fs::remove_dir_all(dir)?;
+        }
+    }
+    Ok(())
+}
+
+/// Move a profile's storage to another location, eg. an external drive or a network
+/// mount, and record it in the profile's config. Since librad derives the storage path
+/// from the radicle home, the storage is linked from its default location.
+pub fn relocate(profile: &Profile, to: &path::Path) -> Result<(), Error> {
+    if !to.is_absolute() {
+        return Err(anyhow!("storage location {} is not absolute", to.display()));
+    }
+    if to.exists() {
+        if to.read_dir()?.next().is_some() {
+            return Err(anyhow!("{} is not empty", to.display()));
+        }
+        fs::remove_dir(to)?;
+    } else if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let link = profile.paths().git_dir();
+    let from = fs::canonicalize(link)
+        .with_context(|| format!("storage of profile {} is not available", profile.id()))?;
+
+    if fs::rename(&from, to).is_err() {
+        // Nb. Directories can't be renamed across file systems.
+        copy_dir(&from, to)
+            .with_context(|| format!("failed to copy storage to {}", to.display()))?;
+        fs::remove_dir_all(&from)?;
+    }
+    if fs::symlink_metadata(link).map_or(false, |m| m.file_type().is_symlink()) {
+        fs::remove_file(link)?;
+    }
+    std::os::unix::fs::symlink(to, link)?;
+
+    let mut config = match Config::profile(profile) {
+        Ok(config) => config,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Config::init(profile)?,
+        Err(err) => return Err(err.into()),
+    };
+    config.storage = Some(to.to_path_buf());
+    config.write(Config::path(profile))?;
+
+    Ok(())
+}
+
+/// Check that a profile's storage is available, if it was moved with [`relocate`], eg.
+/// that the network mount it is on is mounted.
+pub fn check_storage(profile: &Profile) -> Result<(), Error> {
+    let storage = match Config::profile(profile) {
+        Ok(config) => config.storage,
+        Err(_) => None,
+    };
+    match storage {
+        Some(storage) if !profile.paths().git_dir().exists() => Err(args::Error::WithHint {
+            err: anyhow!(
+                "storage of profile {} at {} is not available",
+                profile.id(),
+                storage.display()
+            ),
+            hint: "hint: check that the drive or network mount it is on is available",
+        }
+        .into()),
+        _ => Ok(()),
+    }
+}
+
+/// Recursively copy a directory.
+fn copy_dir(from: &path::Path, to: &path::Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let dest = to.join(entry.file_name());
+
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &dest)?;
+        } else {
+            fs::copy(entry.path(), dest)?;
+        }
+    }
+    Ok(())
+}
+
 /// Get the count of all profiles.
 pub fn count() -> Result<usize, Error> {
     let profiles = list()?;
@@ -98,6 +240,7 @@ pub fn set(id: &ProfileId) -> Result<(), Error> {
 
 /// Open read-only storage.
 pub fn read_only(profile: &Profile) -> Result<ReadOnly, Error> {
+    check_storage(profile)?;
     let storage = ReadOnly::open(profile.paths())?;
 
     Ok(storage)
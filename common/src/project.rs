@@ -11,7 +11,7 @@ use serde::{Deserialize, Serialize};
 use url::Url;
 
 use librad::canonical::Cstring;
-use librad::crypto::BoxedSigner;
+use librad::crypto::{BoxedSigner, PublicKey};
 use librad::git::identities::{self, project, Project};
 use librad::git::local::transport;
 use librad::git::local::url::LocalUrl;
@@ -169,15 +169,23 @@ impl Metadata {
         Reference::head(Some(namespace), None, branch.into())
     }
 
+    /// Whether the project is delegated to the given personal identity, eg. an org's.
+    pub fn is_delegated_to(&self, urn: &Urn) -> bool {
+        self.delegates
+            .iter()
+            .any(|d| matches!(d, Delegate::Indirect { urn: u, .. } if u == urn))
+    }
+
     /// Get the head of a project's default branch under a remote.
     pub fn remote_head(&self, remote: &PeerId) -> Reference<RefLike> {
+        self.remote_branch(remote, RefLike::from(self.default_branch.clone()))
+    }
+
+    /// Get the reference to a project peer's branch.
+    pub fn remote_branch(&self, remote: &PeerId, branch: RefLike) -> Reference<RefLike> {
         let namespace = Namespace::from(self.urn.clone());
 
-        Reference::head(
-            Some(namespace),
-            Some(*remote),
-            RefLike::from(self.default_branch.clone()),
-        )
+        Reference::head(Some(namespace), Some(*remote), branch)
     }
 
     /// Get the reference to a project peer's `rad/self`.
@@ -283,6 +291,47 @@ pub fn create(payload: payload::Project, storage: &Storage) -> anyhow::Result<Pr
     Ok(project)
 }
 
+/// A project delegation: a key, or a personal identity.
+pub type Delegation = Either<PublicKey, Person>;
+
+/// Get the delegations of our latest revision of a project identity.
+pub fn delegations(storage: &Storage, urn: &Urn) -> anyhow::Result<Vec<Delegation>> {
+    let project =
+        project::get(storage, urn)?.ok_or_else(|| anyhow!("project {} not found", urn))?;
+
+    Ok(project
+        .delegations()
+        .iter()
+        .map(|d| match d {
+            Either::Left(pk) => Either::Left(*pk),
+            Either::Right(person) => Either::Right(person.clone()),
+        })
+        .collect())
+}
+
+/// Propose a new revision of a project identity with the given delegations, signed with
+/// the storage's key. The revision needs the signatures of a quorum of the current
+/// delegates to take effect, see [`crate::identity::sign`].
+pub fn set_delegations(
+    storage: &Storage,
+    urn: &Urn,
+    delegations: Vec<Delegation>,
+) -> anyhow::Result<()> {
+    if delegations.is_empty() {
+        anyhow::bail!("a project must have at least one delegate");
+    }
+    let delegations = identities::IndirectDelegation::try_from_iter(delegations)?;
+    project::update(
+        storage,
+        urn,
+        None,
+        None::<ProjectPayload>,
+        Some(delegations),
+    )?;
+
+    Ok(())
+}
+
 /// Initialize a repo as a project.
 pub fn init(
     project: &Project,
@@ -498,7 +547,8 @@ pub fn remote(urn: &Urn, peer: &PeerId, name: &str) -> Result<Remote<LocalUrl>,
     Ok(remote)
 }
 
-/// Get the project URN and repository of the current working directory.
+/// Get the project URN and repository of the current working directory, or of the
+/// repository it is in. See [`git::repository`].
 pub fn cwd() -> anyhow::Result<(Urn, git::Repository)> {
     let repo = git::repository()?;
     let urn = git::rad_remote(&repo)?.url.urn;
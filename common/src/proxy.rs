@@ -0,0 +1,257 @@
+//! Proxy support for network operations.
+//!
+//! The proxy is taken from the `network.proxy` config, or else from the usual
+//! `HTTPS_PROXY`, `HTTP_PROXY` and `ALL_PROXY` environment variables, minus the hosts
+//! in `NO_PROXY`. It applies to seed HTTP APIs and to the `git` processes we spawn, eg.
+//! for fetching over HTTPS. HTTP and SOCKS proxies are supported, eg.
+//! `socks5h://127.0.0.1:9050` for Tor.
+//!
+//! Seeds can have a proxy of their own in the seed config, and `.onion` seeds go through
+//! the Tor daemon's SOCKS proxy, see `network.tor-proxy`.
+//!
+//! Peer-to-peer sync runs over QUIC, ie. UDP, which can't go through these proxies.
+use std::env;
+
+use librad::profile::Profile;
+use url::Url;
+
+use crate::config::Config;
+use crate::seed;
+
+/// Default SOCKS proxy of the Tor daemon.
+pub const DEFAULT_TOR_PROXY: &str = "socks5h://127.0.0.1:9050";
+/// Environment variable with the Tor proxy, set from `network.tor-proxy`.
+pub const RAD_TOR_PROXY: &str = "RAD_TOR_PROXY";
+/// Environment variable with per-seed proxies, as space-separated `<host>=<proxy>`
+/// pairs, set from the seed config.
+pub const RAD_SEED_PROXIES: &str = "RAD_SEED_PROXIES";
+
+/// Environment variables set from the `network.proxy` config, so that child processes
+/// such as `git` use the proxy too. Nb. curl only reads the lowercase `http_proxy`.
+const ENV_VARS: &[&str] = &[
+    "http_proxy",
+    "https_proxy",
+    "all_proxy",
+    "HTTP_PROXY",
+    "HTTPS_PROXY",
+    "ALL_PROXY",
+];
+
+/// Apply the profile's network config to this process and its children. Nb. network
+/// settings in a working copy's config are ignored, as a cloned project could use them
+/// to redirect or unproxy traffic.
+pub fn init(profile: &Profile) {
+    if let Ok(config) = Config::profile(profile) {
+        configure(&config);
+    }
+}
+
+/// Apply network settings to this process and its children.
+pub fn configure(config: &Config) {
+    let network = &config.network;
+
+    if let Some(proxy) = &network.proxy {
+        for var in ENV_VARS {
+            env::set_var(var, proxy);
+        }
+    }
+    if let Some(proxy) = &network.tor_proxy {
+        env::set_var(RAD_TOR_PROXY, proxy);
+    }
+
+    let tor = network.tor_proxy.as_deref().unwrap_or(DEFAULT_TOR_PROXY);
+    let mut seeds = Vec::new();
+    for cfg in config.seeds() {
+        let entry = match (&cfg.proxy, cfg.api.host_str()) {
+            (Some(proxy), Some(host)) => (host, proxy.as_str()),
+            (None, Some(host)) if seed::is_onion(host) => (host, tor),
+            _ => continue,
+        };
+        seeds.push(entry);
+    }
+    if seeds.is_empty() {
+        return;
+    }
+    env::set_var(
+        RAD_SEED_PROXIES,
+        seeds
+            .iter()
+            .map(|(host, proxy)| format!("{}={}", host, proxy))
+            .collect::<Vec<_>>()
+            .join(" "),
+    );
+
+    // Have `git` use the seed proxies as well, through `http.<url>.proxy`.
+    let mut count = env::var("GIT_CONFIG_COUNT")
+        .ok()
+        .and_then(|c| c.parse::<usize>().ok())
+        .unwrap_or(0);
+    for (host, proxy) in &seeds {
+        for scheme in ["http", "https"] {
+            env::set_var(
+                format!("GIT_CONFIG_KEY_{}", count),
+                format!("http.{}://{}/.proxy", scheme, host),
+            );
+            env::set_var(format!("GIT_CONFIG_VALUE_{}", count), proxy);
+            count += 1;
+        }
+    }
+    env::set_var("GIT_CONFIG_COUNT", count.to_string());
+}
+
+/// Whether a proxy is set for any network operation.
+pub fn is_set() -> bool {
+    ["https", "http"]
+        .iter()
+        .any(|scheme| select(scheme, "", |v| env::var(v).ok()).is_some())
+}
+
+/// Get the proxy to use for the given URL, if any.
+pub fn get(url: &Url) -> Option<String> {
+    select(url.scheme(), url.host_str()?, |v| env::var(v).ok())
+}
+
+/// Get an HTTP agent for the given URL, going through the proxy if one is set.
+pub fn agent(url: &Url) -> Result<ureq::Agent, anyhow::Error> {
+    let mut builder = ureq::AgentBuilder::new();
+
+    if let Some(proxy) = get(url) {
+        // Nb. SOCKS5 proxies always resolve host names remotely.
+        let proxy = match proxy.strip_prefix("socks5h://") {
+            Some(rest) => format!("socks5://{}", rest),
+            None => proxy,
+        };
+        let proxy = ureq::Proxy::new(&proxy)
+            .map_err(|err| anyhow::anyhow!("invalid proxy '{}': {}", proxy, err))?;
+
+        builder = builder.proxy(proxy);
+    }
+    Ok(builder.build())
+}
+
+/// Get the proxy of a seed host, if it has one of its own, or is an onion service.
+pub fn for_seed(host: &str) -> Option<String> {
+    select_seed(host, |v| env::var(v).ok())
+}
+
+/// Select the proxy of a seed host, given an environment.
+fn select_seed(host: &str, var: impl Fn(&str) -> Option<String>) -> Option<String> {
+    if let Some(proxies) = var(RAD_SEED_PROXIES) {
+        let proxy = proxies
+            .split_whitespace()
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(h, _)| *h == host)
+            .map(|(_, proxy)| proxy.to_owned());
+
+        if proxy.is_some() {
+            return proxy;
+        }
+    }
+    if seed::is_onion(host) {
+        return Some(var(RAD_TOR_PROXY).unwrap_or_else(|| DEFAULT_TOR_PROXY.to_owned()));
+    }
+    None
+}
+
+/// Select the proxy for a URL scheme and host, given an environment.
+fn select(scheme: &str, host: &str, var: impl Fn(&str) -> Option<String> + Copy) -> Option<String> {
+    if let Some(proxy) = select_seed(host, var) {
+        return Some(proxy);
+    }
+    let either = |name: &str| {
+        var(&name.to_lowercase())
+            .or_else(|| var(name))
+            .filter(|v| !v.is_empty())
+    };
+    if let Some(no_proxy) = either("NO_PROXY") {
+        if is_excluded(host, &no_proxy) {
+            return None;
+        }
+    }
+    let proxy = match scheme {
+        "https" => either("HTTPS_PROXY"),
+        "http" => either("HTTP_PROXY"),
+        _ => None,
+    };
+    proxy.or_else(|| either("ALL_PROXY"))
+}
+
+/// Whether a host is excluded by a `NO_PROXY` value, eg. `localhost,.acme.com`.
+fn is_excluded(host: &str, no_proxy: &str) -> bool {
+    no_proxy
+        .split(',')
+        .map(|entry| entry.trim().trim_start_matches('.'))
+        .filter(|entry| !entry.is_empty())
+        .any(|entry| {
+            entry == "*"
+                || host == entry
+                || host
+                    .strip_suffix(entry)
+                    .map_or(false, |prefix| prefix.ends_with('.'))
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_select() {
+        let env: HashMap<&str, &str> = [
+            ("HTTPS_PROXY", "http://proxy.acme.com:3128"),
+            ("ALL_PROXY", "socks5h://127.0.0.1:9050"),
+            ("no_proxy", "localhost,.internal.acme.com"),
+        ]
+        .iter()
+        .copied()
+        .collect();
+        let var = |name: &str| env.get(name).map(|v| v.to_string());
+
+        assert_eq!(
+            select("https", "seed.acme.com", var).as_deref(),
+            Some("http://proxy.acme.com:3128")
+        );
+        assert_eq!(
+            select("http", "seed.acme.com", var).as_deref(),
+            Some("socks5h://127.0.0.1:9050")
+        );
+        assert_eq!(select("https", "localhost", var), None);
+        assert_eq!(select("https", "git.internal.acme.com", var), None);
+        assert_eq!(select("https", "internal.acme.com", var), None);
+        assert!(select("https", "notinternal.acme.com", var).is_some());
+        assert_eq!(select("https", "seed.acme.com", |_| None), None);
+    }
+
+    #[test]
+    fn test_select_seeds() {
+        let env: HashMap<&str, &str> = [
+            ("ALL_PROXY", "http://proxy.acme.com:3128"),
+            (
+                RAD_SEED_PROXIES,
+                "seed.acme.com=socks5://10.0.0.1:1080 abc.onion=socks5h://10.0.0.2:9050",
+            ),
+        ]
+        .iter()
+        .copied()
+        .collect();
+        let var = |name: &str| env.get(name).map(|v| v.to_string());
+
+        assert_eq!(
+            select("https", "seed.acme.com", var).as_deref(),
+            Some("socks5://10.0.0.1:1080")
+        );
+        assert_eq!(
+            select("http", "abc.onion", var).as_deref(),
+            Some("socks5h://10.0.0.2:9050")
+        );
+        assert_eq!(
+            select("http", "xyz.onion", var).as_deref(),
+            Some(DEFAULT_TOR_PROXY)
+        );
+        assert_eq!(
+            select("https", "other.acme.com", var).as_deref(),
+            Some("http://proxy.acme.com:3128")
+        );
+    }
+}
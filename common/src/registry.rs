@@ -0,0 +1,131 @@
+//! Local registry of projects: where their working copies are, and what was last synced.
+//!
+//! Working copies are registered when a project is initialized or checked out, and the
+//! default branch head is recorded whenever it is pushed to seeds, so that `rad ls` can
+//! show whether a project has unpublished changes.
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use librad::git::Urn;
+use librad::profile::Profile;
+use serde::{Deserialize, Serialize};
+
+use crate::git;
+use crate::store::Store;
+
+/// File name of the registry file, in the profile scope.
+pub const FILE_NAME: &str = "projects.toml";
+
+/// What is known locally about a project.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    /// Working copies of the project.
+    #[serde(default)]
+    pub workdirs: Vec<PathBuf>,
+    /// Default branch head, as of the last push to seeds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub synced: Option<String>,
+}
+
+/// Registered projects, by URN.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Registry {
+    #[serde(default)]
+    pub projects: BTreeMap<String, Entry>,
+}
+
+impl Store for Registry {
+    const FILE_NAME: &'static str = FILE_NAME;
+}
+
+impl Registry {
+    /// Get a project's entry.
+    pub fn get(&self, urn: &Urn) -> Option<&Entry> {
+        self.projects.get(&urn.to_string())
+    }
+
+    /// Register a working copy. Returns `false` if it was already registered.
+    pub fn add_workdir(&mut self, urn: &Urn, path: &Path) -> bool {
+        let entry = self.projects.entry(urn.to_string()).or_default();
+
+        if entry.workdirs.iter().any(|p| p == path) {
+            return false;
+        }
+        entry.workdirs.push(path.to_path_buf());
+
+        true
+    }
+
+    /// Forget a project. Returns its entry, if it was registered.
+    pub fn remove(&mut self, urn: &Urn) -> Option<Entry> {
+        self.projects.remove(&urn.to_string())
+    }
+
+    /// Record the default branch head pushed to seeds.
+    pub fn set_synced(&mut self, urn: &Urn, oid: git::Oid) {
+        self.projects.entry(urn.to_string()).or_default().synced = Some(oid.to_string());
+    }
+
+    /// Default branch head as of the last push to seeds, if any.
+    pub fn synced(&self, urn: &Urn) -> Option<git::Oid> {
+        self.get(urn)
+            .and_then(|e| e.synced.as_deref())
+            .and_then(|oid| git::Oid::from_str(oid).ok())
+    }
+}
+
+/// Register a working copy of a project. The path is canonicalized first.
+pub fn register(profile: &Profile, urn: &Urn, path: &Path) -> Result<(), anyhow::Error> {
+    let path = path.canonicalize()?;
+    let mut registry = Registry::load(profile)?;
+
+    if registry.add_workdir(urn, &path) {
+        registry.save(profile)?;
+    }
+    Ok(())
+}
+
+/// Record the default branch head pushed to seeds.
+pub fn synced(profile: &Profile, urn: &Urn, oid: git::Oid) -> Result<(), anyhow::Error> {
+    let mut registry = Registry::load(profile)?;
+
+    if registry.synced(urn) != Some(oid) {
+        registry.set_synced(urn, oid);
+        registry.save(profile)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_registry() {
+        let urn = Urn::from_str("rad:git:hnrkmg77m8tfzj4gi4pa4mbhgysfgzwntjpao").unwrap();
+        let oid = git::Oid::from_str("a8a1a2cc55e8c3e0b6c2c4e6b8ae7fcc7c0ce7d8").unwrap();
+        let mut registry = Registry::default();
+
+        assert!(registry.get(&urn).is_none());
+        assert!(registry.add_workdir(&urn, Path::new("/tmp/a")));
+        assert!(!registry.add_workdir(&urn, Path::new("/tmp/a")));
+        assert!(registry.add_workdir(&urn, Path::new("/tmp/b")));
+        assert_eq!(registry.synced(&urn), None);
+
+        registry.set_synced(&urn, oid);
+
+        let mut registry: Registry =
+            toml::from_str(&toml::to_string_pretty(&registry).unwrap()).unwrap();
+        let entry = registry.get(&urn).unwrap();
+
+        assert_eq!(
+            entry.workdirs,
+            vec![PathBuf::from("/tmp/a"), PathBuf::from("/tmp/b")]
+        );
+        assert_eq!(registry.synced(&urn), Some(oid));
+
+        assert!(registry.remove(&urn).is_some());
+        assert!(registry.get(&urn).is_none());
+    }
+}
@@ -0,0 +1,140 @@
+//! Device key rotation.
+//!
+//! A device key is bound to the storage of its profile, so rotating it means creating
+//! a new profile with a new key, and moving the personal identity over to it:
+//!
+//! 1. The old key adds the new key to the person's delegations, see
+//!    [`crate::person::set_keys`].
+//! 2. The new profile replicates the person, and co-signs the update with the new key,
+//!    as is required for the new delegation to reach quorum.
+//! 3. The old profile picks up the co-signed revision.
+//!
+//! Retiring the old key is the same dance, the other way around. Refs are copied
+//! between the two storages under the other peer's remote, as if they were fetched from
+//! a seed. A storage that has no view of an identity yet adopts the peer's, once it
+//! verifies, see [`cosign_person`] and [`cosign_project`].
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+use librad::git::identities::{person, project};
+use librad::git::refs::Refs;
+use librad::git::storage::Storage;
+use librad::git::tracking;
+use librad::git::types::{Force, Namespace, Reference};
+use librad::git::Urn;
+use librad::git_ext::RefLike;
+use librad::identities::Person;
+use librad::profile::Profile;
+use librad::PeerId;
+
+use crate::git;
+
+/// Refs of a namespace that are replicated between storages.
+const CATEGORIES: &[&str] = &["rad", "heads", "tags", "cobs"];
+
+/// Copy a peer's refs of an identity from one profile's storage into another's, under
+/// `refs/remotes/<peer>`.
+pub fn replicate(
+    from: &Profile,
+    to: &Profile,
+    urn: &Urn,
+    peer: &PeerId,
+) -> Result<(), anyhow::Error> {
+    let repo = git::Repository::open_bare(to.paths().git_dir())?;
+    let namespace = format!("refs/namespaces/{}/refs", urn.encode_id());
+    let refspecs = CATEGORIES
+        .iter()
+        .map(|c| {
+            format!(
+                "+{ns}/{c}/*:{ns}/remotes/{peer}/{c}/*",
+                ns = namespace,
+                c = c,
+                peer = peer.default_encoding()
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut remote = repo.remote_anonymous(&from.paths().git_dir().to_string_lossy())?;
+    remote.fetch(&refspecs, None, None)?;
+
+    Ok(())
+}
+
+/// Sign a peer's latest view of a person with the storage's key.
+pub fn cosign_person(storage: &Storage, urn: &Urn, peer: PeerId) -> Result<Person, anyhow::Error> {
+    let adopted = if person::get(storage, urn)?.is_none() {
+        let theirs = person::verify(storage, &remote_urn(urn, &peer)?)?
+            .ok_or_else(|| anyhow!("person {} of peer {} was not found", urn, peer))?;
+        adopt(storage, urn, &theirs.content_id.to_string())?;
+        true
+    } else {
+        false
+    };
+    person::merge(storage, urn, peer).or_else(|err| {
+        if adopted {
+            abandon(storage, urn)?;
+        }
+        Err(err.into())
+    })
+}
+
+/// Sign a peer's latest view of a project with the storage's key, and track the peer,
+/// so that its branches are fetched.
+pub fn cosign_project(storage: &Storage, urn: &Urn, peer: PeerId) -> Result<(), anyhow::Error> {
+    let adopted = if project::get(storage, urn)?.is_none() {
+        let theirs = project::verify(storage, &remote_urn(urn, &peer)?)?
+            .ok_or_else(|| anyhow!("project {} of peer {} was not found", urn, peer))?;
+        adopt(storage, urn, &theirs.content_id.to_string())?;
+        true
+    } else {
+        false
+    };
+    if let Err(err) = project::merge(storage, urn, peer) {
+        if adopted {
+            abandon(storage, urn)?;
+        }
+        return Err(err.into());
+    }
+    tracking::track(
+        storage,
+        urn,
+        Some(peer),
+        tracking::config::Config::default(),
+        tracking::policy::Track::Any,
+    )?
+    .ok();
+
+    Ok(())
+}
+
+/// Get the URN of a peer's view of an identity, in its remote.
+fn remote_urn(urn: &Urn, peer: &PeerId) -> Result<Urn, anyhow::Error> {
+    let path = RefLike::try_from(format!("refs/remotes/{}/rad/id", peer.default_encoding()))?;
+
+    Ok(urn.clone().with_path(path))
+}
+
+/// Make a verified identity revision our view of an identity, which we don't have yet.
+fn adopt(storage: &Storage, urn: &Urn, revision: &str) -> Result<(), anyhow::Error> {
+    Reference::rad_id(Namespace::from(urn.clone())).create(
+        storage.as_raw(),
+        git::Oid::from_str(revision)?,
+        Force::False,
+        "rotation: adopt verified identity",
+    )?;
+    Refs::update(storage, urn)?;
+
+    Ok(())
+}
+
+/// Undo [`adopt`], if signing the adopted identity failed.
+fn abandon(storage: &Storage, urn: &Urn) -> Result<(), anyhow::Error> {
+    let name = format!("refs/namespaces/{}/refs/rad/id", urn.encode_id());
+
+    storage.as_raw().find_reference(&name)?.delete()?;
+    Refs::update(storage, urn)?;
+
+    Ok(())
+}
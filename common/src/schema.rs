@@ -0,0 +1,62 @@
+//! JSON Schemas describing the `--json` output of commands.
+//!
+//! Schemas are versioned: whenever the output of a command changes in a way that
+//! isn't backwards compatible, its schema version is bumped.
+use serde_json::{json, Value};
+
+/// JSON Schema dialect used.
+pub const DIALECT: &str = "http://json-schema.org/draft-07/schema#";
+
+/// Wrap a schema in a versioned schema document, for the given command.
+///
+/// The command name is used to identify the schema, eg. `rad-patch/v1`.
+pub fn document(command: &str, version: u32, schema: Value) -> Value {
+    let mut document = json!({
+        "$schema": DIALECT,
+        "$id": format!("rad-{}/v{}", command, version),
+        "title": format!("rad {}", command),
+        "version": version,
+    });
+
+    if let (Some(document), Value::Object(schema)) = (document.as_object_mut(), schema) {
+        document.extend(schema);
+    }
+    document
+}
+
+/// Schema of an object with the given properties, all of which are required.
+pub fn object(properties: &[(&str, Value)]) -> Value {
+    let required = properties.iter().map(|(k, _)| *k).collect::<Vec<_>>();
+    let properties = properties
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.clone()))
+        .collect::<serde_json::Map<_, _>>();
+
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+/// Schema of a string.
+pub fn string(description: &str) -> Value {
+    json!({ "type": "string", "description": description })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_document() {
+        let schema = document("self", 2, object(&[("name", string("Your name"))]));
+
+        assert_eq!(schema["$id"], "rad-self/v2");
+        assert_eq!(schema["version"], 2);
+        assert_eq!(schema["type"], "object");
+        assert_eq!(schema["required"], json!(["name"]));
+        assert_eq!(schema["properties"]["name"]["type"], "string");
+    }
+}
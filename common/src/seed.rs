@@ -11,7 +11,7 @@ use url::{Host, Url};
 
 use crate::args::Error;
 use crate::sync::Seed;
-use crate::{git, project};
+use crate::{git, project, proxy};
 
 pub const CONFIG_SEED_KEY: &str = "rad.seed";
 pub const CONFIG_PEER_KEY: &str = "rad.peer";
@@ -19,6 +19,8 @@ pub const DEFAULT_SEED_GIT_LOCAL_PORT: u16 = 8778;
 pub const DEFAULT_SEED_API_PORT: u16 = 8777;
 pub const DEFAULT_SEED_P2P_PORT: u16 = 8776;
 pub const DEFAULT_SEED_GIT_PORT: u16 = 443;
+/// Top-level domain of Tor onion services.
+pub const ONION_TLD: &str = ".onion";
 
 #[derive(serde::Deserialize)]
 pub struct CommitHeader {
@@ -91,6 +93,11 @@ impl Address {
         }
         None
     }
+
+    /// Whether this is a Tor onion service address.
+    pub fn is_onion(&self) -> bool {
+        is_onion(&self.host.to_string())
+    }
 }
 
 impl TryFrom<Address> for Seed<String> {
@@ -218,14 +225,38 @@ pub fn parse_value(parser: &mut lexopt::Parser) -> anyhow::Result<Seed<String>>
     let value = parser.value()?;
     let value = value.to_string_lossy();
     let value = value.as_ref();
-    let seed = Seed::from_str(value).map_err(|_| Error::WithHint {
+    let seed = parse_seed(value).ok_or_else(|| Error::WithHint {
         err: anyhow!("invalid seed address specified: '{}'", value),
-        hint: "hint: valid seed addresses have the format <peer-id>@<addr>, eg. hyb5to4rshftx4apgmu9s6wnsp4ddmp1mz6ijh4qqey7fb8wrpawxa@pine.radicle.garden:8776",
+        hint: "hint: valid seed addresses have the format <peer-id>@<addr> or rad://<peer-id>@<addr>, eg. hyb5to4rshftx4apgmu9s6wnsp4ddmp1mz6ijh4qqey7fb8wrpawxa@pine.radicle.garden:8776",
     })?;
 
     Ok(seed)
 }
 
+/// Parse a seed given as `<peer-id>@<addr>`, or as a URL with a peer id, eg.
+/// `rad://<peer-id>@<host>[:<port>]`.
+pub fn parse_seed(value: &str) -> Option<Seed<String>> {
+    if let Ok(seed) = Seed::from_str(value) {
+        return Some(seed);
+    }
+    Address::from_str(value)
+        .ok()
+        .and_then(|addr| Seed::try_from(addr).ok())
+}
+
+/// Whether a host is a Tor onion service, eg. `xyz.onion`.
+pub fn is_onion(host: &str) -> bool {
+    host.ends_with(ONION_TLD)
+}
+
+/// Get the base URL of a seed's HTTP API. Onion services are reached over plain HTTP,
+/// as Tor already encrypts and authenticates the connection.
+pub fn api_url(host: &str) -> Result<Url, url::ParseError> {
+    let scheme = if is_onion(host) { "http" } else { "https" };
+
+    Url::parse(&format!("{}://{}", scheme, host))
+}
+
 /// Set the configured "peer" seed within the local repository.
 pub fn set_peer_seed(seed: &Seed<String>, peer_id: &PeerId) -> Result<(), anyhow::Error> {
     let seed = seed.to_string();
@@ -250,12 +281,47 @@ pub fn get_peer_seed(peer_id: &PeerId) -> Result<Url, anyhow::Error> {
     Ok(url)
 }
 
+/// Get the seeds configured for a repository, under [`CONFIG_SEED_KEY`].
+pub fn get_seeds(repo: &git::Repository) -> Result<Vec<Address>, anyhow::Error> {
+    let config = repo.config()?;
+    let entries = config.multivar(CONFIG_SEED_KEY, None)?;
+    let mut seeds = Vec::new();
+
+    for entry in &entries {
+        let entry = entry?;
+        let value = entry
+            .value()
+            .ok_or_else(|| anyhow!("`{}` is not valid UTF-8", CONFIG_SEED_KEY))?;
+        let addr = Address::from_str(value)
+            .with_context(|| format!("`{}` is set to an invalid URL", CONFIG_SEED_KEY))?;
+
+        seeds.push(addr);
+    }
+    Ok(seeds)
+}
+
+/// Set the seeds of a repository, in its local git config. Replaces any seeds that were
+/// configured before; an empty list unsets them.
+pub fn set_seeds(repo: &git::Repository, seeds: &[Address]) -> Result<(), anyhow::Error> {
+    let mut config = repo.config()?.open_level(git2::ConfigLevel::Local)?;
+
+    match config.remove_multivar(CONFIG_SEED_KEY, ".*") {
+        Err(err) if err.code() != git::ErrorCode::NotFound => return Err(err.into()),
+        _ => {}
+    }
+    for seed in seeds {
+        // Nb. The pattern matches no existing value, so the seed is added.
+        config.set_multivar(CONFIG_SEED_KEY, "^$", &seed.to_string())?;
+    }
+    Ok(())
+}
+
 /// Query a seed node for its [`PeerId`].
 pub fn get_seed_id(mut seed: Url) -> Result<PeerId, anyhow::Error> {
     seed.set_port(Some(DEFAULT_SEED_API_PORT)).unwrap();
     seed = seed.join("/v1/peer")?;
 
-    let agent = ureq::Agent::new();
+    let agent = proxy::agent(&seed)?;
     let obj: serde_json::Value = agent.get(seed.as_str()).call()?.into_json()?;
 
     let id = obj
@@ -268,6 +334,49 @@ pub fn get_seed_id(mut seed: Url) -> Result<PeerId, anyhow::Error> {
     Ok(id)
 }
 
+/// Query a seed node for the version of its HTTP API, if it reports one.
+pub fn get_version(mut seed: Url) -> Result<Option<String>, anyhow::Error> {
+    seed.set_port(Some(DEFAULT_SEED_API_PORT)).unwrap();
+
+    let agent = proxy::agent(&seed)?;
+    let obj: serde_json::Value = agent.get(seed.as_str()).call()?.into_json()?;
+
+    Ok(obj
+        .get("version")
+        .and_then(|v| v.as_str())
+        .map(|v| v.to_owned()))
+}
+
+/// Check whether a seed node has a project. Returns `false` if the seed doesn't know
+/// the project.
+pub fn has_project(mut seed: Url, project: &Urn) -> Result<bool, anyhow::Error> {
+    seed.set_port(Some(DEFAULT_SEED_API_PORT)).unwrap();
+    seed = seed.join(&format!("/v1/projects/{}", project))?;
+
+    let agent = proxy::agent(&seed)?;
+    match agent.get(seed.as_str()).call() {
+        Ok(_) => Ok(true),
+        Err(ureq::Error::Status(404, _)) => Ok(false),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Query a seed node for the projects delegated to the given identity.
+pub fn get_delegate_projects(mut seed: Url, delegate: &Urn) -> Result<Vec<Urn>, anyhow::Error> {
+    seed.set_port(Some(DEFAULT_SEED_API_PORT)).unwrap();
+    seed = seed.join(&format!("/v1/delegates/{}/projects", delegate))?;
+
+    let agent = proxy::agent(&seed)?;
+    let projects: Vec<serde_json::Value> = agent.get(seed.as_str()).call()?.into_json()?;
+    let urns = projects
+        .iter()
+        .filter_map(|p| p.get("urn").and_then(|u| u.as_str()))
+        .filter_map(|u| Urn::from_str(u).ok())
+        .collect();
+
+    Ok(urns)
+}
+
 /// Query a seed node for a project commit.
 pub fn get_commit(
     mut seed: Url,
@@ -277,7 +386,7 @@ pub fn get_commit(
     seed.set_port(Some(DEFAULT_SEED_API_PORT)).unwrap();
     seed = seed.join(&format!("/v1/projects/{}/commits/{}", project, commit))?;
 
-    let agent = ureq::Agent::new();
+    let agent = proxy::agent(&seed)?;
     let val: serde_json::Value = agent.get(seed.as_str()).call()?.into_json()?;
     let commit = serde_json::from_value(val)?;
 
@@ -289,7 +398,7 @@ pub fn get_remotes(mut seed: Url, project: &Urn) -> Result<Vec<project::PeerInfo
     seed.set_port(Some(DEFAULT_SEED_API_PORT)).unwrap();
     seed = seed.join(&format!("/v1/projects/{}/remotes", project))?;
 
-    let agent = ureq::Agent::new();
+    let agent = proxy::agent(&seed)?;
     let val: serde_json::Value = agent.get(seed.as_str()).call()?.into_json()?;
     let response = serde_json::from_value(val)?;
 
@@ -341,4 +450,53 @@ mod test {
         assert_eq!(addr.urn, None);
         assert_eq!(addr.port(), DEFAULT_SEED_P2P_PORT);
     }
+
+    #[test]
+    fn test_parse_seed() {
+        let peer =
+            PeerId::from_str("hyb5to4rshftx4apgmu9s6wnsp4ddmp1mz6ijh4qqey7fb8wrpawxa").unwrap();
+        let expected = Seed {
+            peer,
+            addrs: String::from("pine.radicle.garden:8776"),
+            label: None,
+        };
+
+        assert_eq!(
+            parse_seed(&format!("{}@pine.radicle.garden:8776", peer)),
+            Some(expected.clone())
+        );
+        assert_eq!(
+            parse_seed(&format!("rad://{}@pine.radicle.garden", peer)),
+            Some(expected)
+        );
+        assert_eq!(parse_seed("rad://pine.radicle.garden"), None);
+        assert_eq!(parse_seed("pine.radicle.garden"), None);
+    }
+
+    #[test]
+    fn test_seeds_config() {
+        let path = std::env::temp_dir().join("rad").join("test-seeds-config");
+        std::fs::remove_dir_all(&path).ok();
+
+        let repo = git::Repository::init(&path).unwrap();
+        let a = Address::from_str(
+            "rad://hyb5to4rshftx4apgmu9s6wnsp4ddmp1mz6ijh4qqey7fb8wrpawxa@pine.radicle.garden:8776",
+        )
+        .unwrap();
+        let b = Address::from_str(
+            "rad://hyd7wpd8p5aqnm9htsfoatxkckmw6ingnsdudns9code5xq17h1rhw@willow.radicle.garden",
+        )
+        .unwrap();
+
+        assert!(get_seeds(&repo).unwrap().is_empty());
+
+        set_seeds(&repo, &[a.clone(), b.clone()]).unwrap();
+        assert_eq!(get_seeds(&repo).unwrap(), vec![a.clone(), b]);
+
+        set_seeds(&repo, &[a.clone()]).unwrap();
+        assert_eq!(get_seeds(&repo).unwrap(), vec![a]);
+
+        set_seeds(&repo, &[]).unwrap();
+        assert!(get_seeds(&repo).unwrap().is_empty());
+    }
 }
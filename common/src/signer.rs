@@ -1,3 +1,5 @@
+use std::str::FromStr;
+
 use zeroize::Zeroizing;
 
 use librad::crypto::keystore::sign::ed25519;
@@ -9,6 +11,52 @@ use librad::SecretKey;
 use lnk_clib::keys;
 use lnk_clib::keys::ssh::SshAuthSock;
 
+/// Env var selecting the signer backend. See [`Backend`].
+pub const RAD_SIGNER: &str = "RAD_SIGNER";
+
+/// Where commands get the signing key from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// Use ssh-agent if it holds the profile's key, or else the key file.
+    Auto,
+    /// Only use ssh-agent. This includes agents backed by hardware tokens.
+    Agent,
+    /// Only use the key file, unsealed with a passphrase.
+    Key,
+}
+
+impl Default for Backend {
+    fn default() -> Self {
+        Self::Auto
+    }
+}
+
+impl FromStr for Backend {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "agent" => Ok(Self::Agent),
+            "key" => Ok(Self::Key),
+            _ => Err(anyhow::anyhow!(
+                "invalid signer '{}', expected `auto`, `agent` or `key`",
+                s
+            )),
+        }
+    }
+}
+
+impl Backend {
+    /// Get the backend set with [`RAD_SIGNER`], or the default.
+    pub fn from_env() -> Result<Self, anyhow::Error> {
+        match std::env::var(RAD_SIGNER) {
+            Ok(val) if !val.is_empty() => val.parse(),
+            _ => Ok(Self::default()),
+        }
+    }
+}
+
 /// A trait for types that can be converted to signers.
 pub trait ToSigner {
     /// Convert to a signer.
@@ -0,0 +1,293 @@
+//! Storage access on network file systems.
+//!
+//! `flock` and `fcntl` locks are either not supported or not shared between clients on
+//! many network file systems, so writers to storage on such a file system take a lock
+//! file instead, created with `O_EXCL`, which NFS (v3 and later) and SMB do honor.
+use std::convert::TryFrom;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use std::{env, fs, io, thread};
+
+use anyhow::{anyhow, Error, Result};
+
+use librad::profile::Profile;
+
+use crate::args;
+use crate::git;
+
+/// File system types on which file locking and write ordering can't be relied upon.
+pub const NETWORK_FILESYSTEMS: &[&str] = &[
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smb",
+    "smbfs",
+    "smb3",
+    "fuse.sshfs",
+    "9p",
+    "afs",
+    "glusterfs",
+    "ceph",
+];
+
+/// Name of the storage lock file, in the storage directory.
+pub const LOCK_FILE: &str = "rad.lock";
+
+/// Environment variable passing the storage lock down to child processes, which
+/// share the lock of their parent.
+pub const RAD_STORAGE_LOCK: &str = "RAD_STORAGE_LOCK";
+
+/// How long to wait for another process to release the storage lock.
+pub const LOCK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Storage lock held by this process, see [`prepare`].
+static LOCK: Mutex<Option<Lock>> = Mutex::new(None);
+
+/// An exclusive lock on storage, released when dropped.
+#[derive(Debug)]
+pub struct Lock {
+    path: PathBuf,
+}
+
+impl Lock {
+    /// Take the lock file at the given path, waiting up to `timeout` for another
+    /// process to release it. Locks left behind by processes on this host which are
+    /// no longer running are removed.
+    pub fn acquire(path: &Path, timeout: Duration) -> Result<Self, Error> {
+        let owner = Owner::current();
+        let start = Instant::now();
+
+        loop {
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(path)
+            {
+                Ok(_) => {
+                    fs::write(path, owner.to_string())?;
+                    // Nb. Unlike `fs::write`, this makes sure the owner is visible to
+                    // other clients of a network file system.
+                    fs::File::open(path)?.sync_all()?;
+
+                    return Ok(Self {
+                        path: path.to_path_buf(),
+                    });
+                }
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                    let other = Owner::read(path);
+
+                    if other.as_ref().map_or(false, |o| o.is_stale()) {
+                        fs::remove_file(path).ok();
+                        continue;
+                    }
+                    if start.elapsed() >= timeout {
+                        let other = other.map_or(String::from("unknown"), |o| o.to_string());
+
+                        return Err(args::Error::WithHint {
+                            err: anyhow!("storage is locked by another process ({})", other),
+                            hint: "hint: if that process is no longer running, run `rad doctor --fix` to remove the lock",
+                        }
+                        .into());
+                    }
+                    thread::sleep(Duration::from_millis(100));
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        fs::remove_file(&self.path).ok();
+    }
+}
+
+/// The process holding a lock.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Owner {
+    pub host: String,
+    pub pid: u32,
+}
+
+impl Owner {
+    /// The current process.
+    pub fn current() -> Self {
+        Self {
+            host: hostname(),
+            pid: std::process::id(),
+        }
+    }
+
+    /// Parse an owner, as formatted by [`Owner`]'s `Display` implementation.
+    pub fn parse(s: &str) -> Option<Self> {
+        let (host, pid) = s.trim().rsplit_once(' ')?;
+
+        Some(Self {
+            host: host.to_owned(),
+            pid: pid.parse().ok()?,
+        })
+    }
+
+    /// Read the owner of a lock file.
+    pub fn read(path: &Path) -> Option<Self> {
+        Self::parse(&fs::read_to_string(path).ok()?)
+    }
+
+    /// Whether the owner is a process on this host which is no longer running.
+    /// Processes on other hosts are assumed to be running.
+    pub fn is_stale(&self) -> bool {
+        if self.host != hostname() {
+            return false;
+        }
+        // Nb. Negative pids would signal process groups.
+        let pid = match libc::pid_t::try_from(self.pid) {
+            Ok(pid) if pid > 0 => pid,
+            _ => return true,
+        };
+        // Signal 0 only checks whether the process exists. Processes of other users
+        // can't be signaled, but exist.
+        let result = unsafe { libc::kill(pid, 0) };
+
+        result != 0 && io::Error::last_os_error().raw_os_error() == Some(libc::ESRCH)
+    }
+}
+
+impl std::fmt::Display for Owner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.host, self.pid)
+    }
+}
+
+/// Prepare a profile's storage for writing. On network file systems, this takes the
+/// storage lock until [`release`] is called and makes sure object files are synced to
+/// disk, unless configured otherwise. Nb. commands exit with `radicle_terminal::exit`,
+/// which releases the lock.
+pub fn prepare(profile: &Profile) -> Result<(), Error> {
+    let dir = profile.paths().git_dir();
+
+    if !is_network(dir) {
+        return Ok(());
+    }
+    let mut lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    if lock.is_none() && env::var_os(RAD_STORAGE_LOCK).is_none() {
+        *lock = Some(Lock::acquire(&dir.join(LOCK_FILE), LOCK_TIMEOUT)?);
+        env::set_var(RAD_STORAGE_LOCK, Owner::current().to_string());
+    }
+
+    let repo = git::Repository::open_bare(dir)?;
+    let mut config = repo.config()?;
+
+    if config.get_entry("core.fsyncObjectFiles").is_err() {
+        config.set_bool("core.fsyncObjectFiles", true)?;
+    }
+    Ok(())
+}
+
+/// Release the storage lock taken by [`prepare`], if any.
+pub fn release() {
+    let mut lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    if lock.take().is_some() {
+        env::remove_var(RAD_STORAGE_LOCK);
+    }
+}
+
+/// Get the file system type of the mount containing the given path. Only supported on
+/// Linux.
+pub fn filesystem(path: &Path) -> Option<String> {
+    let path = path.canonicalize().ok()?;
+    let mounts = fs::read_to_string("/proc/mounts").ok()?;
+
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _device = fields.next()?;
+            let mount = fields.next()?;
+            let fstype = fields.next()?;
+
+            Some((Path::new(mount).to_path_buf(), fstype.to_owned()))
+        })
+        .filter(|(mount, _)| path.starts_with(mount))
+        .max_by_key(|(mount, _)| mount.as_os_str().len())
+        .map(|(_, fstype)| fstype)
+}
+
+/// Whether the given path is on a network file system.
+pub fn is_network(path: &Path) -> bool {
+    filesystem(path).map_or(false, |fs| NETWORK_FILESYSTEMS.contains(&fs.as_str()))
+}
+
+/// Find references pointing to objects missing from the repository, eg. after an
+/// interrupted write to a network file system.
+pub fn dangling(repo: &git::Repository) -> Result<Vec<String>, Error> {
+    let mut dangling = Vec::new();
+
+    for r in repo.references()? {
+        let r = r?;
+        let name = String::from_utf8_lossy(r.name_bytes()).into_owned();
+
+        match r.target() {
+            Some(oid) if repo.find_object(oid, None).is_err() => dangling.push(name),
+            // Symbolic references are resolved through their target.
+            _ => {}
+        }
+    }
+    Ok(dangling)
+}
+
+/// Get the name of this host.
+fn hostname() -> String {
+    let mut buf = [0u8; 256];
+    let result = unsafe { libc::gethostname(buf.as_mut_ptr() as *mut libc::c_char, buf.len()) };
+
+    if result != 0 {
+        return env::var("HOSTNAME").unwrap_or_default();
+    }
+    // Nb. The name isn't null-terminated if it was truncated.
+    let len = buf.iter().position(|b| *b == 0).unwrap_or(buf.len());
+
+    String::from_utf8_lossy(&buf[..len]).into_owned()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_lock() {
+        let tmp = env::temp_dir().join("rad").join("test-lock");
+        fs::remove_dir_all(&tmp).ok();
+        fs::create_dir_all(&tmp).unwrap();
+
+        let path = tmp.join(LOCK_FILE);
+
+        let lock = Lock::acquire(&path, Duration::ZERO).unwrap();
+        assert_eq!(Owner::read(&path), Some(Owner::current()));
+        assert!(Lock::acquire(&path, Duration::ZERO).is_err());
+
+        drop(lock);
+        assert!(!path.exists());
+        Lock::acquire(&path, Duration::ZERO).unwrap();
+    }
+
+    #[test]
+    fn test_lock_stale() {
+        let tmp = env::temp_dir().join("rad").join("test-lock-stale");
+        fs::remove_dir_all(&tmp).ok();
+        fs::create_dir_all(&tmp).unwrap();
+
+        let path = tmp.join(LOCK_FILE);
+        let stale = Owner {
+            host: hostname(),
+            pid: u32::MAX,
+        };
+        fs::write(&path, stale.to_string()).unwrap();
+
+        assert!(stale.is_stale());
+        Lock::acquire(&path, Duration::ZERO).unwrap();
+    }
+}
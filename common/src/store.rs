@@ -0,0 +1,80 @@
+//! Collections stored as TOML files in the profile scope, eg. the allowlist.
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use librad::profile::Profile;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// A collection stored as a TOML file in the profile scope. A missing file is an empty
+/// collection.
+pub trait Store: Default + Serialize + DeserializeOwned {
+    /// Name of the file, in the profile scope.
+    const FILE_NAME: &'static str;
+
+    fn read<P: AsRef<Path>>(path: P) -> Result<Self, io::Error> {
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(toml::from_str(&content)?),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn load(profile: &Profile) -> Result<Self, io::Error> {
+        Self::read(Self::path(profile))
+    }
+
+    fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), anyhow::Error> {
+        let contents = toml::to_string_pretty(self)?;
+        fs::write(path, contents)?;
+
+        Ok(())
+    }
+
+    fn save(&self, profile: &Profile) -> Result<(), anyhow::Error> {
+        self.write(Self::path(profile))
+    }
+
+    fn path(profile: &Profile) -> PathBuf {
+        profile.paths().seeds_file().with_file_name(Self::FILE_NAME)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+    struct Names {
+        #[serde(default)]
+        names: Vec<String>,
+    }
+
+    impl Store for Names {
+        const FILE_NAME: &'static str = "names.toml";
+    }
+
+    #[test]
+    fn test_read_write() {
+        let tmp = env::temp_dir().join("rad").join("test-store");
+        fs::remove_dir_all(&tmp).ok();
+        fs::create_dir_all(&tmp).unwrap();
+
+        let path = tmp.join(Names::FILE_NAME);
+        assert_eq!(Names::read(&path).unwrap(), Names::default());
+
+        let names = Names {
+            names: vec![String::from("alice")],
+        };
+        names.write(&path).unwrap();
+        assert_eq!(Names::read(&path).unwrap(), names);
+
+        fs::write(&path, "names = 1").unwrap();
+        assert!(Names::read(&path).is_err());
+    }
+}
@@ -1,11 +1,17 @@
+mod connect;
 mod push;
 
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashSet};
 use std::convert::TryInto;
+use std::fs;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time;
 
 use anyhow::anyhow;
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
 
 use librad::crypto::BoxedSigner;
 use librad::git::Urn;
@@ -18,7 +24,7 @@ use librad::net::{
     replication, Network,
 };
 use librad::profile::Profile;
-use librad::Signer;
+use librad::{PeerId, Signer};
 use link_async::Spawner;
 use lnk_clib::seed::store::FileStore;
 
@@ -26,7 +32,15 @@ pub use lnk_clib::seed::{Seed, Seeds};
 pub use lnk_sync::Mode;
 
 use crate::config;
+use crate::git;
 use crate::nonempty::NonEmpty;
+use crate::storage::{Lock, Owner, LOCK_TIMEOUT};
+use crate::store::Store;
+
+/// Number of seeds synced with concurrently, by default.
+pub const DEFAULT_JOBS: usize = 4;
+/// Number of times a sync with a seed is retried after a network failure, by default.
+pub const DEFAULT_RETRIES: u32 = 2;
 
 /// Sync result of a seed.
 #[derive(Debug)]
@@ -34,55 +48,261 @@ pub struct SyncResult {
     pub seed: Seed<Vec<SocketAddr>>,
     pub fetch: Option<Result<replication::Success, client::error::Replicate>>,
     pub push: Option<Result<push::Success, push::Error>>,
+    /// Objects the seed received when pushing, as it reported them. Only the objects
+    /// the seed was missing are sent, as negotiated by its fetch from us.
+    pub received: Option<Transfer>,
+}
+
+impl SyncResult {
+    /// A result for a seed that was skipped, eg. because it was synced by an earlier,
+    /// interrupted sync.
+    pub fn skipped(seed: &Seed<String>) -> Self {
+        Self {
+            seed: Seed {
+                peer: seed.peer,
+                addrs: Vec::new(),
+                label: seed.label.clone(),
+            },
+            fetch: None,
+            push: None,
+            received: None,
+        }
+    }
+
+    /// Whether the seed was skipped.
+    pub fn is_skipped(&self) -> bool {
+        self.fetch.is_none() && self.push.is_none()
+    }
+
+    /// Whether the sync with this seed succeeded.
+    pub fn is_ok(&self) -> bool {
+        !matches!(self.fetch, Some(Err(_))) && !matches!(self.push, Some(Err(_)))
+    }
+
+    /// Whether the sync failed because the seed couldn't be reached, or dropped the
+    /// connection, as opposed to the seed refusing the sync. Such failures are worth
+    /// retrying.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self.fetch,
+            Some(Err(client::error::Replicate::NoConnection(_)))
+        ) || matches!(
+            self.push,
+            Some(Err(push::Error::Client(_))) | Some(Err(push::Error::NoResponse))
+        )
+    }
 }
 
-/// Sync the given URN with the provided list of seeds.
+/// Exponential backoff, for retrying syncs.
+#[derive(Debug, Clone, Copy)]
+pub struct Backoff {
+    /// Delay before the first retry.
+    pub base: time::Duration,
+    /// Longest delay between two retries.
+    pub max: time::Duration,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            base: time::Duration::from_secs(1),
+            max: time::Duration::from_secs(30),
+        }
+    }
+}
+
+impl Backoff {
+    /// Delay before the given retry, starting at `1`, with random jitter so that
+    /// retries of concurrent clients don't line up.
+    pub fn delay(&self, retry: u32) -> time::Duration {
+        self.delay_with(retry, rand::random())
+    }
+
+    /// Delay before the given retry, with the given jitter between `0` and `1`. The
+    /// delay doubles on every retry, and jitter takes up to half of it off.
+    pub fn delay_with(&self, retry: u32, jitter: f64) -> time::Duration {
+        let exp = 2u32.saturating_pow(retry.saturating_sub(1));
+        let delay = self.base.saturating_mul(exp).min(self.max);
+
+        delay.mul_f64(1. - jitter.clamp(0., 1.) / 2.)
+    }
+}
+
+/// Sync timeouts.
+#[derive(Debug, Clone, Copy)]
+pub struct Timeout {
+    /// Time allowed for finding an address that connects, for seeds with several.
+    pub connect: time::Duration,
+    /// Time allowed for fetching from a seed. Fetches don't report progress, so this
+    /// bounds the whole fetch.
+    pub fetch: time::Duration,
+    /// Time allowed between two progress reports of a seed, when pushing.
+    pub push: time::Duration,
+}
+
+/// Progress of a sync, reported as it happens.
+#[derive(Debug)]
+pub enum Progress<'a> {
+    /// Fetching from a seed.
+    Fetching(&'a Seed<Vec<SocketAddr>>),
+    /// Pushing to a seed.
+    Pushing(&'a Seed<Vec<SocketAddr>>),
+    /// Git transfer progress reported by a seed being pushed to.
+    Transfer(&'a Seed<Vec<SocketAddr>>, Transfer),
+    /// Done syncing with a seed.
+    Done(&'a SyncResult),
+}
+
+/// Transfer stage of a pack being received.
+pub const RECEIVING: &str = "Receiving objects";
+
+/// Git transfer progress, eg. `Receiving objects:  45% (450/1000), 1.20 MiB | 2.00 MiB/s`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transfer {
+    /// Transfer stage, eg. `Receiving objects`.
+    pub stage: String,
+    /// Objects transferred.
+    pub done: u64,
+    /// Total objects.
+    pub total: u64,
+    /// Data transferred, if reported, eg. `1.20 MiB`.
+    pub bytes: Option<String>,
+}
+
+impl Transfer {
+    /// Parse a git progress message. Only the last update of messages holding several,
+    /// separated by carriage returns, is kept.
+    pub fn parse(message: &str) -> Option<Self> {
+        let line = message
+            .split(|c| c == '\r' || c == '\n')
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty())
+            .last()?;
+        let line = line.strip_prefix("remote:").unwrap_or(line).trim();
+        let (stage, rest) = line.split_once(':')?;
+        let (_, counts) = rest.split_once('(')?;
+        let (counts, rest) = counts.split_once(')')?;
+        let (done, total) = counts.split_once('/')?;
+        let bytes = rest
+            .trim_start_matches(',')
+            .split('|')
+            .next()
+            .map(|b| b.trim().trim_end_matches(", done.").trim())
+            .filter(|b| !b.is_empty() && !b.starts_with("done"))
+            .map(|b| b.to_owned());
+
+        Some(Self {
+            stage: stage.trim().to_owned(),
+            done: done.trim().parse().ok()?,
+            total: total.trim().parse().ok()?,
+            bytes,
+        })
+    }
+}
+
+/// Sync the given URN with the provided list of seeds, syncing with up to `jobs` seeds
+/// at a time. Results are returned in the order of the seeds.
 pub async fn sync<S, E>(
     client: &Client<S, E>,
+    endpoint: &E,
     urn: Urn,
     seeds: Seeds,
     mode: Mode,
-    timeout: time::Duration,
+    timeout: Timeout,
+    jobs: usize,
+    progress: impl FnMut(Progress<'_>),
 ) -> Vec<SyncResult>
 where
     S: Signer + Clone,
     E: ConnectPeer + Clone + Send + Sync + 'static,
 {
-    let mut syncs = Vec::with_capacity(seeds.len());
     let is_push = mode.is_push();
     let is_fetch = mode.is_fetch();
     let Seeds(seeds) = seeds;
+    // Seeds are synced concurrently on the current task, so progress is never reported
+    // from two places at once.
+    let progress = RefCell::new(progress);
+    let report = |p: Progress<'_>| (progress.borrow_mut())(p);
 
-    for seed in seeds {
-        let fetch = if is_fetch {
-            match tokio::time::timeout(timeout, client.replicate(seed.clone(), urn.clone(), None))
-                .await
-            {
-                Ok(result) => Some(result),
-                Err(_) => Some(Err(client::error::Replicate::NoConnection(
-                    client::error::NoConnection(seed.peer),
-                ))),
-            }
-        } else {
-            None
-        };
+    stream::iter(seeds)
+        .map(|seed| {
+            let urn = urn.clone();
+            let report = &report;
 
-        let push = if is_push {
-            Some(push::push(client, urn.clone(), seed.clone(), timeout).await)
-        } else {
-            None
-        };
+            async move {
+                // Nb. The client tries a seed's addresses one after the other, so we
+                // find one that connects first.
+                let target = if seed.addrs.len() > 1 {
+                    let addr =
+                        connect::race(endpoint, seed.peer, &seed.addrs, timeout.connect).await;
+                    Seed {
+                        peer: seed.peer,
+                        addrs: addr.into_iter().collect(),
+                        label: seed.label.clone(),
+                    }
+                } else {
+                    seed.clone()
+                };
 
-        syncs.push(SyncResult { seed, fetch, push })
-    }
-    syncs
+                let fetch = if is_fetch {
+                    report(Progress::Fetching(&seed));
+
+                    match tokio::time::timeout(
+                        timeout.fetch,
+                        client.replicate(target.clone(), urn.clone(), None),
+                    )
+                    .await
+                    {
+                        Ok(result) => Some(result),
+                        Err(_) => Some(Err(client::error::Replicate::NoConnection(
+                            client::error::NoConnection(seed.peer),
+                        ))),
+                    }
+                } else {
+                    None
+                };
+
+                let mut received = None;
+                let push = if is_push {
+                    report(Progress::Pushing(&seed));
+
+                    Some(
+                        push::push(client, urn, target, timeout.push, |msg| {
+                            if let Some(transfer) = Transfer::parse(msg) {
+                                if transfer.stage == RECEIVING {
+                                    received = Some(transfer.clone());
+                                }
+                                report(Progress::Transfer(&seed, transfer));
+                            }
+                        })
+                        .await,
+                    )
+                } else {
+                    None
+                };
+
+                let result = SyncResult {
+                    seed,
+                    fetch,
+                    push,
+                    received,
+                };
+                report(Progress::Done(&result));
+
+                result
+            }
+        })
+        .buffered(jobs.max(1))
+        .collect()
+        .await
 }
 
-/// Create a sync client.
+/// Create a sync client, along with its endpoint, which [`sync`] uses to pick the
+/// address to connect to seeds on.
 pub async fn client(
     signer: BoxedSigner,
     profile: &Profile,
-) -> anyhow::Result<Client<BoxedSigner, SendOnly>> {
+) -> anyhow::Result<(Client<BoxedSigner, SendOnly>, SendOnly)> {
     let spawner = Spawner::from_current().ok_or(anyhow!("cannot create spawner"))?;
     let network = Network::default();
     let config = client::Config {
@@ -93,14 +313,36 @@ pub async fn client(
         network: network.clone(),
     };
     let endpoint = quic::SendOnly::new(signer, network).await?;
-    let client = Client::new(config, Arc::new(spawner), endpoint)?;
+    let client = Client::new(config, Arc::new(spawner), endpoint.clone())?;
 
-    Ok(client)
+    Ok((client, endpoint))
 }
 
 /// Get the seeds configured for the profile.
-/// First checks local (working copy) config, then global.
+/// First checks the working copy's git config (see `rad seed`), then local config, then
+/// global.
 pub fn seeds(profile: &Profile) -> anyhow::Result<NonEmpty<Seed<String>>> {
+    if let Ok(repo) = crate::git::repository() {
+        // Nb. Older versions stored the seed's git URL here, which has no peer id and
+        // can't be synced with.
+        let seeds = crate::seed::get_seeds(&repo)?
+            .into_iter()
+            .filter(|addr| addr.peer().is_some())
+            .map(|addr| {
+                let label = addr.host.to_string();
+                let seed: Seed<String> = addr.try_into()?;
+
+                Ok(Seed {
+                    label: Some(label),
+                    ..seed
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        if let Ok(seeds) = seeds.try_into() {
+            return Ok(seeds);
+        }
+    }
     let config = config::Config::load(profile)?;
     let seeds = config
         .seeds()
@@ -122,3 +364,426 @@ pub fn seeds(profile: &Profile) -> anyhow::Result<NonEmpty<Seed<String>>> {
         .try_into()
         .map_err(|_| anyhow!("No seeds configured for profile {}", profile.id()))
 }
+
+/// A selection of refs to push from a working copy to storage, before syncing.
+///
+/// Nb. Seeds replicate whole projects, so this only selects which of the working copy's
+/// refs end up in storage; COB refs are written to storage directly and are always synced.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Refs {
+    refspecs: Vec<String>,
+}
+
+impl Refs {
+    /// Select a branch, eg. `master`.
+    pub fn branch(mut self, name: &str) -> Self {
+        let name = name.trim_start_matches("refs/heads/");
+
+        self.refspecs
+            .push(format!("refs/heads/{}:refs/heads/{}", name, name));
+        self
+    }
+
+    /// Select tags matching a glob, eg. `radicle-patch/*`.
+    pub fn tags(mut self, glob: &str) -> Self {
+        let glob = glob.trim_start_matches("refs/tags/");
+
+        self.refspecs
+            .push(format!("refs/tags/{}:refs/tags/{}", glob, glob));
+        self
+    }
+
+    /// Select refs with a git refspec, eg. `refs/heads/feature/*:refs/heads/feature/*`.
+    /// A ref name without a destination is pushed to the same name.
+    pub fn refspec(mut self, spec: &str) -> Self {
+        if spec.contains(':') {
+            self.refspecs.push(spec.to_owned());
+        } else if spec.starts_with("refs/") {
+            self.refspecs.push(format!("{}:{}", spec, spec));
+        } else {
+            return self.branch(spec);
+        }
+        self
+    }
+
+    /// Whether no refs are selected.
+    pub fn is_empty(&self) -> bool {
+        self.refspecs.is_empty()
+    }
+
+    /// The selected refspecs.
+    pub fn refspecs(&self) -> &[String] {
+        &self.refspecs
+    }
+
+    /// Push the selected refs of the working copy at the given path to storage, via
+    /// the `rad` remote.
+    pub fn push(&self, repo: &std::path::Path) -> anyhow::Result<String> {
+        if self.is_empty() {
+            return Ok(String::new());
+        }
+        crate::git::git(
+            repo,
+            ["push", "rad"]
+                .iter()
+                .map(|s| s.to_string())
+                .chain(self.refspecs.iter().cloned()),
+        )
+    }
+}
+
+/// Refs of a project in storage. Only local refs are included unless `remotes` is set,
+/// ie. the refs of tracked peers are excluded. Comparing snapshots tells whether there
+/// was activity, eg. something new to push.
+pub fn refs_snapshot(
+    repo: &git2::Repository,
+    urn: &Urn,
+    remotes: bool,
+) -> Result<BTreeMap<String, git2::Oid>, git2::Error> {
+    let prefix = format!("refs/namespaces/{}/refs/", urn.encode_id());
+    let remotes_prefix = format!("{}remotes/", prefix);
+    let mut refs = BTreeMap::new();
+
+    for r in repo.references_glob(&format!("{}*", prefix))? {
+        let r = r?;
+        if let (Some(name), Some(oid)) = (r.name(), r.target()) {
+            if remotes || !name.starts_with(&remotes_prefix) {
+                refs.insert(name.to_owned(), oid);
+            }
+        }
+    }
+    Ok(refs)
+}
+
+/// Count the objects reachable from our refs of a project that match the given globs,
+/// eg. `cobs/*`. This is what pushing them would send if the seed had none of them.
+pub fn reachable_objects(
+    repo: &git2::Repository,
+    urn: &Urn,
+    globs: &[&str],
+) -> Result<usize, git2::Error> {
+    let mut walk = repo.revwalk()?;
+    let mut seen = HashSet::new();
+
+    for glob in globs {
+        walk.push_glob(&format!(
+            "refs/namespaces/{}/refs/{}",
+            urn.encode_id(),
+            glob
+        ))?;
+    }
+    for oid in walk {
+        let commit = repo.find_commit(oid?)?;
+        let tree = commit.tree()?;
+
+        seen.insert(commit.id());
+        if !seen.insert(tree.id()) {
+            continue;
+        }
+        tree.walk(git2::TreeWalkMode::PreOrder, |_, entry| {
+            seen.insert(entry.id());
+            git2::TreeWalkResult::Ok
+        })?;
+    }
+    Ok(seen.len())
+}
+
+/// Debounces updates: an update is due once there were no changes for a while, so that
+/// bursts of changes, eg. pushing several branches, result in a single sync.
+#[derive(Debug, Clone)]
+pub struct Debounce {
+    delay: time::Duration,
+    pending: Option<time::Instant>,
+}
+
+impl Debounce {
+    pub fn new(delay: time::Duration) -> Self {
+        Self {
+            delay,
+            pending: None,
+        }
+    }
+
+    /// Record a change.
+    pub fn changed(&mut self, now: time::Instant) {
+        self.pending = Some(now);
+    }
+
+    /// Whether an update is due. Returns `true` once per burst of changes.
+    pub fn due(&mut self, now: time::Instant) -> bool {
+        match self.pending {
+            Some(since) if now.duration_since(since) >= self.delay => {
+                self.pending = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// File name of the sync journal, in the profile scope.
+pub const JOURNAL_FILE_NAME: &str = "sync-journal.toml";
+
+/// How long an interrupted sync can be resumed for, in seconds.
+pub const JOURNAL_EXPIRY: u64 = 60 * 60;
+
+/// A sync in progress, and the seeds it is done with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub urn: String,
+    /// Sync mode, eg. `fetch`.
+    pub mode: String,
+    /// Signed refs of the project when the sync started, ie. what is pushed to seeds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refs: Option<String>,
+    /// Process running the sync, see [`Owner`].
+    #[serde(default)]
+    pub owner: String,
+    /// When the sync started, in seconds since the epoch.
+    pub started: u64,
+    /// Seeds synced with.
+    #[serde(default)]
+    pub done: Vec<String>,
+}
+
+impl JournalEntry {
+    fn is(&self, urn: &str, mode: &str, owner: &str) -> bool {
+        self.urn == urn && self.mode == mode && self.owner == owner
+    }
+}
+
+/// Journal of syncs in progress, so that interrupted syncs can be resumed instead of
+/// syncing with every seed again.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Journal {
+    #[serde(default)]
+    pub syncs: Vec<JournalEntry>,
+}
+
+impl Store for Journal {
+    const FILE_NAME: &'static str = JOURNAL_FILE_NAME;
+}
+
+impl Journal {
+    /// Load the journal, update it and save it. Other processes may be syncing too, so
+    /// the journal is loaded anew for every update, under a lock. It is replaced in one
+    /// go, so that it is never read half-written.
+    pub fn update<T>(profile: &Profile, f: impl FnOnce(&mut Self) -> T) -> anyhow::Result<T> {
+        let path = Self::path(profile);
+        let _lock = Lock::acquire(&path.with_extension("lock"), LOCK_TIMEOUT)?;
+        let mut journal = Self::read(&path)?;
+        let result = f(&mut journal);
+        let tmp = path.with_extension("tmp");
+
+        journal.write(&tmp)?;
+        fs::rename(&tmp, &path)?;
+
+        Ok(result)
+    }
+
+    /// Start a sync, or resume an earlier sync of the same refs, if it was interrupted
+    /// less than [`JOURNAL_EXPIRY`] seconds ago. Syncs of processes that are still
+    /// running aren't resumed. Returns the seeds that are already synced.
+    pub fn begin(
+        &mut self,
+        urn: &Urn,
+        mode: Mode,
+        refs: Option<git::Oid>,
+        owner: &Owner,
+        now: u64,
+    ) -> Vec<PeerId> {
+        let (urn, mode) = (urn.to_string(), mode_name(mode));
+        let refs = refs.map(|oid| oid.to_string());
+
+        self.syncs
+            .retain(|e| now.saturating_sub(e.started) < JOURNAL_EXPIRY);
+
+        if let Some(entry) = self.syncs.iter_mut().find(|e| {
+            e.urn == urn
+                && e.mode == mode
+                && e.refs == refs
+                && Owner::parse(&e.owner).map_or(true, |o| o == *owner || o.is_stale())
+        }) {
+            entry.owner = owner.to_string();
+
+            return entry.done.iter().filter_map(|p| p.parse().ok()).collect();
+        }
+        self.syncs.push(JournalEntry {
+            urn,
+            mode: mode.to_owned(),
+            refs,
+            owner: owner.to_string(),
+            started: now,
+            done: Vec::new(),
+        });
+
+        Vec::new()
+    }
+
+    /// Record that a seed was synced with.
+    pub fn complete(&mut self, urn: &Urn, mode: Mode, owner: &Owner, peer: &PeerId) {
+        let (urn, mode, owner) = (urn.to_string(), mode_name(mode), owner.to_string());
+        let peer = peer.to_string();
+
+        if let Some(entry) = self.syncs.iter_mut().find(|e| e.is(&urn, mode, &owner)) {
+            if !entry.done.contains(&peer) {
+                entry.done.push(peer);
+            }
+        }
+    }
+
+    /// End a sync.
+    pub fn finish(&mut self, urn: &Urn, mode: Mode, owner: &Owner) {
+        let (urn, mode, owner) = (urn.to_string(), mode_name(mode), owner.to_string());
+
+        self.syncs.retain(|e| !e.is(&urn, mode, &owner));
+    }
+}
+
+fn mode_name(mode: Mode) -> &'static str {
+    match mode {
+        Mode::Fetch => "fetch",
+        Mode::Push => "push",
+        Mode::All => "all",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_refs() {
+        let refs = Refs::default()
+            .branch("master")
+            .branch("refs/heads/fix")
+            .tags("radicle-patch/*")
+            .refspec("refs/notes/commits")
+            .refspec("feature")
+            .refspec("refs/heads/a:refs/heads/b");
+
+        assert_eq!(
+            refs.refspecs(),
+            &[
+                "refs/heads/master:refs/heads/master",
+                "refs/heads/fix:refs/heads/fix",
+                "refs/tags/radicle-patch/*:refs/tags/radicle-patch/*",
+                "refs/notes/commits:refs/notes/commits",
+                "refs/heads/feature:refs/heads/feature",
+                "refs/heads/a:refs/heads/b",
+            ]
+        );
+        assert!(Refs::default().is_empty());
+    }
+
+    #[test]
+    fn test_transfer() {
+        assert_eq!(
+            Transfer::parse(
+                "remote: Counting objects: 10% (1/10)\rReceiving objects:  45% (450/1000), 1.20 MiB | 2.00 MiB/s"
+            ),
+            Some(Transfer {
+                stage: "Receiving objects".to_owned(),
+                done: 450,
+                total: 1000,
+                bytes: Some("1.20 MiB".to_owned()),
+            })
+        );
+        assert_eq!(
+            Transfer::parse("Resolving deltas: 100% (12/12), done."),
+            Some(Transfer {
+                stage: "Resolving deltas".to_owned(),
+                done: 12,
+                total: 12,
+                bytes: None,
+            })
+        );
+        assert_eq!(Transfer::parse("fetching rad/ids/*"), None);
+    }
+
+    #[test]
+    fn test_journal() {
+        let urn = Urn::from_str("rad:git:hnrkmg77m8tfzj4gi4pa4mbhgysfgzwntjpao").unwrap();
+        let peer = PeerId::from(librad::SecretKey::new());
+        let refs = git::Oid::from_str("a8a1a2cc55e8c3e0b6c2c4e6b8ae7fcc7c0ce7d8").ok();
+        let changed = git::Oid::from_str("cb18e95ada2bb38aadd8e6cef0963ce37a87add3").ok();
+        let me = Owner::current();
+        // A process that was interrupted, and another one that is still running.
+        let gone = Owner {
+            pid: u32::MAX,
+            ..Owner::current()
+        };
+        let other = Owner {
+            host: String::from("elsewhere"),
+            pid: 1,
+        };
+        let mut journal = Journal::default();
+
+        assert!(journal
+            .begin(&urn, Mode::Push, refs, &gone, 1000)
+            .is_empty());
+        journal.complete(&urn, Mode::Push, &gone, &peer);
+        journal.complete(&urn, Mode::Push, &gone, &peer);
+
+        // An interrupted sync is resumed, but only for the same mode and refs.
+        assert!(journal.begin(&urn, Mode::Fetch, refs, &me, 1010).is_empty());
+        assert!(journal
+            .begin(&urn, Mode::Push, changed, &me, 1010)
+            .is_empty());
+        assert_eq!(journal.begin(&urn, Mode::Push, refs, &me, 1010), vec![peer]);
+
+        // Syncs of running processes aren't resumed.
+        assert!(journal
+            .begin(&urn, Mode::Push, refs, &other, 1010)
+            .is_empty());
+
+        // Stale syncs start over.
+        assert!(journal
+            .begin(&urn, Mode::Push, refs, &me, 1000 + JOURNAL_EXPIRY)
+            .is_empty());
+
+        journal.finish(&urn, Mode::Push, &me);
+        assert!(journal.begin(&urn, Mode::Push, refs, &me, 1000).is_empty());
+    }
+
+    #[test]
+    fn test_backoff() {
+        let backoff = Backoff {
+            base: time::Duration::from_secs(1),
+            max: time::Duration::from_secs(10),
+        };
+
+        assert_eq!(backoff.delay_with(1, 0.), time::Duration::from_secs(1));
+        assert_eq!(backoff.delay_with(2, 0.), time::Duration::from_secs(2));
+        assert_eq!(backoff.delay_with(3, 0.), time::Duration::from_secs(4));
+        assert_eq!(backoff.delay_with(5, 0.), time::Duration::from_secs(10));
+        assert_eq!(backoff.delay_with(64, 0.), time::Duration::from_secs(10));
+        assert_eq!(backoff.delay_with(3, 1.), time::Duration::from_secs(2));
+
+        for retry in 1..8 {
+            let delay = backoff.delay(retry);
+
+            assert!(delay >= backoff.delay_with(retry, 1.));
+            assert!(delay <= backoff.delay_with(retry, 0.));
+        }
+    }
+
+    #[test]
+    fn test_debounce() {
+        let delay = time::Duration::from_secs(5);
+        let start = time::Instant::now();
+        let mut debounce = Debounce::new(delay);
+
+        assert!(!debounce.due(start));
+
+        debounce.changed(start);
+        assert!(!debounce.due(start + time::Duration::from_secs(3)));
+
+        // A change while pending pushes the update back.
+        debounce.changed(start + time::Duration::from_secs(3));
+        assert!(!debounce.due(start + time::Duration::from_secs(6)));
+        assert!(debounce.due(start + time::Duration::from_secs(8)));
+        assert!(!debounce.due(start + time::Duration::from_secs(20)));
+    }
+}
@@ -0,0 +1,113 @@
+//! Connecting to seeds that have several addresses, eg. both IPv6 and IPv4.
+//!
+//! The addresses of a seed are otherwise tried one after the other, so a seed whose
+//! first address is unreachable, eg. on a network with broken IPv6, only connects
+//! once that attempt times out. Instead, connection attempts are raced as in "Happy
+//! Eyeballs" (RFC 8305): addresses are interleaved by family, IPv6 first, and each
+//! attempt starts [`ATTEMPT_DELAY`] after the previous one, or as soon as it fails.
+//! The first address to connect wins.
+use std::net::SocketAddr;
+use std::time;
+
+use futures::stream::{FuturesUnordered, StreamExt};
+
+use librad::net::quic::ConnectPeer;
+use librad::PeerId;
+
+use crate::tokio;
+
+/// Delay before the next connection attempt is started, if the previous one is still
+/// pending. This is the value recommended by RFC 8305.
+pub const ATTEMPT_DELAY: time::Duration = time::Duration::from_millis(250);
+
+/// Race connection attempts to a peer's addresses, and return the first address that
+/// connects, if any.
+pub(super) async fn race<E>(
+    endpoint: &E,
+    peer: PeerId,
+    addrs: &[SocketAddr],
+    timeout: time::Duration,
+) -> Option<SocketAddr>
+where
+    E: ConnectPeer + Send + Sync,
+{
+    let race = async {
+        let mut pending = interleave(addrs).into_iter();
+        let mut attempts = FuturesUnordered::new();
+
+        loop {
+            if let Some(addr) = pending.next() {
+                attempts
+                    .push(async move { endpoint.connect(peer, Some(addr)).await.map(|_| addr) });
+            } else if attempts.is_empty() {
+                return None;
+            }
+
+            let next = if pending.len() > 0 {
+                tokio::time::timeout(ATTEMPT_DELAY, attempts.next()).await
+            } else {
+                Ok(attempts.next().await)
+            };
+            match next {
+                // An address connected.
+                Ok(Some(Some(addr))) => return Some(addr),
+                // An attempt failed: start the next one right away.
+                Ok(Some(None)) => continue,
+                Ok(None) => return None,
+                // The previous attempt is still pending: start the next one alongside.
+                Err(_) => continue,
+            }
+        }
+    };
+    tokio::time::timeout(timeout, race).await.ok().flatten()
+}
+
+/// Order addresses for connecting, alternating between IPv6 and IPv4, starting with
+/// IPv6. The order within each family is kept.
+pub fn interleave(addrs: &[SocketAddr]) -> Vec<SocketAddr> {
+    let (v6, v4): (Vec<_>, Vec<_>) = addrs.iter().copied().partition(|a| a.is_ipv6());
+    let (mut v6, mut v4) = (v6.into_iter(), v4.into_iter());
+    let mut ordered = Vec::with_capacity(addrs.len());
+
+    loop {
+        match (v6.next(), v4.next()) {
+            (None, None) => break,
+            (a, b) => ordered.extend(a.into_iter().chain(b)),
+        }
+    }
+    ordered
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_interleave() {
+        let addrs: Vec<SocketAddr> = [
+            "10.0.0.1:8776",
+            "10.0.0.2:8776",
+            "10.0.0.3:8776",
+            "[::1]:8776",
+            "[::2]:8776",
+        ]
+        .iter()
+        .map(|a| a.parse().unwrap())
+        .collect();
+
+        assert_eq!(
+            interleave(&addrs)
+                .iter()
+                .map(|a| a.to_string())
+                .collect::<Vec<_>>(),
+            [
+                "[::1]:8776",
+                "10.0.0.1:8776",
+                "[::2]:8776",
+                "10.0.0.2:8776",
+                "10.0.0.3:8776"
+            ]
+        );
+        assert!(interleave(&[]).is_empty());
+    }
+}
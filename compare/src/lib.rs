@@ -0,0 +1,258 @@
+use std::ffi::OsString;
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+use librad::git::Urn;
+use librad::PeerId;
+
+use radicle_common as common;
+use radicle_common::args::{Args, Error, Help};
+use radicle_common::{git, profile, project};
+use radicle_terminal as term;
+
+pub const HELP: Help = Help {
+    name: "compare",
+    description: env!("CARGO_PKG_DESCRIPTION"),
+    version: env!("CARGO_PKG_VERSION"),
+    usage: r#"
+Usage
+
+    rad compare <branch> <branch> [--patch] [--all] [--stat]
+
+    Compares two branches of the current project in storage, showing the
+    commits unique to each side and a summary of the changes between them.
+
+    Branches are given as `<peer-id>/<branch>` for a branch of a tracked peer,
+    or as `<branch>` for one of your own branches, eg.
+
+        rad compare hyb5to4rshftx4apgmu9s6wnsp4ddmp1mz6ijh4qqey7fb8wrpawxa/master master
+
+Options
+
+    --patch     Show the full diff between the two branches
+    --all       List all commits unique to each side, instead of summarizing them
+    --stat      Show the diffstat of each commit
+    --help      Print help
+"#,
+};
+
+/// A branch, either of a peer or of the local peer.
+#[derive(Debug, Clone)]
+pub struct Branch {
+    pub peer: Option<PeerId>,
+    pub name: String,
+}
+
+impl Branch {
+    /// Fully qualified name of the branch in storage.
+    fn reference(&self, urn: &Urn) -> String {
+        match &self.peer {
+            Some(peer) => format!(
+                "refs/namespaces/{}/refs/remotes/{}/heads/{}",
+                urn.encode_id(),
+                peer,
+                self.name
+            ),
+            None => format!(
+                "refs/namespaces/{}/refs/heads/{}",
+                urn.encode_id(),
+                self.name
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Branch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.peer {
+            Some(peer) => write!(f, "{}/{}", common::fmt::peer(peer), self.name),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+impl FromStr for Branch {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((peer, name)) = s.split_once('/') {
+            if let Ok(peer) = PeerId::from_str(peer) {
+                return Ok(Self {
+                    peer: Some(peer),
+                    name: name.to_owned(),
+                });
+            }
+        }
+        Ok(Self {
+            peer: None,
+            name: s.to_owned(),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Options {
+    pub left: Branch,
+    pub right: Branch,
+    pub patch: bool,
+    pub all: bool,
+    pub stat: bool,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut branches = Vec::new();
+        let mut patch = false;
+        let mut all = false;
+        let mut stat = false;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("patch") | Short('p') => {
+                    patch = true;
+                }
+                Long("all") => {
+                    all = true;
+                }
+                Long("stat") => {
+                    stat = true;
+                }
+                Long("help") => {
+                    return Err(Error::Help.into());
+                }
+                Value(val) if branches.len() < 2 => {
+                    let val = val.to_string_lossy();
+                    branches.push(Branch::from_str(&val)?);
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+
+        match branches.as_slice() {
+            [left, right] => Ok((
+                Options {
+                    left: left.clone(),
+                    right: right.clone(),
+                    patch,
+                    all,
+                    stat,
+                },
+                vec![],
+            )),
+            _ => Err(anyhow!("two branches to compare must be provided")),
+        }
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let (urn, _) = project::cwd()
+        .map_err(|_| anyhow!("this command must be run in the context of a project"))?;
+    let profile = ctx.profile()?;
+    let storage = profile::read_only(&profile)?;
+    let project = project::get(&storage, &urn)?
+        .ok_or_else(|| anyhow!("couldn't load project {} from local state", urn))?;
+    let repo = git::Repository::open_bare(profile.paths().git_dir())?;
+
+    let left = resolve(&repo, &urn, &options.left)?;
+    let right = resolve(&repo, &urn, &options.right)?;
+    let base = repo.merge_base(left.id(), right.id()).map_err(|_| {
+        anyhow!(
+            "branches '{}' and '{}' have no common history",
+            options.left,
+            options.right
+        )
+    })?;
+
+    term::info!(
+        "{} {} ({}) {} {} ({})",
+        term::format::highlight(&project.name),
+        term::format::tertiary(&options.left),
+        term::format::secondary(common::fmt::oid(&left.id())),
+        term::format::dim("↔"),
+        term::format::tertiary(&options.right),
+        term::format::secondary(common::fmt::oid(&right.id())),
+    );
+    term::info!(
+        "{}",
+        term::format::dim(format!("merge base {}", common::fmt::oid(&base)))
+    );
+
+    for (branch, head) in [(&options.left, &left), (&options.right, &right)] {
+        let limit = if options.all {
+            usize::MAX
+        } else {
+            term::patch::COMMIT_LIMIT
+        };
+        let (commits, total) =
+            common::patch::patch_commits_limited(&repo, &base, &head.id(), limit)?;
+
+        term::blank();
+        term::info!(
+            "{} commit(s) only on {}",
+            term::format::bold(total),
+            term::format::tertiary(branch)
+        );
+        if !commits.is_empty() {
+            term::blank();
+            term::patch::list_commits(&repo, &commits, total, options.stat)?;
+        }
+    }
+
+    let diff = repo.diff_tree_to_tree(Some(&left.tree()?), Some(&right.tree()?), None)?;
+    let stats = diff.stats()?;
+
+    term::blank();
+    term::info!(
+        "{} file(s) changed, {} {}",
+        stats.files_changed(),
+        term::format::positive(format!("{} insertion(s)(+)", stats.insertions())),
+        term::format::negative(format!("{} deletion(s)(-)", stats.deletions())),
+    );
+
+    if options.patch {
+        term::blank();
+        diff.print(git::DiffFormat::Patch, |_, _, line| {
+            let content = String::from_utf8_lossy(line.content());
+            let content = content.trim_end_matches('\n');
+
+            match line.origin() {
+                '+' => term::print(term::format::positive(format!("+{}", content))),
+                '-' => term::print(term::format::negative(format!("-{}", content))),
+                ' ' => term::print(format!(" {}", content)),
+                'F' => term::print(term::format::bold(content)),
+                'H' => term::print(term::format::secondary(content)),
+                _ => term::print(content),
+            }
+            true
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Find the head commit of a branch in storage.
+fn resolve<'r>(
+    repo: &'r git::Repository,
+    urn: &Urn,
+    branch: &Branch,
+) -> anyhow::Result<git::Commit<'r>> {
+    let reference =
+        repo.find_reference(&branch.reference(urn))
+            .map_err(|_| match &branch.peer {
+                Some(_) => Error::WithHint {
+                    err: anyhow!("branch '{}' was not found in storage", branch),
+                    hint: "hint: make sure the peer is tracked, and run `rad sync --fetch`",
+                },
+                None => Error::WithHint {
+                    err: anyhow!("branch '{}' was not found in storage", branch),
+                    hint: "hint: run `git push rad` to publish your branch",
+                },
+            })?;
+
+    Ok(reference.peel_to_commit()?)
+}
@@ -0,0 +1,323 @@
+use std::ffi::OsString;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use either::Either;
+
+use librad::git::storage::Storage;
+use librad::git::Urn;
+use librad::PeerId;
+
+use radicle_common as common;
+use radicle_common::args::{Args, Error, Help};
+use radicle_common::project::Delegation;
+use radicle_common::{git, identity, keys, person, project};
+use radicle_terminal as term;
+
+pub const HELP: Help = Help {
+    name: "delegate",
+    description: env!("CARGO_PKG_DESCRIPTION"),
+    version: env!("CARGO_PKG_VERSION"),
+    usage: r#"
+Usage
+
+    rad delegate [list] [--project <urn>]
+    rad delegate add <peer-id | urn> [--project <urn>]
+    rad delegate remove <peer-id | urn> [--project <urn>]
+
+    Manages the delegates of a project, ie. its maintainers. A delegate is
+    either a key, given as a peer id, or a personal identity, given as a
+    URN, in which case any of the person's keys can act for them. The project
+    defaults to the current one.
+
+    Adding or removing a delegate proposes a new revision of the project's
+    identity, signed with your key. If the project has other delegates, the
+    change only takes effect once a majority of the current delegates signed
+    it: publish it with `rad id publish`, and have them run
+    `rad id sign --peer <your-peer-id>` after fetching your refs. See
+    `rad id show` for the status of the revision.
+
+    To add a personal identity, it must be in your storage, eg. because you
+    fetched a project they maintain.
+
+Options
+
+    --project <urn>    Project to manage the delegates of
+    --help             Print help
+"#,
+};
+
+/// A delegate, as given on the command line.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Delegate {
+    /// A key.
+    Key(PeerId),
+    /// A personal identity.
+    Person(Urn),
+}
+
+impl FromStr for Delegate {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(peer) = PeerId::from_str(s) {
+            Ok(Self::Key(peer))
+        } else if let Ok(urn) = Urn::from_str(s) {
+            Ok(Self::Person(urn))
+        } else {
+            Err(anyhow!(
+                "invalid delegate '{}', expected a peer id or URN",
+                s
+            ))
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum OperationName {
+    List,
+    Add,
+    Remove,
+}
+
+#[derive(Debug)]
+pub enum Operation {
+    List,
+    Add { delegate: Delegate },
+    Remove { delegate: Delegate },
+}
+
+#[derive(Debug)]
+pub struct Options {
+    pub op: Operation,
+    pub project: Option<Urn>,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut op: Option<OperationName> = None;
+        let mut delegate: Option<Delegate> = None;
+        let mut project: Option<Urn> = None;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("help") => {
+                    return Err(Error::Help.into());
+                }
+                Long("project") => {
+                    let val = parser.value()?;
+                    let val = val.to_string_lossy();
+
+                    project =
+                        Some(Urn::from_str(&val).map_err(|_| anyhow!("invalid URN '{}'", val))?);
+                }
+                Value(val) if op.is_none() => match val.to_string_lossy().as_ref() {
+                    "l" | "list" => op = Some(OperationName::List),
+                    "a" | "add" => op = Some(OperationName::Add),
+                    "r" | "remove" => op = Some(OperationName::Remove),
+
+                    unknown => anyhow::bail!("unknown operation '{}'", unknown),
+                },
+                Value(val)
+                    if delegate.is_none()
+                        && matches!(op, Some(OperationName::Add | OperationName::Remove)) =>
+                {
+                    delegate = Some(Delegate::from_str(&val.to_string_lossy())?);
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+
+        let delegate = || {
+            delegate
+                .ok_or_else(|| anyhow!("a delegate must be specified, see `rad delegate --help`"))
+        };
+        let op = match op.unwrap_or(OperationName::List) {
+            OperationName::List => Operation::List,
+            OperationName::Add => Operation::Add {
+                delegate: delegate()?,
+            },
+            OperationName::Remove => Operation::Remove {
+                delegate: delegate()?,
+            },
+        };
+
+        Ok((Options { op, project }, vec![]))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let profile = ctx.profile()?;
+    let signer = term::signer(&profile)?;
+    let storage = keys::storage(&profile, signer)?;
+    let urn = match options.project {
+        Some(urn) => urn,
+        None => project::cwd()
+            .map(|(urn, _)| urn)
+            .map_err(|_| Error::WithHint {
+                err: anyhow!("a project must be specified outside of a project"),
+                hint: "hint: use `--project <urn>`",
+            })?,
+    };
+    let mut delegations = project::delegations(&storage, &urn)?;
+
+    match options.op {
+        Operation::List => {
+            list(&delegations, storage.peer_id());
+            return Ok(());
+        }
+        Operation::Add { delegate } => {
+            if delegations.iter().any(|d| is_delegate(d, &delegate)) {
+                anyhow::bail!("{} is already a delegate of {}", name(&delegate), urn);
+            }
+            let delegation = match &delegate {
+                Delegate::Key(peer) => Either::Left(*peer.as_public_key()),
+                Delegate::Person(person) => {
+                    Either::Right(person::get(&storage, person)?.ok_or_else(|| {
+                        Error::WithHint {
+                            err: anyhow!("person {} was not found in storage", person),
+                            hint: "hint: fetch a project they maintain first, eg. with `rad track`",
+                        }
+                    })?)
+                }
+            };
+            delegations.push(delegation);
+            project::set_delegations(&storage, &urn, delegations)?;
+
+            term::success!(
+                "Proposed adding {} as a delegate of {}",
+                term::format::highlight(name(&delegate)),
+                term::format::highlight(&urn)
+            );
+        }
+        Operation::Remove { delegate } => {
+            let count = delegations.len();
+            delegations.retain(|d| !is_delegate(d, &delegate));
+
+            if delegations.len() == count {
+                if let Delegate::Key(peer) = &delegate {
+                    if let Some(person) = owner(&delegations, peer) {
+                        return Err(Error::WithHint {
+                            err: anyhow!(
+                                "{} is a key of delegate {}, not a delegate itself",
+                                common::fmt::peer(peer),
+                                person
+                            ),
+                            hint: "hint: remove the personal identity instead, by its URN",
+                        }
+                        .into());
+                    }
+                }
+                anyhow::bail!("{} is not a delegate of {}", name(&delegate), urn);
+            }
+            project::set_delegations(&storage, &urn, delegations)?;
+
+            term::success!(
+                "Proposed removing {} as a delegate of {}",
+                term::format::highlight(name(&delegate)),
+                term::format::highlight(&urn)
+            );
+        }
+    }
+
+    quorum(&storage, &urn)
+}
+
+/// Report whether the new revision of a project's identity is in effect.
+fn quorum(storage: &Storage, urn: &Urn) -> anyhow::Result<()> {
+    let repo = git::Repository::open_bare(storage.path())?;
+    let head = identity::history(&repo, urn, None)?
+        .pop()
+        .ok_or_else(|| anyhow!("project {} has no identity revisions", urn))?;
+
+    if identity::verified(storage, urn)? == Some(head.commit) {
+        term::success!(
+            "Revision {} is in effect",
+            term::format::tertiary(common::fmt::oid(&head.commit))
+        );
+        term::tip!("To publish it, run `rad id publish`");
+    } else {
+        term::tip!(
+            "Revision {} needs the signatures of other delegates: publish it with `rad id publish`, \
+             and have them run `rad id sign {} --peer {}`",
+            common::fmt::oid(&head.commit),
+            urn,
+            storage.peer_id()
+        );
+    }
+    Ok(())
+}
+
+/// Print the delegates of a project.
+fn list(delegations: &[Delegation], me: &PeerId) {
+    let mut table = term::Table::default();
+
+    for delegation in delegations {
+        match delegation {
+            Either::Left(pk) => {
+                let peer = PeerId::from(*pk);
+                table.push([
+                    term::format::tertiary(peer.default_encoding()),
+                    term::format::dim("key"),
+                    if peer == *me {
+                        term::format::badge_primary("you")
+                    } else {
+                        String::new()
+                    },
+                ]);
+            }
+            Either::Right(person) => {
+                let mine = person.delegations().iter().any(|k| k == me.as_public_key());
+                table.push([
+                    term::format::tertiary(person.urn()),
+                    term::format::dim(format!(
+                        "person {} ({} key(s))",
+                        person.subject().name,
+                        person.delegations().len()
+                    )),
+                    if mine {
+                        term::format::badge_primary("you")
+                    } else {
+                        String::new()
+                    },
+                ]);
+            }
+        }
+    }
+    table.render();
+}
+
+/// Whether a delegation is the given delegate.
+fn is_delegate(delegation: &Delegation, delegate: &Delegate) -> bool {
+    match (delegation, delegate) {
+        (Either::Left(pk), Delegate::Key(peer)) => pk == peer.as_public_key(),
+        (Either::Right(person), Delegate::Person(urn)) => person.urn() == *urn,
+        _ => false,
+    }
+}
+
+/// Get the personal identity delegate that the given key belongs to, if any.
+fn owner(delegations: &[Delegation], peer: &PeerId) -> Option<Urn> {
+    delegations.iter().find_map(|d| match d {
+        Either::Right(person)
+            if person
+                .delegations()
+                .iter()
+                .any(|k| k == peer.as_public_key()) =>
+        {
+            Some(person.urn())
+        }
+        _ => None,
+    })
+}
+
+/// Display name of a delegate.
+fn name(delegate: &Delegate) -> String {
+    match delegate {
+        Delegate::Key(peer) => common::fmt::peer(peer),
+        Delegate::Person(urn) => urn.to_string(),
+    }
+}
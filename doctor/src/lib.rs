@@ -0,0 +1,203 @@
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+
+use radicle_common::args::{Args, Error, Help};
+use radicle_common::git;
+use radicle_common::storage::{self, Owner};
+use radicle_terminal as term;
+
+pub const HELP: Help = Help {
+    name: "doctor",
+    description: env!("CARGO_PKG_DESCRIPTION"),
+    version: env!("CARGO_PKG_VERSION"),
+    usage: r#"
+Usage
+
+    rad doctor [--fix] [--fsck]
+
+    Checks the storage of the active profile for risky configurations,
+    such as keeping the monorepo on a network filesystem without
+    durable writes enabled, and for signs of corruption.
+
+    On network filesystems, commands writing to storage take a lock file
+    in the storage directory, and enable durable writes unless they were
+    configured explicitly.
+
+Options
+
+    --fix       Apply the recommended storage settings and remove the
+                storage lock if its process isn't running
+    --fsck      Check the monorepo for corruption (may take a while)
+    --help      Print help
+"#,
+};
+
+#[derive(Default, Debug)]
+pub struct Options {
+    pub fix: bool,
+    pub fsck: bool,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut fix = false;
+        let mut fsck = false;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("fix") => {
+                    fix = true;
+                }
+                Long("fsck") => {
+                    fsck = true;
+                }
+                Long("help") => {
+                    return Err(Error::Help.into());
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+
+        Ok((Options { fix, fsck }, vec![]))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let profile = ctx.profile()?;
+    let monorepo = profile.paths().git_dir().to_path_buf();
+    let mut problems = 0;
+
+    term::headline(&format!(
+        "Checking storage at {}",
+        term::format::highlight(monorepo.display())
+    ));
+    term::blank();
+
+    // Filesystem type.
+    let network = match storage::filesystem(&monorepo) {
+        Some(fstype) if storage::NETWORK_FILESYSTEMS.contains(&fstype.as_str()) => {
+            term::warning(&format!(
+                "Storage is on a network filesystem ({}); concurrent access from \
+                 multiple machines is not supported",
+                fstype
+            ));
+            true
+        }
+        Some(fstype) => {
+            term::success!("Filesystem type is {}", term::format::dim(fstype));
+            false
+        }
+        None => {
+            term::info!("Filesystem type could not be determined");
+            false
+        }
+    };
+
+    // Durable writes.
+    let repo = git::Repository::open_bare(&monorepo)?;
+    let mut config = repo.config()?;
+    let fsync = config.get_bool("core.fsyncObjectFiles").unwrap_or(false);
+
+    if fsync {
+        term::success!("Object files are synced to disk");
+    } else if network && options.fix {
+        config.set_bool("core.fsyncObjectFiles", true)?;
+        term::success!("Enabled `core.fsyncObjectFiles` in storage");
+    } else if network {
+        problems += 1;
+        term::warning("Object files are not synced to disk (`core.fsyncObjectFiles`)");
+        term::tip!("Run `rad doctor --fix` to enable it.");
+    }
+
+    // Storage lock, eg. left behind on another host.
+    let lock = monorepo.join(storage::LOCK_FILE);
+    if let Some(owner) = Owner::read(&lock) {
+        if options.fix && owner.is_stale() {
+            fs::remove_file(&lock)?;
+            term::success!("Removed storage lock of process {}", owner);
+        } else {
+            problems += 1;
+            term::warning(&format!("Storage is locked by process {}", owner));
+            term::tip!(
+                "If that process is no longer running, remove {}.",
+                term::format::tertiary(lock.display())
+            );
+        }
+    }
+
+    // Stale lock files, eg. left behind by an interrupted process.
+    let locks = stale_locks(&monorepo.join("refs"))?;
+    if locks.is_empty() {
+        term::success!("No stale lock files found");
+    } else {
+        problems += 1;
+
+        for lock in &locks {
+            term::warning(&format!("Lock file found: {}", lock.display()));
+        }
+        term::tip!("If no other radicle process is running, these lock files can be removed.");
+    }
+
+    // Corruption.
+    let dangling = storage::dangling(&repo)?;
+    if dangling.is_empty() {
+        term::success!("All references point to existing objects");
+    } else {
+        problems += 1;
+
+        for r in &dangling {
+            term::warning(&format!("Reference points to a missing object: {}", r));
+        }
+        term::tip!("Run `rad sync --fetch` to fetch the missing objects from a seed.");
+    }
+    if options.fsck {
+        let spinner = term::spinner("Checking storage for corruption...");
+
+        match git::git(&monorepo, ["fsck", "--connectivity-only", "--no-progress"]) {
+            Ok(_) => spinner.finish(),
+            Err(err) => {
+                problems += 1;
+                spinner.failed();
+                term::blob(err);
+            }
+        }
+    }
+
+    term::blank();
+    if problems == 0 {
+        term::success!("No problems found");
+    } else {
+        anyhow::bail!("{} problem(s) found", problems);
+    }
+
+    Ok(())
+}
+
+/// Find `*.lock` files under the given directory.
+fn stale_locks(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut locks = Vec::new();
+    let mut dirs = vec![dir.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries {
+            let path = entry?.path();
+
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path.extension().map_or(false, |e| e == "lock") {
+                locks.push(path);
+            }
+        }
+    }
+    Ok(locks)
+}
@@ -214,7 +214,7 @@ async fn setup(
     };
 
     let seed_host: String = term::text_input("Seed host", None)?;
-    let seed_url = url::Url::parse(&format!("https://{}", seed_host))?;
+    let seed_url = seed::api_url(&seed_host)?;
 
     let spinner = term::spinner("Querying seed...");
     let seed_id = match seed::get_seed_id(seed_url) {
@@ -0,0 +1,143 @@
+use std::ffi::OsString;
+
+use anyhow::anyhow;
+
+use radicle_common::args::{Args, Error, Help};
+use radicle_common::{config, profile, project, schema};
+use radicle_terminal as term;
+
+/// Version of the JSON output schema.
+pub const SCHEMA_VERSION: u32 = 1;
+
+pub const HELP: Help = Help {
+    name: "env",
+    description: env!("CARGO_PKG_DESCRIPTION"),
+    version: env!("CARGO_PKG_VERSION"),
+    usage: r#"
+Usage
+
+    rad env [--json] [--schema]
+
+    Prints the current radicle context as shell variable exports, suitable
+    for `eval "$(rad env)"`. Project variables are only set when run from
+    within a project.
+
+Variables
+
+    RAD_PROFILE             Active profile id
+    RAD_PROFILE_PATH        Active profile directory
+    RAD_PEER_ID             Peer ID of the active profile
+    RAD_SELF                URN of your personal identity
+    RAD_MONOREPO            Path of the git storage
+    RAD_PROJECT             URN of the current project
+    RAD_PROJECT_NAME        Name of the current project
+    RAD_DEFAULT_BRANCH      Default branch of the current project
+
+Options
+
+    --json      Output the variables as a JSON object
+    --schema    Print the JSON Schema of the `--json` output
+    --help      Print help
+"#,
+};
+
+#[derive(Default, Debug)]
+pub struct Options {
+    pub json: bool,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut json = false;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("json") => {
+                    json = true;
+                }
+                Long("schema") => {
+                    return Err(Error::Schema(json_schema()).into());
+                }
+                Long("help") => {
+                    return Err(Error::Help.into());
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+
+        Ok((Options { json }, vec![]))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let profile = ctx.profile()?;
+    let storage = profile::read_only(&profile)?;
+    let mut vars = Vec::new();
+
+    vars.push(("RAD_PROFILE", profile.id().to_string()));
+    if let Some(path) = config::Config::path(&profile).parent() {
+        vars.push(("RAD_PROFILE_PATH", path.display().to_string()));
+    }
+    vars.push(("RAD_PEER_ID", storage.peer_id().to_string()));
+    if let Some(urn) = storage.config()?.user()? {
+        vars.push(("RAD_SELF", urn.to_string()));
+    }
+    vars.push((
+        "RAD_MONOREPO",
+        profile.paths().git_dir().display().to_string(),
+    ));
+
+    if let Ok((urn, _)) = project::cwd() {
+        vars.push(("RAD_PROJECT", urn.to_string()));
+
+        if let Some(project) = project::get(&storage, &urn)? {
+            vars.push(("RAD_PROJECT_NAME", project.name));
+            vars.push(("RAD_DEFAULT_BRANCH", project.default_branch.to_string()));
+        }
+    }
+
+    if options.json {
+        let object = vars
+            .into_iter()
+            .map(|(k, v)| (k.to_owned(), radicle_common::json::Value::String(v)))
+            .collect::<radicle_common::json::Map<_, _>>();
+
+        println!("{}", radicle_common::json::to_string_pretty(&object)?);
+    } else {
+        for (key, value) in vars {
+            println!("export {}={}", key, quote(&value));
+        }
+    }
+
+    Ok(())
+}
+
+/// JSON Schema of the `--json` output. Project variables are only present within a project,
+/// and `RAD_SELF` only once a personal identity is set.
+pub fn json_schema() -> radicle_common::json::Value {
+    let object = radicle_common::json::json!({
+        "type": "object",
+        "properties": {
+            "RAD_PROFILE": schema::string("Active profile id"),
+            "RAD_PROFILE_PATH": schema::string("Active profile directory"),
+            "RAD_PEER_ID": schema::string("Peer ID of the active profile"),
+            "RAD_SELF": schema::string("URN of your personal identity"),
+            "RAD_MONOREPO": schema::string("Path of the git storage"),
+            "RAD_PROJECT": schema::string("URN of the current project"),
+            "RAD_PROJECT_NAME": schema::string("Name of the current project"),
+            "RAD_DEFAULT_BRANCH": schema::string("Default branch of the current project"),
+        },
+        "required": ["RAD_PROFILE", "RAD_PEER_ID", "RAD_MONOREPO"],
+        "additionalProperties": false,
+    });
+
+    schema::document("env", SCHEMA_VERSION, object)
+}
+
+/// Quote a value for POSIX shells.
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
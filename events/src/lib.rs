@@ -0,0 +1,811 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
+use std::ffi::OsString;
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::{io, io::Write as _, thread};
+
+use anyhow::anyhow;
+use serde::Serialize;
+
+use librad::git::Storage;
+use librad::git::Urn;
+use librad::profile::Profile;
+
+use radicle_common::args::{self, Args, Error, Help};
+use radicle_common::cobs::advisory::AdvisoryId;
+use radicle_common::cobs::patch::{PatchId, RevisionIx, Verdict};
+use radicle_common::json::{json, Value};
+use radicle_common::{cobs, git, json, keys, project, schema, sync, tokio};
+use radicle_terminal as term;
+
+/// Version of the JSON output schema.
+pub const SCHEMA_VERSION: u32 = 1;
+
+pub const HELP: Help = Help {
+    name: "events",
+    description: env!("CARGO_PKG_DESCRIPTION"),
+    version: env!("CARGO_PKG_VERSION"),
+    usage: r#"
+Usage
+
+    rad events [<urn>] [--follow] [--interval <secs>] [--[no-]fetch]
+               [--batch <count>] [--max-rate <count>]
+               [--review-sla <hours>] [--notify <command>]
+    rad events --schema
+
+    Prints project events as newline-delimited JSON, one event per line.
+    Events are detected by fetching the project from its seeds and comparing
+    the local state before and after. Without `--follow`, a single fetch is
+    done. With `--follow`, failures, eg. of fetching from unreachable seeds,
+    are printed as warnings, and polling goes on. If no URN is given, the
+    project of the working copy is used.
+
+    When a single fetch yields more than `--batch` events, eg. after being
+    offline for a while, they are coalesced into one digest event, which
+    holds the individual events. With `--max-rate`, no more than the given
+    number of lines are output per minute; events over the limit are held
+    back and output as a digest once the limit allows it.
+
+    Open patches assigned to you, eg. with `rad patch assign`, whose latest
+    revision you haven't reviewed within `--review-sla` hours of it being
+    published, yield a review reminder. Likewise, your own open patches on
+    whose latest revision changes were requested are reported, until a new
+    revision is published. Each revision is reported once per run. New security
+    advisories published by a delegate of the project are reported too, see
+    `rad advisory`. With `--notify`, the given command is run through the shell
+    for each of these events, with the event as JSON on its standard input, eg.
+    to send an email or a chat message.
+
+Events
+
+    syncCompleted       The project was fetched from seeds
+    refUpdated          A reference of the project was created, updated or deleted
+    patchDiscovered     A new patch was found
+    reviewReminder      A patch assigned to you is awaiting your review
+    changesRequested    Changes were requested on the latest revision of your patch
+    advisoryPublished   A delegate published a security advisory
+    digest              A batch of events, with counts per event type
+
+Options
+
+    --follow            Keep polling for events until interrupted
+    --interval <secs>   Polling interval with `--follow` (default: 60)
+    --[no-]fetch        Fetch from seeds before checking for events (default: fetch)
+    --batch <count>     Coalesce more than this many events into a digest (default: 10, 0 to disable)
+    --max-rate <count>  Output at most this many lines per minute (default: unlimited)
+    --review-sla <hours>
+                        Remind of assigned patches awaiting review for longer than this
+                        (default: 48, 0 to disable)
+    --notify <command>  Run this command for every review reminder, changes request and advisory
+    --schema            Print the JSON Schema of the output lines
+    --help              Print help
+"#,
+};
+
+#[derive(Debug)]
+pub struct Options {
+    pub urn: Option<Urn>,
+    pub follow: bool,
+    pub interval: Duration,
+    pub fetch: bool,
+    pub batch: usize,
+    pub max_rate: Option<usize>,
+    pub review_sla: Option<Duration>,
+    pub notify: Option<String>,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut urn = None;
+        let mut follow = false;
+        let mut interval = Duration::from_secs(60);
+        let mut fetch = true;
+        let mut batch = 10;
+        let mut max_rate = None;
+        let mut review_sla = Some(Duration::from_secs(DEFAULT_REVIEW_SLA_HOURS * 3600));
+        let mut notify = None;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("follow") | Short('f') => {
+                    follow = true;
+                }
+                Long("interval") => {
+                    let secs: u64 = args::parse_value("interval", parser.value()?)?;
+                    if secs == 0 {
+                        return Err(anyhow!(
+                            "the value specified for '--interval' must be positive"
+                        ));
+                    }
+                    interval = Duration::from_secs(secs);
+                }
+                Long("fetch") => {
+                    fetch = true;
+                }
+                Long("no-fetch") => {
+                    fetch = false;
+                }
+                Long("batch") => {
+                    batch = args::parse_value("batch", parser.value()?)?;
+                }
+                Long("max-rate") => {
+                    let rate: usize = args::parse_value("max-rate", parser.value()?)?;
+                    if rate == 0 {
+                        return Err(anyhow!(
+                            "the value specified for '--max-rate' must be positive"
+                        ));
+                    }
+                    max_rate = Some(rate);
+                }
+                Long("review-sla") => {
+                    let hours: u64 = args::parse_value("review-sla", parser.value()?)?;
+                    review_sla = if hours == 0 {
+                        None
+                    } else {
+                        Some(Duration::from_secs(hours * 3600))
+                    };
+                }
+                Long("notify") => {
+                    notify = Some(parser.value()?.to_string_lossy().into_owned());
+                }
+                Long("schema") => {
+                    return Err(Error::Schema(json_schema()).into());
+                }
+                Long("help") => {
+                    return Err(Error::Help.into());
+                }
+                Value(val) if urn.is_none() => {
+                    let val = val.to_string_lossy();
+                    let val = Urn::from_str(&val).map_err(|_| anyhow!("invalid URN '{}'", val))?;
+
+                    urn = Some(val);
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+
+        Ok((
+            Options {
+                urn,
+                follow,
+                interval,
+                fetch,
+                batch,
+                max_rate,
+                review_sla,
+                notify,
+            },
+            vec![],
+        ))
+    }
+}
+
+/// Default time after which assigned patches awaiting review are reminded of, in hours.
+pub const DEFAULT_REVIEW_SLA_HOURS: u64 = 48;
+
+/// A project event.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Event {
+    #[serde(rename_all = "camelCase")]
+    SyncCompleted {
+        urn: String,
+        seeds: usize,
+        failed: usize,
+    },
+    #[serde(rename_all = "camelCase")]
+    RefUpdated {
+        urn: String,
+        name: String,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    PatchDiscovered {
+        urn: String,
+        id: String,
+        title: String,
+        author: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    ReviewReminder {
+        urn: String,
+        id: String,
+        title: String,
+        author: String,
+        revision: RevisionIx,
+        /// Seconds since the revision was published.
+        waiting: u64,
+    },
+    #[serde(rename_all = "camelCase")]
+    ChangesRequested {
+        urn: String,
+        id: String,
+        title: String,
+        revision: RevisionIx,
+        /// Peers who requested changes.
+        reviewers: Vec<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    AdvisoryPublished {
+        urn: String,
+        id: String,
+        title: String,
+        severity: String,
+        author: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    Digest {
+        urn: String,
+        /// Number of events per event type.
+        counts: BTreeMap<&'static str, usize>,
+        events: Vec<Event>,
+    },
+}
+
+impl Event {
+    /// The event type, as it is output.
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::SyncCompleted { .. } => "syncCompleted",
+            Self::RefUpdated { .. } => "refUpdated",
+            Self::PatchDiscovered { .. } => "patchDiscovered",
+            Self::ReviewReminder { .. } => "reviewReminder",
+            Self::ChangesRequested { .. } => "changesRequested",
+            Self::AdvisoryPublished { .. } => "advisoryPublished",
+            Self::Digest { .. } => "digest",
+        }
+    }
+
+    /// Coalesce events into a digest.
+    fn digest(urn: &Urn, events: Vec<Event>) -> Self {
+        let mut counts = BTreeMap::new();
+        for event in &events {
+            *counts.entry(event.kind()).or_insert(0) += 1;
+        }
+        Self::Digest {
+            urn: urn.to_string(),
+            counts,
+            events,
+        }
+    }
+}
+
+/// JSON Schema of an output line, ie. of an event with the time it was detected.
+pub fn json_schema() -> Value {
+    let digest = json!({ "type": "array", "items": { "oneOf": event_schemas(false) } });
+    let mut events = event_schemas(true);
+
+    events.push(event_schema(
+        "digest",
+        true,
+        vec![
+            ("urn", schema::string("Project URN")),
+            (
+                "counts",
+                json!({
+                    "type": "object",
+                    "additionalProperties": { "type": "integer" },
+                    "description": "Number of events per event type",
+                }),
+            ),
+            ("events", digest),
+        ],
+    ));
+
+    schema::document("events", SCHEMA_VERSION, json!({ "oneOf": events }))
+}
+
+/// Schemas of the events, digests excepted.
+fn event_schemas(timestamp: bool) -> Vec<Value> {
+    let count =
+        |description: &str| json!({ "type": "integer", "minimum": 0, "description": description });
+    let nullable =
+        |description: &str| json!({ "type": ["string", "null"], "description": description });
+
+    vec![
+        event_schema(
+            "syncCompleted",
+            timestamp,
+            vec![
+                ("urn", schema::string("Project URN")),
+                ("seeds", count("Number of seeds synced with")),
+                (
+                    "failed",
+                    count("Number of seeds that couldn't be synced with"),
+                ),
+            ],
+        ),
+        event_schema(
+            "refUpdated",
+            timestamp,
+            vec![
+                ("urn", schema::string("Project URN")),
+                ("name", schema::string("Reference name")),
+                ("old", nullable("Previous target, if the reference existed")),
+                ("new", nullable("New target, if the reference still exists")),
+            ],
+        ),
+        event_schema(
+            "patchDiscovered",
+            timestamp,
+            vec![
+                ("urn", schema::string("Project URN")),
+                ("id", schema::string("Patch identifier")),
+                ("title", schema::string("Patch title")),
+                ("author", schema::string("Peer ID of the patch author")),
+            ],
+        ),
+        event_schema(
+            "reviewReminder",
+            timestamp,
+            vec![
+                ("urn", schema::string("Project URN")),
+                ("id", schema::string("Patch identifier")),
+                ("title", schema::string("Patch title")),
+                ("author", schema::string("Peer ID of the patch author")),
+                ("revision", count("Revision awaiting review")),
+                ("waiting", count("Seconds since the revision was published")),
+            ],
+        ),
+        event_schema(
+            "changesRequested",
+            timestamp,
+            vec![
+                ("urn", schema::string("Project URN")),
+                ("id", schema::string("Patch identifier")),
+                ("title", schema::string("Patch title")),
+                (
+                    "revision",
+                    count("Revision on which changes were requested"),
+                ),
+                (
+                    "reviewers",
+                    json!({
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Peer IDs of the reviewers who requested changes",
+                    }),
+                ),
+            ],
+        ),
+        event_schema(
+            "advisoryPublished",
+            timestamp,
+            vec![
+                ("urn", schema::string("Project URN")),
+                ("id", schema::string("Advisory identifier")),
+                ("title", schema::string("Advisory title")),
+                (
+                    "severity",
+                    json!({
+                        "enum": ["low", "medium", "high", "critical"],
+                        "description": "Severity of the vulnerability",
+                    }),
+                ),
+                (
+                    "author",
+                    schema::string("Peer ID of the delegate who published it"),
+                ),
+            ],
+        ),
+    ]
+}
+
+/// Schema of an event of the given type, with or without the time it was detected.
+fn event_schema(kind: &str, timestamp: bool, mut fields: Vec<(&str, Value)>) -> Value {
+    fields.insert(0, ("type", json!({ "const": kind })));
+    if timestamp {
+        fields.insert(
+            0,
+            (
+                "timestamp",
+                json!({ "type": "integer", "description": "Seconds since the epoch at which the event was detected" }),
+            ),
+        );
+    }
+    schema::object(&fields)
+}
+
+/// An event, as it is output.
+#[derive(Debug, Serialize)]
+struct Output<'a> {
+    /// Seconds since the epoch at which the event was detected.
+    timestamp: u64,
+    #[serde(flatten)]
+    event: &'a Event,
+}
+
+/// Limits the number of lines output per minute.
+#[derive(Debug)]
+struct RateLimit {
+    max: Option<usize>,
+    sent: VecDeque<Instant>,
+}
+
+impl RateLimit {
+    fn new(max: Option<usize>) -> Self {
+        Self {
+            max,
+            sent: VecDeque::new(),
+        }
+    }
+
+    /// Whether a line can be output now. If so, it is counted towards the limit.
+    fn allow(&mut self) -> bool {
+        let max = match self.max {
+            Some(max) => max,
+            None => return true,
+        };
+        let now = Instant::now();
+
+        while matches!(self.sent.front(), Some(t) if now.duration_since(*t) >= Duration::from_secs(60))
+        {
+            self.sent.pop_front();
+        }
+        if self.sent.len() >= max {
+            return false;
+        }
+        self.sent.push_back(now);
+
+        true
+    }
+}
+
+/// The state of a project in storage, used to detect changes.
+#[derive(Debug, Default)]
+struct State {
+    refs: BTreeMap<String, git::Oid>,
+    patches: BTreeSet<PatchId>,
+    advisories: BTreeSet<AdvisoryId>,
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let urn = match options.urn {
+        Some(urn) => urn,
+        None => project::cwd()
+            .map(|(urn, _)| urn)
+            .map_err(|_| anyhow!("a URN must be given when not in the context of a project"))?,
+    };
+    let profile = ctx.profile()?;
+    let signer = term::signer(&profile)?;
+    let storage = keys::storage(&profile, signer.clone())?;
+    let rt = tokio::runtime::Runtime::new()?;
+
+    project::get(&storage, &urn)?
+        .ok_or_else(|| anyhow!("project {} was not found in local storage", urn))?;
+
+    let mut state = state(&storage, &profile, &urn)?;
+    // Patch revisions that were reminded of, or whose changes requests were reported.
+    let mut reminded = HashSet::new();
+    let mut requested = HashSet::new();
+    let mut limit = RateLimit::new(options.max_rate);
+    // Events held back by the rate limit.
+    let mut pending: Vec<Event> = Vec::new();
+
+    loop {
+        let mut events = std::mem::take(&mut pending);
+        let held = !events.is_empty();
+
+        if options.fetch {
+            let results = sync::seeds(&profile).and_then(|seeds| {
+                term::sync::sync(
+                    urn.clone(),
+                    seeds,
+                    sync::Mode::Fetch,
+                    &profile,
+                    signer.clone(),
+                    &rt,
+                )
+            });
+            if let Some(results) = recover(options.follow, "fetch from seeds", results)? {
+                let failed = results
+                    .iter()
+                    .filter(|r| !matches!(r.fetch, Some(Ok(_))))
+                    .count();
+
+                events.push(Event::SyncCompleted {
+                    urn: urn.to_string(),
+                    seeds: results.len(),
+                    failed,
+                });
+            }
+        }
+
+        let mut notices = Vec::new();
+        let changes = self::state(&storage, &profile, &urn)
+            .and_then(|new| Ok((diff(&storage, &profile, &urn, &state, &new)?, new)));
+        // Nb. On failure, the state is kept, so that changes are detected next time.
+        if let Some((changes, new)) = recover(options.follow, "read project state", changes)? {
+            // Advisories are notified of, like review reminders.
+            let (advisories, changes): (Vec<_>, Vec<_>) = changes
+                .into_iter()
+                .partition(|e| matches!(e, Event::AdvisoryPublished { .. }));
+
+            events.extend(changes);
+            notices.extend(advisories);
+            state = new;
+        }
+
+        let requests = self::changes_requested(&storage, &profile, &urn, &mut requested);
+        if let Some(requests) = recover(options.follow, "check patch reviews", requests)? {
+            notices.extend(requests);
+        }
+        if let Some(sla) = options.review_sla {
+            let reminders = self::reminders(&storage, &profile, &urn, sla, &mut reminded);
+            if let Some(reminders) = recover(options.follow, "check review requests", reminders)? {
+                notices.extend(reminders);
+            }
+        }
+        if let Some(command) = &options.notify {
+            for notice in &notices {
+                // Keep going if the notification fails, the event is still output.
+                if let Err(err) = self::notify(command, notice) {
+                    term::warning(&format!("Notification failed: {}", err));
+                }
+            }
+        }
+        events.extend(notices);
+
+        let events =
+            if (options.batch > 0 && events.len() > options.batch) || (held && events.len() > 1) {
+                vec![Event::digest(&urn, events)]
+            } else {
+                events
+            };
+        for event in events {
+            if !pending.is_empty() || !limit.allow() {
+                // Hold back the individual events, to be coalesced with later ones.
+                match event {
+                    Event::Digest { events, .. } => pending.extend(events),
+                    event => pending.push(event),
+                }
+                continue;
+            }
+            emit(&event)?;
+        }
+
+        if !options.follow {
+            // There won't be a next time, so output what was held back.
+            if !pending.is_empty() {
+                emit(&Event::digest(&urn, pending))?;
+            }
+            break;
+        }
+        thread::sleep(options.interval);
+    }
+
+    Ok(())
+}
+
+/// With `--follow`, warn of a failure and carry on, so that a transient error, eg. a
+/// seed being unreachable, doesn't end the polling. Otherwise, return the error.
+fn recover<T>(follow: bool, action: &str, result: anyhow::Result<T>) -> anyhow::Result<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(err) if follow => {
+            term::warning(&format!("Failed to {}: {}", action, err));
+            Ok(None)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Read the current state of a project.
+fn state(storage: &Storage, profile: &Profile, urn: &Urn) -> anyhow::Result<State> {
+    let repo = git::Repository::open_bare(profile.paths().git_dir())?;
+    let prefix = format!("refs/namespaces/{}/", urn.encode_id());
+    let mut refs = BTreeMap::new();
+
+    for r in repo.references()?.flatten() {
+        if let (Some(name), Some(oid)) = (r.name(), r.target()) {
+            if let Some(name) = name.strip_prefix(&prefix) {
+                refs.insert(name.to_owned(), oid);
+            }
+        }
+    }
+
+    let cobs = cobs::store(profile, storage)?;
+    let patches = cobs
+        .patches()
+        .all(urn)?
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    let advisories = cobs
+        .advisories()
+        .all(urn)?
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+
+    Ok(State {
+        refs,
+        patches,
+        advisories,
+    })
+}
+
+/// Compute the events that lead from the old state to the new state.
+fn diff(
+    storage: &Storage,
+    profile: &Profile,
+    urn: &Urn,
+    old: &State,
+    new: &State,
+) -> anyhow::Result<Vec<Event>> {
+    let mut events = Vec::new();
+    let names = old
+        .refs
+        .keys()
+        .chain(new.refs.keys())
+        .collect::<BTreeSet<_>>();
+
+    for name in names {
+        let (before, after) = (old.refs.get(name), new.refs.get(name));
+        if before != after {
+            events.push(Event::RefUpdated {
+                urn: urn.to_string(),
+                name: name.clone(),
+                old: before.map(|o| o.to_string()),
+                new: after.map(|o| o.to_string()),
+            });
+        }
+    }
+
+    if new.patches.difference(&old.patches).next().is_some() {
+        let cobs = cobs::store(profile, storage)?;
+        let patches = cobs.patches();
+
+        for id in new.patches.difference(&old.patches) {
+            if let Some(patch) = patches.get(urn, id)? {
+                events.push(Event::PatchDiscovered {
+                    urn: urn.to_string(),
+                    id: id.to_string(),
+                    title: patch.title,
+                    author: patch.author.peer.default_encoding(),
+                });
+            }
+        }
+    }
+
+    if new.advisories.difference(&old.advisories).next().is_some() {
+        let project = project::get(storage, urn)?
+            .ok_or_else(|| anyhow!("project {} was not found in local storage", urn))?;
+        let cobs = cobs::store(profile, storage)?;
+        let advisories = cobs.advisories();
+
+        for id in new.advisories.difference(&old.advisories) {
+            // Anyone can publish an advisory, only the project's are reported.
+            match advisories.get(urn, id) {
+                Ok(Some(advisory)) if project.remotes.contains(&advisory.author.peer) => {
+                    events.push(Event::AdvisoryPublished {
+                        urn: urn.to_string(),
+                        id: id.to_string(),
+                        title: advisory.title,
+                        severity: advisory.severity.to_string(),
+                        author: advisory.author.peer.default_encoding(),
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(events)
+}
+
+/// Find the open patches assigned to you, whose latest revision you haven't reviewed within
+/// the `sla`. Revisions in `reminded` are skipped, and reminded revisions are added to it.
+fn reminders(
+    storage: &Storage,
+    profile: &Profile,
+    urn: &Urn,
+    sla: Duration,
+    reminded: &mut HashSet<(PatchId, RevisionIx)>,
+) -> anyhow::Result<Vec<Event>> {
+    let cobs = cobs::store(profile, storage)?;
+    let patches = cobs.patches();
+    let whoami = patches.whoami.urn();
+    let me = storage.peer_id();
+    let now = cobs::Timestamp::now().as_secs();
+    let mut events = Vec::new();
+
+    for (id, patch) in patches.proposed(urn)? {
+        if patch.is_merged() || !patch.assignees.iter().any(|a| &a.peer == me) {
+            continue;
+        }
+        let (ix, revision) = patch.latest();
+        let waiting = now.saturating_sub(revision.timestamp.as_secs());
+
+        if revision.reviews.contains_key(&whoami)
+            || waiting < sla.as_secs()
+            || !reminded.insert((id, ix))
+        {
+            continue;
+        }
+        events.push(Event::ReviewReminder {
+            urn: urn.to_string(),
+            id: id.to_string(),
+            title: patch.title.clone(),
+            author: patch.author.peer.default_encoding(),
+            revision: ix,
+            waiting,
+        });
+    }
+    Ok(events)
+}
+
+/// Find your open patches on whose latest revision changes were requested. Revisions in
+/// `reported` are skipped, and reported revisions are added to it.
+fn changes_requested(
+    storage: &Storage,
+    profile: &Profile,
+    urn: &Urn,
+    reported: &mut HashSet<(PatchId, RevisionIx)>,
+) -> anyhow::Result<Vec<Event>> {
+    let cobs = cobs::store(profile, storage)?;
+    let patches = cobs.patches();
+    let me = storage.peer_id();
+    let mut events = Vec::new();
+
+    for (id, patch) in patches.proposed(urn)? {
+        if patch.is_merged() || &patch.author.peer != me {
+            continue;
+        }
+        let (ix, revision) = patch.latest();
+        let reviewers = revision
+            .reviews
+            .values()
+            .filter(|r| r.verdict == Some(Verdict::RequestChanges))
+            .map(|r| r.author.peer.default_encoding())
+            .collect::<Vec<_>>();
+
+        if reviewers.is_empty() || !reported.insert((id, ix)) {
+            continue;
+        }
+        events.push(Event::ChangesRequested {
+            urn: urn.to_string(),
+            id: id.to_string(),
+            title: patch.title.clone(),
+            revision: ix,
+            reviewers,
+        });
+    }
+    Ok(events)
+}
+
+/// Run a notification command through the shell, with the event as JSON on its stdin.
+fn notify(command: &str, event: &Event) -> anyhow::Result<()> {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(json::to_string(&Output { timestamp, event })?.as_bytes())?;
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        anyhow::bail!("`{}` exited with {}", command, status);
+    }
+    Ok(())
+}
+
+/// Write an event to stdout as a single line of JSON.
+fn emit(event: &Event) -> anyhow::Result<()> {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let mut stdout = io::stdout();
+
+    writeln!(stdout, "{}", json::to_string(&Output { timestamp, event })?)?;
+    stdout.flush()?;
+
+    Ok(())
+}
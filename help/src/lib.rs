@@ -5,15 +5,26 @@ use radicle_terminal as term;
 
 #[cfg(feature = "ethereum")]
 pub use rad_account;
+pub use rad_advisory;
+pub use rad_archive;
 pub use rad_auth;
+pub use rad_badge;
+pub use rad_bridge;
 pub use rad_checkout;
 pub use rad_clone;
 pub use rad_comment;
+pub use rad_compare;
+pub use rad_delegate;
+pub use rad_doctor;
 pub use rad_edit;
 #[cfg(feature = "ethereum")]
 pub use rad_ens;
+pub use rad_env;
+pub use rad_events;
 #[cfg(feature = "ethereum")]
 pub use rad_gov;
+pub use rad_id;
+pub use rad_import_repo;
 pub use rad_init;
 pub use rad_inspect;
 pub use rad_issue;
@@ -21,14 +32,19 @@ pub use rad_ls;
 pub use rad_merge;
 pub use rad_patch;
 pub use rad_path;
+pub use rad_profile;
 pub use rad_pull;
 pub use rad_push;
 pub use rad_remote;
 pub use rad_review;
 pub use rad_rm;
+pub use rad_seed;
 pub use rad_self;
+pub use rad_setup_hooks;
+pub use rad_show;
 pub use rad_sync;
 pub use rad_track;
+pub use rad_tutorial;
 pub use rad_untrack;
 
 pub const HELP: Help = Help {
@@ -40,6 +56,7 @@ pub const HELP: Help = Help {
 
 const COMMANDS: &[Help] = &[
     rad_auth::HELP,
+    rad_profile::HELP,
     rad_init::HELP,
     rad_self::HELP,
     rad_inspect::HELP,
@@ -58,6 +75,7 @@ const COMMANDS: &[Help] = &[
     rad_account::HELP,
     rad_rm::HELP,
     rad_edit::HELP,
+    rad_tutorial::HELP,
     crate::HELP,
 ];
 
@@ -83,7 +101,9 @@ impl Args for Options {
 }
 
 pub fn run(_options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
-    println!("Usage: rad <command> [--help]");
+    println!(
+        "Usage: rad [--storage <path>] [--profile <id>] [--repo <path>] [--git-trace] [--no-confirm] <command> [--help]"
+    );
 
     if ctx.profile().is_err() {
         println!();
@@ -111,6 +131,19 @@ pub fn run(_options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
     println!();
     println!("See `rad <command> --help` to learn about a specific command.");
     println!();
+    println!(
+        "Use `--storage <path>` or set `RAD_HOME` to use a radicle home other than the default."
+    );
+    println!(
+        "Use `--profile <id>` or set `RAD_PROFILE` to use a profile other than the active one."
+    );
+    println!(
+        "Use `--repo <path>` to run a command against a repository other than the current one."
+    );
+    println!("Use `--git-trace` to trace the git operations performed by a command.");
+    println!("Use `--no-confirm` or set `RAD_YES=1` to answer yes to all confirmation prompts.");
+    println!("Set `RAD_SYMBOLS` to `ascii` or `none` to change the symbols shown next to states.");
+    println!();
 
     Ok(())
 }
@@ -0,0 +1,475 @@
+use std::ffi::OsString;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+use librad::git::storage::Storage;
+use librad::git::Urn;
+use librad::PeerId;
+
+use radicle_common as common;
+use radicle_common::args::{Args, Error, Help};
+use radicle_common::identity::{self, Change, Revision};
+use radicle_common::{cobs, git, keys, project, sync, tokio};
+use radicle_terminal as term;
+
+pub const HELP: Help = Help {
+    name: "id",
+    description: env!("CARGO_PKG_DESCRIPTION"),
+    version: env!("CARGO_PKG_VERSION"),
+    usage: r#"
+Usage
+
+    rad id show [<urn>]
+    rad id history [<urn>] [--peer <peer-id>]
+    rad id edit [<urn>] [--name <name>] [--description <text>] [--default-branch <name>]
+    rad id sign [<urn>] --peer <peer-id>
+    rad id publish [<urn>]
+
+    Manages identity documents. The identity defaults to the current project.
+
+    `show` shows the latest revision of the identity, whether it has the
+    signatures of enough delegates to take effect, and the revisions
+    proposed by tracked peers that you haven't signed yet.
+
+    `history` shows every revision of the identity, newest first, with the
+    keys that signed it and what changed: name, description, default branch
+    and delegates.
+
+    `edit` proposes a new revision of a project's identity, signed with your
+    key. The fields to change are given as options; without any, the
+    project payload is opened in your editor. If the project has other
+    delegates, the revision only takes effect once a majority of them signed
+    it: publish it with `rad id publish`, and have them run
+    `rad id sign --peer <your-peer-id>` after fetching your refs.
+
+    `sign` signs the revision proposed by the given peer, and makes it
+    yours. Fetch the peer's refs first, eg. with `rad sync --fetch`. Signing
+    a revision that already has the other signatures it needs, eg. your own
+    proposal, co-signed by the other delegates, publishes it locally.
+
+    `publish` pushes the identity to the seeds, so that other delegates and
+    peers can fetch the new revision.
+
+Options
+
+    --peer <peer-id>           Peer whose revision to show or sign
+    --name <name>              New project name (edit)
+    --description <text>       New project description (edit)
+    --default-branch <name>    New default branch (edit)
+    --help                     Print help
+"#,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum OperationName {
+    Show,
+    History,
+    Edit,
+    Sign,
+    Publish,
+}
+
+#[derive(Debug)]
+pub enum Operation {
+    Show {
+        urn: Option<Urn>,
+    },
+    History {
+        urn: Option<Urn>,
+        peer: Option<PeerId>,
+    },
+    Edit {
+        urn: Option<Urn>,
+        fields: Vec<(&'static str, String)>,
+    },
+    Sign {
+        urn: Option<Urn>,
+        peer: PeerId,
+    },
+    Publish {
+        urn: Option<Urn>,
+    },
+}
+
+#[derive(Debug)]
+pub struct Options {
+    pub op: Operation,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut op: Option<OperationName> = None;
+        let mut urn: Option<Urn> = None;
+        let mut peer: Option<PeerId> = None;
+        let mut fields: Vec<(&'static str, String)> = Vec::new();
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("help") => {
+                    return Err(Error::Help.into());
+                }
+                Long("peer")
+                    if matches!(op, Some(OperationName::History | OperationName::Sign)) =>
+                {
+                    let val = parser.value()?;
+                    let val = val.to_string_lossy();
+
+                    peer = Some(
+                        PeerId::from_str(&val)
+                            .map_err(|_| anyhow!("invalid <peer-id> '{}'", val))?,
+                    );
+                }
+                Long(field @ ("name" | "description" | "default-branch"))
+                    if op == Some(OperationName::Edit) =>
+                {
+                    let field = match field {
+                        "name" => "name",
+                        "description" => "description",
+                        _ => "default_branch",
+                    };
+                    let val = parser.value()?;
+                    let val = val
+                        .into_string()
+                        .map_err(|_| anyhow!("invalid {}: not UTF-8", field))?;
+
+                    fields.push((field, val));
+                }
+                Value(val) if op.is_none() => match val.to_string_lossy().as_ref() {
+                    "s" | "show" => op = Some(OperationName::Show),
+                    "h" | "history" => op = Some(OperationName::History),
+                    "e" | "edit" => op = Some(OperationName::Edit),
+                    "sign" => op = Some(OperationName::Sign),
+                    "publish" => op = Some(OperationName::Publish),
+
+                    unknown => anyhow::bail!("unknown operation '{}'", unknown),
+                },
+                Value(val) if urn.is_none() => {
+                    let val = val.to_string_lossy();
+
+                    urn = Some(Urn::from_str(&val).map_err(|_| anyhow!("invalid URN '{}'", val))?);
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+
+        let op = match op
+            .ok_or_else(|| anyhow!("an operation must be provided, see `rad id --help`"))?
+        {
+            OperationName::Show => Operation::Show { urn },
+            OperationName::History => Operation::History { urn, peer },
+            OperationName::Edit => Operation::Edit { urn, fields },
+            OperationName::Sign => Operation::Sign {
+                urn,
+                peer: peer.ok_or_else(|| {
+                    anyhow!("a peer must be specified with `--peer`, see `rad id --help`")
+                })?,
+            },
+            OperationName::Publish => Operation::Publish { urn },
+        };
+
+        Ok((Options { op }, vec![]))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let profile = ctx.profile()?;
+    let repo = git::Repository::open_bare(profile.paths().git_dir())?;
+
+    match options.op {
+        Operation::Show { urn } => {
+            let urn = resolve(urn, "show")?;
+            let signer = term::signer(&profile)?;
+            let storage = keys::storage(&profile, signer)?;
+
+            show(&repo, &storage, &urn)?;
+        }
+        Operation::History { urn, peer } => {
+            let urn = resolve(urn, "history")?;
+
+            history(&repo, &urn, peer.as_ref())?;
+        }
+        Operation::Edit { urn, fields } => {
+            let urn = resolve(urn, "edit")?;
+            let signer = term::signer(&profile)?;
+            let storage = keys::storage(&profile, signer)?;
+
+            let mut payload = identity::payload(&storage, &urn)?;
+            if fields.is_empty() {
+                let edited = term::Editor::new()
+                    .extension(".json")
+                    .edit(&serde_json::to_string_pretty(&payload)?)?
+                    .ok_or_else(|| anyhow!("operation aborted"))?;
+
+                payload = serde_json::from_str(&edited)
+                    .map_err(|err| anyhow!("invalid project payload: {}", err))?;
+            } else {
+                for (field, value) in &fields {
+                    let value = Some(value.as_str()).filter(|v| !v.is_empty());
+                    identity::set_field(&mut payload, field, value)?;
+                }
+            }
+            if payload == identity::payload(&storage, &urn)? {
+                term::info!("Nothing to change");
+                return Ok(());
+            }
+            identity::propose(&storage, &urn, payload)?;
+
+            let head = latest(&repo, &urn)?;
+            if identity::verified(&storage, &urn)? == Some(head.commit) {
+                term::success!(
+                    "Updated identity of {} to revision {}",
+                    term::format::highlight(&urn),
+                    term::format::tertiary(common::fmt::oid(&head.commit))
+                );
+                term::tip!("To publish it, run `rad id publish`");
+            } else {
+                term::success!(
+                    "Proposed revision {} of {}",
+                    term::format::tertiary(common::fmt::oid(&head.commit)),
+                    term::format::highlight(&urn)
+                );
+                term::tip!(
+                    "It needs the signatures of other delegates: publish it with `rad id publish`, \
+                     and have them run `rad id sign {} --peer {}`",
+                    urn,
+                    storage.peer_id()
+                );
+            }
+        }
+        Operation::Sign { urn, peer } => {
+            let urn = resolve(urn, "sign")?;
+            let signer = term::signer(&profile)?;
+            let storage = keys::storage(&profile, signer)?;
+
+            if !identity::proposals(&repo, &urn)?
+                .iter()
+                .any(|(proposer, _)| *proposer == peer)
+            {
+                return Err(Error::WithHint {
+                    err: anyhow!(
+                        "peer {} has no revision of {} to sign",
+                        common::fmt::peer(&peer),
+                        urn
+                    ),
+                    hint: "hint: fetch the peer's refs first, eg. with `rad sync --fetch`",
+                }
+                .into());
+            }
+            identity::sign(&storage, &urn, peer)?;
+
+            let head = latest(&repo, &urn)?;
+            term::success!(
+                "Signed revision {} of {}",
+                term::format::tertiary(common::fmt::oid(&head.commit)),
+                term::format::highlight(&urn)
+            );
+            if identity::verified(&storage, &urn)? == Some(head.commit) {
+                term::tip!("The revision is in effect. To publish it, run `rad id publish`");
+            } else {
+                term::tip!(
+                    "The revision needs more signatures. To publish yours, run `rad id publish`"
+                );
+            }
+        }
+        Operation::Publish { urn } => {
+            let urn = resolve(urn, "publish")?;
+            let signer = term::signer(&profile)?;
+            let rt = tokio::runtime::Runtime::new()?;
+
+            term::sync::sync(
+                urn,
+                sync::seeds(&profile)?,
+                sync::Mode::Push,
+                &profile,
+                signer,
+                &rt,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Get the identity to operate on, defaulting to the current project.
+fn resolve(urn: Option<Urn>, op: &str) -> anyhow::Result<Urn> {
+    match urn {
+        Some(urn) => Ok(urn),
+        None => project::cwd().map(|(urn, _)| urn).map_err(|_| {
+            Error::WithHint {
+                err: anyhow!("an identity URN must be provided outside of a project"),
+                hint: match op {
+                    "show" => "hint: use `rad id show <urn>`",
+                    "edit" => "hint: use `rad id edit <urn>`",
+                    "sign" => "hint: use `rad id sign <urn> --peer <peer-id>`",
+                    "publish" => "hint: use `rad id publish <urn>`",
+                    _ => "hint: use `rad id history <urn>`",
+                },
+            }
+            .into()
+        }),
+    }
+}
+
+/// Get the latest revision of our view of an identity.
+fn latest(repo: &git::Repository, urn: &Urn) -> anyhow::Result<Revision> {
+    identity::history(repo, urn, None)?
+        .pop()
+        .ok_or_else(|| anyhow!("identity {} has no revisions", urn))
+}
+
+/// Print the latest revision of an identity, and the revisions proposed by peers.
+fn show(repo: &git::Repository, storage: &Storage, urn: &Urn) -> anyhow::Result<()> {
+    let head = latest(repo, urn)?;
+    let verified = identity::verified(storage, urn).ok().flatten();
+
+    term::blank();
+    term::info!(
+        "{} {} {}",
+        term::format::highlight(urn),
+        term::format::tertiary(common::fmt::oid(&head.commit)),
+        if verified == Some(head.commit) {
+            term::format::positive("verified")
+        } else {
+            term::format::yellow("pending signatures")
+        }
+    );
+    print_revision(&head);
+
+    if let Some(verified) = verified.filter(|v| *v != head.commit) {
+        term::blank();
+        term::info!(
+            "{} {}",
+            term::format::dim("In effect:"),
+            term::format::tertiary(common::fmt::oid(&verified))
+        );
+    }
+
+    for (peer, revision) in identity::proposals(repo, urn)? {
+        term::blank();
+        term::info!(
+            "{} {} {}",
+            term::format::secondary("proposed by"),
+            term::format::highlight(common::fmt::peer(&peer)),
+            term::format::tertiary(common::fmt::oid(&revision.commit)),
+        );
+        for change in revision.doc.changes(&head.doc) {
+            term::indented(&pretty_change(&change));
+        }
+        term::indented(&term::format::dim(format!(
+            "To sign it, run `rad id sign {} --peer {}`",
+            urn, peer
+        )));
+    }
+    term::blank();
+
+    Ok(())
+}
+
+/// Print the signers, fields and delegates of a revision.
+fn print_revision(revision: &Revision) {
+    for signer in &revision.signers {
+        let signer = PeerId::from_str(signer)
+            .map(|p| common::fmt::peer(&p))
+            .unwrap_or_else(|_| signer.clone());
+        term::indented(&format!(
+            "{} {}",
+            term::format::dim("signed by"),
+            term::format::highlight(signer)
+        ));
+    }
+    for (field, value) in [
+        ("name", revision.doc.name()),
+        ("description", revision.doc.description()),
+        ("default_branch", revision.doc.default_branch()),
+    ] {
+        if let Some(value) = value {
+            term::indented(&format!("{} {}", term::format::dim(field), value));
+        }
+    }
+    for delegate in revision.doc.delegations() {
+        term::indented(&format!(
+            "{} {}",
+            term::format::dim("delegate"),
+            term::format::tertiary(delegate)
+        ));
+    }
+}
+
+/// Print the revisions of an identity, newest first.
+fn history(repo: &git::Repository, urn: &Urn, peer: Option<&PeerId>) -> anyhow::Result<()> {
+    let revisions = identity::history(repo, urn, peer)?;
+
+    term::blank();
+    term::info!(
+        "{} {}",
+        term::format::highlight(urn),
+        term::format::dim(format!("({} revision(s))", revisions.len()))
+    );
+
+    for (i, revision) in revisions.iter().enumerate().rev() {
+        let changes = match i.checked_sub(1) {
+            Some(prev) => revision.doc.changes(&revisions[prev].doc),
+            None => Vec::new(),
+        };
+
+        term::blank();
+        term::info!(
+            "{} {} {}",
+            term::format::secondary(format!("revision {}", i + 1)),
+            term::format::tertiary(common::fmt::oid(&revision.commit)),
+            term::format::dim(cobs::Timestamp::new(revision.timestamp as u64)),
+        );
+        if i == 0 {
+            print_revision(revision);
+        } else {
+            for signer in &revision.signers {
+                let signer = PeerId::from_str(signer)
+                    .map(|p| common::fmt::peer(&p))
+                    .unwrap_or_else(|_| signer.clone());
+                term::indented(&format!(
+                    "{} {}",
+                    term::format::dim("signed by"),
+                    term::format::highlight(signer)
+                ));
+            }
+        }
+        if i > 0 && changes.is_empty() {
+            term::indented(&term::format::italic(
+                "No changes to name, description, default branch or delegates.",
+            ));
+        }
+        for change in changes {
+            term::indented(&pretty_change(&change));
+        }
+    }
+    term::blank();
+
+    Ok(())
+}
+
+/// Format an identity document change.
+fn pretty_change(change: &Change) -> String {
+    match change {
+        Change::Field { field, old, new } => format!(
+            "{} {} {} {}",
+            term::format::dim(field),
+            term::format::negative(old.as_deref().unwrap_or("∅")),
+            term::format::dim("→"),
+            term::format::positive(new.as_deref().unwrap_or("∅")),
+        ),
+        Change::DelegateAdded(delegate) => format!(
+            "{} {}",
+            term::format::positive("+ delegate"),
+            term::format::tertiary(delegate)
+        ),
+        Change::DelegateRemoved(delegate) => format!(
+            "{} {}",
+            term::format::negative("- delegate"),
+            term::format::tertiary(delegate)
+        ),
+    }
+}
@@ -0,0 +1,287 @@
+use std::ffi::OsString;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, bail};
+
+use radicle_common::args::{Args, Error, Help};
+use radicle_common::Interactive;
+use radicle_common::{git, keys, profile, project};
+use radicle_terminal as term;
+
+pub const HELP: Help = Help {
+    name: "import-repo",
+    description: env!("CARGO_PKG_DESCRIPTION"),
+    version: env!("CARGO_PKG_VERSION"),
+    usage: r#"
+Usage
+
+    rad import-repo <path | url> [<option>...]
+
+    Creates a radicle project from an existing git repository, which may be
+    bare. No working copy is needed. If a URL is given, the repository is first
+    cloned without a working copy, into a temporary directory.
+
+    The project's default branch is pushed to storage. Use `--all` to also
+    push all other branches and tags.
+
+Options
+
+    --name               Name of the project (default: the repository name)
+    --description        Description of the project
+    --default-branch     The default branch of the project (default: the repository's HEAD)
+    --all                Push all branches and tags to storage
+    --no-confirm         Don't ask for confirmation during setup
+    --help               Print help
+"#,
+};
+
+#[derive(Debug, Default)]
+pub struct Options {
+    pub source: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub branch: Option<String>,
+    pub all: bool,
+    pub interactive: Interactive,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut source: Option<String> = None;
+        let mut name = None;
+        let mut description = None;
+        let mut branch = None;
+        let mut all = false;
+        let mut interactive = Interactive::Yes;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("name") if name.is_none() => {
+                    name = Some(parser.value()?.to_string_lossy().into());
+                }
+                Long("description") if description.is_none() => {
+                    description = Some(parser.value()?.to_string_lossy().into());
+                }
+                Long("default-branch") if branch.is_none() => {
+                    branch = Some(parser.value()?.to_string_lossy().into());
+                }
+                Long("all") => {
+                    all = true;
+                }
+                Long("no-confirm") => {
+                    interactive = Interactive::No;
+                }
+                Long("help") => {
+                    return Err(Error::Help.into());
+                }
+                Value(val) if source.is_none() => {
+                    let val = val
+                        .to_str()
+                        .ok_or_else(|| anyhow!("repository path or URL is not UTF-8"))?;
+
+                    source = Some(val.to_owned());
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+
+        Ok((
+            Options {
+                source: source
+                    .ok_or_else(|| anyhow!("a repository path or URL must be provided"))?,
+                name,
+                description,
+                branch,
+                all,
+                interactive,
+            },
+            vec![],
+        ))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let profile = ctx.profile()?;
+    let local = Path::new(&options.source);
+
+    if local.exists() {
+        let path = local.canonicalize()?;
+
+        import(&path, options, &profile)
+    } else {
+        // Nb. The clone is only needed until the project is in storage.
+        let tmp = std::env::temp_dir().join(format!("rad-import-{}", std::process::id()));
+        let spinner = term::spinner(format!(
+            "Cloning {} without a working copy...",
+            term::format::tertiary(&options.source)
+        ));
+
+        let args = vec![
+            OsString::from("clone"),
+            OsString::from("--bare"),
+            OsString::from(&options.source),
+            tmp.clone().into_os_string(),
+        ];
+        if let Err(err) = git::git(&std::env::temp_dir(), args) {
+            spinner.failed();
+            return Err(err);
+        }
+        spinner.finish();
+
+        let name = options
+            .name
+            .clone()
+            .or_else(|| name_from_url(&options.source));
+        let result = import(&tmp, Options { name, ..options }, &profile);
+
+        fs::remove_dir_all(&tmp).ok();
+
+        result
+    }
+}
+
+fn import(path: &Path, options: Options, profile: &profile::Profile) -> anyhow::Result<()> {
+    let interactive = options.interactive;
+
+    term::headline(&format!(
+        "Importing 🌱 project from {}",
+        term::format::highlight(path.display())
+    ));
+
+    let repo = git::Repository::open(path)?;
+    if let Ok(remote) = git::rad_remote(&repo) {
+        bail!(
+            "repository is already initialized with remote {}",
+            remote.url
+        );
+    }
+
+    let signer = term::signer(profile)?;
+    let storage = keys::storage(profile, signer.clone())?;
+
+    let head: String = repo
+        .head()
+        .ok()
+        .and_then(|head| head.shorthand().map(|h| h.to_owned()))
+        .ok_or_else(|| anyhow!("repository head does not point to any commits"))?;
+
+    let name = options.name.unwrap_or_else(|| {
+        let default = default_name(path);
+        if interactive.yes() {
+            term::text_input("Name", default).unwrap()
+        } else {
+            default.unwrap_or_default()
+        }
+    });
+    if name.is_empty() {
+        bail!("a project name must be given with `--name`");
+    }
+    let description = options.description.unwrap_or_else(|| {
+        if interactive.yes() {
+            term::text_input("Description", None).unwrap()
+        } else {
+            String::new()
+        }
+    });
+    let branch = options.branch.unwrap_or_else(|| {
+        if interactive.yes() {
+            term::text_input("Default branch", Some(head)).unwrap()
+        } else {
+            head
+        }
+    });
+
+    let mut spinner = term::spinner("Importing...");
+    let payload = project::payload(name, description, branch.clone());
+    let proj = match project::create(payload, &storage).and_then(|proj| {
+        project::init(&proj, &repo, &storage, profile.paths(), signer).map(|_| proj)
+    }) {
+        Ok(proj) => proj,
+        Err(err) => {
+            spinner.failed();
+            term::blank();
+
+            use radicle_common::identities::git::validation;
+            use radicle_common::identities::git::Error;
+
+            match err.downcast_ref::<Error>() {
+                Some(Error::Validation(validation::Error::MissingDefaultBranch { .. })) => bail!(
+                    "the `{}` branch was either not found, or has no commits",
+                    branch
+                ),
+                Some(_) | None => return Err(err),
+            }
+        }
+    };
+    spinner.message(format!(
+        "Project {} created",
+        term::format::highlight(&proj.subject().name)
+    ));
+    spinner.finish();
+
+    if options.all {
+        let spinner = term::spinner("Pushing all branches and tags to storage...");
+
+        match git::git(
+            path,
+            [
+                "push",
+                "rad",
+                "refs/heads/*:refs/heads/*",
+                "refs/tags/*:refs/tags/*",
+            ],
+        ) {
+            Ok(_) => spinner.finish(),
+            Err(err) => {
+                spinner.failed();
+                return Err(err);
+            }
+        }
+    }
+
+    term::blank();
+    term::info!(
+        "Your project id is {}.",
+        term::format::highlight(&proj.urn().to_string())
+    );
+    term::info!("To get a working copy of the project, run:");
+    term::indented(&term::format::secondary(format!(
+        "rad checkout {}",
+        proj.urn()
+    )));
+    term::blank();
+
+    Ok(())
+}
+
+/// Project name suggested for a repository path, eg. `heartwood` for `/src/heartwood.git`.
+fn default_name(path: &Path) -> Option<String> {
+    let path = if path.ends_with(".git") {
+        path.parent()?
+    } else {
+        path
+    };
+    let name = path.file_name()?.to_string_lossy();
+
+    Some(name.trim_end_matches(".git").to_owned())
+}
+
+/// Project name suggested for a repository URL, eg. `heartwood` for
+/// `https://example.com/heartwood.git`.
+fn name_from_url(url: &str) -> Option<String> {
+    let name = url
+        .trim_end_matches('/')
+        .rsplit(|c| c == '/' || c == ':')
+        .next()?;
+    let name = name.trim_end_matches(".git");
+
+    if name.is_empty() {
+        None
+    } else {
+        Some(name.to_owned())
+    }
+}
@@ -11,7 +11,7 @@ use librad::PeerId;
 use radicle_common::args::{Args, Error, Help};
 use radicle_common::json;
 use radicle_common::Interactive;
-use radicle_common::{git, keys, profile, project};
+use radicle_common::{git, keys, profile, project, registry};
 use radicle_terminal as term;
 
 pub const HELP: Help = Help {
@@ -23,9 +23,14 @@ Usage
 
     rad init [<path>] [<option>...]
 
+    Initializes the git repository at <path>, or in the current directory, as
+    a radicle project. The repository may be bare, or be nested in another
+    directory, eg. `rad init --path services/api`.
+
 Options
 
-    --name               Name of the project
+    --path <dir>         Path of the repository to initialize (default: `.`)
+    --name               Name of the project (default: the repository directory name)
     --description        Description of the project
     --default-branch     The default branch of the project
     --set-upstream, -u   Setup the upstream of the default branch
@@ -91,6 +96,9 @@ impl Args for Options {
 
                     branch = Some(value);
                 }
+                Long("path") if path.is_none() => {
+                    path = Some(parser.value()?.into());
+                }
                 Long("set-upstream") | Short('u') => {
                     set_upstream = true;
                 }
@@ -149,7 +157,17 @@ pub fn init(options: Options, profile: &profile::Profile) -> anyhow::Result<()>
         }
     ));
 
-    let repo = git::Repository::open(&path)?;
+    let repo = match git::Repository::open(&path) {
+        Ok(repo) => repo,
+        Err(err) if err.code() == git::ErrorCode::NotFound => {
+            return Err(Error::WithHint {
+                err: anyhow!("{} is not the root of a git repository", path.display()),
+                hint: "hint: pass the path of the repository root, or run `git init` first",
+            }
+            .into());
+        }
+        Err(err) => return Err(err.into()),
+    };
     if let Ok(remote) = git::rad_remote(&repo) {
         bail!(
             "repository is already initialized with remote {}",
@@ -167,7 +185,9 @@ pub fn init(options: Options, profile: &profile::Profile) -> anyhow::Result<()>
         .ok_or_else(|| anyhow!("error: repository head does not point to any commits"))?;
 
     let name = options.name.unwrap_or_else(|| {
-        let default = path.file_name().map(|f| f.to_string_lossy().to_string());
+        let default = path
+            .file_name()
+            .map(|f| f.to_string_lossy().trim_end_matches(".git").to_owned());
         term::text_input("Name", default).unwrap()
     });
     let description = options
@@ -201,7 +221,15 @@ pub fn init(options: Options, profile: &profile::Profile) -> anyhow::Result<()>
                 term::blank();
             }
 
-            if options.set_upstream || git::branch_remote(&repo, &branch).is_err() {
+            // Nb. Only used to list working copies with `rad ls`.
+            if let Err(err) = registry::register(profile, &urn, &path) {
+                term::warning(&format!("Could not register working copy: {:#}", err));
+            }
+
+            // Nb. Bare repositories have no branches to track `rad`.
+            if !repo.is_bare()
+                && (options.set_upstream || git::branch_remote(&repo, &branch).is_err())
+            {
                 let branch = git::RefLike::try_from(branch)?;
                 let branch = git::OneLevel::from(branch);
 
@@ -210,18 +238,28 @@ pub fn init(options: Options, profile: &profile::Profile) -> anyhow::Result<()>
             }
 
             // Setup radicle signing key.
-            self::setup_signing(storage.peer_id(), &repo, interactive)?;
+            if repo.is_bare() {
+                term::info!("Skipping signing setup, since the repository is bare.");
+            } else {
+                self::setup_signing(storage.peer_id(), &repo, interactive)?;
+            }
 
             term::blank();
             term::info!(
                 "Your project id is {}. You can show it any time by running:",
                 term::format::highlight(&urn.to_string())
             );
-            term::indented(&term::format::secondary("rad ."));
+            // Commands run from elsewhere need to be pointed at the repository.
+            let rad = if path == cwd {
+                String::from("rad")
+            } else {
+                format!("rad --repo {}", path.display())
+            };
+            term::indented(&term::format::secondary(format!("{} .", rad)));
 
             term::blank();
             term::info!("To publish your project to the network, run:");
-            term::indented(&term::format::secondary("rad push"));
+            term::indented(&term::format::secondary(format!("{} push", rad)));
             term::blank();
         }
         Err(err) => {
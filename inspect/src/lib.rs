@@ -5,14 +5,16 @@ use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::str::FromStr;
 
+use radicle_common as common;
 use radicle_common::args::{Args, Error, Help};
-use radicle_common::{git, profile};
+use radicle_common::{git, profile, project};
 use radicle_terminal as term;
 
-use librad::git::identities::any;
+use librad::git::identities::{any, SomeIdentity};
 use librad::git::storage::ReadOnlyStorage;
 use librad::git::types::Reference;
 use librad::git::Urn;
+use librad::PeerId;
 
 use anyhow::anyhow;
 
@@ -33,9 +35,15 @@ Usage
     Inspects the given path or URN. If neither is specified,
     the current project is inspected.
 
+    With `--details`, a project's metadata is shown: its name, description
+    and default branch, its delegates and their keys, and the remotes tracked
+    on this device, with the head of their signed refs and default branch.
+    For a person, its name and keys are shown.
+
 Options
 
     --id        Return the ID without the URN scheme
+    --details   Show the details of the project or person
     --payload   Inspect the object's payload
     --refs      Inspect the object's refs on the local device (requires `tree`)
     --history   Show object's history
@@ -51,6 +59,7 @@ pub struct Options {
     pub payload: bool,
     pub history: bool,
     pub id: bool,
+    pub details: bool,
 }
 
 impl Args for Options {
@@ -64,6 +73,7 @@ impl Args for Options {
         let mut payload = false;
         let mut history = false;
         let mut id = false;
+        let mut details = false;
 
         while let Some(arg) = parser.next()? {
             match arg {
@@ -82,6 +92,9 @@ impl Args for Options {
                 Long("id") => {
                     id = true;
                 }
+                Long("details") => {
+                    details = true;
+                }
                 Value(val) if path.is_none() && urn.is_none() => {
                     let val = val.to_string_lossy();
 
@@ -102,6 +115,7 @@ impl Args for Options {
         Ok((
             Options {
                 id,
+                details,
                 path,
                 payload,
                 history,
@@ -218,6 +232,8 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
 
             _ => return Err(anyhow!("Couldn't find reference to {} in storage", urn)),
         }
+    } else if options.details {
+        details(&urn, &storage, &profile)?;
     } else if options.id {
         term::info!("{}", term::format::highlight(urn.encode_id()));
     } else {
@@ -226,3 +242,142 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Print the details of a project or person.
+fn details(
+    urn: &Urn,
+    storage: &librad::git::storage::ReadOnly,
+    profile: &profile::Profile,
+) -> anyhow::Result<()> {
+    let project = match project::get(storage, urn)? {
+        Some(project) => project,
+        None => match any::get(storage, urn)? {
+            Some(SomeIdentity::Person(person)) => {
+                let mut table = term::Table::default();
+                table.push([term::format::dim("urn"), term::format::highlight(urn)]);
+                table.push([
+                    term::format::dim("name"),
+                    term::format::bold(&person.subject().name),
+                ]);
+                for key in person.delegations().iter() {
+                    table.push([
+                        term::format::dim("key"),
+                        term::format::tertiary(PeerId::from(*key)),
+                    ]);
+                }
+                table.render();
+
+                return Ok(());
+            }
+            Some(_) => anyhow::bail!("details of {} can't be shown", urn),
+            None => anyhow::bail!("no project or person found for {}", urn),
+        },
+    };
+    let monorepo = git::Repository::open_bare(profile.paths().git_dir())?;
+    let namespace = format!("refs/namespaces/{}", urn.encode_id());
+    let target = |name: String| {
+        monorepo
+            .find_reference(&name)
+            .ok()
+            .and_then(|r| r.target())
+            .map(|oid| term::format::secondary(common::fmt::oid(&oid)))
+            .unwrap_or_else(|| term::format::dim("-"))
+    };
+
+    let mut table = term::Table::default();
+    table.push([term::format::dim("urn"), term::format::highlight(urn)]);
+    table.push([term::format::dim("name"), term::format::bold(&project.name)]);
+    table.push([
+        term::format::dim("description"),
+        if project.description.is_empty() {
+            term::format::italic("No description")
+        } else {
+            project.description.clone()
+        },
+    ]);
+    table.push([
+        term::format::dim("default branch"),
+        term::format::highlight(&project.default_branch),
+    ]);
+    table.render();
+
+    term::blank();
+    term::print(&term::format::badge_primary("DELEGATES"));
+    term::blank();
+
+    let mut table = term::Table::default();
+    for delegate in &project.delegates {
+        match delegate {
+            project::Delegate::Direct { id } => {
+                table.push([
+                    term::format::dim("key"),
+                    String::new(),
+                    term::format::tertiary(id),
+                ]);
+            }
+            project::Delegate::Indirect { urn, ids } => {
+                let name = ids
+                    .iter()
+                    .next()
+                    .map(|id| project::PeerInfo::get(id, &project, storage))
+                    .and_then(|info| info.person.map(|p| p.name))
+                    .unwrap_or_default();
+
+                table.push([
+                    term::format::bold(name),
+                    term::format::dim(urn),
+                    String::new(),
+                ]);
+                for id in ids {
+                    table.push([String::new(), String::new(), term::format::tertiary(id)]);
+                }
+            }
+        }
+    }
+    table.render();
+
+    term::blank();
+    term::print(&term::format::badge_secondary("REMOTES"));
+    term::blank();
+
+    let mut table = term::Table::default();
+    let me = *storage.peer_id();
+    table.push([
+        term::format::bold(project::PeerInfo::get(&me, &project, storage).name()),
+        term::format::tertiary(me),
+        target(format!("{}/refs/rad/signed_refs", namespace)),
+        target(format!(
+            "{}/refs/heads/{}",
+            namespace, project.default_branch
+        )),
+        term::format::secondary("(you)"),
+    ]);
+
+    let mut tracked = project::tracked(&project, storage)?
+        .into_iter()
+        .collect::<Vec<_>>();
+    tracked.sort_by_key(|(_, info)| info.name());
+
+    for (peer, info) in tracked {
+        table.push([
+            term::format::bold(info.name()),
+            term::format::tertiary(peer),
+            target(format!(
+                "{}/refs/remotes/{}/rad/signed_refs",
+                namespace, peer
+            )),
+            target(format!(
+                "{}/refs/remotes/{}/heads/{}",
+                namespace, peer, project.default_branch
+            )),
+            if info.delegate {
+                term::format::badge_primary("delegate")
+            } else {
+                String::new()
+            },
+        ]);
+    }
+    table.render();
+
+    Ok(())
+}
@@ -2,12 +2,20 @@
 use std::ffi::OsString;
 use std::str::FromStr;
 
-use anyhow::{anyhow, Context};
+use anyhow::anyhow;
 
+use librad::PeerId;
+
+use radicle_common as common;
+use radicle_common::allowlist::Allowlist;
 use radicle_common::args::{Args, Error, Help};
 use radicle_common::cobs::issue::*;
+use radicle_common::cobs::patch::{self, Patch};
+use radicle_common::store::Store;
 use radicle_common::{cobs, keys, project};
 use radicle_terminal as term;
+use radicle_terminal::format::{labeled, Indicator};
+use radicle_terminal::patch::{Comment, Validation};
 
 pub const HELP: Help = Help {
     name: "issue",
@@ -16,31 +24,72 @@ pub const HELP: Help = Help {
     usage: r#"
 Usage
 
-    rad issue new [--title <title>] [--description <text>]
+    rad issue new [--title <title>] [--description <text>] [--no-confirm]
+    rad issue list [--unsolicited] [--assignee <peer-id>] [--label <label>] [--full] [--verbose]
+    rad issue show <id> [--verbose]
+    rad issue comment <id> [--message <text>]
+    rad issue close <id> [--solved]
+    rad issue reopen <id>
+    rad issue assign <id> <peer-id>...
+    rad issue label <id> <label>...
     rad issue state <id> [--closed | --open | --solved]
     rad issue delete <id>
-    rad issue react <id> [--emoji <char>]
-    rad issue list
+    rad issue react <id> <emoji> [--comment]
+    rad issue redact <id> [--comment]
+
+    When creating an issue, the title is prompted for, and an editor is
+    opened for the description, unless they are given as options.
+
+    Reactions are added to the issue itself, unless `--comment` is given,
+    in which case the comment to react to is prompted for. The same goes
+    for redactions, which replace the content of your issue description or
+    comment with a tombstone.
+
+    Redacted content remains in the issue history, which is replicated to
+    other peers, so redacting doesn't remove it from storage.
 
 Options
 
-    --help      Print help
+    --unsolicited           Also list issues from peers not on the collaborator allowlist
+    --assignee <peer-id>    Only list issues assigned to the given peer
+    --label <label>         Only list issues with the given label (may be repeated)
+    --full                  Don't truncate issue titles and names to fit the terminal width
+    --verbose, -v           Show absolute dates and times, instead of relative ones
+    --no-confirm            Don't ask for confirmation before creating the issue
+    --comment               React to, or redact a comment of the issue, instead of the issue
+    --help                  Print help
 "#,
 };
 
-#[derive(serde::Deserialize, serde::Serialize, Debug)]
-pub struct Metadata {
-    title: String,
-    labels: Vec<cobs::Label>,
-}
+/// Template shown in the editor when writing an issue description.
+pub const DESCRIPTION_MSG: &str = r#"
+<!--
+Please enter a description for your issue. Markdown is supported.
+Leaving this blank is also okay.
+-->
+"#;
+
+/// Template shown in the editor when commenting on an issue.
+pub const COMMENT_MSG: &str = r#"
+<!--
+Please enter a comment for the issue. Markdown is supported.
+-->
+"#;
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum OperationName {
     Create,
+    List,
+    Show,
+    Comment,
+    Close,
+    Reopen,
+    Assign,
+    Label,
     State,
     React,
+    Redact,
     Delete,
-    List,
 }
 
 impl Default for OperationName {
@@ -53,26 +102,53 @@ impl Default for OperationName {
 pub enum Operation {
     Create {
         title: Option<String>,
-        description: Option<String>,
+        description: Comment,
+        confirm: bool,
+    },
+    List {
+        unsolicited: bool,
+        assignee: Option<PeerId>,
+        labels: Vec<cobs::Label>,
+        full: bool,
+    },
+    Show {
+        id: cobs::Identifier,
+    },
+    Comment {
+        id: cobs::Identifier,
+        message: Comment,
     },
     State {
-        id: cobs::issue::IssueId,
-        state: cobs::issue::State,
+        id: cobs::Identifier,
+        state: State,
+    },
+    Assign {
+        id: cobs::Identifier,
+        peers: Vec<PeerId>,
+    },
+    Label {
+        id: cobs::Identifier,
+        labels: Vec<cobs::Label>,
     },
     Delete {
-        id: cobs::issue::IssueId,
+        id: cobs::Identifier,
     },
     React {
-        id: cobs::issue::IssueId,
+        id: cobs::Identifier,
         reaction: cobs::Reaction,
+        comment: bool,
+    },
+    Redact {
+        id: cobs::Identifier,
+        comment: bool,
     },
-    List,
 }
 
 /// Tool options.
 #[derive(Debug)]
 pub struct Options {
     pub op: Operation,
+    pub verbose: bool,
 }
 
 impl Args for Options {
@@ -81,11 +157,20 @@ impl Args for Options {
 
         let mut parser = lexopt::Parser::from_args(args);
         let mut op: Option<OperationName> = None;
-        let mut id: Option<cobs::issue::IssueId> = None;
+        let mut id: Option<cobs::Identifier> = None;
         let mut title: Option<String> = None;
         let mut reaction: Option<cobs::Reaction> = None;
-        let mut description: Option<String> = None;
-        let mut state: Option<cobs::issue::State> = None;
+        let mut comment = false;
+        let mut message = Comment::default();
+        let mut state: Option<State> = None;
+        let mut solved = false;
+        let mut unsolicited = false;
+        let mut full = false;
+        let mut verbose = false;
+        let mut confirm = true;
+        let mut assignee: Option<PeerId> = None;
+        let mut peers: Vec<PeerId> = Vec::new();
+        let mut labels: Vec<cobs::Label> = Vec::new();
 
         while let Some(arg) = parser.next()? {
             match arg {
@@ -95,72 +180,198 @@ impl Args for Options {
                 Long("title") if op == Some(OperationName::Create) => {
                     title = Some(parser.value()?.to_string_lossy().into());
                 }
+                Long("description") if op == Some(OperationName::Create) => {
+                    message.append(&parser.value()?.to_string_lossy());
+                }
+                Long("no-confirm") if op == Some(OperationName::Create) => {
+                    confirm = false;
+                }
+                Long("message") | Short('m') if op == Some(OperationName::Comment) => {
+                    message.append(&parser.value()?.to_string_lossy());
+                }
                 Long("closed") if op == Some(OperationName::State) => {
-                    state = Some(cobs::issue::State::Closed {
+                    state = Some(State::Closed {
                         reason: CloseReason::Other,
                     });
                 }
                 Long("open") if op == Some(OperationName::State) => {
-                    state = Some(cobs::issue::State::Open);
+                    state = Some(State::Open);
                 }
                 Long("solved") if op == Some(OperationName::State) => {
-                    state = Some(cobs::issue::State::Closed {
+                    state = Some(State::Closed {
                         reason: CloseReason::Solved,
                     });
                 }
+                Long("solved") if op == Some(OperationName::Close) => {
+                    solved = true;
+                }
                 Long("reaction") if op == Some(OperationName::React) => {
-                    if let Some(emoji) = parser.value()?.to_str() {
-                        reaction = Some(
-                            cobs::Reaction::from_str(emoji)
-                                .map_err(|_| anyhow!("invalid emoji"))?,
-                        );
-                    }
+                    let val = parser.value()?;
+                    let val = val.to_string_lossy();
+
+                    reaction = Some(
+                        cobs::Reaction::from_str(&val)
+                            .map_err(|_| anyhow!("invalid emoji '{}'", val))?,
+                    );
                 }
-                Long("description") if op == Some(OperationName::Create) => {
-                    description = Some(parser.value()?.to_string_lossy().into());
+                Long("comment")
+                    if op == Some(OperationName::React) || op == Some(OperationName::Redact) =>
+                {
+                    comment = true;
+                }
+                Long("unsolicited") if op.is_none() || op == Some(OperationName::List) => {
+                    unsolicited = true;
+                }
+                Long("verbose") | Short('v') => {
+                    verbose = true;
+                }
+                Long("full") if op.is_none() || op == Some(OperationName::List) => {
+                    full = true;
+                }
+                Long("assignee") if op.is_none() || op == Some(OperationName::List) => {
+                    let val = parser.value()?;
+                    let val = val.to_string_lossy();
+
+                    assignee = Some(
+                        PeerId::from_str(&val)
+                            .map_err(|_| anyhow!("invalid <peer-id> '{}'", val))?,
+                    );
+                }
+                Long("label") if op.is_none() || op == Some(OperationName::List) => {
+                    let val = parser.value()?;
+                    let val = val.to_string_lossy();
+
+                    labels.push(
+                        cobs::Label::new(val.as_ref())
+                            .map_err(|_| anyhow!("invalid label '{}'", val))?,
+                    );
                 }
                 Value(val) if op.is_none() => match val.to_string_lossy().as_ref() {
                     "n" | "new" => op = Some(OperationName::Create),
+                    "l" | "list" => op = Some(OperationName::List),
+                    "show" => op = Some(OperationName::Show),
+                    "c" | "comment" => op = Some(OperationName::Comment),
+                    "close" => op = Some(OperationName::Close),
+                    "reopen" => op = Some(OperationName::Reopen),
+                    "assign" => op = Some(OperationName::Assign),
+                    "label" => op = Some(OperationName::Label),
                     "s" | "state" => op = Some(OperationName::State),
                     "d" | "delete" => op = Some(OperationName::Delete),
-                    "l" | "list" => op = Some(OperationName::List),
                     "r" | "react" => op = Some(OperationName::React),
+                    "redact" => op = Some(OperationName::Redact),
 
                     unknown => anyhow::bail!("unknown operation '{}'", unknown),
                 },
-                Value(val) if op.is_some() => {
+                Value(val) if op.is_some() && id.is_none() => {
                     let val = val
                         .to_str()
                         .ok_or_else(|| anyhow!("issue id specified is not UTF-8"))?;
 
                     id = Some(
-                        IssueId::from_str(val)
+                        cobs::Identifier::from_str(val)
                             .map_err(|_| anyhow!("invalid issue id '{}'", val))?,
                     );
                 }
+                Value(val) if op == Some(OperationName::Assign) => {
+                    let val = val.to_string_lossy();
+
+                    peers.push(
+                        PeerId::from_str(&val)
+                            .map_err(|_| anyhow!("invalid <peer-id> '{}'", val))?,
+                    );
+                }
+                Value(val) if op == Some(OperationName::React) && reaction.is_none() => {
+                    let val = val.to_string_lossy();
+
+                    reaction = Some(
+                        cobs::Reaction::from_str(&val)
+                            .map_err(|_| anyhow!("invalid emoji '{}'", val))?,
+                    );
+                }
+                Value(val) if op == Some(OperationName::Label) => {
+                    let val = val.to_string_lossy();
+
+                    labels.push(
+                        cobs::Label::new(val.as_ref())
+                            .map_err(|_| anyhow!("invalid label '{}'", val))?,
+                    );
+                }
                 _ => {
                     return Err(anyhow!(arg.unexpected()));
                 }
             }
         }
 
+        let require_id = || {
+            id.clone()
+                .ok_or_else(|| anyhow!("an issue id must be provided"))
+        };
         let op = match op.unwrap_or_default() {
-            OperationName::Create => Operation::Create { title, description },
+            OperationName::Create => Operation::Create {
+                title,
+                description: message,
+                confirm,
+            },
+            OperationName::List => Operation::List {
+                unsolicited,
+                assignee,
+                labels,
+                full,
+            },
+            OperationName::Show => Operation::Show { id: require_id()? },
+            OperationName::Comment => Operation::Comment {
+                id: require_id()?,
+                message,
+            },
+            OperationName::Close => Operation::State {
+                id: require_id()?,
+                state: State::Closed {
+                    reason: if solved {
+                        CloseReason::Solved
+                    } else {
+                        CloseReason::Other
+                    },
+                },
+            },
+            OperationName::Reopen => Operation::State {
+                id: require_id()?,
+                state: State::Open,
+            },
+            OperationName::Assign => {
+                if peers.is_empty() {
+                    anyhow::bail!("at least one peer to assign must be provided");
+                }
+                Operation::Assign {
+                    id: require_id()?,
+                    peers,
+                }
+            }
+            OperationName::Label => {
+                if labels.is_empty() {
+                    anyhow::bail!("at least one label must be provided");
+                }
+                Operation::Label {
+                    id: require_id()?,
+                    labels,
+                }
+            }
             OperationName::State => Operation::State {
-                id: id.ok_or_else(|| anyhow!("an issue id must be provided"))?,
+                id: require_id()?,
                 state: state.ok_or_else(|| anyhow!("a state operation must be provided"))?,
             },
             OperationName::React => Operation::React {
-                id: id.ok_or_else(|| anyhow!("an issue id must be provided"))?,
+                id: require_id()?,
                 reaction: reaction.ok_or_else(|| anyhow!("a reaction emoji must be provided"))?,
+                comment,
             },
-            OperationName::Delete => Operation::Delete {
-                id: id.ok_or_else(|| anyhow!("an issue id to remove must be provided"))?,
+            OperationName::Redact => Operation::Redact {
+                id: require_id()?,
+                comment,
             },
-            OperationName::List => Operation::List,
+            OperationName::Delete => Operation::Delete { id: require_id()? },
         };
 
-        Ok((Options { op }, vec![]))
+        Ok((Options { op, verbose }, vec![]))
     }
 }
 
@@ -168,74 +379,417 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
     let profile = ctx.profile()?;
     let signer = term::signer(&profile)?;
     let storage = keys::storage(&profile, signer)?;
-    let (project, _) = project::cwd()?;
+    let (project, _) = project::cwd()
+        .map_err(|_| anyhow!("this command must be run in the context of a project"))?;
     let cobs = cobs::store(&profile, &storage)?;
     let issues = cobs.issues();
 
+    // Resolve a full or partial issue id.
+    let resolve = |id: &cobs::Identifier| -> anyhow::Result<(IssueId, Issue)> {
+        issues
+            .resolve::<Issue>(&project, id)?
+            .ok_or_else(|| anyhow!("couldn't find issue {} locally", id))
+    };
+
+    let verbose = options.verbose;
+
     match options.op {
         Operation::Create {
-            title: Some(title),
-            description: Some(description),
+            title,
+            description,
+            confirm,
         } => {
-            issues.create(&project, &title, &description, &[])?;
-        }
-        Operation::State { id, state } => {
-            issues.lifecycle(&project, &id, state)?;
+            let title = match title {
+                Some(title) => title,
+                None => term::text_input("Title", None)?,
+            };
+            let title = title.trim();
+            if title.is_empty() {
+                anyhow::bail!("a title must be given");
+            }
+            let description = description.get(DESCRIPTION_MSG, Validation::optional())?;
+
+            let title_pretty = &term::format::dim(format!("╭─ {} ───────", title));
+
+            term::blank();
+            term::print(title_pretty);
+            term::blank();
+
+            if description.is_empty() {
+                term::print(term::format::italic("No description provided."));
+            } else {
+                term::markdown(&description);
+            }
+
+            term::blank();
+            term::print(&term::format::dim(format!(
+                "╰{}",
+                "─".repeat(term::text_width(title_pretty) - 1)
+            )));
+            term::blank();
+
+            if confirm && !term::confirm("Create issue?") {
+                anyhow::bail!("issue creation aborted by user");
+            }
+            let id = issues.create(&project, title, &description, &[])?;
+
+            term::success!("Issue {} created 🌱", term::format::highlight(id));
         }
-        Operation::React { id, reaction } => {
-            if let Some(issue) = issues.get(&project, &id)? {
-                let comment_id = term::comment_select(&issue).unwrap();
-                issues.react(&project, &id, comment_id, reaction)?;
+        Operation::List {
+            unsolicited,
+            assignee,
+            labels,
+            full,
+        } => {
+            let allowlist = Allowlist::load(&profile)?;
+            let meta = project::get(&storage, &project)?
+                .ok_or_else(|| anyhow!("couldn't load project {} from local state", project))?;
+            let mut open = Vec::new();
+            let mut closed = Vec::new();
+            let mut hidden = 0;
+
+            for (id, mut issue) in issues.all(&project)? {
+                if !unsolicited && !allowlist.allows(&meta, &issue.author().peer) {
+                    hidden += 1;
+                    continue;
+                }
+                if let Some(peer) = assignee {
+                    if !issue.assignees().iter().any(|a| a.peer == peer) {
+                        continue;
+                    }
+                }
+                if !labels.iter().all(|l| issue.labels().contains(l)) {
+                    continue;
+                }
+                issue.author.resolve(&storage).ok();
+
+                match issue.state() {
+                    State::Open => open.push((id, issue)),
+                    State::Closed { .. } => closed.push((id, issue)),
+                }
+            }
+
+            for (badge, group) in vec![
+                (term::format::badge_positive("OPEN"), open),
+                (term::format::badge_secondary("CLOSED"), closed),
+            ] {
+                term::blank();
+                term::print(&badge);
+                term::blank();
+
+                if group.is_empty() {
+                    term::print(&term::format::italic("Nothing to show."));
+                    continue;
+                }
+                let mut table = term::Table::new(term::table::TableOptions {
+                    full,
+                    ..term::table::TableOptions::default()
+                });
+
+                for (id, issue) in group {
+                    table.push([
+                        term::format::tertiary(common::fmt::cob(&id)),
+                        term::format::bold(issue.title()),
+                        term::format::dim(issue.author().name()),
+                        pretty_labels(&issue),
+                        pretty_assignees(&issue),
+                        term::format::dim(format!("💬 {}", issue.comments().len())),
+                        term::format::dim(pretty_reactions(issue.reactions())),
+                        term::format::italic(term::format::timestamp(issue.timestamp(), verbose)),
+                    ]);
+                }
+                table.render();
+            }
+            term::blank();
+
+            if hidden > 0 {
+                term::tip!(
+                    "{} unsolicited issue(s) hidden, use `rad issue list --unsolicited` to show them",
+                    hidden
+                );
             }
         }
-        Operation::Create { title, description } => {
-            let meta = Metadata {
-                title: title.unwrap_or("Enter a title".to_owned()),
-                labels: vec![],
-            };
-            let yaml = serde_yaml::to_string(&meta)?;
-            let doc = format!(
-                "{}---\n\n{}",
-                yaml,
-                description.unwrap_or("Enter a description...".to_owned())
+        Operation::Show { id } => {
+            let (id, mut issue) = resolve(&id)?;
+            issue.resolve(&storage).ok();
+
+            term::blank();
+            term::info!(
+                "{} {} {}",
+                term::format::bold(issue.title()),
+                term::format::tertiary(common::fmt::cob(&id)),
+                pretty_state(issue.state()),
             );
+            term::info!(
+                "{} {} {}",
+                term::format::dim("opened by"),
+                term::format::highlight(issue.author().name()),
+                term::format::dim(term::format::timestamp(issue.timestamp(), verbose)),
+            );
+            if !issue.labels().is_empty() {
+                term::info!("{} {}", term::format::dim("labels"), pretty_labels(&issue));
+            }
+            if !issue.assignees().is_empty() {
+                term::info!(
+                    "{} {}",
+                    term::format::dim("assigned to"),
+                    pretty_assignees(&issue)
+                );
+            }
+            term::blank();
 
-            if let Some(text) = term::Editor::new().edit(&doc)? {
-                let mut meta = String::new();
-                let mut frontmatter = false;
-                let mut lines = text.lines();
-
-                while let Some(line) = lines.by_ref().next() {
-                    if line.trim() == "---" {
-                        if frontmatter {
-                            break;
-                        } else {
-                            frontmatter = true;
-                            continue;
-                        }
-                    }
-                    if frontmatter {
-                        meta.push_str(line);
-                        meta.push('\n');
+            if issue.comment.is_redacted() {
+                term::print(term::format::italic("This description was redacted."));
+            } else if issue.description().is_empty() {
+                term::print(term::format::italic("No description provided."));
+            } else {
+                term::markdown(issue.description());
+            }
+            if !issue.reactions().is_empty() {
+                term::info!("{}", pretty_reactions(issue.reactions()));
+            }
+
+            // Patches that close this issue, via a `Closes:` trailer.
+            let linked = cobs
+                .patches()
+                .all(&project)?
+                .into_iter()
+                .filter(|(_, p)| !p.is_retracted())
+                .filter(|(_, p)| {
+                    p.closes()
+                        .iter()
+                        .any(|i| issues.resolve_id::<Issue>(&project, i).ok().flatten() == Some(id))
+                })
+                .collect::<Vec<_>>();
+
+            if !linked.is_empty() {
+                term::blank();
+                term::info!("{}", term::format::dim("Linked patches"));
+
+                let mut table = term::Table::default();
+                for (patch_id, patch) in &linked {
+                    table.push([
+                        term::format::tertiary(common::fmt::cob(patch_id)),
+                        term::format::bold(&patch.title),
+                        pretty_patch_state(patch),
+                    ]);
+                }
+                table.render();
+            }
+
+            for comment in issue.comments() {
+                term::blank();
+                term::info!(
+                    "{} {} {}",
+                    term::format::dim("╭─"),
+                    term::format::highlight(comment.author.name()),
+                    term::format::dim(term::format::timestamp(comment.timestamp, verbose)),
+                );
+                if comment.is_redacted() {
+                    term::print(term::format::italic("This comment was redacted."));
+                } else {
+                    term::markdown(&comment.body);
+                }
+                if !comment.reactions.is_empty() {
+                    term::info!("{}", pretty_reactions(&comment.reactions));
+                }
+
+                for reply in &comment.replies {
+                    term::indented(&format!(
+                        "{} {} {}",
+                        term::format::dim("↳"),
+                        term::format::highlight(reply.author.name()),
+                        term::format::dim(term::format::timestamp(reply.timestamp, verbose)),
+                    ));
+                    for line in reply.body.lines() {
+                        term::indented(line);
                     }
                 }
+            }
+            term::blank();
+        }
+        Operation::Comment { id, message } => {
+            let (id, _) = resolve(&id)?;
+            let body = message.get(COMMENT_MSG, Validation::required())?;
+            issues.comment(&project, &id, &body)?;
+
+            term::success!(
+                "Comment added to issue {}",
+                term::format::highlight(common::fmt::cob(&id))
+            );
+        }
+        Operation::State { id, state } => {
+            let (id, issue) = resolve(&id)?;
+
+            if issue.state() == state {
+                term::info!("Issue is already {}, nothing to do.", pretty_state(state));
+                return Ok(());
+            }
+            issues.lifecycle(&project, &id, state)?;
 
-                let description: String = lines.collect::<Vec<&str>>().join("\n");
-                let meta: Metadata =
-                    serde_yaml::from_str(&meta).context("failed to parse yaml front-matter")?;
+            term::success!(
+                "Issue {} is now {}",
+                term::format::highlight(common::fmt::cob(&id)),
+                pretty_state(state)
+            );
+        }
+        Operation::Assign { id, peers } => {
+            let (id, _) = resolve(&id)?;
+            let meta = project::get(&storage, &project)?
+                .ok_or_else(|| anyhow!("couldn't load project {} from local state", project))?;
+            let mut assignees = Vec::new();
 
-                issues.create(&project, &meta.title, description.trim(), &meta.labels)?;
+            for peer in &peers {
+                let info = project::PeerInfo::get(peer, &meta, &storage);
+                let person = info.person.as_ref().ok_or_else(|| Error::WithHint {
+                    err: anyhow!(
+                        "the identity of peer {} was not found",
+                        common::fmt::peer(peer)
+                    ),
+                    hint: "hint: the peer must be tracked, and must have published their identity",
+                })?;
+                assignees.push(cobs::Author::new(person.urn.clone(), *peer));
             }
+            issues.assign(&project, &id, &assignees)?;
+
+            term::success!(
+                "Issue {} assigned to {}",
+                term::format::highlight(common::fmt::cob(&id)),
+                peers
+                    .iter()
+                    .map(|p| term::format::tertiary(common::fmt::peer(p)))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        Operation::Label { id, labels } => {
+            let (id, _) = resolve(&id)?;
+
+            issues.label(&project, &id, &labels)?;
+
+            term::success!(
+                "Issue {} labeled {}",
+                term::format::highlight(common::fmt::cob(&id)),
+                term::format::tertiary(
+                    labels
+                        .iter()
+                        .map(|l| l.name())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            );
         }
-        Operation::List => {
-            for (id, issue) in issues.all(&project)? {
-                println!("{} {}", id, issue.title());
+        Operation::React {
+            id,
+            reaction,
+            comment,
+        } => {
+            let (id, issue) = resolve(&id)?;
+            let comment_id = if comment {
+                term::comment_select(&issue).ok_or_else(|| anyhow!("no comment was selected"))?
+            } else {
+                cobs::CommentId::root()
+            };
+            issues.react(&project, &id, comment_id, reaction)?;
+
+            term::success!(
+                "Reacted with {} to issue {}",
+                reaction.emoji,
+                term::format::highlight(common::fmt::cob(&id))
+            );
+        }
+        Operation::Redact { id, comment } => {
+            let (id, issue) = resolve(&id)?;
+            let comment_id = if comment {
+                term::comment_select(&issue).ok_or_else(|| anyhow!("no comment was selected"))?
+            } else {
+                cobs::CommentId::root()
+            };
+            let author = match usize::from(comment_id) {
+                0 => &issue.comment.author,
+                ix => &issue.comments()[ix - 1].author,
+            };
+            if author.urn() != &cobs.whoami.urn() {
+                anyhow::bail!("you can only redact your own issues and comments");
             }
+            if !term::confirm("Redact content? This can't be undone.") {
+                anyhow::bail!("redaction aborted by user");
+            }
+            issues.redact(&project, &id, comment_id)?;
+
+            term::success!(
+                "Content of issue {} redacted",
+                term::format::highlight(common::fmt::cob(&id))
+            );
         }
         Operation::Delete { id } => {
+            let (id, _) = resolve(&id)?;
+
             issues.remove(&project, &id)?;
         }
     }
 
     Ok(())
 }
+
+/// Format the state of an issue.
+fn pretty_state(state: State) -> String {
+    match state {
+        State::Open => term::format::positive(labeled(Indicator::Open, "open")),
+        State::Closed {
+            reason: CloseReason::Solved,
+        } => term::format::secondary(labeled(Indicator::Success, "solved")),
+        State::Closed {
+            reason: CloseReason::Other,
+        } => term::format::negative(labeled(Indicator::Closed, "closed")),
+    }
+}
+
+/// Format the state of a patch linked to an issue.
+fn pretty_patch_state(patch: &Patch) -> String {
+    if patch.is_merged() {
+        return term::format::positive(labeled(Indicator::Merged, "merged"));
+    }
+    match patch.state {
+        patch::State::Draft => term::format::dim(labeled(Indicator::Draft, "draft")),
+        patch::State::Proposed => term::format::secondary(labeled(Indicator::Open, "open")),
+        patch::State::Archived | patch::State::Retracted => {
+            term::format::negative(labeled(Indicator::Closed, "closed"))
+        }
+    }
+}
+
+/// Format the labels of an issue, eg. `bug, ux`.
+fn pretty_labels(issue: &Issue) -> String {
+    let mut labels = issue
+        .labels()
+        .iter()
+        .map(|l| l.name().to_owned())
+        .collect::<Vec<_>>();
+    labels.sort();
+
+    term::format::tertiary(labels.join(", "))
+}
+
+/// Format the assignees of an issue.
+fn pretty_assignees(issue: &Issue) -> String {
+    term::format::highlight(
+        issue
+            .assignees()
+            .iter()
+            .map(|a| a.name())
+            .collect::<Vec<_>>()
+            .join(", "),
+    )
+}
+
+/// Format reactions, eg. `👍 2  🎉 1`.
+fn pretty_reactions(reactions: &std::collections::HashMap<cobs::Reaction, usize>) -> String {
+    let mut reactions = reactions
+        .iter()
+        .map(|(r, n)| format!("{} {}", r.emoji, n))
+        .collect::<Vec<_>>();
+    reactions.sort();
+
+    reactions.join("  ")
+}
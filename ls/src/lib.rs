@@ -1,9 +1,17 @@
 use std::ffi::OsString;
+use std::path::PathBuf;
+
+use librad::git::Urn;
 
 use radicle_common::args::{Args, Error, Help};
-use radicle_common::{profile, project};
+use radicle_common::registry::Registry;
+use radicle_common::store::Store;
+use radicle_common::{git, json, profile, project, registry, schema};
 use radicle_terminal as term;
 
+/// Version of the JSON output schema.
+pub const SCHEMA_VERSION: u32 = 1;
+
 pub const HELP: Help = Help {
     name: "ls",
     description: env!("CARGO_PKG_DESCRIPTION"),
@@ -13,22 +21,57 @@ Usage
 
     rad ls [<option>...]
 
+    Lists the projects in local storage, with the head of their default branch,
+    their working copy, and whether the head was pushed to seeds.
+
+    Working copies are known once a project is initialized or checked out, or when
+    `rad ls` is run from inside one. Sync status is known once a project is pushed
+    to seeds, eg. with `rad sync`.
+
 Options
 
-    --help    Print help
+    --filter <text>   Only list projects whose name, id or description contain <text>
+    --json            Output projects as JSON
+    --schema          Print the JSON Schema of the `--json` output
+    --full            Don't truncate names and descriptions to fit the terminal width
+    --help            Print help
 "#,
 };
 
-pub struct Options {}
+pub struct Options {
+    pub full: bool,
+    pub filter: Option<String>,
+    pub json: bool,
+}
 
 impl Args for Options {
     fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
         use lexopt::prelude::*;
 
         let mut parser = lexopt::Parser::from_args(args);
+        let mut full = false;
+        let mut filter = None;
+        let mut json = false;
 
-        if let Some(arg) = parser.next()? {
+        while let Some(arg) = parser.next()? {
             match arg {
+                Long("full") => {
+                    full = true;
+                }
+                Long("filter") => {
+                    let val = parser.value()?;
+                    let val = val
+                        .to_str()
+                        .ok_or_else(|| anyhow::anyhow!("filter specified is not UTF-8"))?;
+
+                    filter = Some(val.to_lowercase());
+                }
+                Long("json") => {
+                    json = true;
+                }
+                Long("schema") => {
+                    return Err(Error::Schema(json_schema()).into());
+                }
                 Long("help") => {
                     return Err(Error::Help.into());
                 }
@@ -36,25 +79,166 @@ impl Args for Options {
             }
         }
 
-        Ok((Options {}, vec![]))
+        Ok((Options { full, filter, json }, vec![]))
+    }
+}
+
+/// JSON Schema of the `--json` output.
+pub fn json_schema() -> json::Value {
+    let nullable =
+        |description: &str| json::json!({ "type": ["string", "null"], "description": description });
+    let project = schema::object(&[
+        ("urn", schema::string("Project URN")),
+        ("name", schema::string("Project name")),
+        ("description", schema::string("Project description")),
+        (
+            "defaultBranch",
+            schema::string("Default branch of the project"),
+        ),
+        ("head", nullable("Head of the default branch, if any")),
+        (
+            "workdirs",
+            json::json!({ "type": "array", "items": { "type": "string" }, "description": "Known working copies" }),
+        ),
+        (
+            "synced",
+            nullable("Default branch head last pushed to seeds, if any"),
+        ),
+        (
+            "status",
+            json::json!({ "type": "string", "enum": ["unknown", "synced", "ahead", "diverged"] }),
+        ),
+        (
+            "ahead",
+            json::json!({ "type": "integer", "minimum": 0, "description": "Number of commits not pushed to seeds" }),
+        ),
+    ]);
+
+    schema::document(
+        "ls",
+        SCHEMA_VERSION,
+        json::json!({ "type": "array", "items": project }),
+    )
+}
+
+/// Whether the default branch head was pushed to seeds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SyncStatus {
+    /// Never pushed, as far as we know.
+    Unknown,
+    /// The head was pushed.
+    Synced,
+    /// The head has commits that weren't pushed.
+    Ahead(usize),
+    /// The head was rewritten since it was pushed.
+    Diverged,
+}
+
+impl SyncStatus {
+    fn new(repo: &git::Repository, head: Option<git::Oid>, synced: Option<git::Oid>) -> Self {
+        let (head, synced) = match (head, synced) {
+            (Some(head), Some(synced)) => (head, synced),
+            _ => return Self::Unknown,
+        };
+        match repo.graph_ahead_behind(head, synced) {
+            Ok((0, 0)) => Self::Synced,
+            Ok((ahead, 0)) => Self::Ahead(ahead),
+            Ok(_) => Self::Diverged,
+            Err(_) => Self::Unknown,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Unknown => "unknown",
+            Self::Synced => "synced",
+            Self::Ahead(_) => "ahead",
+            Self::Diverged => "diverged",
+        }
+    }
+
+    fn format(&self) -> String {
+        match self {
+            Self::Unknown => term::format::dim("unknown"),
+            Self::Synced => term::format::positive("synced"),
+            Self::Ahead(n) => term::format::yellow(format!("{} ahead", n)),
+            Self::Diverged => term::format::negative("diverged"),
+        }
     }
 }
 
-pub fn run(_options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
     let profile = ctx.profile()?;
     let storage = profile::read_only(&profile)?;
     let projs = project::list(&storage)?;
-    let mut table = term::Table::default();
+    let repo = git::Repository::open_bare(storage.path())?;
+
+    // Register the working copy we're in, if any, so that it's listed from now on.
+    if let Ok((urn, cwd)) = project::cwd() {
+        if let Some(workdir) = cwd.workdir() {
+            registry::register(&profile, &urn, workdir).ok();
+        }
+    }
+    let registry = Registry::load(&profile)?;
+
+    let projs = projs.into_iter().filter(|(urn, meta, _)| {
+        options.filter.as_ref().map_or(true, |filter| {
+            meta.name.to_lowercase().contains(filter)
+                || meta.description.to_lowercase().contains(filter)
+                || urn.to_string().to_lowercase().contains(filter)
+        })
+    });
+
+    if options.json {
+        let mut objs = Vec::new();
+
+        for (urn, meta, head) in projs {
+            let workdirs = workdirs(&registry, &urn);
+            let synced = registry.synced(&urn);
+            let status = SyncStatus::new(&repo, head, synced);
+
+            objs.push(json::json!({
+                "urn": urn.to_string(),
+                "name": meta.name,
+                "description": meta.description,
+                "defaultBranch": meta.default_branch,
+                "head": head.map(|h| h.to_string()),
+                "workdirs": workdirs,
+                "synced": synced.map(|h| h.to_string()),
+                "status": status.name(),
+                "ahead": match status {
+                    SyncStatus::Ahead(n) => n,
+                    _ => 0,
+                },
+            }));
+        }
+        println!("{}", json::to_string_pretty(&objs)?);
+
+        return Ok(());
+    }
+
+    let mut table = term::Table::new(term::table::TableOptions {
+        full: options.full,
+        ..term::table::TableOptions::default()
+    });
 
     for (urn, meta, head) in projs {
+        let status = SyncStatus::new(&repo, head, registry.synced(&urn));
         let head = head
             .map(|h| format!("{:.7}", h.to_string()))
             .unwrap_or_else(String::new);
+        let workdir = match workdirs(&registry, &urn).as_slice() {
+            [] => String::new(),
+            [path] => path.display().to_string(),
+            [path, rest @ ..] => format!("{} (+{})", path.display(), rest.len()),
+        };
 
         table.push([
             term::format::bold(meta.name),
             term::format::tertiary(urn),
             term::format::secondary(head),
+            status.format(),
+            workdir,
             term::format::italic(meta.description),
         ]);
     }
@@ -62,3 +246,11 @@ pub fn run(_options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Registered working copies of a project that still exist.
+fn workdirs(registry: &Registry, urn: &Urn) -> Vec<PathBuf> {
+    registry
+        .get(urn)
+        .map(|e| e.workdirs.iter().filter(|p| p.exists()).cloned().collect())
+        .unwrap_or_default()
+}
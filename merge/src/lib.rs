@@ -6,9 +6,13 @@ use anyhow::{anyhow, Context};
 
 use radicle_common as common;
 use radicle_common::args::{Args, Error, Help};
+use radicle_common::cobs::issue::{self, Issue};
 use radicle_common::cobs::patch::{Patch, PatchId};
+use radicle_common::cobs::review::Verdict;
+use radicle_common::config::Config;
 use radicle_common::patch::MergeStyle;
-use radicle_common::{cobs, git, keys, project};
+use radicle_common::tokio;
+use radicle_common::{cobs, git, keys, merge, project, sync};
 use radicle_terminal as term;
 
 use cobs::patch::RevisionIx;
@@ -23,12 +27,34 @@ Usage
     rad merge [<id>] [<option>...]
 
     To specify a patch to merge, use the fully qualified patch id
-    or an unambiguous prefix of it.
+    or an unambiguous prefix of it. The patch is merged into the
+    project's default branch, which must be checked out.
+
+    Conflicts in generated files can be resolved by merge drivers, configured
+    in `.radicle/merge-drivers.toml` on the default branch, eg.
+
+        [[driver]]
+        path = "CHANGELOG.md"
+        strategy = "union"        # or "ours", "theirs"
+
+        [[driver]]
+        path = "Cargo.lock"
+        command = "cargo generate-lockfile"
+
+    Before merging, the patch is fetched from seeds, so that a revision that was
+    superseded on the network isn't merged by mistake. The merge is also refused
+    unless the revision was approved against the current default branch, ie. it
+    has no commits that weren't there when the revision was approved, unless
+    `allow-stale-merge` is set in the `[policy]` section of the config.
 
 Options
 
-    -i, --interactive         Ask for confirmations
     -r, --revision <number>   Revision number to merge, defaults to the latest
+        --[no-]sync           Push the merged branch and sync to seeds (default: no-sync)
+        --no-fetch            Don't fetch the patch from seeds before merging
+        --no-confirm          Don't ask for confirmation before merging
+        --all                 List all commits being merged, instead of summarizing them
+        --stat                Show the diffstat of each commit being merged
         --help                Print help
 "#,
 };
@@ -48,6 +74,10 @@ pub struct Options {
     pub id: cobs::Identifier,
     pub interactive: bool,
     pub revision: Option<RevisionIx>,
+    pub sync: bool,
+    pub fetch: bool,
+    pub all: bool,
+    pub stat: bool,
 }
 
 impl Args for Options {
@@ -57,7 +87,11 @@ impl Args for Options {
         let mut parser = lexopt::Parser::from_args(args);
         let mut id: Option<cobs::Identifier> = None;
         let mut revision: Option<RevisionIx> = None;
-        let mut interactive = false;
+        let mut interactive = true;
+        let mut sync = false;
+        let mut fetch = true;
+        let mut all = false;
+        let mut stat = false;
 
         while let Some(arg) = parser.next()? {
             match arg {
@@ -67,6 +101,24 @@ impl Args for Options {
                 Long("interactive") | Short('i') => {
                     interactive = true;
                 }
+                Long("no-confirm") => {
+                    interactive = false;
+                }
+                Long("sync") => {
+                    sync = true;
+                }
+                Long("no-sync") => {
+                    sync = false;
+                }
+                Long("no-fetch") => {
+                    fetch = false;
+                }
+                Long("all") => {
+                    all = true;
+                }
+                Long("stat") => {
+                    stat = true;
+                }
                 Long("revision") | Short('r') => {
                     let value = parser.value()?;
                     let id =
@@ -94,6 +146,10 @@ impl Args for Options {
                 id: id.ok_or_else(|| anyhow!("a patch id to merge must be provided"))?,
                 interactive,
                 revision,
+                sync,
+                fetch,
+                all,
+                stat,
             },
             vec![],
         ))
@@ -108,8 +164,8 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
         .map_err(|_| anyhow!("this command must be run in the context of a project"))?;
     let profile = ctx.profile()?;
     let signer = term::signer(&profile)?;
-    let storage = keys::storage(&profile, signer)?;
-    let _project = project::get(&storage, &urn)?
+    let storage = keys::storage(&profile, signer.clone())?;
+    let project = project::get(&storage, &urn)?
         .ok_or_else(|| anyhow!("couldn't load project {} from local state", urn))?;
     let cobs = cobs::store(&profile, &storage)?;
     let patches = cobs.patches();
@@ -124,6 +180,47 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
     let (patch_id, mut patch) = patches
         .resolve::<Patch>(&urn, &options.id)?
         .ok_or_else(|| anyhow!("couldn't find patch {} locally", &options.id))?;
+
+    // Make sure we aren't merging a revision that was superseded on the network.
+    if options.fetch {
+        let local = patch.version();
+        let rt = tokio::runtime::Runtime::new()?;
+        let fetched = sync::seeds(&profile).and_then(|seeds| {
+            term::sync::sync(
+                urn.clone(),
+                seeds,
+                sync::Mode::Fetch,
+                &profile,
+                signer.clone(),
+                &rt,
+            )
+        });
+
+        match fetched {
+            Ok(results) if results.iter().any(|r| r.is_ok()) => {
+                if let Some(fetched) = patches.get(&urn, &patch_id)? {
+                    patch = fetched;
+                }
+            }
+            _ => {
+                term::warning("Could not fetch the patch from seeds, it may be out of date");
+            }
+        }
+        if patch.version() != local {
+            term::warning(&format!(
+                "Patch {} was updated on the network, R{} is its latest revision",
+                common::fmt::cob(&patch_id),
+                patch.version()
+            ));
+            if options.revision.is_none() {
+                return Err(common::Error::WithHint {
+                    err: anyhow!("patch has a new revision"),
+                    hint: "hint: review the new revision before merging it, or merge an older one with `--revision`",
+                }
+                .into());
+            }
+        }
+    }
     patch.author.resolve(&storage).ok();
 
     let head = repo.head()?;
@@ -133,12 +230,65 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
     let head_oid = head
         .target()
         .ok_or_else(|| anyhow!("cannot merge into detatched head; aborting"))?;
+    let default_branch = project.default_branch.to_string();
+    if branch != default_branch {
+        return Err(common::Error::WithHint {
+            err: anyhow!(
+                "patches must be merged into the default branch `{}`, but `{}` is checked out",
+                default_branch,
+                branch
+            ),
+            hint: "hint: checkout the default branch and try again",
+        }
+        .into());
+    }
     let revision_id = options.revision.unwrap_or_else(|| patch.version());
     let revision = patch
         .revisions
         .get(revision_id)
         .ok_or_else(|| anyhow!("revision R{} does not exist", revision_id))?;
 
+    if revision_id < patch.version() {
+        term::warning(&format!(
+            "R{} is superseded by R{}",
+            revision_id,
+            patch.version()
+        ));
+    }
+
+    // Make sure the default branch didn't move since the revision was approved, ie. that
+    // it has no commits the branch approved against didn't have. Approvals that don't
+    // record that branch, or whose branch head isn't known here, don't count.
+    let moved = revision
+        .reviews
+        .values()
+        .filter(|r| r.verdict == Some(Verdict::Accept))
+        .filter_map(|r| r.target)
+        .filter_map(|target| common::patch::commits_since(&repo, &*target, &head_oid).ok())
+        .min();
+    let stale = match moved {
+        Some(0) => None,
+        Some(n) => Some(format!(
+            "{} has {} commit(s) that weren't there when R{} was approved",
+            branch, n, revision_id
+        )),
+        None => Some(format!(
+            "R{} has no approval that can be checked against {}",
+            revision_id, branch
+        )),
+    };
+    if let Some(msg) = stale {
+        if Config::policy(&profile)?.allow_stale_merge {
+            term::warning(&msg);
+        } else {
+            return Err(common::Error::WithHint {
+                err: anyhow!(msg),
+                hint: "hint: ask for the patch to be reviewed again, or set `allow-stale-merge = true` in the config's `[policy]` section",
+            }
+            .into());
+        }
+    }
+
     //
     // Analyze merge
     //
@@ -146,6 +296,7 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
         .find_annotated_commit(revision.oid.into())
         .context("patch head not found in local repository")?;
     let (merge, _merge_pref) = repo.merge_analysis(&[&patch_commit])?;
+    let drivers = merge::Drivers::load(&repo, &head.peel_to_commit()?)?;
 
     let merge_style = if merge.is_fast_forward() {
         // The given merge input is a fast-forward from HEAD and no merge needs to be performed.
@@ -164,11 +315,24 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
             .context("failed to perform merge analysis")?;
 
         if index.has_conflicts() {
-            return Err(common::Error::WithHint {
-                err: anyhow!("patch conflicts with {}", branch),
-                hint: "Patch must be rebased before it can be merged.",
+            let unresolved = drivers.unresolved(&index)?;
+
+            if !unresolved.is_empty() {
+                return Err(common::Error::WithHint {
+                    err: anyhow!(
+                        "patch conflicts with {} in {}",
+                        branch,
+                        unresolved.join(", ")
+                    ),
+                    hint: "hint: the patch must be rebased before it can be merged, or the conflicting files given a merge driver in `.radicle/merge-drivers.toml`",
+                }
+                .into());
             }
-            .into());
+            term::info!(
+                "{} conflict(s) with {} will be resolved by merge drivers",
+                index.conflicts()?.count(),
+                branch
+            );
         }
         MergeStyle::Commit
     } else if merge.is_up_to_date() {
@@ -212,6 +376,19 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
         merge_style_pretty
     );
 
+    // Summary of the commits this merge brings into the branch.
+    let limit = if options.all {
+        usize::MAX
+    } else {
+        term::patch::COMMIT_LIMIT
+    };
+    let (commits, total) =
+        common::patch::patch_commits_limited(&repo, &head_oid, &*revision.oid, limit)?;
+
+    term::blank();
+    term::patch::list_commits(&repo, &commits, total, options.stat)?;
+    term::blank();
+
     if options.interactive && !term::confirm("Confirm?") {
         anyhow::bail!("merge aborted by user");
     }
@@ -221,7 +398,14 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
     //
     match merge_style {
         MergeStyle::Commit => {
-            merge_commit(&repo, patch_id, &patch_commit, &patch, cobs.whoami.urn())?;
+            merge_commit(
+                &repo,
+                patch_id,
+                &patch_commit,
+                &patch,
+                &drivers,
+                cobs.whoami.urn(),
+            )?;
         }
         MergeStyle::FastForward => {
             fast_forward(&repo, &revision.oid)?;
@@ -242,10 +426,70 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
     // TODO: Don't allow merging the same revision twice?
     patches.merge(&urn, &patch_id, revision_id, head_oid.into())?;
 
-    term::success!(
-        "Patch state updated, use {} to publish",
-        term::format::secondary("`rad push`")
-    );
+    // Close the issues referenced with `Closes:` trailers in the patch description.
+    let issues = cobs.issues();
+    for id in patch.closes() {
+        match issues.resolve::<Issue>(&urn, &id) {
+            Ok(Some((issue_id, issue))) => {
+                if issue.state() != issue::State::Open {
+                    continue;
+                }
+                issues.lifecycle(
+                    &urn,
+                    &issue_id,
+                    issue::State::Closed {
+                        reason: issue::CloseReason::Solved,
+                    },
+                )?;
+                term::success!(
+                    "Closed issue {} {}",
+                    term::format::highlight(common::fmt::cob(&issue_id)),
+                    term::format::italic(issue.title())
+                );
+            }
+            Ok(None) => {
+                term::warning(&format!(
+                    "Issue {} referenced by the patch was not found, it was not closed",
+                    id
+                ));
+            }
+            Err(err) => {
+                term::warning(&format!(
+                    "Issue {} referenced by the patch was not closed: {}",
+                    id, err
+                ));
+            }
+        }
+    }
+
+    if options.sync {
+        let mut spinner = term::spinner(format!(
+            "Pushing {} to storage...",
+            term::format::highlight(branch)
+        ));
+        git::git(
+            repo.workdir()
+                .ok_or_else(|| anyhow!("cannot push from bare repo"))?,
+            ["push", "rad"],
+        )?;
+        spinner.finish();
+
+        let rt = tokio::runtime::Runtime::new()?;
+
+        term::sync::sync(
+            urn,
+            sync::seeds(&profile)?,
+            sync::Mode::Push,
+            &profile,
+            signer,
+            &rt,
+        )?;
+    } else {
+        term::success!(
+            "Patch state updated, use {} to publish",
+            term::format::secondary("`rad push`")
+        );
+    }
 
     Ok(())
 }
@@ -262,6 +506,7 @@ fn merge_commit(
     patch_id: PatchId,
     patch_commit: &git::AnnotatedCommit,
     patch: &Patch,
+    drivers: &merge::Drivers,
     whoami: common::Urn,
 ) -> anyhow::Result<()> {
     let description = patch.description().trim();
@@ -285,17 +530,21 @@ fn merge_commit(
         patch.author.peer.default_encoding()
     )?;
     writeln!(&mut merge_msg, "Rad-Committer: {}", whoami)?;
+    for coauthor in &patch.coauthors {
+        writeln!(&mut merge_msg, "{}", coauthor.trailer())?;
+    }
     writeln!(&mut merge_msg)?;
     writeln!(&mut merge_msg, "{}", MERGE_HELP_MSG.join("\n").as_str())?;
 
     // Offer user the chance to edit the message before committing.
-    let merge_msg = match term::Editor::new()
-        .require_save(true)
-        .trim_newlines(true)
-        .extension(".git-commit")
-        .edit(&merge_msg)
-        .unwrap()
-    {
+    let merge_msg = match term::interrupt::deferred(|| {
+        term::Editor::new()
+            .require_save(true)
+            .trim_newlines(true)
+            .extension(".git-commit")
+            .edit(&merge_msg)
+            .unwrap()
+    }) {
         Some(s) => s
             .lines()
             .filter(|l| !l.starts_with('#'))
@@ -313,6 +562,18 @@ fn merge_commit(
     repo.merge(&[patch_commit], Some(merge_opts.patience(true)), None)
         .context("merge failed")?;
 
+    // Resolve conflicts in generated files.
+    for (path, driver) in drivers.resolve(repo)? {
+        term::success!(
+            "Resolved conflict in {} with {}",
+            term::format::highlight(path),
+            term::format::secondary(driver)
+        );
+    }
+    if repo.index()?.has_conflicts() {
+        anyhow::bail!("merge has unresolved conflicts, run `git merge --abort` to abort it");
+    }
+
     // Commit staged changes.
     let commit = repo.find_commit(patch_commit.id())?;
     let author = commit.author();
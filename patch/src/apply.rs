@@ -0,0 +1,263 @@
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context as _};
+
+use librad::git::storage::ReadOnlyStorage;
+use librad::git_ext::Oid;
+
+use radicle_common as common;
+use radicle_common::args::{Args, Error};
+use radicle_common::cobs::patch::MergeTarget;
+use radicle_common::{cobs, git, keys, mail, project};
+use radicle_terminal as term;
+
+#[derive(Debug)]
+pub struct Options {
+    pub path: PathBuf,
+    pub branch: Option<String>,
+    pub push: bool,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut path: Option<PathBuf> = None;
+        let mut branch = None;
+        let mut push = true;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("help") => {
+                    return Err(Error::Help.into());
+                }
+                Long("branch") | Short('b') => {
+                    branch = Some(parser.value()?.to_string_lossy().into());
+                }
+                Long("push") => {
+                    push = true;
+                }
+                Long("no-push") => {
+                    push = false;
+                }
+                Value(val) if path.is_none() => {
+                    path = Some(PathBuf::from(val));
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+
+        Ok((
+            Options {
+                path: path
+                    .ok_or_else(|| anyhow!("an mbox file or patch directory must be provided"))?,
+                branch,
+                push,
+            },
+            vec![],
+        ))
+    }
+}
+
+/// A patch series, as produced by `git format-patch`.
+struct Series {
+    /// The cover letter, if any, as a title and description.
+    cover: Option<(String, String)>,
+    /// Files containing the patches, in order.
+    files: Vec<PathBuf>,
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let (urn, repo) = project::cwd()
+        .map_err(|_| anyhow!("this command must be run in the context of a project"))?;
+    let profile = ctx.profile()?;
+    let signer = term::signer(&profile)?;
+    let storage = keys::storage(&profile, signer)?;
+    let project = project::get(&storage, &urn)?
+        .ok_or_else(|| anyhow!("couldn't load project {} from local state", urn))?;
+    let cobs = cobs::store(&profile, &storage)?;
+    let patches = cobs.patches();
+
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| anyhow!("cannot apply patches in a bare repository"))?
+        .to_path_buf();
+    // Nb. Temporary files are kept in the git directory, so they don't show up as changes.
+    let tmp = repo.path().join("rad-apply");
+    let series = if options.path.is_dir() {
+        read_dir(&options.path)?
+    } else {
+        read_mbox(&options.path, &tmp)?
+    };
+    if series.files.is_empty() {
+        anyhow::bail!("no patches found in {}", options.path.display());
+    }
+
+    let default_branch = project.default_branch.to_string();
+    let base = repo
+        .find_branch(&default_branch, git::BranchType::Local)
+        .map_err(|_| Error::WithHint {
+            err: anyhow!("branch '{}' was not found", default_branch),
+            hint:
+                "hint: patches are applied on top of the default branch, which must exist locally",
+        })?
+        .get()
+        .peel_to_commit()?;
+
+    let title = match &series.cover {
+        Some((title, _)) => title.clone(),
+        None => mail::subject(&fs::read_to_string(&series.files[0])?)
+            .ok_or_else(|| anyhow!("couldn't find a subject in {}", series.files[0].display()))?,
+    };
+    if title.is_empty() {
+        return Err(Error::WithHint {
+            err: anyhow!("the patch series has no title"),
+            hint: "hint: fill in the subject of the cover letter",
+        }
+        .into());
+    }
+    let branch = match options.branch {
+        Some(branch) => branch,
+        None => match mail::slug(&title) {
+            slug if slug.is_empty() => {
+                return Err(Error::WithHint {
+                    err: anyhow!("couldn't derive a branch name from '{}'", title),
+                    hint: "hint: name the branch with `--branch <name>`",
+                }
+                .into())
+            }
+            slug => format!("patch/{}", slug),
+        },
+    };
+
+    term::headline(&format!(
+        "🌱 Applying {} patch(es) to {} on {}",
+        series.files.len(),
+        term::format::highlight(&project.name),
+        term::format::highlight(&branch)
+    ));
+
+    repo.branch(&branch, &base, false)
+        .with_context(|| format!("failed to create branch '{}'", branch))?;
+    git::git(&workdir, ["checkout", branch.as_str()])?;
+
+    let spinner = term::spinner("Applying patches...");
+    let mut args = vec![OsString::from("am"), OsString::from("--3way")];
+    args.extend(series.files.iter().map(|f| f.as_os_str().to_owned()));
+
+    let result = git::git(&workdir, args);
+    if !options.path.is_dir() {
+        fs::remove_dir_all(&tmp).ok();
+    }
+    if let Err(err) = result {
+        spinner.failed();
+        term::blob(&err);
+        term::tip!(
+            "Resolve the conflicts and run `git am --continue`, or abort with `git am --abort`."
+        );
+
+        return Err(anyhow!("failed to apply patches on branch '{}'", branch));
+    }
+    spinner.finish();
+
+    let head = repo.head()?.peel_to_commit()?;
+    let (title, description) = match series.cover {
+        Some(cover) => cover,
+        None => {
+            let message = head.message().unwrap_or_default();
+            let (title, description) = message.split_once("\n\n").unwrap_or((message, ""));
+
+            (title.trim().to_owned(), description.trim().to_owned())
+        }
+    };
+
+    if options.push {
+        let spinner = term::spinner("Pushing branch to storage...");
+        git::git(&workdir, ["push", "rad", branch.as_str()])?;
+        spinner.finish();
+    } else if storage.find_object(Oid::from(head.id()))?.is_none() {
+        return Err(Error::WithHint {
+            err: anyhow!("branch head was not found in storage"),
+            hint: "hint: run `git push rad` and try again, or omit `--no-push`",
+        }
+        .into());
+    }
+
+    let id = patches.create(
+        &urn,
+        &title,
+        &description,
+        MergeTarget::default(),
+        base.id(),
+        head.id(),
+        &[],
+    )?;
+
+    term::blank();
+    term::success!(
+        "Patch {} created from {} 🌱",
+        term::format::highlight(common::fmt::cob(&id)),
+        options.path.display()
+    );
+
+    Ok(())
+}
+
+/// Read a patch series from a directory of `.patch` files.
+fn read_dir(path: &Path) -> anyhow::Result<Series> {
+    let mut files = fs::read_dir(path)?
+        .map(|e| e.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    files.retain(|f| f.extension().map_or(false, |e| e == "patch"));
+
+    read_series(files)
+}
+
+/// Read a patch series from an mbox file. The patches are split into the given
+/// temporary directory.
+fn read_mbox(path: &Path, tmp: &Path) -> anyhow::Result<Series> {
+    let path = fs::canonicalize(path)?;
+
+    fs::remove_dir_all(tmp).ok();
+    fs::create_dir_all(tmp)?;
+
+    // Nb. `-b` treats a file without an mbox `From` line as a single message.
+    let mut args = vec![OsString::from("mailsplit"), OsString::from("-b")];
+    let mut output = OsString::from("-o");
+    output.push(tmp);
+    args.push(output);
+    args.push(path.into_os_string());
+
+    git::git(tmp, args).context("failed to split mbox")?;
+
+    let files = fs::read_dir(tmp)?
+        .map(|e| e.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    read_series(files)
+}
+
+/// Read a patch series from the given files, in file name order, the first cover
+/// letter among them excepted.
+fn read_series(mut files: Vec<PathBuf>) -> anyhow::Result<Series> {
+    files.sort();
+
+    let mut cover = None;
+    let mut series = Vec::new();
+
+    for file in files {
+        let content = fs::read_to_string(&file)?;
+
+        match mail::cover_letter(&content) {
+            Some(letter) if cover.is_none() => cover = Some(letter),
+            _ => series.push(file),
+        }
+    }
+    Ok(Series {
+        cover,
+        files: series,
+    })
+}
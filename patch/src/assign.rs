@@ -0,0 +1,108 @@
+use std::ffi::OsString;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+use librad::PeerId;
+
+use radicle_common as common;
+use radicle_common::args::{Args, Error};
+use radicle_common::cobs::patch::Patch;
+use radicle_common::{cobs, keys, project};
+use radicle_terminal as term;
+
+#[derive(Debug)]
+pub struct Options {
+    pub id: cobs::Identifier,
+    pub peers: Vec<PeerId>,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut id: Option<cobs::Identifier> = None;
+        let mut peers = Vec::new();
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("help") => {
+                    return Err(Error::Help.into());
+                }
+                Value(val) if id.is_none() => {
+                    let val = val
+                        .to_str()
+                        .ok_or_else(|| anyhow!("patch id specified is not UTF-8"))?;
+
+                    id = Some(
+                        cobs::Identifier::from_str(val)
+                            .map_err(|_| anyhow!("invalid patch id '{}'", val))?,
+                    );
+                }
+                Value(val) => {
+                    let val = val.to_string_lossy();
+                    let peer = PeerId::from_str(&val)
+                        .map_err(|_| anyhow!("invalid <peer-id> '{}'", val))?;
+
+                    peers.push(peer);
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+        if peers.is_empty() {
+            anyhow::bail!("at least one peer must be provided");
+        }
+
+        Ok((
+            Options {
+                id: id.ok_or_else(|| anyhow!("a patch id must be provided"))?,
+                peers,
+            },
+            vec![],
+        ))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let (urn, _) = project::cwd()
+        .map_err(|_| anyhow!("this command must be run in the context of a project"))?;
+    let profile = ctx.profile()?;
+    let signer = term::signer(&profile)?;
+    let storage = keys::storage(&profile, signer)?;
+    let cobs = cobs::store(&profile, &storage)?;
+    let patches = cobs.patches();
+    let meta = project::get(&storage, &urn)?
+        .ok_or_else(|| anyhow!("couldn't load project {} from local state", urn))?;
+
+    let (patch_id, _) = patches
+        .resolve::<Patch>(&urn, &options.id)?
+        .ok_or_else(|| anyhow!("couldn't find patch {} locally", options.id))?;
+
+    let mut assignees = Vec::new();
+    for peer in &options.peers {
+        let info = project::PeerInfo::get(peer, &meta, &storage);
+        let person = info.person.as_ref().ok_or_else(|| Error::WithHint {
+            err: anyhow!(
+                "the identity of peer {} was not found",
+                common::fmt::peer(peer)
+            ),
+            hint: "hint: the peer must be tracked, and must have published their identity",
+        })?;
+        assignees.push(cobs::Author::new(person.urn.clone(), *peer));
+    }
+    patches.assign(&urn, &patch_id, &assignees)?;
+
+    term::success!(
+        "Patch {} assigned to {}",
+        term::format::highlight(common::fmt::cob(&patch_id)),
+        options
+            .peers
+            .iter()
+            .map(|p| term::format::tertiary(common::fmt::peer(p)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use std::ffi::OsString;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+use radicle_common as common;
+use radicle_common::args::{Args, Error};
+use radicle_common::cobs::patch::{CheckState, Patch, RevisionIx};
+use radicle_common::{cobs, keys, project};
+use radicle_terminal as term;
+
+/// Name of the check, if none is given.
+pub const DEFAULT_NAME: &str = "ci";
+
+#[derive(Debug)]
+pub struct Options {
+    pub id: cobs::Identifier,
+    pub name: String,
+    pub state: CheckState,
+    pub url: Option<String>,
+    pub revision: Option<RevisionIx>,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut id: Option<cobs::Identifier> = None;
+        let mut name = DEFAULT_NAME.to_owned();
+        let mut state = None;
+        let mut url = None;
+        let mut revision = None;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("help") => {
+                    return Err(Error::Help.into());
+                }
+                Long("state") => {
+                    let val = parser.value()?;
+                    let val = val.to_string_lossy();
+
+                    state = Some(CheckState::from_str(&val).map_err(|_| Error::WithHint {
+                        err: anyhow!("invalid check state '{}'", val),
+                        hint: "hint: the check state must be `pass` or `fail`",
+                    })?);
+                }
+                Long("url") => {
+                    url = Some(parser.value()?.to_string_lossy().into());
+                }
+                Long("name") => {
+                    name = parser.value()?.to_string_lossy().into();
+                }
+                Long("revision") | Short('r') => {
+                    revision = Some(common::args::parse_value("revision", parser.value()?)?);
+                }
+                Value(val) if id.is_none() => {
+                    let val = val
+                        .to_str()
+                        .ok_or_else(|| anyhow!("patch id specified is not UTF-8"))?;
+
+                    id = Some(
+                        cobs::Identifier::from_str(val)
+                            .map_err(|_| anyhow!("invalid patch id '{}'", val))?,
+                    );
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+        if name.trim().is_empty() {
+            anyhow::bail!("the check name must not be empty");
+        }
+
+        Ok((
+            Options {
+                id: id.ok_or_else(|| anyhow!("a patch id must be provided"))?,
+                name,
+                state: state
+                    .ok_or_else(|| anyhow!("a check state must be provided with `--state`"))?,
+                url,
+                revision,
+            },
+            vec![],
+        ))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let (urn, _) = project::cwd()
+        .map_err(|_| anyhow!("this command must be run in the context of a project"))?;
+    let profile = ctx.profile()?;
+    let signer = term::signer(&profile)?;
+    let storage = keys::storage(&profile, signer)?;
+    let cobs = cobs::store(&profile, &storage)?;
+    let patches = cobs.patches();
+
+    let (patch_id, patch) = patches
+        .resolve::<Patch>(&urn, &options.id)?
+        .ok_or_else(|| anyhow!("couldn't find patch {} locally", options.id))?;
+
+    let revision_ix = options.revision.unwrap_or_else(|| patch.version());
+    let revision = patch
+        .revisions
+        .get(revision_ix)
+        .ok_or_else(|| anyhow!("revision R{} does not exist", revision_ix))?;
+
+    patches.check(
+        &urn,
+        &patch_id,
+        options.name.trim(),
+        revision.oid,
+        options.state,
+        options.url.as_deref(),
+    )?;
+
+    term::success!(
+        "Check {} recorded as {} for patch {} {}",
+        term::format::tertiary(options.name.trim()),
+        crate::pretty_check_state(options.state),
+        term::format::highlight(common::fmt::cob(&patch_id)),
+        term::format::dim(format!("R{}", revision_ix)),
+    );
+
+    Ok(())
+}
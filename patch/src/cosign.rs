@@ -0,0 +1,122 @@
+use std::ffi::OsString;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+use radicle_common as common;
+use radicle_common::args::{Args, Error};
+use radicle_common::cobs::patch::Patch;
+use radicle_common::{cobs, keys, person, project};
+use radicle_terminal as term;
+
+#[derive(Debug)]
+pub struct Options {
+    pub id: cobs::Identifier,
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut id: Option<cobs::Identifier> = None;
+        let mut name = None;
+        let mut email = None;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("help") => {
+                    return Err(Error::Help.into());
+                }
+                Long("name") => {
+                    name = Some(parser.value()?.to_string_lossy().into());
+                }
+                Long("email") => {
+                    email = Some(parser.value()?.to_string_lossy().into());
+                }
+                Value(val) if id.is_none() => {
+                    let val = val
+                        .to_str()
+                        .ok_or_else(|| anyhow!("patch id specified is not UTF-8"))?;
+
+                    id = Some(
+                        cobs::Identifier::from_str(val)
+                            .map_err(|_| anyhow!("invalid patch id '{}'", val))?,
+                    );
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+
+        Ok((
+            Options {
+                id: id.ok_or_else(|| anyhow!("a patch id must be provided"))?,
+                name,
+                email,
+            },
+            vec![],
+        ))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let (urn, repo) = project::cwd()
+        .map_err(|_| anyhow!("this command must be run in the context of a project"))?;
+    let profile = ctx.profile()?;
+    let signer = term::signer(&profile)?;
+    let storage = keys::storage(&profile, signer)?;
+    let whoami = person::local(&storage)?;
+    let cobs = cobs::store(&profile, &storage)?;
+    let patches = cobs.patches();
+
+    let (patch_id, patch) = patches
+        .resolve::<Patch>(&urn, &options.id)?
+        .ok_or_else(|| anyhow!("couldn't find patch {} locally", options.id))?;
+
+    if patch.author.urn() == &whoami.urn() {
+        anyhow::bail!("you can't co-sign your own patch");
+    }
+    if patch
+        .coauthors
+        .iter()
+        .any(|c| c.author.urn() == &whoami.urn())
+    {
+        anyhow::bail!(
+            "you have already co-signed patch {}",
+            common::fmt::cob(&patch_id)
+        );
+    }
+
+    // The trailer identity defaults to the one used for commits in this working copy.
+    let config = repo.config()?;
+    let name = match options.name {
+        Some(name) => name,
+        None => config
+            .get_string("user.name")
+            .map_err(|_| Error::WithHint {
+                err: anyhow!("a name for the `Co-authored-by` trailer could not be determined"),
+                hint: "hint: set `user.name` in your git config, or use `--name`",
+            })?,
+    };
+    let email = match options.email {
+        Some(email) => email,
+        None => config
+            .get_string("user.email")
+            .map_err(|_| Error::WithHint {
+                err: anyhow!("an email for the `Co-authored-by` trailer could not be determined"),
+                hint: "hint: set `user.email` in your git config, or use `--email`",
+            })?,
+    };
+
+    patches.cosign(&urn, &patch_id, &name, &email)?;
+
+    term::success!(
+        "Patch {} co-signed as {}",
+        term::format::highlight(common::fmt::cob(&patch_id)),
+        term::format::secondary(format!("{} <{}>", name, email))
+    );
+
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use std::ffi::OsString;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+use radicle_common as common;
+use radicle_common::args::{Args, Error};
+use radicle_common::cobs::patch::{Patch, State};
+use radicle_common::{cobs, keys, person, project, Urn};
+use radicle_terminal as term;
+
+#[derive(Debug)]
+pub struct Options {
+    pub id: cobs::Identifier,
+    pub project: Option<Urn>,
+    pub confirm: bool,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut id: Option<cobs::Identifier> = None;
+        let mut project: Option<Urn> = None;
+        let mut confirm = true;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("help") => {
+                    return Err(Error::Help.into());
+                }
+                Long("project") => {
+                    let val = parser.value()?;
+                    let val = val.to_string_lossy();
+
+                    project =
+                        Some(Urn::from_str(&val).map_err(|_| anyhow!("invalid URN '{}'", val))?);
+                }
+                Long("no-confirm") => {
+                    confirm = false;
+                }
+                Value(val) if id.is_none() => {
+                    let val = val
+                        .to_str()
+                        .ok_or_else(|| anyhow!("patch id specified is not UTF-8"))?;
+
+                    id = Some(
+                        cobs::Identifier::from_str(val)
+                            .map_err(|_| anyhow!("invalid patch id '{}'", val))?,
+                    );
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+
+        Ok((
+            Options {
+                id: id.ok_or_else(|| anyhow!("a patch id must be provided"))?,
+                project,
+                confirm,
+            },
+            vec![],
+        ))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let urn = match options.project {
+        Some(urn) => urn,
+        None => project::cwd()
+            .map(|(urn, _)| urn)
+            .map_err(|_| Error::WithHint {
+                err: anyhow!("a project must be specified outside of a project"),
+                hint: "hint: use `--project <urn>`",
+            })?,
+    };
+    let profile = ctx.profile()?;
+    let signer = term::signer(&profile)?;
+    let storage = keys::storage(&profile, signer)?;
+    let whoami = person::local(&storage)?;
+    let cobs = cobs::store(&profile, &storage)?;
+    let patches = cobs.patches();
+
+    let (patch_id, patch) = patches
+        .resolve::<Patch>(&urn, &options.id)?
+        .ok_or_else(|| anyhow!("couldn't find patch {} locally", options.id))?;
+
+    if patch.author.urn() != &whoami.urn() {
+        anyhow::bail!("only the author of a patch can delete it");
+    }
+    if patch.is_retracted() {
+        anyhow::bail!("patch {} was already deleted", common::fmt::cob(&patch_id));
+    }
+    if patch.is_merged() {
+        anyhow::bail!(
+            "patch {} was merged and can't be deleted",
+            common::fmt::cob(&patch_id)
+        );
+    }
+
+    if options.confirm
+        && !term::confirm(format!(
+            "Delete patch {} {}?",
+            term::format::highlight(common::fmt::cob(&patch_id)),
+            term::format::italic(&patch.title)
+        ))
+    {
+        anyhow::bail!("patch deletion aborted by user");
+    }
+
+    patches.lifecycle(&urn, &patch_id, State::Retracted)?;
+
+    term::success!(
+        "Patch {} deleted",
+        term::format::highlight(common::fmt::cob(&patch_id))
+    );
+    term::tip!("Run `rad sync` to publish the deletion to seeds.");
+
+    Ok(())
+}
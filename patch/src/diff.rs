@@ -0,0 +1,180 @@
+use std::ffi::OsString;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+use radicle_common as common;
+use radicle_common::args::{Args, Error};
+use radicle_common::cobs::patch::{Patch, RevisionIx};
+use radicle_common::patch::Interdiff;
+use radicle_common::{cobs, git, keys, patch, project};
+use radicle_terminal as term;
+
+#[derive(Debug)]
+pub struct Options {
+    pub id: cobs::Identifier,
+    /// Revisions to compare. Defaults to the last two revisions.
+    pub revisions: Option<(RevisionIx, RevisionIx)>,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut id: Option<cobs::Identifier> = None;
+        let mut revisions = None;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("help") => {
+                    return Err(Error::Help.into());
+                }
+                Long("revisions") => {
+                    let val = parser.value()?;
+                    let val = val.to_string_lossy();
+                    let range = val
+                        .split_once("..")
+                        .and_then(|(from, to)| Some((from.parse().ok()?, to.parse().ok()?)))
+                        .ok_or_else(|| Error::WithHint {
+                            err: anyhow!("invalid revision range '{}'", val),
+                            hint: "hint: revision ranges are of the form `<n>..<m>`, eg. `0..2`",
+                        })?;
+
+                    revisions = Some(range);
+                }
+                Value(val) if id.is_none() => {
+                    let val = val
+                        .to_str()
+                        .ok_or_else(|| anyhow!("patch id specified is not UTF-8"))?;
+
+                    id = Some(
+                        cobs::Identifier::from_str(val)
+                            .map_err(|_| anyhow!("invalid patch id '{}'", val))?,
+                    );
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+
+        Ok((
+            Options {
+                id: id.ok_or_else(|| anyhow!("a patch id to diff must be provided"))?,
+                revisions,
+            },
+            vec![],
+        ))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let (urn, _) = project::cwd()
+        .map_err(|_| anyhow!("this command must be run in the context of a project"))?;
+    let profile = ctx.profile()?;
+    let signer = term::signer(&profile)?;
+    let storage = keys::storage(&profile, signer)?;
+    let cobs = cobs::store(&profile, &storage)?;
+    let patches = cobs.patches();
+
+    let (patch_id, patch) = patches
+        .resolve::<Patch>(&urn, &options.id)?
+        .ok_or_else(|| anyhow!("couldn't find patch {} locally", options.id))?;
+
+    let (from, to) = match options.revisions {
+        Some(range) => range,
+        None if patch.version() > 0 => (patch.version() - 1, patch.version()),
+        None => anyhow::bail!("patch {} has only one revision", patch_id),
+    };
+    let old = patch
+        .revisions
+        .get(from)
+        .ok_or_else(|| anyhow!("revision R{} does not exist", from))?;
+    let new = patch
+        .revisions
+        .get(to)
+        .ok_or_else(|| anyhow!("revision R{} does not exist", to))?;
+
+    let monorepo = git::Repository::open_bare(profile.paths().git_dir())?;
+    let interdiff = patch::interdiff(&monorepo, (&*old.base, &*old.oid), (&*new.base, &*new.oid))?;
+
+    term::info!(
+        "{} {} {}",
+        term::format::bold(&patch.title),
+        term::format::dim(format!("R{}", from)),
+        term::format::dim(format!("→ R{}", to)),
+    );
+    if old.base != new.base {
+        term::info!(
+            "{}",
+            term::format::dim(format!(
+                "base changed from {} to {}",
+                common::fmt::oid(&*old.base),
+                common::fmt::oid(&*new.base)
+            ))
+        );
+    }
+    term::blank();
+
+    for item in interdiff {
+        match item {
+            Interdiff::Unchanged { old, new } => {
+                term::info!(
+                    "{} {} {} {}",
+                    term::format::dim(short(&old)),
+                    term::format::dim("="),
+                    term::format::dim(short(&new)),
+                    term::format::dim(summary(&new)),
+                );
+            }
+            Interdiff::Changed { old, new, diff } => {
+                term::info!(
+                    "{} {} {} {}",
+                    term::format::secondary(short(&old)),
+                    term::format::yellow("!"),
+                    term::format::secondary(short(&new)),
+                    summary(&new),
+                );
+                for line in diff {
+                    let line = if line.starts_with('+') {
+                        term::format::positive(line)
+                    } else if line.starts_with('-') {
+                        term::format::negative(line)
+                    } else if line.starts_with("@@") {
+                        term::format::tertiary(line)
+                    } else {
+                        line
+                    };
+                    term::indented(&line);
+                }
+            }
+            Interdiff::Added(new) => {
+                term::info!(
+                    "{} {} {} {}",
+                    term::format::dim("-------"),
+                    term::format::positive(">"),
+                    term::format::positive(short(&new)),
+                    summary(&new),
+                );
+            }
+            Interdiff::Removed(old) => {
+                term::info!(
+                    "{} {} {} {}",
+                    term::format::negative(short(&old)),
+                    term::format::negative("<"),
+                    term::format::dim("-------"),
+                    summary(&old),
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn short(commit: &git::Commit) -> String {
+    common::fmt::oid(&commit.id())
+}
+
+fn summary<'a>(commit: &'a git::Commit) -> &'a str {
+    commit.summary().unwrap_or_default()
+}
@@ -0,0 +1,169 @@
+use std::ffi::OsString;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+use radicle_common as common;
+use radicle_common::args::{Args, Error};
+use radicle_common::cobs::patch::{Patch, PatchId, RevisionIx};
+use radicle_common::mail::{self, COVER_BLURB, COVER_SUBJECT, PATCH_ID_HEADER};
+use radicle_common::{cobs, git, keys, project};
+use radicle_terminal as term;
+
+#[derive(Debug)]
+pub struct Options {
+    pub id: cobs::Identifier,
+    pub revision: Option<RevisionIx>,
+    pub mbox: bool,
+    pub output: Option<PathBuf>,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut id: Option<cobs::Identifier> = None;
+        let mut revision: Option<RevisionIx> = None;
+        let mut mbox = false;
+        let mut output = None;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("help") => {
+                    return Err(Error::Help.into());
+                }
+                Long("revision") | Short('r') => {
+                    let value = parser.value()?;
+                    let ix =
+                        RevisionIx::from_str(value.to_str().unwrap_or_default()).map_err(|_| {
+                            anyhow!("invalid revision number `{}`", value.to_string_lossy())
+                        })?;
+                    revision = Some(ix);
+                }
+                Long("mbox") => {
+                    mbox = true;
+                }
+                Long("output") | Short('o') => {
+                    output = Some(PathBuf::from(parser.value()?));
+                }
+                Value(val) if id.is_none() => {
+                    let val = val
+                        .to_str()
+                        .ok_or_else(|| anyhow!("patch id specified is not UTF-8"))?;
+
+                    id = Some(
+                        cobs::Identifier::from_str(val)
+                            .map_err(|_| anyhow!("invalid patch id '{}'", val))?,
+                    );
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+
+        Ok((
+            Options {
+                id: id.ok_or_else(|| anyhow!("a patch id to export must be provided"))?,
+                revision,
+                mbox,
+                output,
+            },
+            vec![],
+        ))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let (urn, repo) = project::cwd()
+        .map_err(|_| anyhow!("this command must be run in the context of a project"))?;
+    let profile = ctx.profile()?;
+    let signer = term::signer(&profile)?;
+    let storage = keys::storage(&profile, signer)?;
+    let cobs = cobs::store(&profile, &storage)?;
+    let patches = cobs.patches();
+
+    let (patch_id, mut patch) = patches
+        .resolve::<Patch>(&urn, &options.id)?
+        .ok_or_else(|| anyhow!("couldn't find patch {} locally", options.id))?;
+    patch.author.resolve(&storage).ok();
+
+    let ix = options.revision.unwrap_or_else(|| patch.version());
+    let revision = patch
+        .revisions
+        .get(ix)
+        .ok_or_else(|| anyhow!("revision R{} does not exist", ix))?;
+
+    let monorepo = git::Repository::open_bare(profile.paths().git_dir())?;
+    let commits = common::patch::patch_commits(&monorepo, &*revision.base, &*revision.oid)?;
+    let cwd = std::env::current_dir()?;
+    let range = format!("{}..{}", *revision.base, *revision.oid);
+    let cover = cover_letter(&patch_id, &patch, &*revision.oid, commits.len(), &repo)?;
+
+    // Nb. `format-patch` is run from within storage, so output paths must be absolute.
+    if options.mbox {
+        let output = cwd.join(
+            options
+                .output
+                .unwrap_or_else(|| PathBuf::from(format!("{}.mbox", common::fmt::cob(&patch_id)))),
+        );
+        let series = git::git(
+            profile.paths().git_dir(),
+            ["format-patch", "--stdout", "--numbered", &range],
+        )?;
+        fs::write(&output, format!("{}\n{}", cover, series))?;
+
+        term::success!(
+            "Exported {} commit(s) to {}",
+            commits.len(),
+            term::format::highlight(output.display())
+        );
+    } else {
+        let output =
+            cwd.join(options.output.unwrap_or_else(|| {
+                PathBuf::from(format!("patch-{}", common::fmt::cob(&patch_id)))
+            }));
+        fs::create_dir_all(&output)?;
+
+        let output_dir = output.to_string_lossy();
+        git::git(
+            profile.paths().git_dir(),
+            ["format-patch", "--numbered", "-o", &output_dir, &range],
+        )?;
+        fs::write(output.join("0000-cover-letter.patch"), cover)?;
+
+        term::success!(
+            "Exported {} commit(s) and a cover letter to {}",
+            commits.len(),
+            term::format::highlight(output.display())
+        );
+    }
+
+    Ok(())
+}
+
+/// Build an mbox-formatted cover letter, from the patch title and description.
+fn cover_letter(
+    patch_id: &PatchId,
+    patch: &Patch,
+    head: &git::Oid,
+    count: usize,
+    repo: &git::Repository,
+) -> anyhow::Result<String> {
+    // Nb. The letter is sent by the local user, who may not be the patch author.
+    let signature = repo.signature()?;
+    let sender = String::from_utf8_lossy(signature.name_bytes()).into_owned();
+    let from = match signature.email() {
+        Some(email) => format!("{} <{}>", sender, email),
+        None => sender.clone(),
+    };
+    // Nb. The date on the first line is a fixed marker, as used by `git format-patch`.
+    let template = format!(
+        "From {} Mon Sep 17 00:00:00 2001\nFrom: {}\nSubject: [PATCH 0/{}] {}\n{}: {}\n\n{}",
+        head, from, count, COVER_SUBJECT, PATCH_ID_HEADER, patch_id, COVER_BLURB
+    );
+    let blurb = mail::cover_blurb(&sender, &patch.author.name(), patch.description().trim());
+
+    Ok(mail::fill_cover_letter(&template, &patch.title, &blurb))
+}
@@ -0,0 +1,157 @@
+//! Patch list filters.
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+use librad::PeerId;
+
+use radicle_common::cobs::patch::Patch;
+use radicle_common::cobs::{Label, Timestamp};
+use radicle_common::git;
+
+/// Filter patches by state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateFilter {
+    /// Proposed patches that haven't been merged.
+    Open,
+    /// Patches with a merged latest revision.
+    Merged,
+    /// Archived patches.
+    Closed,
+    /// All patches.
+    All,
+}
+
+impl FromStr for StateFilter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "open" => Ok(Self::Open),
+            "merged" => Ok(Self::Merged),
+            "closed" => Ok(Self::Closed),
+            "all" => Ok(Self::All),
+            _ => Err(anyhow!(
+                "invalid state '{}', expected one of `open`, `merged`, `closed` or `all`",
+                s
+            )),
+        }
+    }
+}
+
+/// Patch list filter.
+#[derive(Debug, Default)]
+pub struct Filter {
+    /// Only show patches by this peer.
+    pub author: Option<PeerId>,
+    /// Only show your own patches. Resolved to an `author` filter once the profile is loaded.
+    pub mine: bool,
+    /// Only show patches in this state. If not set, proposed and closed patches are shown.
+    pub state: Option<StateFilter>,
+    /// Only show patches whose head is contained in this local branch.
+    pub branch: Option<String>,
+    /// Only show patches with all of these labels.
+    pub labels: Vec<Label>,
+}
+
+impl Filter {
+    pub fn matches(&self, patch: &Patch, repo: Option<&git::Repository>) -> anyhow::Result<bool> {
+        // Retracted patches are never shown.
+        if patch.is_retracted() {
+            return Ok(false);
+        }
+        if let Some(author) = &self.author {
+            if &patch.author.peer != author {
+                return Ok(false);
+            }
+        }
+
+        if !self.labels.iter().all(|l| patch.labels.contains(l)) {
+            return Ok(false);
+        }
+
+        let state = match self.state {
+            None => patch.is_proposed() || patch.is_archived(),
+            Some(StateFilter::Open) => patch.is_proposed() && !patch.is_merged(),
+            Some(StateFilter::Merged) => patch.is_merged(),
+            Some(StateFilter::Closed) => patch.is_archived(),
+            Some(StateFilter::All) => true,
+        };
+        if !state {
+            return Ok(false);
+        }
+
+        if let Some(name) = &self.branch {
+            let repo = repo.ok_or_else(|| anyhow!("`--branch` requires a working copy"))?;
+            let branch = repo.find_branch(name, git::BranchType::Local)?;
+            let tip = branch
+                .get()
+                .target()
+                .ok_or_else(|| anyhow!("branch '{}' has no target", name))?;
+            let head = **patch.head();
+
+            if tip != head && !repo.graph_descendant_of(tip, head)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Patch list sort order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sort {
+    /// By creation time.
+    Created,
+    /// By time of the latest revision, review or merge.
+    Updated,
+    /// By author name.
+    Author,
+}
+
+impl Default for Sort {
+    fn default() -> Self {
+        Self::Created
+    }
+}
+
+impl FromStr for Sort {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "created" => Ok(Self::Created),
+            "updated" => Ok(Self::Updated),
+            "author" => Ok(Self::Author),
+            _ => Err(anyhow!(
+                "invalid sort order '{}', expected one of `created`, `updated` or `author`",
+                s
+            )),
+        }
+    }
+}
+
+impl Sort {
+    /// Sort patches in place. Authors should be resolved to sort by author name.
+    pub fn sort<K>(&self, patches: &mut [(K, Patch)]) {
+        match self {
+            Self::Created => patches.sort_by_key(|(_, p)| p.timestamp),
+            Self::Updated => patches.sort_by_key(|(_, p)| updated(p)),
+            Self::Author => patches.sort_by_key(|(_, p)| p.author.name()),
+        }
+    }
+}
+
+/// Time of the latest activity on a patch.
+fn updated(patch: &Patch) -> Timestamp {
+    patch
+        .revisions
+        .iter()
+        .flat_map(|r| {
+            std::iter::once(r.timestamp)
+                .chain(r.reviews.values().map(|r| r.timestamp))
+                .chain(r.merges.iter().map(|m| m.timestamp))
+        })
+        .max()
+        .unwrap_or(patch.timestamp)
+}
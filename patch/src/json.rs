@@ -0,0 +1,59 @@
+//! Machine-readable patch output.
+use serde::Serialize;
+
+use librad::git_ext::Oid;
+
+use radicle_common::cobs::patch::{Patch, PatchId, State};
+use radicle_common::json::{json, Value};
+use radicle_common::schema;
+
+/// Version of the JSON output schema.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// JSON representation of a patch.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchJson {
+    pub id: String,
+    pub title: String,
+    pub author: String,
+    pub state: State,
+    pub base: Oid,
+    pub head: Oid,
+    pub revisions: usize,
+}
+
+impl PatchJson {
+    pub fn new(id: &PatchId, patch: &Patch) -> Self {
+        let (_, revision) = patch.latest();
+
+        Self {
+            id: id.to_string(),
+            title: patch.title.clone(),
+            author: patch.author.peer.default_encoding(),
+            state: patch.state,
+            base: revision.base,
+            head: revision.oid,
+            revisions: patch.revisions.len(),
+        }
+    }
+
+    /// JSON Schema of a patch.
+    pub fn schema() -> Value {
+        schema::object(&[
+            ("id", schema::string("Patch identifier")),
+            ("title", schema::string("Patch title")),
+            ("author", schema::string("Peer ID of the patch author")),
+            (
+                "state",
+                json!({ "type": "string", "enum": ["draft", "proposed", "archived", "retracted"] }),
+            ),
+            ("base", schema::string("Base commit of the latest revision")),
+            ("head", schema::string("Head commit of the latest revision")),
+            (
+                "revisions",
+                json!({ "type": "integer", "minimum": 1, "description": "Number of revisions" }),
+            ),
+        ])
+    }
+}
@@ -0,0 +1,93 @@
+use std::ffi::OsString;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+use radicle_common as common;
+use radicle_common::args::{Args, Error};
+use radicle_common::cobs::patch::Patch;
+use radicle_common::cobs::Label;
+use radicle_common::{cobs, keys, project};
+use radicle_terminal as term;
+
+#[derive(Debug)]
+pub struct Options {
+    pub id: cobs::Identifier,
+    pub labels: Vec<Label>,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut id: Option<cobs::Identifier> = None;
+        let mut labels = Vec::new();
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("help") => {
+                    return Err(Error::Help.into());
+                }
+                Value(val) if id.is_none() => {
+                    let val = val
+                        .to_str()
+                        .ok_or_else(|| anyhow!("patch id specified is not UTF-8"))?;
+
+                    id = Some(
+                        cobs::Identifier::from_str(val)
+                            .map_err(|_| anyhow!("invalid patch id '{}'", val))?,
+                    );
+                }
+                Value(val) => {
+                    let val = val.to_string_lossy();
+                    let label =
+                        Label::new(val.as_ref()).map_err(|_| anyhow!("invalid label '{}'", val))?;
+
+                    labels.push(label);
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+        if labels.is_empty() {
+            anyhow::bail!("at least one label must be provided");
+        }
+
+        Ok((
+            Options {
+                id: id.ok_or_else(|| anyhow!("a patch id must be provided"))?,
+                labels,
+            },
+            vec![],
+        ))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let (urn, _) = project::cwd()
+        .map_err(|_| anyhow!("this command must be run in the context of a project"))?;
+    let profile = ctx.profile()?;
+    let signer = term::signer(&profile)?;
+    let storage = keys::storage(&profile, signer)?;
+    let cobs = cobs::store(&profile, &storage)?;
+    let patches = cobs.patches();
+
+    let (patch_id, _) = patches
+        .resolve::<Patch>(&urn, &options.id)?
+        .ok_or_else(|| anyhow!("couldn't find patch {} locally", options.id))?;
+
+    patches.label(&urn, &patch_id, &options.labels)?;
+
+    term::success!(
+        "Patch {} labeled {}",
+        term::format::highlight(common::fmt::cob(&patch_id)),
+        options
+            .labels
+            .iter()
+            .map(|l| term::format::secondary(l.name()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    Ok(())
+}
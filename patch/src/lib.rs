@@ -1,28 +1,54 @@
 #![allow(clippy::or_fun_call)]
 #![allow(clippy::too_many_arguments)]
 #![allow(clippy::for_kv_map)]
+use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
 use std::ffi::OsString;
+use std::io::{self, Write as _};
 use std::path::Path;
 use std::str::FromStr;
+use std::{thread, time};
 
 use anyhow::anyhow;
 
-use common::cobs::patch::Verdict;
+use common::cobs::patch::{Check, CheckState, ReviewCount, State, Verdict};
 use librad::git::identities::local::LocalIdentity;
 use librad::git::storage::ReadOnlyStorage;
 use librad::git::Storage;
+use librad::git::Urn;
 use librad::git_ext::{Oid, RefLike};
 use librad::profile::Profile;
+use librad::PeerId;
 
 use radicle_common as common;
+use radicle_common::allowlist::Allowlist;
 use radicle_common::args::{Args, Error, Help};
 use radicle_common::cobs::patch::{MergeTarget, Patch, PatchId, PatchStore};
+use radicle_common::store::Store;
 use radicle_common::tokio;
 use radicle_common::{cobs, git, keys, patch, project, sync};
 use radicle_terminal as term;
+use radicle_terminal::format::{labeled, Indicator};
 use radicle_terminal::patch::Comment;
 
+mod apply;
+mod assign;
+mod check;
+mod cosign;
+mod delete;
+mod diff;
+mod export;
+mod filter;
+mod json;
+mod label;
+mod lifecycle;
+pub mod show;
+mod transfer;
+
+use filter::{Filter, Sort, StateFilter};
+
+use json::{PatchJson, SCHEMA_VERSION};
+
 pub const HELP: Help = Help {
     name: "patch",
     description: env!("CARGO_PKG_DESCRIPTION"),
@@ -30,23 +56,130 @@ pub const HELP: Help = Help {
     usage: r#"
 Usage
 
-    rad patch [<option>...]
+    rad patch [<option>...] [<revspec>]
+    rad patch propose [<option>...] [<revspec>]
+    rad patch show <id> [--all] [--stat] [--json] [--schema]
+    rad patch diff <id> [--revisions <n>..<m>]
+    rad patch export <id> [--revision <number>] [--mbox] [--output <path>]
+    rad patch apply <mbox|dir> [--branch <name>] [--[no-]push]
+    rad patch close <id> [--reason <text>]
+    rad patch reopen <id>
+    rad patch delete <id> [--project <urn>] [--no-confirm]
+    rad patch label <id> <label>...
+    rad patch assign <id> <peer-id>...
+    rad patch co-sign <id> [--name <name>] [--email <email>]
+    rad patch transfer <id> <peer-id>
+    rad patch check <id> --state <pass|fail> [--url <link>] [--name <name>] [--revision <number>]
+    rad patch review <id> [--accept|--reject|--request-changes] [--comment <string>] [<option>...]
+    rad patch merge <id> [--revision <number>] [--[no-]sync] [--no-fetch] [--no-confirm]
 
 Create options
 
+    A patch is created from the current branch head, unless a revision or range
+    of the current branch is given, eg. `HEAD~1` or `HEAD~2..HEAD`. The start of
+    a range must be on the base branch.
+
+    Creating a patch can be split in two steps: `--push-only` publishes the
+    patch head, eg. to back it up or to let CI run on it first, and `propose`
+    later creates the patch from the published head, without pushing.
+
+    Once published, a branch without an upstream is set up to track its
+    counterpart on the `rad` remote, eg. `rad/<branch>`.
+
     -u, --update [<id>]        Update an existing patch (default: no)
+        --base <branch>        Branch to target, optionally of a given peer, eg. `<peer-id>/<branch>`
+                               (default: the project's default branch)
         --[no-]sync            Sync patch to seed (default: sync)
         --[no-]push            Push patch head to storage (default: true)
+        --push-only            Push the patch head to storage and seeds, without creating the patch
+        --dry-run              Show what the patch would look like, without pushing or creating it
+        --force                Create a new patch, even if one already exists for the current branch
+        --all                  List all commits of the patch, instead of summarizing them
+        --stat                 Show the diffstat of each commit of the patch
     -m, --message [<string>]   Provide a comment message to the patch or revision (default: prompt)
         --no-message           Leave the patch or revision comment message blank
 
+Diff options
+
+        --revisions <n>..<m>   Revisions to compare, eg. `0..2` (default: the last two)
+
+Export options
+
+    -r, --revision <number>    Revision number to export, defaults to the latest
+        --mbox                 Export to a single mbox file, instead of one file per commit
+    -o, --output <path>        Output directory, or file with `--mbox` (default: `patch-<id>`)
+
+Apply options
+
+    -b, --branch <name>        Branch to create for the patch (default: `patch/<title>`)
+        --[no-]push            Push the branch to storage (default: true)
+
+Close options
+
+        --reason <text>        Explain why the patch is being closed, as a comment
+
+Delete options
+
+        --project <urn>        Project of the patch (default: the current one)
+        --no-confirm           Don't ask for confirmation before deleting
+
+Co-sign options
+
+        --name <name>          Name to use in the `Co-authored-by` trailer (default: git `user.name`)
+        --email <email>        Email to use in the `Co-authored-by` trailer (default: git `user.email`)
+
+Check options
+
+        --state <state>        Outcome of the check: pass or fail
+        --url <link>           Link to the check details, eg. a CI job
+        --name <name>          Name of the check (default: ci)
+    -r, --revision <number>    Revision number that was checked, defaults to the latest
+
+Review options
+
+    -r, --revision <number>    Revision number to review, defaults to the latest
+        --accept               Accept the patch
+        --reject               Reject the patch
+        --request-changes      Ask the author for changes, until a new revision is published
+    -c, --comment <string>     Provide a comment with the review (default: prompt)
+        --no-message           Don't provide a comment with the review
+        --no-checklist         Don't go through the project's review checklist
+
+Merge options
+
+    -r, --revision <number>    Revision number to merge, defaults to the latest
+        --[no-]sync            Push the merged branch and sync to seeds (default: no-sync)
+        --no-fetch             Don't fetch the patch from seeds before merging
+        --no-confirm           Don't ask for confirmation before merging
+
 Options
 
     -l, --list                 List all patches (default: false)
+        --json                 Output patches as JSON, with `--list` or `show`
+        --schema               Print the JSON Schema of the `--json` output, with `--list` or `show`
+        --unsolicited          Also list patches from peers not on the collaborator allowlist
+        --author <peer-id>     Only list patches by the given peer
+        --mine                 Only list your own patches
+        --org <urn>            List open patches of all projects delegated to the given identity,
+                               eg. an org's, fetching them from seeds (implies `--list`)
+        --state <state>        Only list patches in the given state: open, merged, closed or all
+        --branch <name>        Only list patches whose head is on the given local branch
+        --label <label>        Only list patches with the given label (may be repeated)
+        --sort <order>         Sort patches by: created, updated or author (default: created)
+        --reverse              Reverse the sort order
+        --limit <n>            Only list the first <n> patches
+        --no-pager             Don't page the list through `$PAGER`
+        --watch                Keep the list on screen, and refresh it when patches change
+        --interval <secs>      With `--watch`, fetch from seeds every <secs> seconds (default: 60)
+        --full                 Don't truncate patch titles to fit the terminal width
+    -v, --verbose              Show absolute dates and times, and the output of git commands
         --help                 Print help
 "#,
 };
 
+/// How often storage is checked for changes, with `--watch`.
+pub const WATCH_INTERVAL: time::Duration = time::Duration::from_secs(1);
+
 pub const PATCH_MSG: &str = r#"
 <!--
 Please enter a patch message for your changes. An empty
@@ -56,6 +189,9 @@ The first line is the patch title. The patch description
 follows, and must be separated with a blank line, just
 like a commit message. Markdown is supported in the title
 and description.
+
+To close issues when the patch is merged, add a trailer
+to the description, eg. `Closes: <issue-id>`.
 -->
 "#;
 
@@ -79,33 +215,270 @@ impl Default for Update {
     }
 }
 
+/// Branch a patch is based on, eg. `release/1.0` or `<peer-id>/release/1.0`.
+#[derive(Debug, Clone)]
+pub struct Base {
+    /// Peer whose branch should be targeted. All tracked peers if `None`.
+    pub peer: Option<PeerId>,
+    pub branch: RefLike,
+}
+
+impl FromStr for Base {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((peer, branch)) = s.split_once('/') {
+            if let Ok(peer) = PeerId::from_str(peer) {
+                return Ok(Self {
+                    peer: Some(peer),
+                    branch: RefLike::try_from(branch)?,
+                });
+            }
+        }
+        Ok(Self {
+            peer: None,
+            branch: RefLike::try_from(s)?,
+        })
+    }
+}
+
 #[derive(Default, Debug)]
 pub struct Options {
     pub list: bool,
+    pub json: bool,
+    pub unsolicited: bool,
+    pub filter: Filter,
+    pub org: Option<Urn>,
+    pub sort: Sort,
+    pub reverse: bool,
+    pub limit: Option<usize>,
+    pub pager: bool,
+    pub watch: Option<time::Duration>,
+    pub full: bool,
     pub verbose: bool,
     pub sync: bool,
     pub push: bool,
+    pub push_only: bool,
+    pub dry_run: bool,
+    pub force: bool,
+    pub all: bool,
+    pub stat: bool,
     pub update: Update,
+    pub base: Option<Base>,
+    pub range: Option<String>,
     pub message: Comment,
+    pub subcommand: Option<Subcommand>,
+}
+
+/// Patch subcommands, eg. `rad patch review`.
+#[derive(Debug)]
+pub enum Subcommand {
+    Show(show::Options),
+    Diff(diff::Options),
+    Export(export::Options),
+    Apply(apply::Options),
+    Lifecycle(lifecycle::Options),
+    Delete(delete::Options),
+    Label(label::Options),
+    Assign(assign::Options),
+    CoSign(cosign::Options),
+    Transfer(transfer::Options),
+    Check(check::Options),
+    Review(rad_review::Options),
+    Merge(rad_merge::Options),
 }
 
 impl Args for Options {
     fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        if let Some((first, rest)) = args.split_first() {
+            let (subcommand, unparsed) = match first.to_string_lossy().as_ref() {
+                "show" => {
+                    let (options, unparsed) = show::Options::from_args(rest.to_vec())?;
+                    (Subcommand::Show(options), unparsed)
+                }
+                "diff" => {
+                    let (options, unparsed) = diff::Options::from_args(rest.to_vec())?;
+                    (Subcommand::Diff(options), unparsed)
+                }
+                "export" => {
+                    let (options, unparsed) = export::Options::from_args(rest.to_vec())?;
+                    (Subcommand::Export(options), unparsed)
+                }
+                "apply" => {
+                    let (options, unparsed) = apply::Options::from_args(rest.to_vec())?;
+                    (Subcommand::Apply(options), unparsed)
+                }
+                "close" | "reopen" => {
+                    let (options, unparsed) = lifecycle::Options::from_args(args)?;
+                    (Subcommand::Lifecycle(options), unparsed)
+                }
+                "delete" => {
+                    let (options, unparsed) = delete::Options::from_args(rest.to_vec())?;
+                    (Subcommand::Delete(options), unparsed)
+                }
+                "label" => {
+                    let (options, unparsed) = label::Options::from_args(rest.to_vec())?;
+                    (Subcommand::Label(options), unparsed)
+                }
+                "assign" => {
+                    let (options, unparsed) = assign::Options::from_args(rest.to_vec())?;
+                    (Subcommand::Assign(options), unparsed)
+                }
+                "co-sign" => {
+                    let (options, unparsed) = cosign::Options::from_args(rest.to_vec())?;
+                    (Subcommand::CoSign(options), unparsed)
+                }
+                "transfer" => {
+                    let (options, unparsed) = transfer::Options::from_args(rest.to_vec())?;
+                    (Subcommand::Transfer(options), unparsed)
+                }
+                "check" => {
+                    let (options, unparsed) = check::Options::from_args(rest.to_vec())?;
+                    (Subcommand::Check(options), unparsed)
+                }
+                "review" => {
+                    let (options, unparsed) = rad_review::Options::from_args(rest.to_vec())?;
+                    (Subcommand::Review(options), unparsed)
+                }
+                "merge" => {
+                    let (options, unparsed) = rad_merge::Options::from_args(rest.to_vec())?;
+                    (Subcommand::Merge(options), unparsed)
+                }
+                "propose" => {
+                    let (mut options, unparsed) = Self::from_flags(rest.to_vec())?;
+
+                    if options.list || options.json || options.push_only {
+                        anyhow::bail!(
+                            "`propose` can't be used with `--list`, `--json` or `--push-only`"
+                        );
+                    }
+                    // The patch head is expected to have been published already.
+                    options.push = false;
+
+                    return Ok((options, unparsed));
+                }
+                _ => return Self::from_flags(args),
+            };
+
+            return Ok((
+                Options {
+                    subcommand: Some(subcommand),
+                    ..Options::default()
+                },
+                unparsed,
+            ));
+        }
+        Self::from_flags(args)
+    }
+}
+
+impl Options {
+    /// Parse the options of the default (create or list) operation.
+    fn from_flags(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
         use lexopt::prelude::*;
 
         let mut parser = lexopt::Parser::from_args(args);
         let mut list = false;
+        let mut json = false;
+        let mut unsolicited = false;
+        let mut filter = Filter::default();
+        let mut org = None;
+        let mut sort = Sort::default();
+        let mut reverse = false;
+        let mut limit = None;
+        let mut pager = true;
+        let mut watch = false;
+        let mut interval = time::Duration::from_secs(60);
+        let mut full = false;
         let mut verbose = false;
         let mut sync = true;
         let mut message = Comment::default();
         let mut push = true;
+        let mut push_only = false;
+        let mut dry_run = false;
+        let mut force = false;
+        let mut all = false;
+        let mut stat = false;
         let mut update = Update::default();
+        let mut base = None;
+        let mut range = None;
 
         while let Some(arg) = parser.next()? {
             match arg {
                 Long("list") | Short('l') => {
                     list = true;
                 }
+                Long("json") => {
+                    json = true;
+                }
+                Long("schema") => {
+                    let schema =
+                        common::json::json!({ "type": "array", "items": PatchJson::schema() });
+                    let schema = common::schema::document("patch", SCHEMA_VERSION, schema);
+
+                    return Err(Error::Schema(schema).into());
+                }
+                Long("unsolicited") => {
+                    unsolicited = true;
+                }
+                Long("author") => {
+                    let val = parser.value()?;
+                    let val = val.to_string_lossy();
+                    let peer = PeerId::from_str(&val)
+                        .map_err(|_| anyhow!("invalid <peer-id> '{}'", val))?;
+
+                    filter.author = Some(peer);
+                }
+                Long("mine") => {
+                    filter.mine = true;
+                }
+                Long("org") => {
+                    let val = parser.value()?;
+                    let val = val.to_string_lossy();
+
+                    org = Some(Urn::from_str(&val).map_err(|_| anyhow!("invalid URN '{}'", val))?);
+                    list = true;
+                }
+                Long("state") => {
+                    let val = parser.value()?;
+                    let state = StateFilter::from_str(&val.to_string_lossy())?;
+
+                    filter.state = Some(state);
+                }
+                Long("branch") => {
+                    filter.branch = Some(parser.value()?.to_string_lossy().into());
+                }
+                Long("label") => {
+                    let val = parser.value()?;
+                    let val = val.to_string_lossy();
+                    let label = cobs::Label::new(val.as_ref())
+                        .map_err(|_| anyhow!("invalid label '{}'", val))?;
+
+                    filter.labels.push(label);
+                }
+                Long("sort") => {
+                    let val = parser.value()?;
+                    sort = Sort::from_str(&val.to_string_lossy())?;
+                }
+                Long("reverse") => {
+                    reverse = true;
+                }
+                Long("limit") => {
+                    limit = Some(common::args::parse_value("limit", parser.value()?)?);
+                }
+                Long("no-pager") => {
+                    pager = false;
+                }
+                Long("watch") => {
+                    watch = true;
+                }
+                Long("full") => {
+                    full = true;
+                }
+                Long("interval") => {
+                    let secs: u64 = common::args::parse_value("interval", parser.value()?)?;
+                    interval = time::Duration::from_secs(secs);
+                }
                 Long("verbose") | Short('v') => {
                     verbose = true;
                 }
@@ -129,6 +502,13 @@ impl Args for Options {
                         update = Update::Any;
                     }
                 }
+                Long("base") => {
+                    let val = parser.value()?;
+                    let val = val.to_string_lossy();
+
+                    base =
+                        Some(Base::from_str(&val).map_err(|_| anyhow!("invalid base '{}'", val))?);
+                }
                 Long("sync") => {
                     sync = true;
                 }
@@ -141,28 +521,103 @@ impl Args for Options {
                 Long("no-push") => {
                     push = false;
                 }
+                Long("push-only") => {
+                    push_only = true;
+                }
+                Long("dry-run") => {
+                    dry_run = true;
+                }
+                Long("force") | Short('f') => {
+                    force = true;
+                }
+                Long("all") => {
+                    all = true;
+                }
+                Long("stat") => {
+                    stat = true;
+                }
                 Long("help") => {
                     return Err(Error::Help.into());
                 }
+                Value(val) if range.is_none() => {
+                    let val = val
+                        .to_str()
+                        .ok_or_else(|| anyhow!("revspec specified is not UTF-8"))?;
+
+                    range = Some(val.to_owned());
+                }
                 _ => return Err(anyhow::anyhow!(arg.unexpected())),
             }
         }
+        if range.is_some() && (list || json) {
+            anyhow::bail!("a revspec can only be given when creating or updating a patch");
+        }
+        if push_only && (list || json || !push) {
+            anyhow::bail!("`--push-only` can't be used with `--list`, `--json` or `--no-push`");
+        }
+        if filter.mine && filter.author.is_some() {
+            anyhow::bail!("`--mine` can't be used with `--author`");
+        }
+        if org.is_some() && (json || watch) {
+            anyhow::bail!("`--org` can't be used with `--json` or `--watch`");
+        }
+        if watch && (!list || json) {
+            anyhow::bail!("`--watch` can only be used with `--list`, without `--json`");
+        }
 
         Ok((
             Options {
                 list,
+                json,
+                unsolicited,
+                filter,
+                org,
+                sort,
+                reverse,
+                limit,
+                pager,
+                watch: if watch { Some(interval) } else { None },
+                full,
                 sync,
                 message,
                 push,
+                push_only,
+                dry_run,
+                force,
+                all,
+                stat,
                 update,
+                base,
+                range,
                 verbose,
+                subcommand: None,
             },
             vec![],
         ))
     }
 }
 
-pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+pub fn run(mut options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    match options.subcommand {
+        Some(Subcommand::Show(options)) => return show::run(options, ctx),
+        Some(Subcommand::Diff(options)) => return diff::run(options, ctx),
+        Some(Subcommand::Export(options)) => return export::run(options, ctx),
+        Some(Subcommand::Apply(options)) => return apply::run(options, ctx),
+        Some(Subcommand::Lifecycle(options)) => return lifecycle::run(options, ctx),
+        Some(Subcommand::Delete(options)) => return delete::run(options, ctx),
+        Some(Subcommand::Label(options)) => return label::run(options, ctx),
+        Some(Subcommand::Assign(options)) => return assign::run(options, ctx),
+        Some(Subcommand::CoSign(options)) => return cosign::run(options, ctx),
+        Some(Subcommand::Transfer(options)) => return transfer::run(options, ctx),
+        Some(Subcommand::Check(options)) => return check::run(options, ctx),
+        Some(Subcommand::Review(options)) => return rad_review::run(options, ctx),
+        Some(Subcommand::Merge(options)) => return rad_merge::run(options, ctx),
+        None => {}
+    }
+
+    if let Some(org) = options.org.clone() {
+        return list_org(&org, options, ctx);
+    }
     let (urn, repo) = project::cwd()
         .map_err(|_| anyhow!("this command must be run in the context of a project"))?;
 
@@ -172,6 +627,9 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
     let project = project::get(&storage, &urn)?
         .ok_or_else(|| anyhow!("couldn't load project {} from local state", urn))?;
 
+    if options.filter.mine {
+        options.filter.author = Some(*storage.peer_id());
+    }
     if options.list {
         list(&storage, Some(repo), &profile, &project, options)?;
     } else {
@@ -188,63 +646,396 @@ fn list(
     project: &project::Metadata,
     options: Options,
 ) -> anyhow::Result<()> {
+    // Syncing writes progress to the terminal, which would corrupt JSON output.
+    if options.sync && !options.json {
+        fetch(project, profile)?;
+    }
+
+    let cobs = cobs::store(profile, storage)?;
+    let patches = cobs.patches();
+
+    if let Some(interval) = options.watch {
+        return watch(
+            storage, repo, profile, project, &patches, interval, &options,
+        );
+    }
+    let proposed = select(&patches, storage, repo.as_ref(), project, &options)?;
+
+    if options.json {
+        let allowlist = Allowlist::load(profile)?;
+        let json = proposed
+            .iter()
+            .filter(|(_, p)| options.unsolicited || allowlist.allows(project, &p.author.peer))
+            .map(|(id, p)| PatchJson::new(id, p))
+            .collect::<Vec<_>>();
+
+        println!("{}", common::json::to_string_pretty(&json)?);
+
+        return Ok(());
+    }
+    // Nb. Once paging, standard output is no longer the terminal.
+    let width = term::columns();
+    let _pager = if options.pager {
+        term::pager::start()
+    } else {
+        None
+    };
+
+    let result = render(
+        &mut io::stdout(),
+        width,
+        proposed,
+        &HashSet::new(),
+        &cobs.whoami,
+        storage,
+        &repo,
+        profile,
+        project,
+        &options,
+    );
+
+    match result {
+        // The user quit the pager before all patches were listed.
+        Err(err) if term::pager::is_broken_pipe(&err) => Ok(()),
+        result => result,
+    }
+}
+
+/// Fetch the project from its seeds.
+fn fetch(project: &project::Metadata, profile: &Profile) -> anyhow::Result<()> {
+    let rt = tokio::runtime::Runtime::new()?;
+
+    term::sync::sync(
+        project.urn.clone(),
+        sync::seeds(profile)?,
+        sync::Mode::Fetch,
+        profile,
+        term::signer(profile)?,
+        &rt,
+    )?;
+
+    Ok(())
+}
+
+/// List the open patches of every project delegated to the given identity, eg. an org's.
+/// Projects of the identity that seeds know of are fetched into storage first.
+fn list_org(org: &Urn, mut options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let profile = ctx.profile()?;
+    let signer = term::signer(&profile)?;
+    let storage = keys::storage(&profile, signer.clone())?;
+
     if options.sync {
         let rt = tokio::runtime::Runtime::new()?;
-
-        term::sync::sync(
-            project.urn.clone(),
-            sync::seeds(profile)?,
-            sync::Mode::Fetch,
-            profile,
-            term::signer(profile)?,
-            &rt,
-        )?;
+        let seeds = sync::seeds(&profile)?;
+
+        for seed in seeds.iter() {
+            let host = seed
+                .addrs
+                .rsplit_once(':')
+                .map(|(host, _)| host)
+                .unwrap_or(seed.addrs.as_str());
+            let url = common::seed::api_url(host)?;
+
+            // Seeds that can't be queried are skipped, the projects may be on others.
+            let urns = match common::seed::get_delegate_projects(url, org) {
+                Ok(urns) => urns,
+                Err(err) => {
+                    term::warning(&format!("Couldn't query seed {}: {}", host, err));
+                    continue;
+                }
+            };
+            for urn in urns {
+                if project::get(&storage, &urn)?.is_none() {
+                    term::sync::sync(
+                        urn,
+                        seeds.clone(),
+                        sync::Mode::Fetch,
+                        &profile,
+                        signer.clone(),
+                        &rt,
+                    )?;
+                }
+            }
+        }
+    }
+    // Unless a state is given, only open patches are of interest.
+    if options.filter.state.is_none() {
+        options.filter.state = Some(StateFilter::Open);
+    }
+    if options.filter.mine {
+        options.filter.author = Some(*storage.peer_id());
     }
 
-    let cobs = cobs::store(profile, storage)?;
+    let monorepo = git::Repository::open_bare(profile.paths().git_dir())?;
+    let cobs = cobs::store(&profile, &storage)?;
     let patches = cobs.patches();
-    let proposed = patches.proposed(&project.urn)?;
+    let mut projects = project::list(&storage)?
+        .into_iter()
+        .map(|(_, meta, _)| meta)
+        .filter(|meta| meta.is_delegated_to(org))
+        .collect::<Vec<_>>();
+    projects.sort_by(|a, b| a.name.cmp(&b.name));
+
+    if projects.is_empty() {
+        anyhow::bail!("no project delegated to {} was found", org);
+    }
+    let mut total = 0;
+
+    for project in &projects {
+        if options.sync {
+            // Keep going if a project can't be fetched, its local patches are still listed.
+            if let Err(err) = fetch(project, &profile) {
+                term::warning(&format!("Fetch of {} failed: {}", project.name, err));
+            }
+        }
+        let mut proposed = select(&patches, &storage, None, project, &options)?;
+        if proposed.is_empty() {
+            continue;
+        }
+        total += proposed.len();
+
+        term::blank();
+        term::print(&term::format::badge_secondary(&project.name));
+
+        for (id, patch) in &mut proposed {
+            term::blank();
+            print(
+                &mut io::stdout(),
+                term::columns(),
+                &patches.whoami,
+                id,
+                patch,
+                project,
+                &monorepo,
+                &None,
+                &storage,
+                false,
+                &options,
+            )?;
+        }
+    }
+    term::blank();
+
+    if total == 0 {
+        term::print(&term::format::italic("Nothing to show."));
+        term::blank();
+    } else {
+        term::info!(
+            "{} patch(es) across {} project(s)",
+            term::format::highlight(total),
+            projects.len()
+        );
+    }
+    Ok(())
+}
+
+/// Patches to list, filtered and sorted according to the options.
+fn select(
+    patches: &PatchStore,
+    storage: &Storage,
+    repo: Option<&git::Repository>,
+    project: &project::Metadata,
+    options: &Options,
+) -> anyhow::Result<Vec<(PatchId, Patch)>> {
+    let mut proposed = Vec::new();
+
+    for (id, patch) in patches.all(&project.urn)? {
+        if options.filter.matches(&patch, repo)? {
+            proposed.push((id, patch));
+        }
+    }
+
+    if options.sort == Sort::Author {
+        for (_, patch) in &mut proposed {
+            patch.author.resolve(storage).ok();
+        }
+    }
+    options.sort.sort(&mut proposed);
+
+    if options.reverse {
+        proposed.reverse();
+    }
+    if let Some(limit) = options.limit {
+        proposed.truncate(limit);
+    }
+    Ok(proposed)
+}
+
+/// Keep the patch list on screen, redrawing it when the project's refs change in storage,
+/// and fetching from seeds every `interval`. Patches that changed since the previous
+/// redraw are highlighted.
+fn watch(
+    storage: &Storage,
+    repo: Option<git::Repository>,
+    profile: &Profile,
+    project: &project::Metadata,
+    patches: &PatchStore,
+    interval: time::Duration,
+    options: &Options,
+) -> anyhow::Result<()> {
+    let monorepo = git::Repository::open_bare(profile.paths().git_dir())?;
+    let mut snapshot = None;
+    let mut seen: HashMap<PatchId, String> = HashMap::new();
+    let mut fetched = time::Instant::now();
+
+    loop {
+        let refs = sync::refs_snapshot(&monorepo, &project.urn, true)?;
+
+        if snapshot.as_ref() != Some(&refs) {
+            let proposed = select(patches, storage, repo.as_ref(), project, options)?;
+            let mut changed = HashSet::new();
+
+            for (id, patch) in &proposed {
+                let state = common::json::to_string(patch)?;
+                let previous = seen.insert(*id, state.clone());
+
+                // Everything is new on the first redraw, so nothing is highlighted.
+                if snapshot.is_some() && previous.as_ref() != Some(&state) {
+                    changed.insert(*id);
+                }
+            }
+            snapshot = Some(refs);
+
+            term::clear();
+            term::info!(
+                "Watching patches of {}, updated {}. Press Ctrl-C to stop.",
+                term::format::highlight(&project.name),
+                term::format::dim(chrono::Local::now().format("%H:%M:%S"))
+            );
+            render(
+                &mut io::stdout(),
+                term::columns(),
+                proposed,
+                &changed,
+                &patches.whoami,
+                storage,
+                &repo,
+                profile,
+                project,
+                options,
+            )?;
+        }
+        thread::sleep(WATCH_INTERVAL);
+
+        if options.sync && fetched.elapsed() >= interval {
+            // Keep watching if seeds can't be reached, they may be by the next fetch.
+            if let Err(err) = fetch(project, profile) {
+                term::warning(&format!("Fetch failed: {}", err));
+            }
+            fetched = time::Instant::now();
+        }
+    }
+}
+
+/// Print the patch list, highlighting the `changed` patches.
+#[allow(clippy::too_many_arguments)]
+fn render(
+    w: &mut dyn io::Write,
+    width: Option<usize>,
+    proposed: Vec<(PatchId, Patch)>,
+    changed: &HashSet<PatchId>,
+    whoami: &LocalIdentity,
+    storage: &Storage,
+    repo: &Option<git::Repository>,
+    profile: &Profile,
+    project: &project::Metadata,
+    options: &Options,
+) -> anyhow::Result<()> {
     let monorepo = git::Repository::open_bare(profile.paths().git_dir())?;
+    let allowlist = Allowlist::load(profile)?;
 
     // Patches the user authored.
     let mut own = Vec::new();
     // Patches other users authored.
     let mut other = Vec::new();
+    // Patches from peers not on the collaborator allowlist.
+    let mut unsolicited = Vec::new();
+    // Patches that were closed.
+    let mut closed = Vec::new();
 
     for (id, patch) in proposed {
-        if *patch.author.urn() == cobs.whoami.urn() {
+        if patch.is_archived() {
+            closed.push((id, patch));
+        } else if *patch.author.urn() == whoami.urn() {
             own.push((id, patch));
-        } else {
+        } else if allowlist.allows(project, &patch.author.peer) {
             other.push((id, patch));
+        } else {
+            unsolicited.push((id, patch));
         }
     }
-    term::blank();
-    term::print(&term::format::badge_positive("YOU PROPOSED"));
+    let show = |w: &mut dyn io::Write, id: &PatchId, patch: &mut Patch| {
+        print(
+            w,
+            width,
+            whoami,
+            id,
+            patch,
+            project,
+            &monorepo,
+            repo,
+            storage,
+            changed.contains(id),
+            options,
+        )
+    };
+    writeln!(w)?;
+    writeln!(w, "{}", term::format::badge_positive("YOU PROPOSED"))?;
 
     if own.is_empty() {
-        term::blank();
-        term::print(&term::format::italic("Nothing to show."));
+        writeln!(w)?;
+        writeln!(w, "{}", term::format::italic("Nothing to show."))?;
     } else {
         for (id, patch) in &mut own {
-            term::blank();
+            writeln!(w)?;
 
-            print(&cobs.whoami, id, patch, project, &monorepo, &repo, storage)?;
+            show(w, id, patch)?;
         }
     }
-    term::blank();
-    term::print(&term::format::badge_secondary("OTHERS PROPOSED"));
+    writeln!(w)?;
+    writeln!(w, "{}", term::format::badge_secondary("OTHERS PROPOSED"))?;
 
     if other.is_empty() {
-        term::blank();
-        term::print(&term::format::italic("Nothing to show."));
+        writeln!(w)?;
+        writeln!(w, "{}", term::format::italic("Nothing to show."))?;
     } else {
         for (id, patch) in &mut other {
-            term::blank();
+            writeln!(w)?;
 
-            print(&cobs.whoami, id, patch, project, &monorepo, &repo, storage)?;
+            show(w, id, patch)?;
+        }
+    }
+    writeln!(w)?;
+
+    if !closed.is_empty() {
+        writeln!(w, "{}", term::format::badge_primary("CLOSED"))?;
+
+        for (id, patch) in &mut closed {
+            writeln!(w)?;
+
+            show(w, id, patch)?;
+        }
+        writeln!(w)?;
+    }
+
+    if !unsolicited.is_empty() {
+        if options.unsolicited {
+            writeln!(w, "{}", term::format::badge_negative("UNSOLICITED"))?;
+
+            for (id, patch) in &mut unsolicited {
+                writeln!(w)?;
+
+                show(w, id, patch)?;
+            }
+            writeln!(w)?;
+        } else {
+            let tip = format!(
+                "{} unsolicited patch(es) hidden, use `rad patch --list --unsolicited` to show them",
+                unsolicited.len()
+            );
+            writeln!(w, "{}", term::format::tip(tip))?;
         }
     }
-    term::blank();
 
     Ok(())
 }
@@ -275,12 +1066,21 @@ fn update(
         term::format::dim(format!("R{}", current + 1)),
         term::format::secondary(common::fmt::oid(head)),
     );
-    let message = options.message.get(REVISION_MSG);
+    let message = options
+        .message
+        .get(REVISION_MSG, term::patch::Validation::optional())?;
 
     // Difference between the two revisions.
     term::patch::print_commits_ahead_behind(repo, *head, *current_revision.oid)?;
     term::blank();
 
+    if options.dry_run {
+        term::info!(
+            "Dry run: patch {} was not updated.",
+            term::format::highlight(patch_id)
+        );
+        return Ok(());
+    }
     if !term::confirm("Continue?") {
         anyhow::bail!("patch update aborted by user");
     }
@@ -289,11 +1089,15 @@ fn update(
     assert_eq!(new, current + 1);
 
     term::blank();
+    term::interrupt::published(format!("Revision R{} of patch {}", new, patch_id));
     term::success!("Patch {} updated 🌱", term::format::highlight(patch_id));
     term::blank();
 
     if options.sync {
         let rt = tokio::runtime::Runtime::new()?;
+        let _cleanup = term::interrupt::on_interrupt(|| {
+            term::tip!("Run `rad sync` to finish publishing the patch to seeds.");
+        });
 
         term::sync::sync(
             project.urn.clone(),
@@ -325,12 +1129,58 @@ fn create(
     // `HEAD`; This is what we are proposing as a patch.
     let head = repo.head()?;
     let head_oid = head.target().ok_or(anyhow!("invalid HEAD ref; aborting"))?;
-    let head_commit = repo.find_commit(head_oid)?;
     let head_branch = head
         .shorthand()
         .ok_or(anyhow!("cannot create patch from detached head; aborting"))?;
     let head_branch = RefLike::try_from(head_branch)?;
 
+    // If a range is given, propose its end instead of the branch head.
+    let (range_start, head_oid) = match &options.range {
+        Some(spec) => resolve_range(repo, spec, head_oid)?,
+        None => (None, head_oid),
+    };
+    let head_commit = repo.find_commit(head_oid)?;
+
+    // Look for an existing patch before anything is pushed, so that nothing is published
+    // if we stop here.
+    let existing = match &options.update {
+        Update::No if !options.force && !options.push_only => {
+            let mut existing =
+                find_unmerged_on_branch(&head_branch, head_oid, &patches, &project.urn, repo)?;
+
+            if let Some((id, patch)) = existing.pop() {
+                term::warning(&format!(
+                    "Patch {} {} already exists for branch {}",
+                    term::format::tertiary(common::fmt::cob(&id)),
+                    term::format::italic(&patch.title),
+                    term::format::highlight(&head_branch.to_string()),
+                ));
+                term::blank();
+
+                if !existing.is_empty() {
+                    return Err(Error::WithHint {
+                        err: anyhow!("more than one patch exists for the current branch"),
+                        hint: "hint: use `rad patch --update <id>` to update one of them, or `--force` to create a new patch",
+                    }
+                    .into());
+                }
+                if **patch.head() == head_oid {
+                    return Err(Error::WithHint {
+                        err: anyhow!("the patch is already up to date with the current branch"),
+                        hint: "hint: use `--force` to create a new patch anyway",
+                    }
+                    .into());
+                }
+                term::tip!("Use `--force` to create a new patch instead.");
+
+                Some((id, patch))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    };
+
     // Make sure the `HEAD` commit can be found in the monorepo. Otherwise there
     // is no way for anyone to merge this patch.
     let mut spinner = term::spinner(format!(
@@ -338,41 +1188,128 @@ fn create(
         term::format::secondary(common::fmt::oid(&head_oid))
     ));
     if storage.find_object(Oid::from(head_oid))?.is_none() {
-        if !options.push {
+        // Commits of the branch that weren't pushed to its upstream, if it has one.
+        let unpublished = git::upstream_ahead_behind(repo, &head_branch.to_string())
+            .ok()
+            .flatten()
+            .map(|(ahead, _)| ahead)
+            .filter(|ahead| *ahead > 0);
+
+        if options.dry_run {
+            spinner.message(format!(
+                "HEAD ({}) would be pushed to storage",
+                term::format::secondary(common::fmt::oid(&head_oid))
+            ));
+        } else if !options.push {
             spinner.failed();
             term::blank();
 
             return Err(Error::WithHint {
-                err: anyhow!("Current branch head was not found in storage"),
-                hint: "hint: run `git push rad` and try again",
+                err: match unpublished {
+                    Some(n) => anyhow!(
+                        "Current branch head was not found in storage, {} commit(s) are unpublished",
+                        n
+                    ),
+                    None => anyhow!("Current branch head was not found in storage"),
+                },
+                hint: "hint: run `rad patch --push-only` or `git push rad` and try again",
             }
             .into());
+        } else {
+            spinner.message(match unpublished {
+                Some(n) => format!("Pushing {} unpublished commit(s) to storage...", n),
+                None => String::from("Pushing HEAD to storage..."),
+            });
+
+            // Only push the patch branch, not every branch of the working copy.
+            let output = sync::Refs::default()
+                .branch(&head_branch.to_string())
+                .push(Path::new("."))?;
+            term::interrupt::published(format!("Branch {} to storage", head_branch));
+            if options.verbose {
+                spinner.finish();
+                term::blob(output);
+            }
         }
-        spinner.message("Pushing HEAD to storage...");
+    }
+    spinner.finish();
 
-        let output = git::git(Path::new("."), ["push", "rad"])?;
-        if options.verbose {
-            spinner.finish();
-            term::blob(output);
+    if options.push_only {
+        if options.dry_run {
+            term::info!("Dry run: nothing was pushed.");
+            return Ok(());
         }
+        if options.sync {
+            let rt = tokio::runtime::Runtime::new()?;
+            let _cleanup = term::interrupt::on_interrupt(|| {
+                term::tip!("Run `rad sync` to finish publishing the branch to seeds.");
+            });
+
+            term::sync::sync(
+                project.urn.clone(),
+                sync::seeds(profile)?,
+                sync::Mode::Push,
+                profile,
+                term::signer(profile)?,
+                &rt,
+            )?;
+        }
+        term::success!(
+            "Branch {} published at {}",
+            term::format::highlight(&head_branch.to_string()),
+            term::format::secondary(common::fmt::oid(&head_oid))
+        );
+        set_upstream(repo, &head_branch)?;
+        term::tip!("Run `rad patch propose` to create the patch.");
+
+        return Ok(());
     }
-    spinner.finish();
 
     // Determine the merge target for this patch. This can ben any tracked remote's "default"
     // branch, as well as your own (eg. `rad/master`).
     let mut spinner = term::spinner("Analyzing remotes...");
-    let targets = patch::find_merge_targets(&head_oid, storage, project)?;
+    let target_branch = options
+        .base
+        .as_ref()
+        .map(|b| b.branch.clone())
+        .unwrap_or_else(|| RefLike::from(project.default_branch.clone()));
+    let mut targets = patch::find_merge_targets_on(&head_oid, &target_branch, storage, project)?;
+
+    if let Some(peer) = options.base.as_ref().and_then(|b| b.peer) {
+        targets.not_merged.retain(|(info, _)| info.id == peer);
+
+        if targets.not_merged.is_empty() && !targets.merged.iter().any(|info| info.id == peer) {
+            spinner.failed();
+            anyhow::bail!(
+                "branch '{}' of peer {} was not found in storage",
+                target_branch,
+                peer
+            );
+        }
+    }
 
     // eg. `refs/namespaces/<proj>/refs/remotes/<peer>/heads/master`
     let (target_peer, target_oid) = match targets.not_merged.as_slice() {
+        [] if targets.merged.is_empty() => {
+            spinner.failed();
+            anyhow::bail!("no tracked peer has a '{}' branch", target_branch);
+        }
         [] => {
             spinner.message("All tracked peers are up to date.");
             return Ok(());
         }
         [target] => target,
         _ => {
-            // TODO: Let user select which branch to use as a target.
-            todo!();
+            spinner.failed();
+            return Err(Error::WithHint {
+                err: anyhow!(
+                    "ambiguous base: {} tracked peers have a different '{}' branch",
+                    targets.not_merged.len(),
+                    target_branch
+                ),
+                hint: "hint: pick the peer whose branch to target with `--base <peer-id>/<branch>`",
+            }
+            .into());
         }
     };
     // TODO: Tell user how many peers don't have this change.
@@ -385,10 +1322,32 @@ fn create(
 
     // The merge base is basically the commit at which the histories diverge.
     let base_oid = repo.merge_base((*target_oid).into(), head_oid)?;
-    let commits = patch::patch_commits(repo, &base_oid, &head_oid)?;
+
+    // The range must pick up where the target branch leaves off, otherwise the commits
+    // between the base and the start of the range would be missing from the patch.
+    if let Some(start) = range_start {
+        if start != base_oid && !repo.graph_descendant_of((*target_oid).into(), start)? {
+            return Err(Error::WithHint {
+                err: anyhow!(
+                    "range start {} is not on branch '{}', the patch would be missing commits",
+                    common::fmt::oid(&start),
+                    target_branch
+                ),
+                hint: "hint: start the range at the merge base with the target branch, or omit it",
+            }
+            .into());
+        }
+    }
+    let limit = if options.all {
+        usize::MAX
+    } else {
+        term::patch::COMMIT_LIMIT
+    };
+    let (commits, total) = patch::patch_commits_limited(repo, &base_oid, &head_oid, limit)?;
 
     let patch = match &options.update {
-        Update::No => None,
+        Update::No if options.force => None,
+        Update::No => existing,
         Update::Any => {
             let mut spinner = term::spinner("Finding patches to update...");
             let mut result = find_unmerged_with_base(
@@ -432,12 +1391,17 @@ fn create(
     };
 
     if let Some((id, patch)) = patch {
-        if term::confirm("Update?") {
+        if options.dry_run || term::confirm("Update?") {
+            let dry_run = options.dry_run;
             term::blank();
 
-            return update(
+            update(
                 patch, id, &base_oid, &head_oid, &patches, project, repo, options, profile,
-            );
+            )?;
+            if !dry_run {
+                set_upstream(repo, &head_branch)?;
+            }
+            return Ok(());
         } else {
             anyhow::bail!("Patch update aborted by user");
         }
@@ -450,7 +1414,7 @@ fn create(
     term::info!(
         "{}/{} ({}) <- {}/{} ({})",
         target_peer.name(),
-        term::format::highlight(&project.default_branch.to_string()),
+        term::format::highlight(&target_branch.to_string()),
         term::format::secondary(&common::fmt::oid(target_oid)),
         user_name,
         term::format::highlight(&head_branch.to_string()),
@@ -464,17 +1428,20 @@ fn create(
 
     // List commits in patch that aren't in the target branch.
     term::blank();
-    term::patch::list_commits(&commits)?;
+    term::patch::list_commits(repo, &commits, total, options.stat)?;
     term::blank();
 
-    if !term::confirm("Continue?") {
+    if !options.dry_run && !term::confirm("Continue?") {
         anyhow::bail!("patch proposal aborted by user");
     }
 
     let message = head_commit
         .message()
         .ok_or(anyhow!("commit summary is not valid UTF-8; aborting"))?;
-    let message = options.message.get(&format!("{}{}", message, PATCH_MSG));
+    let message = options.message.get(
+        &format!("{}{}", message, PATCH_MSG),
+        term::patch::Validation::title(),
+    )?;
     let (title, description) = message.split_once("\n\n").unwrap_or((&message, ""));
     let (title, description) = (title.trim(), description.trim());
     let description = description.replace(PATCH_MSG.trim(), ""); // Delete help message.
@@ -502,6 +1469,10 @@ fn create(
     )));
     term::blank();
 
+    if options.dry_run {
+        term::info!("Dry run: no patch was created.");
+        return Ok(());
+    }
     if !term::confirm("Create patch?") {
         anyhow::bail!("patch proposal aborted by user");
     }
@@ -517,10 +1488,15 @@ fn create(
     )?;
 
     term::blank();
+    term::interrupt::published(format!("Patch {}", id));
     term::success!("Patch {} created 🌱", term::format::highlight(id));
+    set_upstream(repo, &head_branch)?;
 
     if options.sync {
         let rt = tokio::runtime::Runtime::new()?;
+        let _cleanup = term::interrupt::on_interrupt(|| {
+            term::tip!("Run `rad sync` to finish publishing the patch to seeds.");
+        });
 
         term::sync::sync(
             project.urn.clone(),
@@ -535,6 +1511,24 @@ fn create(
     Ok(())
 }
 
+/// Set the upstream of the patch branch to the `rad` remote, if it has none, so that
+/// `git status` shows how the branch compares to what was published.
+fn set_upstream(repo: &git::Repository, branch: &RefLike) -> anyhow::Result<()> {
+    let branch = branch.to_string();
+
+    if repo.is_bare() || git::rad_remote(repo).is_err() {
+        return Ok(());
+    }
+    if git::set_upstream_if_unset(repo, "rad", &branch)? {
+        term::info!(
+            "Branch {} set up to track {}",
+            term::format::highlight(&branch),
+            term::format::tertiary(format!("rad/{}", branch))
+        );
+    }
+    Ok(())
+}
+
 /// Create a human friendly message about git's sync status.
 fn pretty_sync_status(
     repo: &git::Repository,
@@ -552,6 +1546,84 @@ fn pretty_sync_status(
     Ok(format!("ahead {}, behind {}", ahead, behind))
 }
 
+/// Create a compact summary of the changes in a revision, eg. '2 commit(s) +10 −3'.
+fn pretty_diffstat(
+    repo: &git::Repository,
+    base_oid: git::Oid,
+    revision_oid: git::Oid,
+) -> anyhow::Result<String> {
+    let base_oid = repo.merge_base(base_oid, revision_oid)?;
+    let (commits, _) = repo.graph_ahead_behind(revision_oid, base_oid)?;
+    let old = repo.find_commit(base_oid)?.tree()?;
+    let new = repo.find_commit(revision_oid)?.tree()?;
+    let stats = repo
+        .diff_tree_to_tree(Some(&old), Some(&new), None)?
+        .stats()?;
+
+    Ok(format!(
+        "{} {} {}",
+        term::format::dim(format!("{} commit(s)", commits)),
+        term::format::positive(format!("+{}", stats.insertions())),
+        term::format::negative(format!("−{}", stats.deletions())),
+    ))
+}
+
+/// Format the state of a patch.
+fn pretty_state(patch: &Patch) -> String {
+    if patch.is_merged() {
+        return term::format::positive(labeled(Indicator::Merged, "merged"));
+    }
+    match patch.state {
+        State::Draft => term::format::dim(labeled(Indicator::Draft, "draft")),
+        State::Proposed => term::format::secondary(labeled(Indicator::Open, "open")),
+        State::Archived | State::Retracted => {
+            term::format::negative(labeled(Indicator::Closed, "closed"))
+        }
+    }
+}
+
+/// Format the outcome of a check.
+fn pretty_check_state(state: CheckState) -> String {
+    match state {
+        CheckState::Pass => term::format::positive(labeled(Indicator::Success, "pass")),
+        CheckState::Fail => term::format::negative(labeled(Indicator::Failure, "fail")),
+    }
+}
+
+/// Create a human friendly summary of the checks on a revision, eg. '✓ ci ✗ lint'.
+fn pretty_checks(checks: &[&Check]) -> String {
+    checks
+        .iter()
+        .map(|c| match c.state {
+            CheckState::Pass => term::format::positive(labeled(Indicator::Success, &c.name)),
+            CheckState::Fail => term::format::negative(labeled(Indicator::Failure, &c.name)),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Create a human friendly summary of the reviews on a revision.
+fn pretty_review_count(count: ReviewCount) -> String {
+    if count.total == 0 {
+        return term::format::dim("no reviews");
+    }
+    let changes = if count.changes_requested > 0 {
+        format!(
+            " {}",
+            term::format::yellow(labeled(Indicator::Pending, count.changes_requested))
+        )
+    } else {
+        String::new()
+    };
+    format!(
+        "{} {}{} {}",
+        term::format::positive(labeled(Indicator::Success, count.accepted)),
+        term::format::negative(labeled(Indicator::Failure, count.rejected)),
+        changes,
+        term::format::dim(format!("({} review(s))", count.total)),
+    )
+}
+
 /// Make a human friendly string for commit version information.
 ///
 /// For example '<oid> (branch1[, branch2])'.
@@ -586,7 +1658,13 @@ fn pretty_commit_version(
 }
 
 /// Adds patch details as a new row to `table` and render later.
+/// Patches that `changed`, eg. while watching, are marked. Unless `--full` is set, the
+/// title is truncated so that the patch fits on one line of the terminal. Times are
+/// relative, or absolute with `--verbose`.
+#[allow(clippy::too_many_arguments)]
 pub fn print(
+    w: &mut dyn io::Write,
+    width: Option<usize>,
     whoami: &LocalIdentity,
     patch_id: &PatchId,
     patch: &mut Patch,
@@ -594,6 +1672,8 @@ pub fn print(
     monorepo: &git::Repository,
     repo: &Option<git::Repository>,
     storage: &Storage,
+    changed: bool,
+    options: &Options,
 ) -> anyhow::Result<()> {
     for r in patch.revisions.iter_mut() {
         for (_, r) in &mut r.reviews {
@@ -601,6 +1681,9 @@ pub fn print(
         }
     }
     patch.author.resolve(storage).ok();
+    for assignee in &mut patch.assignees {
+        assignee.resolve(storage).ok();
+    }
 
     let verified = project.verified(storage)?;
     let target_head = common::patch::patch_merge_target_oid(patch.target, verified, storage)?;
@@ -616,18 +1699,149 @@ pub fn print(
     if you {
         author_info.push(term::format::secondary("(you)"));
     }
-    author_info.push(term::format::dim(patch.timestamp));
+    author_info.push(term::format::dim(term::format::timestamp(
+        patch.timestamp,
+        options.verbose,
+    )));
 
     let revision = patch.revisions.last();
-    term::info!(
-        "{} {} {} {} {}",
-        term::format::bold(&patch.title),
+    let title = if changed {
+        format!(
+            "{} {}",
+            term::format::yellow("●"),
+            term::format::bold(&patch.title)
+        )
+    } else {
+        term::format::bold(&patch.title)
+    };
+    let details = format!(
+        "{} {} {} {} {} {} {}",
         term::format::highlight(common::fmt::cob(patch_id)),
+        pretty_state(patch),
         term::format::dim(format!("R{}", patch.version())),
         pretty_commit_version(&revision.oid, repo)?,
         pretty_sync_status(monorepo, *revision.oid, target_head)?,
+        // Nb. The revision's commits may not have been fetched yet.
+        pretty_diffstat(monorepo, *revision.base, *revision.oid)
+            .unwrap_or_else(|_| term::format::dim("(diffstat unavailable)")),
+        pretty_review_count(revision.review_count()),
     );
-    term::info!("{}", author_info.join(" "));
+    let author_info = author_info.join(" ");
+    let width = if options.full { None } else { width };
+
+    match width {
+        Some(width) => {
+            let available = width
+                .saturating_sub(term::text_width(&details) + 2)
+                .max(term::table::MIN_COLUMN_WIDTH);
+
+            writeln!(
+                w,
+                "{} {}",
+                term::format::truncate(title, available),
+                details
+            )?;
+            writeln!(
+                w,
+                "{}",
+                term::format::truncate(author_info, width.saturating_sub(1))
+            )?;
+        }
+        None => {
+            writeln!(w, "{} {}", title, details)?;
+            writeln!(w, "{}", author_info)?;
+        }
+    }
+
+    if !patch.labels.is_empty() {
+        let mut labels = patch.labels.iter().map(|l| l.name()).collect::<Vec<_>>();
+        labels.sort_unstable();
+
+        writeln!(
+            w,
+            "{}{}",
+            " ".repeat(term::text_width(prefix)),
+            term::format::yellow(labels.join(", "))
+        )?;
+    }
+
+    // Your own open patches, on whose latest revision changes were requested.
+    if you && !patch.is_merged() && !patch.is_archived() {
+        let mut reviewers = revision
+            .reviews
+            .values()
+            .filter(|r| r.verdict == Some(Verdict::RequestChanges))
+            .map(|r| term::format::tertiary(r.author.name()))
+            .collect::<Vec<_>>();
+        reviewers.sort();
+
+        if !reviewers.is_empty() {
+            writeln!(
+                w,
+                "{}{} {}",
+                " ".repeat(term::text_width(prefix)),
+                term::format::badge_negative("changes requested"),
+                term::format::dim(format!("by {}", reviewers.join(", "))),
+            )?;
+        }
+    }
+
+    // Your own open patches, whose local branch has commits that weren't published.
+    if let (true, false, Some(repo)) = (you, patch.is_merged() || patch.is_archived(), repo) {
+        for (branch, count) in common::patch::unpublished_commits(repo, &*revision.oid)? {
+            writeln!(
+                w,
+                "{}{} {}",
+                " ".repeat(term::text_width(prefix)),
+                term::format::yellow(format!("{} unpublished commit(s) on {}", count, branch)),
+                term::format::dim(format!(
+                    "(run `rad patch --update {}`)",
+                    common::fmt::cob(patch_id)
+                )),
+            )?;
+        }
+    }
+
+    let checks = patch.checks();
+    if !checks.is_empty() {
+        writeln!(
+            w,
+            "{}{} {}",
+            " ".repeat(term::text_width(prefix)),
+            term::format::dim("checks"),
+            pretty_checks(&checks)
+        )?;
+    }
+
+    if !patch.assignees.is_empty() {
+        writeln!(
+            w,
+            "{}{} {}",
+            " ".repeat(term::text_width(prefix)),
+            term::format::dim("assigned to"),
+            patch
+                .assignees
+                .iter()
+                .map(|a| term::format::tertiary(a.name()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )?;
+    }
+
+    if !patch.coauthors.is_empty() {
+        writeln!(
+            w,
+            "{}{} {}",
+            " ".repeat(term::text_width(prefix)),
+            term::format::dim("co-authored by"),
+            patch
+                .coauthors
+                .iter()
+                .map(|c| term::format::tertiary(&c.name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )?;
+    }
 
     let mut timeline = Vec::new();
     for merge in &revision.merges {
@@ -646,7 +1860,7 @@ pub fn print(
             format!(
                 "{}{} by {} {}",
                 " ".repeat(term::text_width(prefix)),
-                term::format::secondary(term::format::dim("✓ merged")),
+                term::format::secondary(term::format::dim(labeled(Indicator::Merged, "merged"))),
                 term::format::tertiary(peer.name()),
                 badges.join(" "),
             ),
@@ -654,8 +1868,16 @@ pub fn print(
     }
     for (_, review) in &revision.reviews {
         let verdict = match review.verdict {
-            Some(Verdict::Accept) => term::format::positive(term::format::dim("✓ accepted")),
-            Some(Verdict::Reject) => term::format::negative(term::format::dim("✗ rejected")),
+            Some(Verdict::Accept) => {
+                term::format::positive(term::format::dim(labeled(Indicator::Success, "accepted")))
+            }
+            Some(Verdict::Reject) => {
+                term::format::negative(term::format::dim(labeled(Indicator::Failure, "rejected")))
+            }
+            Some(Verdict::RequestChanges) => term::format::yellow(term::format::dim(labeled(
+                Indicator::Pending,
+                "changes requested",
+            ))),
             None => term::format::negative(term::format::dim("⋄ reviewed")),
         };
         let peer = project::PeerInfo::get(&review.author.peer, project, storage);
@@ -668,6 +1890,16 @@ pub fn print(
             badges.push(term::format::secondary("(you)"));
         }
 
+        if let Some((checked, total)) = review.checklist_completion() {
+            let completion = format!("☑ {}/{}", checked, total);
+
+            badges.push(if checked == total {
+                term::format::positive(completion)
+            } else {
+                term::format::dim(completion)
+            });
+        }
+
         timeline.push((
             review.timestamp,
             format!(
@@ -682,12 +1914,108 @@ pub fn print(
     timeline.sort_by_key(|(t, _)| *t);
 
     for (time, event) in timeline.iter().rev() {
-        term::info!("{} {}", event, term::format::dim(time));
+        writeln!(
+            w,
+            "{} {}",
+            event,
+            term::format::dim(term::format::timestamp(*time, options.verbose))
+        )?;
     }
 
     Ok(())
 }
 
+/// Resolve a revspec such as `HEAD~1` or `HEAD~2..HEAD` into an optional range start,
+/// and the commit to propose. The commit must be on the current branch.
+fn resolve_range(
+    repo: &git::Repository,
+    spec: &str,
+    branch_head: git::Oid,
+) -> anyhow::Result<(Option<git::Oid>, git::Oid)> {
+    if spec.contains("...") {
+        anyhow::bail!(
+            "symmetric difference '{}' is not supported, use '<from>..<to>'",
+            spec
+        );
+    }
+    let resolve = |rev: &str| -> anyhow::Result<git::Oid> {
+        let rev = if rev.is_empty() { "HEAD" } else { rev };
+        let commit = repo
+            .revparse_single(rev)
+            .and_then(|obj| obj.peel_to_commit())
+            .map_err(|_| anyhow!("revision '{}' could not be resolved to a commit", rev))?;
+
+        Ok(commit.id())
+    };
+    let (start, end) = match spec.split_once("..") {
+        Some((start, end)) => (Some(resolve(start)?), resolve(end)?),
+        None => (None, resolve(spec)?),
+    };
+
+    if end != branch_head && !repo.graph_descendant_of(branch_head, end)? {
+        return Err(Error::WithHint {
+            err: anyhow!(
+                "commit {} is not on the current branch",
+                common::fmt::oid(&end)
+            ),
+            hint: "hint: check out the branch containing the commit and try again",
+        }
+        .into());
+    }
+    if let Some(start) = start {
+        if start == end || !repo.graph_descendant_of(end, start)? {
+            anyhow::bail!(
+                "range '{}' is empty or its start is not an ancestor of its end",
+                spec
+            );
+        }
+    }
+    Ok((start, end))
+}
+
+/// Find my unmerged patches that were created from the given branch, ie. whose head is the
+/// branch head, or a commit the branch was published at.
+fn find_unmerged_on_branch(
+    branch: &RefLike,
+    branch_head: git::Oid,
+    patches: &PatchStore,
+    project: &common::Urn,
+    repo: &git::Repository,
+) -> anyhow::Result<Vec<(PatchId, Patch)>> {
+    let published = published_heads(repo, &branch.to_string());
+    let mut matches = Vec::new();
+
+    for (id, patch) in patches.proposed_by(patches.whoami.urn(), project)? {
+        if patch.is_merged() {
+            continue;
+        }
+        let head = **patch.head();
+
+        // Nb. Ancestry isn't enough: the patch of a branch this one is stacked on is
+        // also an ancestor of the branch head.
+        if head == branch_head || published.contains(&head) {
+            matches.push((id, patch));
+        }
+    }
+    Ok(matches)
+}
+
+/// Commits the given branch was published at, according to the reflog of its upstream.
+fn published_heads(repo: &git::Repository, branch: &str) -> Vec<git::Oid> {
+    let upstream = repo
+        .find_branch(branch, git::BranchType::Local)
+        .and_then(|b| b.upstream());
+    let name = match upstream.as_ref().map(|u| u.get().name()) {
+        Ok(Some(name)) => name,
+        _ => return Vec::new(),
+    };
+
+    match repo.reflog(name) {
+        Ok(reflog) => reflog.iter().map(|e| e.id_new()).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
 /// Find patches with a merge base equal to the one provided.
 fn find_unmerged_with_base(
     patch_head: git::Oid,
@@ -0,0 +1,129 @@
+use std::ffi::OsString;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+use radicle_common as common;
+use radicle_common::args::{Args, Error};
+use radicle_common::cobs::patch::{Patch, State};
+use radicle_common::{cobs, keys, project};
+use radicle_terminal as term;
+
+/// Patch state change.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Operation {
+    Close,
+    Reopen,
+}
+
+#[derive(Debug)]
+pub struct Options {
+    pub op: Operation,
+    pub id: cobs::Identifier,
+    pub reason: Option<String>,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut op: Option<Operation> = None;
+        let mut id: Option<cobs::Identifier> = None;
+        let mut reason: Option<String> = None;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("help") => {
+                    return Err(Error::Help.into());
+                }
+                Long("reason") if op == Some(Operation::Close) => {
+                    reason = Some(parser.value()?.to_string_lossy().into());
+                }
+                Value(val) if op.is_none() => match val.to_string_lossy().as_ref() {
+                    "close" => op = Some(Operation::Close),
+                    "reopen" => op = Some(Operation::Reopen),
+
+                    unknown => anyhow::bail!("unknown operation '{}'", unknown),
+                },
+                Value(val) if id.is_none() => {
+                    let val = val
+                        .to_str()
+                        .ok_or_else(|| anyhow!("patch id specified is not UTF-8"))?;
+
+                    id = Some(
+                        cobs::Identifier::from_str(val)
+                            .map_err(|_| anyhow!("invalid patch id '{}'", val))?,
+                    );
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+
+        Ok((
+            Options {
+                op: op.ok_or_else(|| anyhow!("an operation must be provided"))?,
+                id: id.ok_or_else(|| anyhow!("a patch id must be provided"))?,
+                reason,
+            },
+            vec![],
+        ))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let (urn, _) = project::cwd()
+        .map_err(|_| anyhow!("this command must be run in the context of a project"))?;
+    let profile = ctx.profile()?;
+    let signer = term::signer(&profile)?;
+    let storage = keys::storage(&profile, signer)?;
+    let cobs = cobs::store(&profile, &storage)?;
+    let patches = cobs.patches();
+
+    let (patch_id, patch) = patches
+        .resolve::<Patch>(&urn, &options.id)?
+        .ok_or_else(|| anyhow!("couldn't find patch {} locally", options.id))?;
+
+    // Peers ignore state changes made by anyone else, see `Patch::from_history_moderated`.
+    let whoami = cobs.whoami.urn();
+    if patch.author.urn() != &whoami && !cobs.delegates(&urn).contains(&whoami) {
+        return Err(Error::WithHint {
+            err: anyhow!("only the patch author and project delegates can change its state"),
+            hint: "hint: review the patch with `rad patch review <id> --comment <text>` instead",
+        }
+        .into());
+    }
+
+    match options.op {
+        Operation::Close => {
+            if patch.is_retracted() {
+                anyhow::bail!("patch {} was retracted", common::fmt::cob(&patch_id));
+            }
+            if patch.is_archived() {
+                anyhow::bail!("patch {} is already closed", common::fmt::cob(&patch_id));
+            }
+            if let Some(reason) = &options.reason {
+                patches.comment(&urn, &patch_id, patch.version(), reason)?;
+            }
+            patches.lifecycle(&urn, &patch_id, State::Archived)?;
+
+            term::success!(
+                "Patch {} closed",
+                term::format::highlight(common::fmt::cob(&patch_id))
+            );
+        }
+        Operation::Reopen => {
+            if !patch.is_archived() {
+                anyhow::bail!("patch {} is not closed", common::fmt::cob(&patch_id));
+            }
+            patches.lifecycle(&urn, &patch_id, State::Proposed)?;
+
+            term::success!(
+                "Patch {} reopened",
+                term::format::highlight(common::fmt::cob(&patch_id))
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use std::ffi::OsString;
+use std::io;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+use radicle_common::args::{Args, Error};
+use radicle_common::cobs::patch::Patch;
+use radicle_common::{cobs, git, keys, project, schema};
+use radicle_terminal as term;
+
+use crate::json::{PatchJson, SCHEMA_VERSION};
+
+#[derive(Debug)]
+pub struct Options {
+    pub id: cobs::Identifier,
+    pub json: bool,
+    pub all: bool,
+    pub stat: bool,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut id: Option<cobs::Identifier> = None;
+        let mut json = false;
+        let mut all = false;
+        let mut stat = false;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("help") => {
+                    return Err(Error::Help.into());
+                }
+                Long("json") => {
+                    json = true;
+                }
+                Long("all") => {
+                    all = true;
+                }
+                Long("stat") => {
+                    stat = true;
+                }
+                Long("schema") => {
+                    let schema =
+                        schema::document("patch-show", SCHEMA_VERSION, PatchJson::schema());
+
+                    return Err(Error::Schema(schema).into());
+                }
+                Value(val) if id.is_none() => {
+                    let val = val
+                        .to_str()
+                        .ok_or_else(|| anyhow!("patch id specified is not UTF-8"))?;
+
+                    id = Some(
+                        cobs::Identifier::from_str(val)
+                            .map_err(|_| anyhow!("invalid patch id '{}'", val))?,
+                    );
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+
+        Ok((
+            Options {
+                id: id.ok_or_else(|| anyhow!("a patch id to show must be provided"))?,
+                json,
+                all,
+                stat,
+            },
+            vec![],
+        ))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let (urn, repo) = project::cwd()
+        .map_err(|_| anyhow!("this command must be run in the context of a project"))?;
+    let profile = ctx.profile()?;
+    let signer = term::signer(&profile)?;
+    let storage = keys::storage(&profile, signer)?;
+    let project = project::get(&storage, &urn)?
+        .ok_or_else(|| anyhow!("couldn't load project {} from local state", urn))?;
+    let cobs = cobs::store(&profile, &storage)?;
+    let patches = cobs.patches();
+
+    let (patch_id, mut patch) = patches
+        .resolve::<Patch>(&urn, &options.id)?
+        .ok_or_else(|| anyhow!("couldn't find patch {} locally", options.id))?;
+
+    if options.json {
+        let json = PatchJson::new(&patch_id, &patch);
+        println!("{}", radicle_common::json::to_string_pretty(&json)?);
+
+        return Ok(());
+    }
+
+    let monorepo = git::Repository::open_bare(profile.paths().git_dir())?;
+
+    term::blank();
+    crate::print(
+        &mut io::stdout(),
+        term::columns(),
+        &cobs.whoami,
+        &patch_id,
+        &mut patch,
+        &project,
+        &monorepo,
+        &Some(repo),
+        &storage,
+        false,
+        &crate::Options::default(),
+    )?;
+    term::blank();
+
+    if !patch.description().is_empty() {
+        term::markdown(patch.description());
+        term::blank();
+    }
+
+    let revision = patch.latest().1;
+    let limit = if options.all {
+        usize::MAX
+    } else {
+        term::patch::COMMIT_LIMIT
+    };
+    let (commits, total) = radicle_common::patch::patch_commits_limited(
+        &monorepo,
+        &*revision.base,
+        &*revision.oid,
+        limit,
+    )?;
+
+    term::patch::list_commits(&monorepo, &commits, total, options.stat)?;
+    term::blank();
+
+    Ok(())
+}
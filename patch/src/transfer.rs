@@ -0,0 +1,123 @@
+use std::ffi::OsString;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+use librad::PeerId;
+
+use radicle_common as common;
+use radicle_common::args::{Args, Error};
+use radicle_common::cobs::patch::Patch;
+use radicle_common::cobs::Author;
+use radicle_common::{cobs, keys, person, project};
+use radicle_terminal as term;
+
+#[derive(Debug)]
+pub struct Options {
+    pub id: cobs::Identifier,
+    pub peer: PeerId,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut id: Option<cobs::Identifier> = None;
+        let mut peer: Option<PeerId> = None;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("help") => {
+                    return Err(Error::Help.into());
+                }
+                Value(val) if id.is_none() => {
+                    let val = val
+                        .to_str()
+                        .ok_or_else(|| anyhow!("patch id specified is not UTF-8"))?;
+
+                    id = Some(
+                        cobs::Identifier::from_str(val)
+                            .map_err(|_| anyhow!("invalid patch id '{}'", val))?,
+                    );
+                }
+                Value(val) if peer.is_none() => {
+                    let val = val.to_string_lossy();
+
+                    peer = Some(
+                        PeerId::from_str(&val)
+                            .map_err(|_| anyhow!("invalid <peer-id> '{}'", val))?,
+                    );
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+
+        Ok((
+            Options {
+                id: id.ok_or_else(|| anyhow!("a patch id must be provided"))?,
+                peer: peer
+                    .ok_or_else(|| anyhow!("a peer to transfer the patch to must be provided"))?,
+            },
+            vec![],
+        ))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let (urn, _) = project::cwd()
+        .map_err(|_| anyhow!("this command must be run in the context of a project"))?;
+    let profile = ctx.profile()?;
+    let signer = term::signer(&profile)?;
+    let storage = keys::storage(&profile, signer)?;
+    let project = project::get(&storage, &urn)?
+        .ok_or_else(|| anyhow!("couldn't load project {} from local state", urn))?;
+    let whoami = person::local(&storage)?;
+    let cobs = cobs::store(&profile, &storage)?;
+    let patches = cobs.patches();
+
+    let (patch_id, patch) = patches
+        .resolve::<Patch>(&urn, &options.id)?
+        .ok_or_else(|| anyhow!("couldn't find patch {} locally", options.id))?;
+
+    // Peers ignore transfers made by anyone else, see `Patch::from_history_moderated`.
+    // Nb. Delegates that are only keys can't be told apart from other peers.
+    if patch.author.urn() != &whoami.urn() && !cobs.delegates(&urn).contains(&whoami.urn()) {
+        anyhow::bail!("only the author of a patch or a project delegate can transfer it");
+    }
+    if patch.author.peer == options.peer {
+        anyhow::bail!(
+            "patch {} is already owned by {}",
+            common::fmt::cob(&patch_id),
+            common::fmt::peer(&options.peer)
+        );
+    }
+
+    let owner = project::PeerInfo::get(&options.peer, &project, &storage);
+    let person = owner.person.as_ref().ok_or_else(|| Error::WithHint {
+        err: anyhow!(
+            "the identity of peer {} was not found",
+            common::fmt::peer(&options.peer)
+        ),
+        hint: "hint: the peer must be tracked, and must have published their identity",
+    })?;
+
+    if !term::confirm(format!(
+        "Transfer patch {} {} to {}?",
+        term::format::highlight(common::fmt::cob(&patch_id)),
+        term::format::italic(&patch.title),
+        term::format::tertiary(owner.name())
+    )) {
+        anyhow::bail!("patch transfer aborted by user");
+    }
+    patches.transfer(&urn, &patch_id, &Author::new(person.urn.clone(), owner.id))?;
+
+    term::success!(
+        "Patch {} transferred to {}",
+        term::format::highlight(common::fmt::cob(&patch_id)),
+        term::format::tertiary(owner.name())
+    );
+    term::tip!("Run `rad sync` to publish the transfer to seeds.");
+
+    Ok(())
+}
@@ -0,0 +1,295 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context as _};
+
+use librad::profile::ProfileId;
+
+use radicle_common::args::{Args, Error, Help};
+use radicle_common::config::Config;
+use radicle_common::keychain;
+use radicle_common::profile::{self, Profile};
+use radicle_terminal as term;
+
+pub const HELP: Help = Help {
+    name: "profile",
+    description: env!("CARGO_PKG_DESCRIPTION"),
+    version: env!("CARGO_PKG_VERSION"),
+    usage: r#"
+Usage
+
+    rad profile [list]
+    rad profile create [--name <name>] [--stdin]
+    rad profile switch <id>
+    rad profile remove <id> [--no-confirm]
+    rad profile storage [<path>] [--no-confirm]
+
+    Profiles keep separate identities, keys and storage, eg. for work and
+    personal use. Commands use the active profile, unless another one is
+    given with `rad --profile <id>` or the `RAD_PROFILE` environment variable.
+
+    Creating a profile makes it the active profile. The active profile can't
+    be removed; switch to another one first.
+
+    `storage` shows where the active profile's storage is. Given a path, it
+    moves the storage there, eg. to an external drive or a network mount, and
+    records the location in the profile's config.
+
+Options
+
+    --name <name>   Name of the identity to create, with `create`
+    --stdin         Read the passphrase from stdin, with `create`
+    --no-confirm    Don't ask for confirmation, with `remove` and `storage`
+    --help          Print help
+"#,
+};
+
+#[derive(Debug)]
+pub enum Operation {
+    List,
+    Create { name: Option<String>, stdin: bool },
+    Switch { id: ProfileId },
+    Remove { id: ProfileId, confirm: bool },
+    Storage { to: Option<PathBuf>, confirm: bool },
+}
+
+impl Default for Operation {
+    fn default() -> Self {
+        Self::List
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Options {
+    pub op: Operation,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut op: Option<String> = None;
+        let mut id: Option<ProfileId> = None;
+        let mut path: Option<PathBuf> = None;
+        let mut name: Option<String> = None;
+        let mut stdin = false;
+        let mut confirm = true;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("name") if op.as_deref() == Some("create") => {
+                    let val = parser.value()?;
+                    let val = val
+                        .to_str()
+                        .ok_or_else(|| anyhow!("invalid name specified with `--name`"))?;
+
+                    name = Some(val.to_owned());
+                }
+                Long("stdin") if op.as_deref() == Some("create") => {
+                    stdin = true;
+                }
+                Long("no-confirm") if matches!(op.as_deref(), Some("remove" | "storage")) => {
+                    confirm = false;
+                }
+                Long("help") => {
+                    return Err(Error::Help.into());
+                }
+                Value(val) if op.is_none() => match val.to_string_lossy().as_ref() {
+                    "list" | "l" => op = Some(String::from("list")),
+                    "create" | "c" => op = Some(String::from("create")),
+                    "switch" | "s" => op = Some(String::from("switch")),
+                    "remove" | "rm" => op = Some(String::from("remove")),
+                    "storage" => op = Some(String::from("storage")),
+                    unknown => anyhow::bail!("unknown operation '{}'", unknown),
+                },
+                Value(val)
+                    if matches!(op.as_deref(), Some("switch" | "remove")) && id.is_none() =>
+                {
+                    let val = val.to_string_lossy();
+                    let val = ProfileId::from_str(&val)
+                        .with_context(|| format!("invalid profile id '{}'", val))?;
+
+                    id = Some(val);
+                }
+                Value(val) if op.as_deref() == Some("storage") && path.is_none() => {
+                    path = Some(PathBuf::from(val));
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+
+        let id = || id.ok_or_else(|| anyhow!("a profile id must be provided"));
+        let op = match op.as_deref() {
+            Some("create") => Operation::Create { name, stdin },
+            Some("switch") => Operation::Switch { id: id()? },
+            Some("remove") => Operation::Remove { id: id()?, confirm },
+            Some("storage") => Operation::Storage { to: path, confirm },
+            _ => Operation::List,
+        };
+
+        Ok((Options { op }, vec![]))
+    }
+}
+
+pub fn run(options: Options, _ctx: impl term::Context) -> anyhow::Result<()> {
+    match options.op {
+        Operation::List => list(),
+        Operation::Create { name, stdin } => rad_auth::init(rad_auth::Options {
+            init: true,
+            active: false,
+            stdin,
+            name,
+            profile: None,
+            keychain: None,
+            rotate: false,
+        }),
+        Operation::Switch { id } => {
+            let profile = find(&id)?;
+
+            profile::set(profile.id())?;
+            term::success!(
+                "Profile {} activated",
+                term::format::highlight(profile.id())
+            );
+            term::tip!(
+                "To add its key to ssh-agent, run {}.",
+                term::format::secondary("`rad auth`")
+            );
+
+            Ok(())
+        }
+        Operation::Remove { id, confirm } => {
+            let profile = find(&id)?;
+            let name = profile::name(Some(&profile)).unwrap_or_default();
+
+            if confirm
+                && !term::confirm(format!(
+                    "Remove profile {} {}, with its keys and storage? This can't be undone.",
+                    term::format::highlight(profile.id()),
+                    term::format::dim(format!("({})", name))
+                ))
+            {
+                anyhow::bail!("removal aborted by user");
+            }
+            let keychain = keychain::is_enabled(&profile);
+
+            profile::remove(&profile).map_err(|err| {
+                if profile::active().ok().flatten().map(|p| p.id().clone()) == Some(id.clone()) {
+                    Error::WithHint {
+                        err,
+                        hint:
+                            "hint: switch to another profile with `rad profile switch <id>` first",
+                    }
+                    .into()
+                } else {
+                    err
+                }
+            })?;
+            if keychain {
+                // The profile is gone either way, a stale keychain entry is harmless.
+                keychain::remove(&id).ok();
+            }
+            term::success!("Profile {} removed", term::format::highlight(&id));
+
+            Ok(())
+        }
+        Operation::Storage { to, confirm } => storage(to, confirm),
+    }
+}
+
+fn storage(path: Option<PathBuf>, confirm: bool) -> anyhow::Result<()> {
+    let profile = profile::default()?;
+
+    let path = match path {
+        Some(path) if path.is_absolute() => path,
+        Some(path) => std::env::current_dir()?.join(path),
+        None => {
+            let storage = match Config::profile(&profile) {
+                Ok(config) => config.storage,
+                Err(_) => None,
+            };
+            let path = storage.unwrap_or_else(|| profile.paths().git_dir().to_path_buf());
+
+            term::print(path.display());
+            profile::check_storage(&profile)?;
+
+            return Ok(());
+        }
+    };
+
+    if confirm
+        && !term::confirm(format!(
+            "Move the storage of profile {} to {}?",
+            term::format::highlight(profile.id()),
+            term::format::tertiary(path.display())
+        ))
+    {
+        anyhow::bail!("move aborted by user");
+    }
+    let spinner = term::spinner("Moving storage...");
+    if let Err(err) = profile::relocate(&profile, &path) {
+        spinner.failed();
+        return Err(err);
+    }
+    spinner.finish();
+
+    term::success!(
+        "Storage of profile {} moved to {}",
+        term::format::highlight(profile.id()),
+        term::format::tertiary(path.display())
+    );
+
+    Ok(())
+}
+
+fn list() -> anyhow::Result<()> {
+    let profiles = profile::list()?;
+    let active = profile::default().ok();
+
+    if profiles.is_empty() {
+        term::print(term::format::italic("No profiles found."));
+        term::tip!(
+            "To create a profile, run {}.",
+            term::format::secondary("`rad profile create`")
+        );
+        return Ok(());
+    }
+
+    let mut table = term::Table::default();
+    for profile in &profiles {
+        let is_active = active.as_ref().map_or(false, |a| a.id() == profile.id());
+        let (name, user) = match profile::read_only(profile).and_then(|s| {
+            let config = s.config()?;
+            Ok::<_, anyhow::Error>((config.user_name().ok(), config.user()?))
+        }) {
+            Ok((name, user)) => (name.unwrap_or_default(), user),
+            Err(_) => (String::new(), None),
+        };
+
+        table.push([
+            if is_active {
+                term::format::positive("*")
+            } else {
+                String::from(" ")
+            },
+            term::format::highlight(profile.id()),
+            term::format::bold(name),
+            term::format::tertiary(user.map(|u| u.to_string()).unwrap_or_default()),
+        ]);
+    }
+    table.render();
+
+    Ok(())
+}
+
+fn find(id: &ProfileId) -> anyhow::Result<Profile> {
+    profile::get(id)?.ok_or_else(|| {
+        Error::WithHint {
+            err: anyhow!("profile {} not found", id),
+            hint: "hint: to list your profiles, run `rad profile list`",
+        }
+        .into()
+    })
+}
@@ -1,11 +1,14 @@
 use std::ffi::OsString;
 use std::path::Path;
 
+use radicle_common::archive::Archive;
 use radicle_common::args::{Args, Error, Help};
+use radicle_common::config::{Config, Protection};
 use radicle_common::git;
 
+use radicle_common::store::Store;
 use radicle_common::sync::Mode;
-use radicle_common::{seed, sync};
+use radicle_common::{profile, project, seed, sync, Urn};
 use radicle_terminal as term;
 
 use anyhow::anyhow;
@@ -27,11 +30,16 @@ Options
     --all               Push all branches (default: false)
     --sync              Sync after pushing to the "rad" remote (default: true)
     --no-sync           Do not sync after pushing to the "rad" remote
+    --allow-default     Push to the default branch, even if the project policy protects it
     --help              Print help
 
+    Direct pushes to the project's default branch can be discouraged by setting
+    `protect-default-branch` to "warn" or "block" in the `[policy]` section of
+    the project's `Radicle.toml`.
+
 Git options
 
-    -f, --force           Force push
+    -f, --force           Force push, archiving overwritten branch tips
     -u, --set-upstream    Set upstream tracking branch
 
 "#,
@@ -45,6 +53,7 @@ pub struct Options {
     pub all: bool,
     pub set_upstream: bool,
     pub sync: bool,
+    pub allow_default: bool,
 }
 
 impl Args for Options {
@@ -58,6 +67,7 @@ impl Args for Options {
         let mut sync = true;
         let mut seed = None;
         let mut set_upstream = false;
+        let mut allow_default = false;
 
         while let Some(arg) = parser.next()? {
             match arg {
@@ -85,6 +95,9 @@ impl Args for Options {
                 Long("force") | Short('f') => {
                     force = true;
                 }
+                Long("allow-default") => {
+                    allow_default = true;
+                }
                 arg => {
                     return Err(anyhow!(arg.unexpected()));
                 }
@@ -99,6 +112,7 @@ impl Args for Options {
                 set_upstream,
                 sync,
                 verbose,
+                allow_default,
             },
             vec![],
         ))
@@ -106,7 +120,7 @@ impl Args for Options {
 }
 
 pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
-    ctx.profile()?;
+    let profile = ctx.profile()?;
 
     term::info!("Pushing 🌱 to remote `rad`");
 
@@ -126,14 +140,69 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
     }
     args.push("rad"); // Push to "rad" remote.
 
+    let repo = git::repository()?;
+    let protection = Config::policy(&profile)?.protect_default_branch;
+
+    if protection != Protection::Off && !options.allow_default {
+        if let Some(branch) = default_branch_pushed(&repo, &profile, options.all)? {
+            match protection {
+                Protection::Block => {
+                    return Err(Error::WithHint {
+                        err: anyhow!(
+                            "direct pushes to the default branch '{}' are blocked by the project policy",
+                            branch
+                        ),
+                        hint: "hint: propose your changes with `rad patch`, or use `--allow-default` to push anyway",
+                    }
+                    .into());
+                }
+                Protection::Warn => {
+                    term::warning(&format!(
+                        "Pushing directly to the default branch '{}'",
+                        branch
+                    ));
+                    term::tip!("Consider proposing your changes with `rad patch` instead.");
+                }
+                Protection::Off => {}
+            }
+        }
+    }
+
     term::subcommand(&format!("git {}", args.join(" ")));
 
+    // Remember the branch tips in storage that a force push could overwrite.
+    let tips = if options.force {
+        storage_tips(&repo, &profile, options.all)?
+    } else {
+        None
+    };
+
     // Push to monorepo.
     match git::git(Path::new("."), args) {
         Ok(output) => term::blob(output),
         Err(err) => return Err(err),
     }
 
+    if let Some((urn, tips)) = tips {
+        let storage = profile::read_only(&profile)?;
+        let mut archive = Archive::load(&profile)?;
+        let mut archived = 0;
+
+        for (branch, oid) in tips {
+            if project::get_local_head(&storage, &urn, &branch)? != Some(oid) {
+                archive.record(&urn, &format!("refs/heads/{}", branch), oid, "push --force");
+                archived += 1;
+            }
+        }
+        if archived > 0 {
+            archive.save(&profile)?;
+            term::info!(
+                "{} overwritten branch tip(s) archived, use `rad archive restore` to recover them",
+                archived
+            );
+        }
+    }
+
     if options.sync {
         // Sync monorepo to seed.
         rad_sync::run(
@@ -143,6 +212,7 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
                 mode: Mode::Push,
                 origin: None,
                 sync_self: false,
+                ..rad_sync::Options::default()
             },
             ctx,
         )?;
@@ -150,3 +220,69 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Tips in storage of the project branches that a push may update: the current branch,
+/// or all local branches.
+fn storage_tips(
+    repo: &git::Repository,
+    profile: &profile::Profile,
+    all: bool,
+) -> anyhow::Result<Option<(Urn, Vec<(String, git::Oid)>)>> {
+    let urn = match project::cwd() {
+        Ok((urn, _)) => urn,
+        Err(_) => return Ok(None),
+    };
+    let storage = profile::read_only(profile)?;
+    let mut branches = Vec::new();
+
+    if all {
+        for r in repo.references_glob("refs/heads/*")?.flatten() {
+            if let Some(branch) = r.shorthand() {
+                branches.push(branch.to_owned());
+            }
+        }
+    } else if let Some(branch) = repo.head()?.shorthand() {
+        branches.push(branch.to_owned());
+    }
+
+    let mut tips = Vec::new();
+    for branch in branches {
+        if let Some(oid) = project::get_local_head(&storage, &urn, &branch)? {
+            tips.push((branch, oid));
+        }
+    }
+    Ok(Some((urn, tips)))
+}
+
+/// The project's default branch, if the push would update it.
+fn default_branch_pushed(
+    repo: &git::Repository,
+    profile: &profile::Profile,
+    all: bool,
+) -> anyhow::Result<Option<String>> {
+    let urn = match project::cwd() {
+        Ok((urn, _)) => urn,
+        Err(_) => return Ok(None),
+    };
+    let storage = profile::read_only(profile)?;
+    let branch = match project::get(&storage, &urn)? {
+        Some(project) => project.default_branch.to_string(),
+        None => return Ok(None),
+    };
+
+    if !all && repo.head()?.shorthand() != Some(branch.as_str()) {
+        return Ok(None);
+    }
+    let local = match repo.refname_to_id(&format!("refs/heads/{}", branch)) {
+        Ok(oid) => oid,
+        Err(_) => return Ok(None),
+    };
+    let remote = repo
+        .refname_to_id(&format!("refs/remotes/rad/{}", branch))
+        .ok();
+
+    if remote == Some(local) {
+        return Ok(None);
+    }
+    Ok(Some(branch))
+}
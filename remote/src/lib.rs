@@ -24,6 +24,13 @@ Usage
     rad remote rm <name | peer-id>
     rad remote ls
 
+    Manages the peer remotes of the working copy. A peer remote is a git remote
+    that fetches the branches of a project peer from the monorepo, eg. with
+    `git fetch <name>`. Adding a remote also tracks the peer.
+
+    `ls` lists the peer remotes of the working copy, with the name of the git
+    remote to fetch. A remote is removed by name, or by peer id.
+
 Examples
 
     rad remote add cloudhead hyn9diwfnytahjq8u3iw63h9jte1ydcatxax3saymwdxqu1zo645pe
@@ -143,6 +150,13 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
 
     match options.op {
         Operation::Add { name, peer, fetch } => {
+            if repo.find_remote(&name).is_ok() {
+                return Err(Error::WithHint {
+                    err: anyhow!("remote '{}' already exists", name),
+                    hint: "hint: choose another name, or remove the remote with `rad remote rm`",
+                }
+                .into());
+            }
             let mut remote = project::remote(&urn, &peer, &name)?;
             remote.save(&repo)?;
 
@@ -171,6 +185,9 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
                 "Remote {} successfully added",
                 term::format::highlight(&name)
             );
+            if !fetch {
+                term::tip!("Run `git fetch {}` to fetch the peer's branches.", name);
+            }
         }
         Operation::Remove { remote } => match find_remote(&remote, &storage, &repo, &urn)? {
             Some(name) => {
@@ -191,7 +208,7 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
                 .ok_or_else(|| anyhow!("project {} not found on local device", urn))?;
             let mut peers = HashSet::new();
 
-            for (_, peer) in git::remotes(&repo)? {
+            for (name, peer) in git::remotes(&repo)? {
                 if !peers.insert(peer) {
                     // Don't show duplicate peers.
                     continue;
@@ -205,12 +222,18 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
 
                 if let Some(person) = project::person(&storage, urn.clone(), &peer)? {
                     table.push([
+                        term::format::highlight(name),
                         term::format::bold(person.subject().name.to_string()),
                         term::format::tertiary(peer),
                         delegate,
                     ]);
                 } else {
-                    table.push([String::new(), term::format::tertiary(peer), delegate]);
+                    table.push([
+                        term::format::highlight(name),
+                        String::new(),
+                        term::format::tertiary(peer),
+                        delegate,
+                    ]);
                 }
             }
             table.render();
@@ -8,11 +8,12 @@ use radicle_common as common;
 use radicle_common::args::{Args, Error, Help};
 use radicle_common::cobs::patch::Patch;
 use radicle_common::tokio;
-use radicle_common::{cobs, keys, project, sync};
+use radicle_common::{cobs, git, keys, project, sync};
 use radicle_terminal as term;
 use radicle_terminal::patch::Comment;
 
-use cobs::patch::RevisionIx;
+use cobs::patch::{ChecklistItem, RevisionIx};
+use cobs::review::{parse_checklist, CHECKLIST_PATH};
 
 pub const HELP: Help = Help {
     name: "review",
@@ -21,17 +22,25 @@ pub const HELP: Help = Help {
     usage: r#"
 Usage
 
-    rad review [<id>] [--accept|--reject] [-m [<string>]] [<option>...]
+    rad review [<id>] [--accept|--reject|--request-changes] [-c [<string>]] [<option>...]
 
     To specify a patch to review, use the fully qualified patch id
     or an unambiguous prefix of it.
 
+    If the project has a review checklist in `.radicle/review-checklist.md`,
+    its list items are presented to be checked off, and their state is
+    stored with the review.
+
+    Requesting changes keeps the patch open, and flags it back to its author
+    until a new revision is published.
+
 Options
 
     -r, --revision <number>   Revision number to review, defaults to the latest
         --[no-]sync           Sync review to seed (default: sync)
-    -m, --message [<string>]  Provide a comment with the review (default: prompt)
+    -c, --comment [<string>]  Provide a comment with the review (default: prompt)
         --no-message          Don't provide a comment with the review
+        --no-checklist        Don't go through the project's review checklist
         --help                Print help
 "#,
 };
@@ -54,6 +63,7 @@ pub struct Options {
     pub sync: bool,
     pub verbose: bool,
     pub verdict: Option<Verdict>,
+    pub checklist: bool,
 }
 
 impl Args for Options {
@@ -67,6 +77,7 @@ impl Args for Options {
         let mut sync = true;
         let mut verbose = false;
         let mut verdict = None;
+        let mut checklist = true;
 
         while let Some(arg) = parser.next()? {
             match arg {
@@ -87,13 +98,16 @@ impl Args for Options {
                 Long("no-sync") => {
                     sync = false;
                 }
-                Long("message") | Short('m') => {
+                Long("comment") | Short('c') | Long("message") | Short('m') => {
                     let txt: String = parser.value()?.to_string_lossy().into();
                     message.append(&txt);
                 }
                 Long("no-message") => {
                     message = Comment::Blank;
                 }
+                Long("no-checklist") => {
+                    checklist = false;
+                }
                 Long("verbose") | Short('v') => {
                     verbose = true;
                 }
@@ -103,6 +117,9 @@ impl Args for Options {
                 Long("reject") if verdict.is_none() => {
                     verdict = Some(Verdict::Reject);
                 }
+                Long("request-changes") if verdict.is_none() => {
+                    verdict = Some(Verdict::RequestChanges);
+                }
                 Value(val) => {
                     let val = val
                         .to_str()
@@ -125,6 +142,7 @@ impl Args for Options {
                 revision,
                 verbose,
                 verdict,
+                checklist,
             },
             vec![],
         ))
@@ -132,7 +150,7 @@ impl Args for Options {
 }
 
 pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
-    let (urn, _) = project::cwd()
+    let (urn, repo) = project::cwd()
         .map_err(|_| anyhow!("this command must be run in the context of a project"))?;
     let profile = ctx.profile()?;
     let signer = term::signer(&profile)?;
@@ -145,17 +163,45 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
         .ok_or_else(|| anyhow!("couldn't find patch {} locally", options.id))?;
     let patch_id_pretty = term::format::tertiary(common::fmt::cob(&patch_id));
     let revision_ix = options.revision.unwrap_or_else(|| patch.version());
-    let _revision = patch
+    let base = patch
         .revisions
         .get(revision_ix)
+        .map(|r| r.base)
         .ok_or_else(|| anyhow!("revision R{} does not exist", revision_ix))?;
-    let message = options.message.get(REVIEW_HELP_MSG);
+    let message = options
+        .message
+        .get(REVIEW_HELP_MSG, term::patch::Validation::optional())?;
+
+    let checklist = if options.checklist {
+        checklist(&repo)?
+    } else {
+        Vec::new()
+    };
 
     patch.author.resolve(&storage).ok();
 
+    // Approvals record the default branch they were given against, so that merging is
+    // refused once it moves, see `rad merge`.
+    let target = match options.verdict {
+        Some(Verdict::Accept) => {
+            let project = project::get(&storage, &urn)?
+                .ok_or_else(|| anyhow!("couldn't load project {} from local state", urn))?;
+            let branch = project.default_branch.to_string();
+            let head = repo
+                .find_branch(&branch, git::BranchType::Local)
+                .ok()
+                .and_then(|b| b.get().target())
+                .unwrap_or(*base);
+
+            Some(head.into())
+        }
+        _ => None,
+    };
+
     let verdict_pretty = match options.verdict {
         Some(Verdict::Accept) => term::format::highlight("Accept"),
         Some(Verdict::Reject) => term::format::negative("Reject"),
+        Some(Verdict::RequestChanges) => term::format::yellow("Request changes on"),
         None => term::format::dim("Review"),
     };
     if !term::confirm(format!(
@@ -175,6 +221,8 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
         options.verdict,
         message,
         vec![],
+        checklist,
+        target,
     )?;
 
     match options.verdict {
@@ -192,6 +240,9 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
                 term::format::negative("rejected")
             );
         }
+        Some(Verdict::RequestChanges) => {
+            term::success!("Changes requested on patch {}", patch_id_pretty,);
+        }
         None => {
             term::success!("Patch {} reviewed", patch_id_pretty);
         }
@@ -212,3 +263,34 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Go through the project's review checklist, if it has one.
+fn checklist(repo: &common::git::Repository) -> anyhow::Result<Vec<ChecklistItem>> {
+    let path = match repo.workdir() {
+        Some(workdir) => workdir.join(CHECKLIST_PATH),
+        None => return Ok(Vec::new()),
+    };
+    let template = match std::fs::read_to_string(&path) {
+        Ok(template) => template,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+    let mut items = parse_checklist(&template);
+    if items.is_empty() {
+        return Ok(items);
+    }
+
+    let checked = term::checklist(
+        "Review checklist (space to toggle, enter to confirm)",
+        &items
+            .iter()
+            .map(|i| (i.text.clone(), i.checked))
+            .collect::<Vec<_>>(),
+    )
+    .ok_or_else(|| anyhow!("Patch review aborted"))?;
+
+    for (item, checked) in items.iter_mut().zip(checked) {
+        item.checked = checked;
+    }
+    Ok(items)
+}
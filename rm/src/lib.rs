@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::ffi::OsString;
 use std::fs;
 use std::str::FromStr;
@@ -6,8 +7,11 @@ use anyhow::anyhow;
 use anyhow::Context as _;
 use librad::git::Urn;
 
+use radicle_common as common;
 use radicle_common::args::{Args, Error, Help};
-use radicle_common::{profile, project};
+use radicle_common::registry::Registry;
+use radicle_common::store::Store;
+use radicle_common::{git, profile, project};
 use radicle_terminal as term;
 
 pub const HELP: Help = Help {
@@ -19,16 +23,25 @@ Usage
 
     rad rm <urn> [<option>...]
 
+    Removes a project from local storage: its refs, including those of tracked
+    peers and collaborative objects such as issues and patches, and the objects
+    only they reference. Working copies are left untouched.
+
+    Before anything is removed, a summary of what will be lost is shown and
+    confirmation is asked for.
+
 Options
 
-    -i        Prompt before removal
-    --help    Print help
+    --no-confirm    Don't ask for confirmation before removal
+    --no-prune      Don't prune unreachable objects from storage
+    --help          Print help
 "#,
 };
 
 pub struct Options {
     urn: Urn,
-    prompt: bool,
+    confirm: bool,
+    prune: bool,
 }
 
 impl Args for Options {
@@ -37,12 +50,20 @@ impl Args for Options {
 
         let mut parser = lexopt::Parser::from_args(args);
         let mut urn: Option<Urn> = None;
-        let mut prompt = false;
+        let mut confirm = true;
+        let mut prune = true;
 
         while let Some(arg) = parser.next()? {
             match arg {
+                // Nb. Confirmation is the default; kept for compatibility.
                 Short('i') => {
-                    prompt = true;
+                    confirm = true;
+                }
+                Long("no-confirm") => {
+                    confirm = false;
+                }
+                Long("no-prune") => {
+                    prune = false;
                 }
                 Long("help") => {
                     return Err(Error::Help.into());
@@ -62,7 +83,8 @@ impl Args for Options {
                 urn: urn.ok_or_else(|| {
                     anyhow!("a URN to remove must be provided; see `rad rm --help`")
                 })?,
-                prompt,
+                confirm,
+                prune,
             },
             vec![],
         ))
@@ -72,28 +94,164 @@ impl Args for Options {
 pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
     let profile = ctx.profile()?;
     let storage = profile::read_only(&profile)?;
+    let urn = &options.urn;
+    let project =
+        project::get(&storage, urn)?.ok_or_else(|| anyhow!("project {} does not exist", urn))?;
+    let monorepo = git::Repository::open_bare(profile.paths().git_dir())?;
+    let refs = namespace_refs(&monorepo, urn)?;
+    let registry = Registry::load(&profile)?;
 
-    if project::get(&storage, &options.urn)?.is_none() {
-        anyhow::bail!("project {} does not exist", options.urn);
-    }
-    term::warning("Experimental tool; use at your own risk!");
+    //
+    // Show what will be lost
+    //
+    let summary = Summary::new(&refs);
 
-    let monorepo = profile.paths().git_dir();
-    let namespace = monorepo
-        .join("refs")
-        .join("namespaces")
-        .join(options.urn.encode_id());
+    term::info!(
+        "{} {} {}",
+        term::format::bold("Removing"),
+        term::format::highlight(&project.name),
+        term::format::tertiary(urn)
+    );
+    term::blank();
+    term::indented(&format!(
+        "{} ref(s): {} branch(es), {} tag(s), {} collaborative object(s)",
+        refs.len(),
+        summary.branches,
+        summary.tags,
+        summary.cobs
+    ));
+    term::indented(&format!(
+        "{} tracked peer(s) besides you",
+        summary.peers.len()
+    ));
+
+    let head = project::get_local_head(&storage, urn, &project.default_branch)?;
+    if let Some(head) = head {
+        if registry.synced(urn) != Some(head) {
+            term::indented(&term::format::yellow(format!(
+                "{} ({}) may not have been pushed to seeds",
+                project.default_branch,
+                common::fmt::oid(&head)
+            )));
+        }
+    }
+    for path in registry
+        .get(urn)
+        .map(|e| e.workdirs.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .filter(|p| p.exists())
+    {
+        term::indented(&term::format::dim(format!(
+            "Working copy {} will be kept",
+            path.display()
+        )));
+    }
+    term::blank();
 
-    if !options.prompt
-        || term::confirm(format!(
-            "Are you sure you would like to delete {}?",
-            term::format::dim(namespace.display())
+    if options.confirm
+        && !term::confirm(format!(
+            "Remove {} from local storage? This can't be undone.",
+            term::format::highlight(&project.name)
         ))
     {
-        rad_untrack::execute(&options.urn, rad_untrack::Options { peer: None }, &profile)?;
+        anyhow::bail!("removal aborted by user");
+    }
+
+    //
+    // Remove project
+    //
+    rad_untrack::execute(urn, rad_untrack::Options { peer: None }, &profile)?;
+
+    // Nb. Refs may be packed, so they must be deleted through git rather than the
+    // file system.
+    for name in namespace_refs(&monorepo, urn)? {
+        if let Ok(mut r) = monorepo.find_reference(&name) {
+            r.delete()
+                .with_context(|| format!("failed to delete ref {}", name))?;
+        }
+    }
+    let namespace = profile
+        .paths()
+        .git_dir()
+        .join("refs")
+        .join("namespaces")
+        .join(urn.encode_id());
+    if namespace.exists() {
         fs::remove_dir_all(namespace)?;
-        term::success!("Successfully removed project {}", options.urn);
+    }
+
+    let mut registry = registry;
+    if registry.remove(urn).is_some() {
+        registry.save(&profile)?;
+    }
+    term::success!(
+        "Removed {} ref(s) of project {}",
+        refs.len(),
+        term::format::highlight(&project.name)
+    );
+
+    if options.prune {
+        let spinner = term::spinner("Pruning unreachable objects...");
+        match git::git(profile.paths().git_dir(), ["gc", "--prune=now", "--quiet"]) {
+            Ok(_) => spinner.finish(),
+            Err(err) => {
+                spinner.failed();
+                term::warning(&format!("Could not prune objects: {}", err));
+            }
+        }
     }
 
     Ok(())
 }
+
+/// Refs of a project, in its namespace.
+fn namespace_refs(repo: &git::Repository, urn: &Urn) -> anyhow::Result<Vec<String>> {
+    let glob = format!("refs/namespaces/{}/*", urn.encode_id());
+    let mut names = Vec::new();
+
+    for r in repo.references_glob(&glob)? {
+        if let Some(name) = r?.name() {
+            names.push(name.to_owned());
+        }
+    }
+    Ok(names)
+}
+
+/// Summary of a project's refs.
+#[derive(Default)]
+struct Summary {
+    branches: usize,
+    tags: usize,
+    cobs: usize,
+    peers: HashSet<String>,
+}
+
+impl Summary {
+    fn new(refs: &[String]) -> Self {
+        let mut summary = Self::default();
+
+        for name in refs {
+            // Skip `refs/namespaces/<id>/`.
+            let name = name.splitn(4, '/').nth(3).unwrap_or_default();
+            let name = if let Some(rest) = name.strip_prefix("refs/remotes/") {
+                let mut parts = rest.splitn(2, '/');
+                if let Some(peer) = parts.next() {
+                    summary.peers.insert(peer.to_owned());
+                }
+                parts.next().unwrap_or_default()
+            } else {
+                name.strip_prefix("refs/").unwrap_or(name)
+            };
+
+            if name.starts_with("heads/") {
+                summary.branches += 1;
+            } else if name.starts_with("tags/") {
+                summary.tags += 1;
+            } else if name.starts_with("cobs/") {
+                summary.cobs += 1;
+            }
+        }
+        summary
+    }
+}
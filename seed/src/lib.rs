@@ -0,0 +1,515 @@
+use std::convert::TryFrom;
+use std::ffi::OsString;
+use std::net::ToSocketAddrs;
+use std::str::FromStr;
+use std::time;
+
+use anyhow::anyhow;
+
+use radicle_common as common;
+use radicle_common::args::{Args, Error, Help};
+use radicle_common::fingerprint::KnownSeeds;
+use radicle_common::seed::{self, Address, Protocol, CONFIG_SEED_KEY};
+use radicle_common::store::Store;
+use radicle_common::{git, keys, profile, project, proxy, sync};
+use radicle_terminal as term;
+use radicle_terminal::format::Indicator;
+
+pub const HELP: Help = Help {
+    name: "seed",
+    description: env!("CARGO_PKG_DESCRIPTION"),
+    version: env!("CARGO_PKG_VERSION"),
+    usage: r#"
+Usage
+
+    rad seed list
+    rad seed get
+    rad seed set <seed>...
+    rad seed unset [<seed>]
+    rad seed ping <seed>
+    rad seed fingerprint [list]
+    rad seed fingerprint pin <seed>
+    rad seed fingerprint forget <host>
+
+    Manages the seeds of the current repository. Seeds are stored in the
+    repository's git config, under `rad.seed`, and are used by `rad sync`,
+    `rad patch` and other commands that sync with seeds. When no seeds are
+    set, the profile's default seeds are used.
+
+    A seed is given as a URL, eg. `rad://<peer-id>@seed.acme.com:8776`, or as
+    `<peer-id>@<host>:<port>`. When the peer id is omitted, eg.
+    `rad://seed.acme.com`, it is looked up through the seed's HTTP API.
+
+    Onion seeds, eg. `rad://<peer-id>@<address>.onion:8776`, are reached
+    through the Tor daemon's SOCKS proxy, `socks5h://127.0.0.1:9050` unless
+    `network.tor-proxy` is set in the profile config. Seeds in the profile
+    config can also have a `proxy` of their own. Their HTTP API and git over
+    HTTP go through the proxy; peer-to-peer sync skips them, as it can't.
+
+    `set` replaces the seeds of the repository, `unset` removes the given
+    seed, or all seeds. `get` prints the URLs of the configured seeds, one per
+    line, and fails if none are set.
+
+    `ping` checks that a seed is reachable and compatible: its address must
+    resolve, and its HTTP API must answer with the expected peer id. The API's
+    response time is reported. When run in a project, it also checks that the
+    seed has the project, and your refs of it. The command fails if any check
+    fails.
+
+    `fingerprint` manages the peer ids pinned for seed hosts. The first time a
+    seed is synced with, its peer id is pinned, like SSH's `known_hosts`. If
+    the seed's peer id changes later, syncing warns loudly, or skips the seed
+    if the profile config has `seed-pinning = "block"` in its `[policy]`
+    table (it can also be `"warn"`, the default, or `"off"`). `pin` pins the
+    given seed's peer id, replacing any previous one, and `forget` removes a
+    pin, so that the seed is pinned again on the next sync.
+
+Options
+
+    --help    Print help
+"#,
+};
+
+#[derive(Debug)]
+pub enum Operation {
+    List,
+    Get,
+    Set { seeds: Vec<String> },
+    Unset { seed: Option<String> },
+    Ping { seed: String },
+    Fingerprint(Fingerprint),
+}
+
+#[derive(Debug)]
+pub enum Fingerprint {
+    List,
+    Pin { seed: String },
+    Forget { host: String },
+}
+
+#[derive(Debug)]
+pub struct Options {
+    pub op: Operation,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut op: Option<String> = None;
+        let mut values: Vec<String> = Vec::new();
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("help") => {
+                    return Err(Error::Help.into());
+                }
+                Value(val) if op.is_none() => {
+                    op = Some(val.to_string_lossy().to_string());
+                }
+                Value(val) => {
+                    values.push(val.to_string_lossy().to_string());
+                }
+                _ => {
+                    return Err(anyhow!(arg.unexpected()));
+                }
+            }
+        }
+
+        let op = match op.as_deref() {
+            None | Some("ls") | Some("list") if values.is_empty() => Operation::List,
+            Some("get") if values.is_empty() => Operation::Get,
+            Some("set") => {
+                if values.is_empty() {
+                    anyhow::bail!("at least one seed must be specified");
+                }
+                Operation::Set { seeds: values }
+            }
+            Some("unset") if values.len() <= 1 => Operation::Unset { seed: values.pop() },
+            Some("ping") if values.len() == 1 => Operation::Ping {
+                seed: values.remove(0),
+            },
+            Some("fingerprint") => match values.first().map(|v| v.as_str()) {
+                None | Some("ls" | "list") if values.len() <= 1 => {
+                    Operation::Fingerprint(Fingerprint::List)
+                }
+                Some("pin") if values.len() == 2 => Operation::Fingerprint(Fingerprint::Pin {
+                    seed: values.remove(1),
+                }),
+                Some("forget") if values.len() == 2 => {
+                    Operation::Fingerprint(Fingerprint::Forget {
+                        host: values.remove(1),
+                    })
+                }
+                Some("ls" | "list" | "pin" | "forget") => return Err(Error::Usage.into()),
+                Some(unknown) => anyhow::bail!("unknown fingerprint operation '{}'", unknown),
+            },
+            Some("ls" | "list" | "get" | "unset" | "ping") => return Err(Error::Usage.into()),
+            Some(unknown) => anyhow::bail!("unknown operation '{}'", unknown),
+            None => return Err(Error::Usage.into()),
+        };
+
+        Ok((Options { op }, vec![]))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    if let Operation::Ping { seed } = &options.op {
+        return ping(parse(seed)?, ctx);
+    }
+    if let Operation::Fingerprint(op) = &options.op {
+        return fingerprint(op, ctx);
+    }
+    let repo = git::repository()?;
+
+    match options.op {
+        Operation::List => {
+            let seeds = seed::get_seeds(&repo)?;
+
+            if seeds.is_empty() {
+                let profile = ctx.profile()?;
+                let defaults = sync::seeds(&profile)?;
+
+                term::info!(
+                    "{}",
+                    term::format::italic("No seeds set, using the profile's default seeds:")
+                );
+                term::blank();
+
+                let mut table = term::Table::default();
+                for seed in defaults.iter() {
+                    table.push([
+                        term::format::highlight(seed.label.clone().unwrap_or_default()),
+                        term::format::tertiary(common::fmt::peer(&seed.peer)),
+                        term::format::dim(&seed.addrs),
+                    ]);
+                }
+                table.render();
+            } else {
+                let mut table = term::Table::default();
+                for addr in &seeds {
+                    table.push([
+                        term::format::highlight(&addr.host),
+                        match addr.peer() {
+                            Some(peer) => term::format::tertiary(common::fmt::peer(&peer)),
+                            None => term::format::negative("no peer id"),
+                        },
+                        term::format::dim(addr),
+                    ]);
+                }
+                table.render();
+            }
+        }
+        Operation::Get => {
+            let seeds = seed::get_seeds(&repo)?;
+
+            if seeds.is_empty() {
+                anyhow::bail!("`{}` is not set", CONFIG_SEED_KEY);
+            }
+            for addr in seeds {
+                term::print(addr);
+            }
+        }
+        Operation::Set { seeds } => {
+            let mut addrs = Vec::new();
+
+            let known = KnownSeeds::load(&ctx.profile()?)?;
+
+            for value in &seeds {
+                let addr = resolve(parse(value)?)?;
+
+                if let (Some(pin), Some(peer)) = (known.get(&addr.host), addr.peer()) {
+                    if pin.peer() != Some(peer) {
+                        term::warning(&format!(
+                            "Seed {} is pinned to peer {}, not {}. Syncing with it will warn, or skip it.",
+                            addr.host,
+                            pin.peer,
+                            common::fmt::peer(&peer)
+                        ));
+                    }
+                }
+
+                if !addrs.contains(&addr) {
+                    addrs.push(addr);
+                }
+            }
+            seed::set_seeds(&repo, &addrs)?;
+
+            for addr in &addrs {
+                term::success!("Seed {} set", term::format::highlight(addr));
+            }
+        }
+        Operation::Unset { seed: None } => {
+            seed::set_seeds(&repo, &[])?;
+            term::success!("Seeds unset, the profile's default seeds will be used");
+        }
+        Operation::Ping { .. } | Operation::Fingerprint(_) => unreachable!(),
+        Operation::Unset { seed: Some(value) } => {
+            let target = parse(&value)?;
+            let mut seeds = seed::get_seeds(&repo)?;
+            let before = seeds.len();
+
+            seeds.retain(|s| {
+                !(s.host == target.host && (target.peer().is_none() || s.peer() == target.peer()))
+            });
+            if seeds.len() == before {
+                anyhow::bail!("seed '{}' is not set", value);
+            }
+            seed::set_seeds(&repo, &seeds)?;
+
+            term::success!("Seed {} unset", term::format::highlight(&value));
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that a seed is reachable and compatible, and whether it has the current project.
+fn ping(addr: Address, ctx: impl term::Context) -> anyhow::Result<()> {
+    let url = seed::api_url(&addr.host.to_string())?;
+    let mut table = term::Table::default();
+    let mut failed = 0;
+    let mut check = |name: &str, result: Result<String, String>| {
+        let (symbol, detail) = match result {
+            Ok(detail) => (
+                term::format::positive(term::format::symbol(Indicator::Success)),
+                detail,
+            ),
+            Err(detail) => {
+                failed += 1;
+                (
+                    term::format::negative(term::format::symbol(Indicator::Failure)),
+                    term::format::negative(detail),
+                )
+            }
+        };
+        table.push([symbol, term::format::bold(name), detail]);
+    };
+
+    term::info!("Checking seed {}...", term::format::highlight(&addr));
+    term::blank();
+
+    let endpoint = format!("{}:{}", addr.host, addr.port());
+    check(
+        "address",
+        // Nb. Hosts behind a proxy, eg. onion services, are resolved by the proxy.
+        match proxy::for_seed(&addr.host.to_string()) {
+            Some(via) => Ok(term::format::dim(format!("{} via {}", endpoint, via))),
+            None => match endpoint.to_socket_addrs() {
+                Ok(addrs) => Ok(term::format::dim(
+                    addrs.map(|a| a.to_string()).collect::<Vec<_>>().join(", "),
+                )),
+                Err(err) => Err(format!("{} did not resolve: {}", endpoint, err)),
+            },
+        },
+    );
+
+    let start = time::Instant::now();
+    let api = seed::get_version(url.clone());
+    let latency = start.elapsed();
+    let reachable = api.is_ok();
+    check(
+        "api",
+        match api {
+            Ok(version) => Ok(format!(
+                "{} {}",
+                term::format::dim(format!("responded in {}ms", latency.as_millis())),
+                term::format::dim(version.map(|v| format!("(v{})", v)).unwrap_or_default()),
+            )),
+            Err(err) => Err(format!("unreachable: {}", err)),
+        },
+    );
+    if !reachable {
+        table.render();
+        anyhow::bail!("seed {} is unreachable", addr.host);
+    }
+
+    let peer = seed::get_seed_id(url.clone());
+    let seed_id = peer.as_ref().ok().copied();
+    check(
+        "peer id",
+        match (peer, addr.peer()) {
+            (Ok(id), Some(expected)) if id != expected => Err(format!(
+                "seed is {}, expected {}",
+                common::fmt::peer(&id),
+                common::fmt::peer(&expected)
+            )),
+            (Ok(id), _) => Ok(term::format::tertiary(common::fmt::peer(&id))),
+            (Err(err), _) => Err(format!("incompatible API: {}", err)),
+        },
+    );
+
+    let profile = ctx.profile()?;
+    let known = KnownSeeds::load(&profile)?;
+    check(
+        "pin",
+        match (known.get(&addr.host), seed_id) {
+            (None, _) => Ok(term::format::dim("not pinned, will be on first sync")),
+            (Some(pin), Some(id)) if pin.peer() != Some(id) => Err(format!(
+                "seed is pinned to {}, run `rad seed fingerprint pin` if the change is expected",
+                pin.peer
+            )),
+            (Some(pin), _) => Ok(term::format::dim(format!(
+                "pinned {}",
+                term::format::timestamp(pin.added, false)
+            ))),
+        },
+    );
+
+    if let Ok((urn, _)) = project::cwd() {
+        let storage = profile::read_only(&profile)?;
+        let me = *storage.peer_id();
+
+        check(
+            "project",
+            match seed::has_project(url.clone(), &urn) {
+                Ok(true) => Ok(term::format::dim(&urn)),
+                Ok(false) => Err(String::from("not replicated, run `rad sync` to push it")),
+                Err(err) => Err(format!("couldn't look up {}: {}", urn, err)),
+            },
+        );
+        check(
+            "your refs",
+            match seed::get_remotes(url, &urn) {
+                Ok(remotes) if remotes.iter().any(|r| r.id == me) => {
+                    Ok(term::format::dim("replicated"))
+                }
+                Ok(_) => Err(String::from("not replicated, run `rad sync` to push them")),
+                Err(err) => Err(format!("couldn't look up remotes: {}", err)),
+            },
+        );
+    }
+    table.render();
+
+    if failed > 0 {
+        anyhow::bail!("{} check(s) failed for seed {}", failed, addr.host);
+    }
+    Ok(())
+}
+
+/// Inspect and manage pinned seed peer ids.
+fn fingerprint(op: &Fingerprint, ctx: impl term::Context) -> anyhow::Result<()> {
+    let profile = ctx.profile()?;
+    let mut known = KnownSeeds::load(&profile)?;
+
+    match op {
+        Fingerprint::List => {
+            if known.seeds.is_empty() {
+                term::print(term::format::italic("No seeds pinned."));
+                return Ok(());
+            }
+            let mut table = term::Table::default();
+            for (host, pin) in &known.seeds {
+                let ssh = pin
+                    .peer()
+                    .and_then(|p| keys::to_ssh_fingerprint(&p).ok())
+                    .unwrap_or_default();
+
+                table.push([
+                    term::format::highlight(host),
+                    term::format::tertiary(&pin.peer),
+                    term::format::secondary(ssh),
+                    term::format::dim(term::format::timestamp(pin.added, false)),
+                ]);
+            }
+            table.render();
+        }
+        Fingerprint::Pin { seed } => {
+            let addr = resolve(parse(seed)?)?;
+            let peer = addr
+                .peer()
+                .ok_or_else(|| anyhow!("seed '{}' has no peer id", seed))?;
+
+            if let Some(previous) = known.get(&addr.host).and_then(|p| p.peer()) {
+                if previous != peer {
+                    term::warning(&format!(
+                        "Replacing pinned peer {} of {}",
+                        common::fmt::peer(&previous),
+                        addr.host
+                    ));
+                }
+            }
+            known.pin(&addr.host, &peer);
+            known.save(&profile)?;
+
+            term::success!(
+                "Seed {} pinned to {}",
+                term::format::highlight(&addr.host),
+                term::format::tertiary(common::fmt::peer(&peer))
+            );
+        }
+        Fingerprint::Forget { host } => {
+            // Accept seed URLs and addresses too, eg. `rad://seed.acme.com`.
+            let host = parse(host).map(|a| a.host).unwrap_or_else(|_| host.clone());
+
+            if !known.forget(&host) {
+                anyhow::bail!("seed {} is not pinned", host);
+            }
+            known.save(&profile)?;
+
+            term::success!(
+                "Seed {} forgotten, it will be pinned again on the next sync",
+                term::format::highlight(&host)
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Parse a seed given as a URL or as `<peer-id>@<host>:<port>`, into a `rad://` address.
+fn parse(s: &str) -> anyhow::Result<Address> {
+    if let Ok(addr) = Address::from_str(s) {
+        return Ok(match addr.protocol {
+            Protocol::Link { .. } => addr,
+            // Git and API URLs name the seed's host, the peer id is looked up.
+            Protocol::Git { .. } | Protocol::Api { .. } => {
+                Address::new(addr.host, Protocol::Link { peer: None })
+            }
+        });
+    }
+    if let Ok(seed) = sync::Seed::<String>::from_str(s) {
+        let url = common::Url::parse(&format!("rad://{}@{}", seed.peer, seed.addrs))?;
+
+        return Address::try_from(url);
+    }
+    Err(Error::WithHint {
+        err: anyhow!("invalid seed '{}'", s),
+        hint: "hint: seeds are given as URLs, eg. `rad://seed.acme.com`, or as `<peer-id>@<host>:<port>`",
+    }
+    .into())
+}
+
+/// Look up the peer id of a seed address, if it has none.
+fn resolve(addr: Address) -> anyhow::Result<Address> {
+    if addr.peer().is_some() {
+        return Ok(addr);
+    }
+    let url = seed::api_url(&addr.host.to_string())?;
+    let mut spinner = term::spinner(format!(
+        "Looking up peer id of {}...",
+        term::format::highlight(&addr.host)
+    ));
+    let peer = match seed::get_seed_id(url) {
+        Ok(peer) => peer,
+        Err(err) => {
+            spinner.failed();
+            return Err(Error::WithHint {
+                err: anyhow!("couldn't get the peer id of {}: {}", addr.host, err),
+                hint: "hint: specify the seed as `<peer-id>@<host>:<port>`",
+            }
+            .into());
+        }
+    };
+    spinner.message(format!(
+        "Peer id of {} is {}",
+        term::format::highlight(&addr.host),
+        term::format::tertiary(common::fmt::peer(&peer))
+    ));
+    spinner.finish();
+
+    Ok(Address {
+        protocol: Protocol::Link { peer: Some(peer) },
+        ..addr
+    })
+}
@@ -2,8 +2,11 @@ use std::ffi::OsString;
 
 use anyhow::anyhow;
 
+use librad::PeerId;
+
 use radicle_common::args::{Args, Error, Help};
-use radicle_common::{keys, person, profile};
+use radicle_common::signer::{Backend, RAD_SIGNER};
+use radicle_common::{keychain, keys, person, profile};
 use radicle_terminal as term;
 
 pub const HELP: Help = Help {
@@ -15,6 +18,10 @@ Usage
 
     rad self [<option>...]
 
+    Shows who you are on this machine: your identity, device key, how
+    commands sign, and where your profile is stored. With an option, only
+    that value is shown.
+
 Options
 
     --name       Show name
@@ -109,10 +116,21 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
 }
 
 fn all(profile: &profile::Profile) -> anyhow::Result<()> {
-    term::info!("Profile {}", term::format::secondary(profile.id()));
+    let active = profile::active()?.map_or(false, |p| p.id() == profile.id());
+    term::info!(
+        "Profile {} {}",
+        term::format::secondary(profile.id()),
+        term::format::dim(if active {
+            "(active)".to_owned()
+        } else {
+            format!("(set with {})", profile::RAD_PROFILE)
+        })
+    );
 
     let mut table = term::Table::default();
     let storage = profile::read_only(profile)?;
+    let peer_id = storage.peer_id();
+    let mut keys = Vec::new();
 
     if let Some(urn) = storage.config()?.user()? {
         if let Some(person) = person::get(&storage, &urn)? {
@@ -120,12 +138,33 @@ fn all(profile: &profile::Profile) -> anyhow::Result<()> {
                 String::from("Name"),
                 term::format::tertiary(&person.subject().name),
             ]);
+            keys = person
+                .delegations()
+                .iter()
+                .map(|k| PeerId::from(*k))
+                .collect();
         }
         table.push([String::from("URN"), term::format::tertiary(&urn)]);
     }
 
-    let peer_id = storage.peer_id();
     table.push([String::from("Peer ID"), term::format::tertiary(&peer_id)]);
+    table.push([String::from("Signer"), signer(profile)]);
+
+    // Devices that can act on behalf of your identity.
+    for key in &keys {
+        table.push([
+            String::from("Identity key"),
+            if key == peer_id {
+                format!(
+                    "{} {}",
+                    term::format::tertiary(key),
+                    term::format::dim("(this device)")
+                )
+            } else {
+                term::format::tertiary(key)
+            },
+        ]);
+    }
 
     let ssh_short = keys::to_ssh_fingerprint(peer_id)?;
     table.push([
@@ -150,5 +189,48 @@ fn all(profile: &profile::Profile) -> anyhow::Result<()> {
 
     table.render_tree();
 
+    if !keys.is_empty() && !keys.contains(peer_id) {
+        term::blank();
+        term::warning("This device's key is not a key of your identity");
+    }
+
     Ok(())
 }
+
+/// How commands sign on behalf of this profile.
+fn signer(profile: &profile::Profile) -> String {
+    let backend = match Backend::from_env() {
+        Ok(backend) => backend,
+        Err(err) => return term::format::negative(err),
+    };
+    let key_file = || {
+        if std::env::var(keys::RAD_PASSPHRASE).is_ok() {
+            term::format::tertiary(format!(
+                "key file (passphrase from {})",
+                keys::RAD_PASSPHRASE
+            ))
+        } else if keychain::is_enabled(profile) {
+            term::format::tertiary("key file (passphrase from keychain)")
+        } else {
+            term::format::tertiary("key file (passphrase prompt)")
+        }
+    };
+
+    match keys::source(profile, backend) {
+        Ok(keys::Source::Agent) => term::format::positive("ssh-agent (key loaded)"),
+        Ok(keys::Source::KeyFile) if backend == Backend::Key => format!(
+            "{} {}",
+            key_file(),
+            term::format::dim(format!("(set with {})", RAD_SIGNER))
+        ),
+        Ok(keys::Source::KeyFile) => match keys::ssh_auth_sock() {
+            Ok(_) => format!(
+                "{} {}",
+                key_file(),
+                term::format::yellow("(key not in ssh-agent, run `rad auth`)")
+            ),
+            Err(_) => key_file(),
+        },
+        Err(err) => term::format::negative(format!("ssh-agent ({})", err)),
+    }
+}
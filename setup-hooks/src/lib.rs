@@ -0,0 +1,217 @@
+use std::ffi::OsString;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context as _};
+
+use radicle_common::args::{Args, Error, Help};
+use radicle_common::config::{Config, Policy, FILE_NAME_LOCAL};
+use radicle_common::project;
+use radicle_terminal as term;
+
+pub const HELP: Help = Help {
+    name: "setup-hooks",
+    description: env!("CARGO_PKG_DESCRIPTION"),
+    version: env!("CARGO_PKG_VERSION"),
+    usage: r#"
+Usage
+
+    rad setup-hooks [--force]
+
+    Installs `commit-msg` and `pre-push` hooks into the current working copy,
+    generated from the `[policy]` section of the project's `Radicle.toml`.
+    Re-run this command after changing the policy.
+
+    Example policy:
+
+        [policy]
+        signoff = true
+        max-subject-length = 72
+        forbidden-files = ["*.pem", ".env"]
+
+Options
+
+    --force     Overwrite existing hooks that weren't installed by rad
+    --help      Print help
+"#,
+};
+
+/// Marker identifying hooks generated by this command.
+const MARKER: &str = "# Generated by `rad setup-hooks`.";
+
+#[derive(Default, Debug)]
+pub struct Options {
+    pub force: bool,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut force = false;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("force") | Short('f') => {
+                    force = true;
+                }
+                Long("help") => {
+                    return Err(Error::Help.into());
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+
+        Ok((Options { force }, vec![]))
+    }
+}
+
+pub fn run(options: Options, _ctx: impl term::Context) -> anyhow::Result<()> {
+    let (_, repo) = project::cwd()
+        .map_err(|_| anyhow!("this command must be run in the context of a project"))?;
+    if repo.is_bare() {
+        anyhow::bail!("cannot install hooks in a bare repository");
+    }
+    let config = Config::local(&repo)?.ok_or_else(|| Error::WithHint {
+        err: anyhow!("no `{}` found in the working copy", FILE_NAME_LOCAL),
+        hint: "hint: add a `[policy]` section to the project's Radicle.toml, see `rad setup-hooks --help`",
+    })?;
+    let policy = config.policy;
+
+    if policy.is_empty() {
+        term::info!("No policy is configured, nothing to install.");
+        return Ok(());
+    }
+
+    let hooks = repo.path().join("hooks");
+    fs::create_dir_all(&hooks)?;
+
+    for (name, script) in [
+        ("commit-msg", commit_msg(&policy)),
+        ("pre-push", pre_push(&policy)?),
+    ] {
+        let path = hooks.join(name);
+
+        if let Some(script) = script {
+            install(&path, &script, options.force)?;
+            term::success!("Installed {} hook", term::format::highlight(name));
+        } else if is_generated(&path) {
+            fs::remove_file(&path)?;
+            term::success!("Removed {} hook", term::format::highlight(name));
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether the hook at the given path was generated by us.
+fn is_generated(path: &Path) -> bool {
+    fs::read_to_string(path)
+        .map(|s| s.contains(MARKER))
+        .unwrap_or(false)
+}
+
+fn install(path: &Path, script: &str, force: bool) -> anyhow::Result<()> {
+    if path.exists() && !is_generated(path) && !force {
+        return Err(Error::WithHint {
+            err: anyhow!("hook {} already exists", path.display()),
+            hint: "hint: use `--force` to overwrite it",
+        }
+        .into());
+    }
+    fs::write(path, script).with_context(|| format!("failed to write {}", path.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(())
+}
+
+/// Generate the `commit-msg` hook, if the policy has message rules.
+fn commit_msg(policy: &Policy) -> Option<String> {
+    if !policy.signoff && policy.max_subject_length.is_none() {
+        return None;
+    }
+    let mut script = format!("#!/bin/sh\n{}\n\nmsg=\"$1\"\n", MARKER);
+
+    if policy.signoff {
+        script.push_str(
+            r#"
+if ! grep -q '^Signed-off-by: ' "$msg"; then
+    echo "rad: commit message must have a 'Signed-off-by' trailer (see \`git commit -s\`)" >&2
+    exit 1
+fi
+"#,
+        );
+    }
+    if let Some(max) = policy.max_subject_length {
+        write!(
+            script,
+            r#"
+subject=$(grep -v '^#' "$msg" | head -n 1)
+if [ ${{#subject}} -gt {max} ]; then
+    echo "rad: commit subject must not be longer than {max} characters" >&2
+    exit 1
+fi
+"#,
+            max = max
+        )
+        .ok();
+    }
+    Some(script)
+}
+
+/// Generate the `pre-push` hook, if the policy has file rules.
+fn pre_push(policy: &Policy) -> anyhow::Result<Option<String>> {
+    if policy.forbidden_files.is_empty() {
+        return Ok(None);
+    }
+    // Patterns are inserted unquoted in a `case` statement, so that they are matched as globs.
+    for pattern in &policy.forbidden_files {
+        if let Some(c) = pattern
+            .chars()
+            .find(|c| !(c.is_ascii_alphanumeric() || "._-/*?[]".contains(*c)))
+        {
+            anyhow::bail!(
+                "invalid character '{}' in forbidden file pattern '{}'",
+                c,
+                pattern
+            );
+        }
+    }
+    let patterns = policy.forbidden_files.join("|");
+
+    Ok(Some(format!(
+        r#"#!/bin/sh
+{marker}
+
+zero=0000000000000000000000000000000000000000
+
+while read -r local_ref local_oid remote_ref remote_oid; do
+    if [ "$local_oid" = "$zero" ]; then
+        continue
+    fi
+    if [ "$remote_oid" = "$zero" ]; then
+        range="$local_oid --not --remotes"
+    else
+        range="$remote_oid..$local_oid"
+    fi
+    git log --format= --name-only $range | sort -u | while read -r file; do
+        case "$file" in
+            {patterns})
+                echo "rad: file '$file' may not be pushed ($local_ref)" >&2
+                exit 1
+                ;;
+        esac
+    done || exit 1
+done
+"#,
+        marker = MARKER,
+        patterns = patterns
+    )))
+}
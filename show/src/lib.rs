@@ -0,0 +1,265 @@
+use std::ffi::OsString;
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+use librad::git::Urn;
+use librad::PeerId;
+
+use radicle_common as common;
+use radicle_common::args::{Args, Error, Help};
+use radicle_common::{git, profile, project};
+use radicle_terminal as term;
+
+use syntect::easy::HighlightLines;
+use syntect::highlighting::ThemeSet;
+use syntect::parsing::SyntaxSet;
+use syntect::util::{as_24_bit_terminal_escaped, LinesWithEndings};
+
+pub const HELP: Help = Help {
+    name: "show",
+    description: env!("CARGO_PKG_DESCRIPTION"),
+    version: env!("CARGO_PKG_VERSION"),
+    usage: r#"
+Usage
+
+    rad show [<urn>] <path>[@<ref>] [--peer <peer-id>] [--raw]
+
+    Prints a file of a project, as found in storage, without a checkout.
+    The project defaults to the one of the current working directory.
+
+    The ref can be a branch name or a commit id, and defaults to the project's
+    default branch. Branches are looked up among your own branches, unless
+    `--peer` is given, in which case the peer's branches are used. If you
+    don't have the branch, the branch of a project delegate is used, eg.
+
+        rad show src/main.rs@master --peer hyb5to4rshftx4apgmu9s6wnsp4ddmp1mz6ijh4qqey7fb8wrpawxa
+
+    Commits must be reachable from the project's refs, or the peer's refs with `--peer`.
+
+Options
+
+    --peer <peer-id>   Show the file as seen by the given tracked peer
+    --raw              Print the file contents as-is, eg. for piping (default when not a terminal)
+    --help             Print help
+"#,
+};
+
+#[derive(Debug)]
+pub struct Options {
+    pub urn: Option<Urn>,
+    pub path: String,
+    pub rev: Option<String>,
+    pub peer: Option<PeerId>,
+    pub raw: bool,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut urn: Option<Urn> = None;
+        let mut target: Option<String> = None;
+        let mut peer: Option<PeerId> = None;
+        let mut raw = false;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("peer") => {
+                    let val = parser.value()?;
+                    let val = val.to_string_lossy();
+
+                    peer = Some(
+                        PeerId::from_str(&val)
+                            .map_err(|_| anyhow!("invalid <peer-id> '{}'", val))?,
+                    );
+                }
+                Long("raw") => {
+                    raw = true;
+                }
+                Long("help") => {
+                    return Err(Error::Help.into());
+                }
+                Value(val) if target.is_none() => {
+                    let val = val
+                        .to_str()
+                        .ok_or_else(|| anyhow!("path specified is not UTF-8"))?;
+
+                    match Urn::from_str(val) {
+                        Ok(val) if urn.is_none() => urn = Some(val),
+                        _ => target = Some(val.to_owned()),
+                    }
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+        let target = target.ok_or_else(|| anyhow!("a path to show must be provided"))?;
+        let (path, rev) = match target.rsplit_once('@') {
+            Some((path, rev)) if !rev.is_empty() => (path.to_owned(), Some(rev.to_owned())),
+            _ => (target, None),
+        };
+        let path = path.trim_start_matches('/').to_owned();
+
+        if path.is_empty() {
+            anyhow::bail!("a path to show must be provided");
+        }
+
+        Ok((
+            Options {
+                urn,
+                path,
+                rev,
+                peer,
+                raw,
+            },
+            vec![],
+        ))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let urn = match options.urn {
+        Some(urn) => urn,
+        None => {
+            let (urn, _) = project::cwd().map_err(|_| Error::WithHint {
+                err: anyhow!("a project URN must be provided outside of a project"),
+                hint: "hint: use `rad show <urn> <path>`",
+            })?;
+            urn
+        }
+    };
+    let profile = ctx.profile()?;
+    let storage = profile::read_only(&profile)?;
+    let project = project::get(&storage, &urn)?
+        .ok_or_else(|| anyhow!("couldn't load project {} from local state", urn))?;
+    let repo = git::Repository::open_bare(profile.paths().git_dir())?;
+
+    let rev = options
+        .rev
+        .clone()
+        .unwrap_or_else(|| project.default_branch.to_string());
+    let commit = resolve(&repo, &project, &rev, options.peer)?;
+    let entry = commit
+        .tree()?
+        .get_path(Path::new(&options.path))
+        .map_err(|_| {
+            anyhow!(
+                "path '{}' was not found at {} ({})",
+                options.path,
+                rev,
+                common::fmt::oid(&commit.id())
+            )
+        })?;
+    let object = entry.to_object(&repo)?;
+    let blob = object
+        .as_blob()
+        .ok_or_else(|| anyhow!("path '{}' is not a file", options.path))?;
+
+    if options.raw || !console::user_attended() {
+        std::io::stdout().write_all(blob.content())?;
+        return Ok(());
+    }
+    if blob.is_binary() {
+        anyhow::bail!(
+            "'{}' is a binary file, use `--raw` to print it anyway",
+            options.path
+        );
+    }
+    let content = String::from_utf8_lossy(blob.content());
+
+    term::info!(
+        "{} {} {}",
+        term::format::highlight(&options.path),
+        term::format::dim("@"),
+        term::format::secondary(format!("{} ({})", rev, common::fmt::oid(&commit.id())))
+    );
+    term::blank();
+    highlight(&options.path, &content)?;
+
+    Ok(())
+}
+
+/// Resolve a branch or commit of a project in storage.
+fn resolve<'r>(
+    repo: &'r git::Repository,
+    project: &project::Metadata,
+    rev: &str,
+    peer: Option<PeerId>,
+) -> anyhow::Result<git::Commit<'r>> {
+    let namespace = format!("refs/namespaces/{}/refs", project.urn.encode_id());
+    let mut candidates = Vec::new();
+
+    match peer {
+        Some(peer) => candidates.push(format!("{}/remotes/{}/heads/{}", namespace, peer, rev)),
+        None => {
+            candidates.push(format!("{}/heads/{}", namespace, rev));
+            for delegate in &project.remotes {
+                candidates.push(format!("{}/remotes/{}/heads/{}", namespace, delegate, rev));
+            }
+        }
+    }
+    for name in candidates {
+        if let Ok(r) = repo.find_reference(&name) {
+            return Ok(r.peel_to_commit()?);
+        }
+    }
+    // Not a branch, perhaps a commit. Storage is shared by all projects, so only commits
+    // reachable from the project refs, or the peer's refs, are considered.
+    let scope = match peer {
+        Some(peer) => format!("{}/remotes/{}/*", namespace, peer),
+        None => format!("{}/*", namespace),
+    };
+    if rev.len() >= 4 && rev.chars().all(|c| c.is_ascii_hexdigit()) {
+        if let Ok(commit) = repo
+            .revparse_single(rev)
+            .and_then(|obj| obj.peel_to_commit())
+        {
+            for r in repo.references_glob(&scope)?.flatten() {
+                let tip = match r.peel_to_commit() {
+                    Ok(tip) => tip.id(),
+                    Err(_) => continue,
+                };
+                if tip == commit.id() || repo.graph_descendant_of(tip, commit.id())? {
+                    return Ok(commit);
+                }
+            }
+        }
+    }
+
+    Err(Error::WithHint {
+        err: anyhow!("ref '{}' was not found in storage", rev),
+        hint: "hint: make sure the branch was published, or the peer is tracked, and run `rad sync --fetch`",
+    }
+    .into())
+}
+
+/// Print file contents with syntax highlighting, based on the file extension.
+fn highlight(path: &str, content: &str) -> anyhow::Result<()> {
+    let syntaxes = SyntaxSet::load_defaults_newlines();
+    let themes = ThemeSet::load_defaults();
+    let theme = &themes.themes["base16-ocean.dark"];
+    let syntax = Path::new(path)
+        .extension()
+        .and_then(|ext| syntaxes.find_syntax_by_extension(&ext.to_string_lossy()))
+        .or_else(|| {
+            content
+                .lines()
+                .next()
+                .and_then(|line| syntaxes.find_syntax_by_first_line(line))
+        })
+        .unwrap_or_else(|| syntaxes.find_syntax_plain_text());
+    let mut highlighter = HighlightLines::new(syntax, theme);
+    let mut stdout = std::io::stdout();
+
+    for line in LinesWithEndings::from(content) {
+        let ranges = highlighter.highlight_line(line, &syntaxes)?;
+        write!(stdout, "{}", as_24_bit_terminal_escaped(&ranges, false))?;
+    }
+    // Reset colors.
+    writeln!(stdout, "\x1b[0m")?;
+
+    Ok(())
+}
@@ -1,24 +1,32 @@
 #![allow(clippy::or_fun_call)]
-use std::convert::TryInto;
+use std::convert::TryFrom;
 use std::ffi::OsString;
 use std::iter;
 use std::str::FromStr;
+use std::{thread, time};
 
 use librad::git::Storage;
 use librad::git::Urn;
 use librad::profile::Profile;
 
+use radicle_common::allowlist::Allowlist;
 use radicle_common::args;
 use radicle_common::args::{Args, Error, Help};
 use radicle_common::nonempty::NonEmpty;
+use radicle_common::pin::{self, Pins};
+use radicle_common::store::Store;
 use radicle_common::sync::Mode;
-use radicle_common::{identity, keys, person, project, sync, tokio};
+use radicle_common::{git, identity, keys, lan, mdns, person, project, sync, tokio};
 use radicle_terminal as term;
 
 use anyhow::anyhow;
 use url::Url;
 
 pub const GATEWAY_HOST: &str = "app.radicle.network";
+/// How often storage is checked for updates, in watch mode.
+pub const WATCH_INTERVAL: time::Duration = time::Duration::from_secs(1);
+/// How long to look for peers on the local network, with `--local`.
+pub const DISCOVERY_WINDOW: time::Duration = time::Duration::from_secs(3);
 pub const HELP: Help = Help {
     name: "sync",
     description: env!("CARGO_PKG_DESCRIPTION"),
@@ -26,20 +34,95 @@ pub const HELP: Help = Help {
     usage: r#"
 Usage
 
-    rad sync [<urn> | <url>] [--seed <address>]... [<options>...]
-    rad sync --self [--seed <address>]...
+    rad sync [<urn> | <url>] [--seed <address>]... [--default-seeds] [--fetch] [<options>...]
+    rad sync [--ref <refspec>]... [--tags <glob>]... [<options>...]
+    rad sync --self [--seed <address>]... [--default-seeds]
+    rad sync --identity [<urn>] [--seed <address>]... [--default-seeds]
+    rad sync --watch [<urn>] [--debounce <secs>] [<options>...]
+    rad sync --local [<urn>] [--serve]
 
     If a <urn> is specified, seeds may be given via the `--seed` option.
     If a <url> is specified, the seed is implied.
     If neither is specified, the URN and seed of the current project is used.
     If the project has no configured seed, the active profile's default seed list is used.
 
+    By default, your refs are pushed to the seeds, and your tracked peers' refs
+    and collaborative objects are fetched from them. With `--fetch`, nothing is
+    pushed, which is what read-only followers of a project usually want.
+
+    With `--ref` and `--tags`, only the given refs of the working copy are
+    pushed to storage before syncing, eg. `--tags 'radicle-patch/*'`. Seeds
+    replicate the whole project, including collaborative objects.
+
+    When syncing with more than one seed, a summary of the result for each seed
+    is shown. The command fails if syncing failed with every seed. Up to `--jobs`
+    seeds are synced with at the same time.
+
+    Seeds that can't be reached, or that drop the connection, are retried up to
+    `--retries` times, waiting longer between each attempt.
+
+    When pushing, each seed fetches from you, negotiating which objects it
+    already has, so that only missing objects, eg. new collaborative object
+    changes, are sent. With `--verbose`, the objects each seed received are
+    compared with all the objects reachable from your refs.
+
+    With `--identity`, your personal identity and the project identity are
+    pushed to the seeds, so that key additions and delegate changes propagate
+    quickly. Nothing is fetched, and no refs of the working copy are pushed.
+    Nb. seeds replicate a project as a whole, so code branches that were
+    already pushed to storage are sent along with the identity.
+
+    With `--watch`, the command keeps running and pushes to the seeds whenever
+    your refs of the project change in storage, eg. after a `git push rad`.
+    Pushes happen once no more changes were seen for `--debounce` seconds.
+
+    With `--local`, no seeds are used: peers on the local network that
+    advertise the project over mDNS are discovered, and their refs are
+    fetched directly from them, eg. to collaborate in the same room without
+    internet access. Only peers you track, and the project's delegates, are
+    fetched from; others are listed. A peer's refs are only updated to the
+    ones it signed, and only its identity is updated if the project's
+    allowlist doesn't allow it. With `--serve`, the command then keeps
+    running, advertising the project and serving your refs of it with
+    `git daemon` on the interface used for mDNS, until interrupted. Nb. the
+    project is visible to anyone on the local network while it is served.
+
+    Seed HTTP APIs and git over HTTPS go through the proxy set with
+    `network.proxy` in the profile config, eg. `socks5h://127.0.0.1:9050`
+    for Tor, or else through `HTTPS_PROXY`, `HTTP_PROXY` and `ALL_PROXY`.
+    Peer-to-peer sync runs over QUIC, which can't be proxied: it fails,
+    unless `network.allow-direct` is set, in which case it connects directly
+    with a warning. Onion seeds, and seeds with a `proxy` of their own, are
+    skipped, see `rad seed`.
+
 Options
 
     --seed <address>    Sync to the given seed (may be specified multiple times)
+    --default-seeds     Also sync to the configured seeds when using `--seed`
+    --fetch             Only fetch from seeds, don't push anything
+    --ref <refspec>     Push the given ref or refspec to storage first (may be specified multiple times)
+    --tags <glob>       Push tags matching the glob to storage first (may be specified multiple times)
     --self              Sync your local identity only
+    --identity          Push your local identity and the project identity only
+    --jobs <n>          Sync with up to <n> seeds at the same time (default: 4)
+    --retries <n>       Retry unreachable seeds up to <n> times (default: 2)
+    --pin               Pin the project's delegate set after syncing, or review a changed one
+    --watch             Keep running, and push to seeds whenever refs change
+    --debounce <secs>   Seconds to wait for changes to settle in watch mode (default: 5)
+    --local             Fetch from peers on the local network instead of seeds
+    --serve             Keep serving the project to the local network, with `--local`
+    --unpin             Remove the project's pinned delegate set
+    --verbose, -v       Report how many objects each seed was sent, and how many it already had
     --help              Print help
 
+Pinning
+
+    When a project's delegate set is pinned, identity updates that add or remove
+    delegates are refused by every fetch, eg. `rad sync`, `rad pull` or
+    `rad track`. If the change is expected, run `rad sync --fetch --pin`: the
+    added and removed delegates are shown, and the new delegate set is pinned
+    once you confirm it.
+
 Seed addresses
 
     A seed address is of the form `<id>@<host>:<port>`.
@@ -50,13 +133,56 @@ Seed addresses
 "#,
 };
 
-#[derive(Default, Debug)]
+#[derive(Debug)]
 pub struct Options {
     pub origin: Option<identity::Origin>,
     pub seeds: Vec<sync::Seed<String>>,
+    pub default_seeds: bool,
+    pub refs: sync::Refs,
     pub mode: Mode,
     pub verbose: bool,
     pub sync_self: bool,
+    pub identity: bool,
+    pub pin: bool,
+    pub unpin: bool,
+    pub watch: Option<time::Duration>,
+    pub local: bool,
+    pub serve: bool,
+    pub jobs: usize,
+    pub retries: u32,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            origin: None,
+            seeds: Vec::new(),
+            default_seeds: false,
+            refs: sync::Refs::default(),
+            mode: Mode::default(),
+            verbose: false,
+            sync_self: false,
+            identity: false,
+            pin: false,
+            unpin: false,
+            watch: None,
+            local: false,
+            serve: false,
+            jobs: sync::DEFAULT_JOBS,
+            retries: sync::DEFAULT_RETRIES,
+        }
+    }
+}
+
+impl Options {
+    /// Options for syncing with seeds.
+    pub fn settings(&self) -> term::sync::Options {
+        term::sync::Options {
+            jobs: self.jobs,
+            retries: self.retries,
+            repin: self.pin,
+        }
+    }
 }
 
 impl Args for Options {
@@ -67,8 +193,20 @@ impl Args for Options {
         let mut verbose = false;
         let mut origin = None;
         let mut sync_self = false;
+        let mut identity = false;
+        let mut pin = false;
+        let mut unpin = false;
         let mut unparsed = Vec::new();
         let mut seeds = Vec::new();
+        let mut default_seeds = false;
+        let mut mode = Mode::default();
+        let mut refs = sync::Refs::default();
+        let mut watch = false;
+        let mut debounce = time::Duration::from_secs(5);
+        let mut local = false;
+        let mut serve = false;
+        let mut jobs = sync::DEFAULT_JOBS;
+        let mut retries = sync::DEFAULT_RETRIES;
 
         while let Some(arg) = parser.next()? {
             match arg {
@@ -78,9 +216,66 @@ impl Args for Options {
                 Long("help") => {
                     return Err(Error::Help.into());
                 }
+                Long("fetch") => {
+                    mode = Mode::Fetch;
+                }
+                Long("ref") => {
+                    refs = refs.refspec(&parser.value()?.to_string_lossy());
+                }
+                Long("tags") => {
+                    refs = refs.tags(&parser.value()?.to_string_lossy());
+                }
                 Long("self") => {
                     sync_self = true;
                 }
+                Long("identity") => {
+                    identity = true;
+                }
+                Long("default-seeds") => {
+                    default_seeds = true;
+                }
+                Long("pin") => {
+                    pin = true;
+                }
+                Long("unpin") => {
+                    unpin = true;
+                }
+                Long("watch") => {
+                    watch = true;
+                }
+                Long("local") => {
+                    local = true;
+                }
+                Long("serve") => {
+                    serve = true;
+                }
+                Long("debounce") => {
+                    let value = parser.value()?;
+                    let secs = value
+                        .to_string_lossy()
+                        .parse::<u64>()
+                        .map_err(|_| anyhow!("invalid value for `--debounce`: {:?}", value))?;
+
+                    debounce = time::Duration::from_secs(secs);
+                }
+                Long("jobs") | Short('j') => {
+                    let value = parser.value()?;
+
+                    jobs = value
+                        .to_string_lossy()
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|n| *n > 0)
+                        .ok_or_else(|| anyhow!("invalid value for `--jobs`: {:?}", value))?;
+                }
+                Long("retries") => {
+                    let value = parser.value()?;
+
+                    retries = value
+                        .to_string_lossy()
+                        .parse::<u32>()
+                        .map_err(|_| anyhow!("invalid value for `--retries`: {:?}", value))?;
+                }
                 Long("seed") => {
                     let value = parser.value()?;
                     let value = value.to_string_lossy();
@@ -121,13 +316,54 @@ impl Args for Options {
             );
         }
 
+        if !refs.is_empty() && matches!(mode, Mode::Fetch) {
+            anyhow::bail!("`--ref` and `--tags` cannot be used with `--fetch`");
+        }
+        if pin && unpin {
+            anyhow::bail!("`--pin` and `--unpin` cannot be used together");
+        }
+        if watch && (sync_self || matches!(mode, Mode::Fetch)) {
+            anyhow::bail!("`--watch` cannot be used with `--self` or `--fetch`");
+        }
+        if identity && (sync_self || watch || !refs.is_empty() || matches!(mode, Mode::Fetch)) {
+            anyhow::bail!(
+                "`--identity` cannot be used with `--self`, `--watch`, `--ref`, `--tags` or `--fetch`"
+            );
+        }
+
+        if serve && !local {
+            anyhow::bail!("`--serve` can only be used with `--local`");
+        }
+        if local
+            && (!seeds.is_empty()
+                || default_seeds
+                || sync_self
+                || identity
+                || watch
+                || !refs.is_empty()
+                || pin
+                || unpin)
+        {
+            anyhow::bail!("`--local` can only be used with `--serve` and `--fetch`");
+        }
+
         Ok((
             Options {
                 origin,
                 seeds,
-                mode: Mode::default(),
+                default_seeds,
+                refs,
+                mode,
                 sync_self,
+                identity,
+                pin,
+                unpin,
                 verbose,
+                watch: if watch { Some(debounce) } else { None },
+                local,
+                serve,
+                jobs,
+                retries,
             },
             unparsed,
         ))
@@ -144,10 +380,20 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
     } else {
         project::cwd().map(|(urn, _)| urn)?
     };
+    if options.local {
+        return sync_local(urn, &profile, &storage, options.serve);
+    }
 
     let seeds = if let Some(seed) = options.origin.as_ref().and_then(|o| o.seed.clone()) {
         NonEmpty::new(seed)
-    } else if let Ok(seeds) = options.seeds.clone().try_into() {
+    } else if let Ok(mut seeds) = NonEmpty::try_from(options.seeds.clone()) {
+        if options.default_seeds {
+            for seed in sync::seeds(&profile)? {
+                if !seeds.iter().any(|s| s.peer == seed.peer) {
+                    seeds.push(seed);
+                }
+            }
+        }
         seeds
     } else {
         sync::seeds(&profile)?
@@ -155,11 +401,180 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
 
     if options.sync_self {
         sync_self(&profile, seeds, storage, options, rt)
+    } else if options.identity {
+        sync_identity(urn, &profile, seeds, storage, options, rt)
+    } else if let Some(debounce) = options.watch {
+        let settings = options.settings();
+
+        if let Err(err) = sync(urn.clone(), &profile, seeds.clone(), storage, options, rt) {
+            term::warning(&format!("Sync failed: {}", err));
+        }
+        watch(urn, &profile, seeds, debounce, settings)
     } else {
         sync(urn, &profile, seeds, storage, options, rt)
     }
 }
 
+/// Fetch a project from peers on the local network, and with `serve`, keep serving it
+/// to them until interrupted.
+pub fn sync_local(
+    urn: Urn,
+    profile: &Profile,
+    storage: &Storage,
+    serve: bool,
+) -> anyhow::Result<()> {
+    let project = project::get(storage, &urn)?
+        .ok_or_else(|| anyhow!("couldn't load project {} from local state", urn))?;
+    let tracked = project::tracked(&project, storage)?;
+    let me = *storage.peer_id();
+
+    term::headline(&format!(
+        "Looking for 🌱 {} on the local network",
+        term::format::highlight(&project.name)
+    ));
+
+    let spinner = term::spinner("Discovering peers...");
+    let peers = match mdns::discover(&urn, DISCOVERY_WINDOW) {
+        Ok(peers) => peers,
+        Err(err) => return Err(spinner.error(err.into())),
+    };
+    let peers = peers
+        .into_iter()
+        .filter(|(_, ann)| ann.peer != me)
+        .collect::<Vec<_>>();
+    spinner.clear();
+
+    if peers.is_empty() {
+        term::info!("No peers found");
+    }
+    let allowlist = Allowlist::load(profile)?;
+    let mut table = term::Table::default();
+    let mut untracked = false;
+    for (addr, ann) in &peers {
+        let status = if !project.remotes.contains(&ann.peer) && !tracked.contains_key(&ann.peer) {
+            untracked = true;
+            term::format::dim("not tracked, skipped")
+        } else {
+            let data = allowlist.allows(&project, &ann.peer);
+
+            match lan::replicate(profile, storage, &urn, &ann.peer, addr, data) {
+                Ok(()) if data => term::format::positive("fetched"),
+                Ok(()) => term::format::positive("fetched identity, not allowed"),
+                Err(err) => term::format::negative(format!("failed: {}", err)),
+            }
+        };
+        table.push([
+            term::format::tertiary(radicle_common::fmt::peer(&ann.peer)),
+            term::format::dim(addr),
+            status,
+        ]);
+    }
+    table.render();
+
+    if untracked {
+        term::tip!("To fetch from a peer, track it first with `rad track <peer-id>`");
+    }
+    if !serve {
+        return Ok(());
+    }
+
+    let mut server = lan::serve(profile, &urn)?;
+    let announcement = mdns::Announcement {
+        urn: urn.clone(),
+        peer: me,
+        port: server.addr.port(),
+    };
+    let advertiser = thread::spawn(move || mdns::advertise(&announcement));
+
+    term::blank();
+    term::info!(
+        "Serving {} to the local network on {}. Press Ctrl-C to stop.",
+        term::format::highlight(&urn),
+        term::format::dim(server.addr)
+    );
+
+    loop {
+        thread::sleep(WATCH_INTERVAL);
+
+        if let Some(status) = server.daemon.try_wait()? {
+            anyhow::bail!("git daemon exited unexpectedly ({})", status);
+        }
+        server.refresh()?;
+
+        if advertiser.is_finished() {
+            return match advertiser.join() {
+                Ok(Err(err)) => Err(anyhow!("mDNS advertising failed: {}", err)),
+                _ => Err(anyhow!("mDNS advertising stopped unexpectedly")),
+            };
+        }
+    }
+}
+
+/// Push to seeds whenever the local refs of the project change in storage, until
+/// interrupted.
+pub fn watch(
+    urn: Urn,
+    profile: &Profile,
+    seeds: NonEmpty<sync::Seed<String>>,
+    debounce: time::Duration,
+    settings: term::sync::Options,
+) -> anyhow::Result<()> {
+    let rt = tokio::runtime::Runtime::new()?;
+    let signer = term::signer(profile)?;
+    let repo = git::Repository::open_bare(profile.paths().git_dir())?;
+    let mut debounce = sync::Debounce::new(debounce);
+    let mut last = sync::refs_snapshot(&repo, &urn, false)?;
+
+    term::blank();
+    term::headline(&format!(
+        "Watching 🌱 identity {} for changes",
+        term::format::highlight(&urn)
+    ));
+    term::info!(
+        "Changes will be pushed to {} seed(s). Press Ctrl-C to stop.",
+        term::format::dim(seeds.len())
+    );
+
+    loop {
+        thread::sleep(WATCH_INTERVAL);
+
+        let now = time::Instant::now();
+        let refs = sync::refs_snapshot(&repo, &urn, false)?;
+
+        if refs != last {
+            debounce.changed(now);
+            last = refs;
+        }
+        if !debounce.due(now) {
+            continue;
+        }
+        term::blank();
+
+        match term::sync::sync_with(
+            urn.clone(),
+            seeds.clone(),
+            Mode::Push,
+            settings,
+            profile,
+            signer.clone(),
+            &rt,
+        ) {
+            Ok(results) => {
+                let failed = results.iter().filter(|r| !r.is_ok()).count();
+                if failed > 0 {
+                    term::warning(&format!(
+                        "Push failed with {} of {} seed(s), retrying on the next change",
+                        failed,
+                        results.len()
+                    ));
+                }
+            }
+            // Keep watching: seeds may be reachable again by the next change.
+            Err(err) => term::warning(&format!("Push failed: {}", err)),
+        }
+    }
+}
+
 pub fn sync_self(
     profile: &Profile,
     seeds: NonEmpty<sync::Seed<String>>,
@@ -176,15 +591,124 @@ pub fn sync_self(
     ));
 
     let signer = term::signer(profile)?;
-    let _result = term::sync::sync(urn, seeds, options.mode, profile, signer, &rt)?;
+    let results = term::sync::sync_with(
+        urn,
+        seeds.clone(),
+        options.mode,
+        options.settings(),
+        profile,
+        signer,
+        &rt,
+    )?;
 
-    if options.verbose {
-        // TODO: When sync result is usable, output should go here.
+    summary(&seeds, &results)
+}
+
+/// Push the local identity and the project identity to seeds.
+pub fn sync_identity(
+    urn: Urn,
+    profile: &Profile,
+    seeds: NonEmpty<sync::Seed<String>>,
+    storage: Storage,
+    options: Options,
+    rt: tokio::runtime::Runtime,
+) -> anyhow::Result<()> {
+    let person = person::local(&storage)?;
+    let project = project::get(&storage, &urn)?
+        .ok_or_else(|| anyhow!("couldn't load project {} from local state", urn))?;
+
+    term::headline(&format!(
+        "Publishing 🌱 identities of {} to {} seed(s)",
+        term::format::highlight(&project.name),
+        term::format::dim(seeds.len())
+    ));
+    term::info!(
+        "Project {} has {} delegate(s)",
+        term::format::highlight(&urn),
+        term::format::dim(project.remotes.len())
+    );
+
+    let signer = term::signer(profile)?;
+    for urn in [person.urn(), urn] {
+        let results = term::sync::sync_with(
+            urn,
+            seeds.clone(),
+            Mode::Push,
+            options.settings(),
+            profile,
+            signer.clone(),
+            &rt,
+        )?;
+        summary(&seeds, &results)?;
     }
+    Ok(())
+}
+
+/// Print how many objects each seed received when pushing, against the objects reachable
+/// from our refs, which is what the seeds would receive without negotiating.
+fn transfer_summary(
+    profile: &Profile,
+    urn: &Urn,
+    results: &NonEmpty<sync::SyncResult>,
+) -> anyhow::Result<()> {
+    let repo = git::Repository::open_bare(profile.paths().git_dir())?;
+    let all = sync::reachable_objects(&repo, urn, &["rad/*", "heads/*", "tags/*", "cobs/*"])?;
+    let cobs = sync::reachable_objects(&repo, urn, &["cobs/*"])?;
+
+    term::info!(
+        "Your refs reach {} object(s), {} of them from collaborative objects",
+        term::format::bold(all),
+        term::format::bold(cobs)
+    );
+    for result in results.iter().filter(|r| matches!(r.push, Some(Ok(_)))) {
+        let seed = result
+            .seed
+            .label
+            .clone()
+            .unwrap_or_else(|| radicle_common::fmt::peer(&result.seed.peer));
+        let sent = result.received.as_ref().map_or(0, |t| t.total as usize);
+        let saved = all.saturating_sub(sent);
+
+        term::indented(&format!(
+            "{} received {} object(s){}, {} already on the seed ({}%)",
+            term::format::highlight(seed),
+            sent,
+            result
+                .received
+                .as_ref()
+                .and_then(|t| t.bytes.as_ref())
+                .map(|b| format!(" ({})", b))
+                .unwrap_or_default(),
+            term::format::positive(saved),
+            if all > 0 { saved * 100 / all } else { 100 },
+        ));
+    }
+    term::blank();
 
     Ok(())
 }
 
+/// Print a per-seed summary when syncing with several seeds, and fail if no seed
+/// could be synced with.
+fn summary(
+    seeds: &NonEmpty<sync::Seed<String>>,
+    results: &NonEmpty<sync::SyncResult>,
+) -> anyhow::Result<()> {
+    let failed = if seeds.len() > 1 {
+        term::blank();
+        term::sync::print_summary(seeds, results)
+    } else {
+        results
+            .iter()
+            .filter(|r| matches!(r.fetch, Some(Err(_))) || matches!(r.push, Some(Err(_))))
+            .count()
+    };
+    if failed == seeds.len() {
+        anyhow::bail!("sync failed with all {} seed(s)", seeds.len());
+    }
+    Ok(())
+}
+
 pub fn sync(
     urn: Urn,
     profile: &Profile,
@@ -194,24 +718,104 @@ pub fn sync(
     rt: tokio::runtime::Runtime,
 ) -> anyhow::Result<()> {
     term::headline(&format!(
-        "Syncing 🌱 identity {} with {} seed(s)",
+        "{} 🌱 identity {} {} {} seed(s)",
+        if matches!(options.mode, Mode::Fetch) {
+            "Fetching"
+        } else {
+            "Syncing"
+        },
         term::format::highlight(&urn),
+        if matches!(options.mode, Mode::Fetch) {
+            "from"
+        } else {
+            "with"
+        },
         term::format::dim(seeds.len())
     ));
 
+    // Nb. The allowlist is applied to tracking entries, which decide what is fetched.
+    if let Some(proj) = project::get(&storage, &urn)? {
+        let restricted = Allowlist::load(profile)?.enforce(&storage, &proj)?;
+        if restricted > 0 && options.verbose {
+            term::info!(
+                "Only fetching the identity of {} peer(s) not on the collaborator allowlist",
+                term::format::dim(restricted)
+            );
+        }
+    }
     let storage = storage.read_only();
+    let mut pins = Pins::load(profile)?;
+
+    if options.unpin {
+        if pins.unpin(&urn) {
+            pins.save(profile)?;
+            term::success!("Removed pinned delegate set for {}", urn);
+        } else {
+            term::info!("No pinned delegate set for {}", urn);
+        }
+    }
+
+    if !options.refs.is_empty() {
+        let mut spinner = term::spinner("Pushing refs to storage...");
+        match project::cwd() {
+            Ok((cwd, repo)) if cwd == urn => {
+                let workdir = repo
+                    .workdir()
+                    .ok_or_else(|| anyhow!("`--ref` and `--tags` require a working copy"))?;
+                if let Err(err) = options.refs.push(workdir) {
+                    spinner.failed();
+                    return Err(err);
+                }
+                spinner.message(format!(
+                    "Pushed {} refspec(s) to storage",
+                    options.refs.refspecs().len()
+                ));
+                spinner.finish();
+            }
+            _ => {
+                spinner.failed();
+                anyhow::bail!(
+                    "`--ref` and `--tags` must be used in a working copy of {}",
+                    urn
+                );
+            }
+        }
+    }
+
     let signer = term::signer(profile)?;
-    let _result = term::sync::sync(
+    let results = term::sync::sync_with(
         urn.clone(),
         seeds.clone(),
         options.mode,
+        options.settings(),
         profile,
         signer,
         &rt,
     )?;
+    summary(&seeds, &results)?;
     term::blank();
 
+    // Nb. A changed delegate set of a pinned project was already reviewed when fetching.
+    if options.pin {
+        if let Some(proj) = project::get(&storage, &urn)? {
+            if pins.verify_project(&proj) == pin::Verification::Unpinned {
+                pins.pin(&urn, &proj.remotes);
+                pins.save(profile)?;
+
+                term::success!(
+                    "Pinned {} delegate(s) for {}",
+                    term::format::dim(proj.remotes.len()),
+                    term::format::highlight(&proj.name)
+                );
+                term::blank();
+            }
+        }
+    }
+
     if options.verbose {
+        if !matches!(options.mode, Mode::Fetch) {
+            transfer_summary(profile, &urn, &results)?;
+        }
         // TODO: When sync result is usable, output should go here.
         // TODO: Depending on the result, we can show `~` as in partial success, `ok` as in total
         //       success and `!!` as in no success.
@@ -219,6 +823,11 @@ pub fn sync(
         // TODO: Success with no refs updated can mean the server is not tracking us.
     }
 
+    if matches!(options.mode, Mode::Fetch) {
+        term::success!("Fetched {} from seeds", term::format::highlight(&urn));
+        return Ok(());
+    }
+
     if let Some(proj) = project::get(&storage, &urn)? {
         let peer_id = storage.peer_id();
 
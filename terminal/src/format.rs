@@ -1,3 +1,7 @@
+use chrono::{Local, TimeZone};
+
+use radicle_common::cobs::Timestamp;
+
 pub use dialoguer::console::style;
 
 pub fn negative<D: std::fmt::Display>(msg: D) -> String {
@@ -55,6 +59,134 @@ pub fn dim<D: std::fmt::Display>(input: D) -> String {
     style(input).dim().to_string()
 }
 
+/// Format a tip, as printed by [`crate::tip!`].
+pub fn tip<D: std::fmt::Display>(input: D) -> String {
+    format!("{} {}", style("=>").blue(), dim(input))
+}
+
 pub fn italic<D: std::fmt::Display>(input: D) -> String {
     style(input).italic().dim().to_string()
 }
+
+/// Format a timestamp relative to now, eg. `3 days ago`.
+pub fn timeago(timestamp: Timestamp) -> String {
+    timestamp.to_string()
+}
+
+/// Format a timestamp as a date and time in the local timezone, eg.
+/// `2022-06-01 14:03 +02:00`. The timezone is taken from `TZ`, or the system's.
+pub fn datetime(timestamp: Timestamp) -> String {
+    Local
+        .timestamp(timestamp.as_secs() as i64, 0)
+        .format("%Y-%m-%d %H:%M %:z")
+        .to_string()
+}
+
+/// Format a timestamp relative to now, or as a date and time if `absolute` is set.
+pub fn timestamp(timestamp: Timestamp, absolute: bool) -> String {
+    if absolute {
+        datetime(timestamp)
+    } else {
+        timeago(timestamp)
+    }
+}
+
+/// Truncate the input to the given display width, ending it with an ellipsis if it
+/// was truncated. Styles are preserved.
+pub fn truncate<D: std::fmt::Display>(input: D, width: usize) -> String {
+    console::truncate_str(&input.to_string(), width, "…").into_owned()
+}
+
+/// Environment variable selecting the state indicator symbols: `unicode` (default),
+/// `ascii` or `none`.
+pub const RAD_SYMBOLS: &str = "RAD_SYMBOLS";
+
+/// A state, eg. of a patch or a check. States are shown with a symbol as well as a
+/// color, so that they can be told apart without color perception.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Indicator {
+    Open,
+    Draft,
+    Merged,
+    Closed,
+    Success,
+    Failure,
+    Pending,
+}
+
+/// Symbols used for state indicators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Symbols {
+    pub open: &'static str,
+    pub draft: &'static str,
+    pub merged: &'static str,
+    pub closed: &'static str,
+    pub success: &'static str,
+    pub failure: &'static str,
+    pub pending: &'static str,
+}
+
+impl Symbols {
+    pub const UNICODE: Self = Self {
+        open: "○",
+        draft: "◌",
+        merged: "●",
+        closed: "⊘",
+        success: "✓",
+        failure: "✗",
+        pending: "…",
+    };
+    pub const ASCII: Self = Self {
+        open: "o",
+        draft: "-",
+        merged: "*",
+        closed: "x",
+        success: "+",
+        failure: "!",
+        pending: "~",
+    };
+    pub const NONE: Self = Self {
+        open: "",
+        draft: "",
+        merged: "",
+        closed: "",
+        success: "",
+        failure: "",
+        pending: "",
+    };
+
+    /// Symbols selected with [`RAD_SYMBOLS`].
+    pub fn current() -> Self {
+        match std::env::var(RAD_SYMBOLS).as_deref() {
+            Ok("ascii") => Self::ASCII,
+            Ok("none") => Self::NONE,
+            _ => Self::UNICODE,
+        }
+    }
+
+    pub fn get(&self, indicator: Indicator) -> &'static str {
+        match indicator {
+            Indicator::Open => self.open,
+            Indicator::Draft => self.draft,
+            Indicator::Merged => self.merged,
+            Indicator::Closed => self.closed,
+            Indicator::Success => self.success,
+            Indicator::Failure => self.failure,
+            Indicator::Pending => self.pending,
+        }
+    }
+}
+
+/// The symbol of a state indicator.
+pub fn symbol(indicator: Indicator) -> &'static str {
+    Symbols::current().get(indicator)
+}
+
+/// A label prefixed with the symbol of its state, eg. `✓ pass`. Colors are left to the
+/// caller.
+pub fn labeled<D: std::fmt::Display>(indicator: Indicator, label: D) -> String {
+    match symbol(indicator) {
+        "" => label.to_string(),
+        symbol => format!("{} {}", symbol, label),
+    }
+}
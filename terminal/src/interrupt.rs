@@ -0,0 +1,114 @@
+//! Interrupt (Ctrl-C) handling.
+//!
+//! When a command is interrupted, the terminal state is restored, registered cleanup
+//! actions are run, and whatever was already published is reported to the user.
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+
+use signal_hook::consts::{SIGINT, SIGTERM};
+use signal_hook::iterator::Signals;
+
+use crate as term;
+
+/// Exit code used when interrupted, following the shell convention of `128 + SIGINT`.
+pub const EXIT_CODE: i32 = 130;
+
+type Action = Box<dyn FnOnce() + Send>;
+
+/// Cleanup actions, run in reverse order of registration.
+static ACTIONS: Mutex<Vec<(usize, Action)>> = Mutex::new(Vec::new());
+/// Things that were published, eg. refs pushed or patches created.
+static PUBLISHED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+/// Next cleanup action id.
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+/// Number of active deferral scopes. See [`deferred`].
+static DEFERRED: AtomicUsize = AtomicUsize::new(0);
+/// Whether an interrupt was received while deferred.
+static PENDING: AtomicBool = AtomicBool::new(false);
+
+/// Install the interrupt handler. Should be called once per process.
+pub fn install() -> anyhow::Result<()> {
+    let mut signals = Signals::new([SIGINT, SIGTERM])?;
+
+    thread::spawn(move || {
+        for _ in signals.forever() {
+            if DEFERRED.load(Ordering::SeqCst) > 0 {
+                PENDING.store(true, Ordering::SeqCst);
+            } else {
+                interrupted();
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// A registered cleanup action. Dropping this unregisters the action, without running it.
+#[must_use]
+pub struct Cleanup {
+    id: usize,
+}
+
+impl Drop for Cleanup {
+    fn drop(&mut self) {
+        lock(&ACTIONS).retain(|(id, _)| *id != self.id);
+    }
+}
+
+/// Register an action to run if the process is interrupted, until the returned
+/// guard is dropped.
+pub fn on_interrupt(action: impl FnOnce() + Send + 'static) -> Cleanup {
+    let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+    lock(&ACTIONS).push((id, Box::new(action)));
+
+    Cleanup { id }
+}
+
+/// Record that something was published, so that it can be reported if the process
+/// is interrupted later on.
+pub fn published(what: impl ToString) {
+    lock(&PUBLISHED).push(what.to_string());
+}
+
+/// Run a function, deferring interrupts until it returns. This is useful when handing
+/// the terminal over to another process, eg. an editor.
+pub fn deferred<T>(f: impl FnOnce() -> T) -> T {
+    DEFERRED.fetch_add(1, Ordering::SeqCst);
+    let result = f();
+
+    if DEFERRED.fetch_sub(1, Ordering::SeqCst) == 1 && PENDING.load(Ordering::SeqCst) {
+        interrupted();
+    }
+    result
+}
+
+fn interrupted() -> ! {
+    // Spinners and prompts hide the cursor.
+    console::Term::stdout().show_cursor().ok();
+    console::Term::stderr().show_cursor().ok();
+
+    term::blank();
+    term::warning("Interrupted");
+
+    let actions = std::mem::take(&mut *lock(&ACTIONS));
+    for (_, action) in actions.into_iter().rev() {
+        action();
+    }
+
+    let published = lock(&PUBLISHED);
+    if published.is_empty() {
+        term::info!("Nothing was published.");
+    } else {
+        term::info!("The following was already published:");
+        for what in published.iter() {
+            term::info!("  * {}", what);
+        }
+    }
+    term::exit(EXIT_CODE);
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // Nb. We're about to exit when this is poisoned, so we may as well carry on.
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
@@ -9,8 +9,10 @@ use librad::profile::Profile;
 
 use dialoguer::{console::style, console::Style, theme::ColorfulTheme, Input, Password};
 
+use radicle_common::args;
 use radicle_common::cobs::issue::Issue;
 use radicle_common::cobs::shared::CommentId;
+use radicle_common::keychain;
 use radicle_common::signer::ToSigner;
 
 use super::command;
@@ -50,11 +52,7 @@ pub fn success_args(args: fmt::Arguments) {
 }
 
 pub fn tip_args(args: fmt::Arguments) {
-    println!(
-        "{} {}",
-        style("=>").blue(),
-        style(format!("{}", args)).dim()
-    );
+    println!("{}", format::tip(args));
 }
 
 pub use info;
@@ -66,6 +64,14 @@ pub fn width() -> usize {
     rows as usize
 }
 
+/// Terminal width, if standard output is a terminal. When output is piped, there is no
+/// width to fit to.
+pub fn columns() -> Option<usize> {
+    console::Term::stdout()
+        .size_checked()
+        .map(|(_, cols)| cols as usize)
+}
+
 pub fn headline(headline: &str) {
     println!();
     println!("{}", style(headline).bold());
@@ -80,6 +86,11 @@ pub fn blank() {
     println!()
 }
 
+/// Clear the screen, eg. before redrawing a live view.
+pub fn clear() {
+    console::Term::stdout().clear_screen().ok();
+}
+
 pub fn print(msg: impl fmt::Display) {
     println!("{}", msg);
 }
@@ -161,7 +172,18 @@ pub fn fail(header: &str, error: &anyhow::Error) {
     }
 }
 
+/// Ask a yes/no question. Always answers yes without prompting when `RAD_YES` is set,
+/// eg. with the global `--no-confirm` flag.
 pub fn ask<D: fmt::Display>(prompt: D, default: bool) -> bool {
+    if args::assume_yes() {
+        println!(
+            "{} {} {}",
+            style(" ⤷".to_owned()).cyan(),
+            prompt,
+            format::dim("yes")
+        );
+        return true;
+    }
     dialoguer::Confirm::new()
         .with_prompt(format!("{} {}", style(" ⤷".to_owned()).cyan(), prompt))
         .wait_for_newline(false)
@@ -179,12 +201,17 @@ pub fn abort<D: fmt::Display>(prompt: D) -> bool {
     ask(format::tertiary(prompt), false)
 }
 
-/// Get the signer. First we try getting it from ssh-agent, otherwise we prompt the user.
+/// Get a signer for the profile, from ssh-agent or the key file, depending on the
+/// backend set with `RAD_SIGNER`.
 pub fn signer(profile: &Profile) -> anyhow::Result<BoxedSigner> {
-    let signer = if let Ok(sock) = keys::ssh_auth_sock() {
-        sock.to_signer(profile)?
-    } else {
-        secret_key(profile)?.to_signer(profile)?
+    let backend = keys::signer::Backend::from_env()?;
+    let source = keys::source(profile, backend).map_err(|err| Error::WithHint {
+        err,
+        hint: "hint: run `rad auth` to add your key to ssh-agent, or unset `RAD_SIGNER`",
+    })?;
+    let signer = match source {
+        keys::Source::Agent => keys::ssh_auth_sock()?.to_signer(profile)?,
+        keys::Source::KeyFile => secret_key(profile)?.to_signer(profile)?,
     };
     Ok(signer)
 }
@@ -333,8 +360,33 @@ pub fn read_passphrase_from_env_var() -> Result<SecUtf8, anyhow::Error> {
     Ok(SecUtf8::from(input.trim_end()))
 }
 
+/// Unseal the profile's key file. The passphrase is taken from `RAD_PASSPHRASE`, the
+/// keychain if the profile opted into it, or prompted for if there is a terminal.
 pub fn secret_key(profile: &Profile) -> Result<keys::signer::ZeroizingSecretKey, anyhow::Error> {
-    let passphrase = secret_input();
+    if let Some(passphrase) = keychain::passphrase(profile) {
+        if std::env::var(keys::RAD_PASSPHRASE).is_err() {
+            let spinner = spinner("Unsealing key...");
+            let key =
+                keys::load_secret_key(profile, passphrase).map_err(|err| Error::WithHint {
+                    err: err.context("the passphrase stored in the keychain is invalid"),
+                    hint: "hint: run `rad auth --keychain` to store the current passphrase",
+                })?;
+            spinner.finish();
+
+            return Ok(key);
+        }
+    }
+    let passphrase = match read_passphrase_from_env_var() {
+        Ok(passphrase) => passphrase,
+        Err(_) if unsafe { libc::isatty(libc::STDIN_FILENO) } == 1 => secret_input(),
+        Err(_) => {
+            return Err(Error::WithHint {
+                err: anyhow::anyhow!("a passphrase is needed to unseal your key"),
+                hint: "hint: add your key to ssh-agent with `rad auth`, or set `RAD_PASSPHRASE`",
+            }
+            .into())
+        }
+    };
     let spinner = spinner("Unsealing key..."); // Nb. Spinner ends when dropped.
     let key = keys::load_secret_key(profile, passphrase)?;
 
@@ -362,6 +414,23 @@ where
     result.map(|i| &options[i])
 }
 
+/// Let the user toggle a list of items. Returns whether each item is checked, or `None`
+/// if the prompt was cancelled.
+pub fn checklist(prompt: &str, items: &[(String, bool)]) -> Option<Vec<bool>> {
+    let selection = dialoguer::MultiSelect::with_theme(&theme())
+        .with_prompt(prompt)
+        .items_checked(
+            &items
+                .iter()
+                .map(|(item, checked)| (item.as_str(), *checked))
+                .collect::<Vec<_>>(),
+        )
+        .interact_opt()
+        .unwrap()?;
+
+    Some((0..items.len()).map(|i| selection.contains(&i)).collect())
+}
+
 pub fn select_with_prompt<'a, T>(prompt: &str, options: &'a [T], active: &'a T) -> Option<&'a T>
 where
     T: fmt::Display + Eq + PartialEq,
@@ -4,9 +4,12 @@ pub mod command;
 #[cfg(feature = "ethereum")]
 pub mod ethereum;
 pub mod format;
+pub mod interrupt;
 pub mod io;
 pub mod keys;
+pub mod pager;
 pub mod patch;
+pub mod progress;
 pub mod spinner;
 pub mod sync;
 pub mod table;
@@ -16,13 +19,14 @@ use std::ffi::OsString;
 use std::process;
 
 use dialoguer::console::style;
-use radicle_common::args::{Args, Error, Help};
+use radicle_common::args::{self, Args, Error, Help};
 use radicle_common::profile;
 use radicle_common::profile::Profile;
 
 pub use console::measure_text_width as text_width;
 pub use dialoguer::Editor;
 pub use io::*;
+pub use progress::{progress, Progress};
 pub use spinner::{spinner, Spinner};
 pub use table::Table;
 pub use textbox::TextBox;
@@ -63,6 +67,13 @@ where
     }
 }
 
+/// Exit the process. Since `process::exit` doesn't run destructors, the storage lock is
+/// released first.
+pub fn exit(code: i32) -> ! {
+    radicle_common::storage::release();
+    process::exit(code);
+}
+
 pub fn run_command<A, C>(help: Help, action: &str, cmd: C) -> !
 where
     A: Args,
@@ -84,7 +95,7 @@ where
         Ok((opts, unparsed)) => {
             if let Err(err) = radicle_common::args::finish(unparsed) {
                 term::error(err);
-                process::exit(1);
+                exit(1);
             }
             opts
         }
@@ -92,11 +103,21 @@ where
             match err.downcast_ref::<Error>() {
                 Some(Error::Help) => {
                     term::help(help.name, help.version, help.description, help.usage);
-                    process::exit(0);
+                    exit(0);
                 }
                 Some(Error::Usage) => {
                     term::usage(help.name, help.usage);
-                    process::exit(1);
+                    exit(1);
+                }
+                Some(Error::Schema(schema)) => {
+                    match radicle_common::json::to_string_pretty(schema) {
+                        Ok(schema) => println!("{}", schema),
+                        Err(err) => {
+                            term::error(err);
+                            exit(1);
+                        }
+                    }
+                    exit(0);
                 }
                 _ => {}
             };
@@ -111,16 +132,33 @@ where
             if let Some(Error::WithHint { hint, .. }) = err.downcast_ref::<Error>() {
                 eprintln!("{}", style(hint).yellow());
             }
+            if let Some(lexopt::Error::UnexpectedOption(option)) = err.downcast_ref() {
+                let options = help.options();
+                let suggestion = option
+                    .strip_prefix("--")
+                    .and_then(|o| args::suggest(o, options.iter().map(|s| s.as_str())));
+
+                if let Some(suggestion) = suggestion {
+                    eprintln!(
+                        "{}",
+                        style(format!("hint: did you mean `--{}`?", suggestion)).yellow()
+                    );
+                }
+            }
 
-            process::exit(1);
+            exit(1);
         }
     };
 
+    if let Err(err) = interrupt::install() {
+        term::warning(&format!("Failed to install interrupt handler: {}", err));
+    }
+
     match cmd.run(options, profile::default) {
-        Ok(()) => process::exit(0),
+        Ok(()) => exit(0),
         Err(err) => {
             term::fail(&format!("{} failed", action), &err);
-            process::exit(1);
+            exit(1);
         }
     }
 }
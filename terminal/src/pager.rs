@@ -0,0 +1,86 @@
+//! Paging of long output.
+//!
+//! While a [`Pager`] is alive, standard output is redirected to a pager process,
+//! which is `$PAGER`, or `less` if it isn't set. Writes fail with a broken pipe once
+//! the user quits the pager, see [`is_broken_pipe`].
+use std::io::{self, Write as _};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::process::{Child, Command, Stdio};
+
+/// Pager used when `$PAGER` isn't set.
+pub const DEFAULT_PAGER: &str = "less";
+/// Options for `less`, if not already set: quit if the output fits on one screen,
+/// pass colors through, and don't clear the screen on exit.
+const LESS: &str = "FRX";
+
+/// A running pager. Dropping it restores standard output and waits for the
+/// user to quit the pager.
+#[must_use]
+pub struct Pager {
+    child: Child,
+    stdout: RawFd,
+}
+
+impl Drop for Pager {
+    fn drop(&mut self) {
+        io::stdout().flush().ok();
+
+        // Restoring standard output closes our end of the pipe, which lets the pager
+        // know there is no more output.
+        unsafe {
+            libc::dup2(self.stdout, libc::STDOUT_FILENO);
+            libc::close(self.stdout);
+        }
+        self.child.wait().ok();
+    }
+}
+
+/// Start paging standard output, if it is a terminal and paging isn't disabled,
+/// eg. with `PAGER=cat`.
+pub fn start() -> Option<Pager> {
+    if unsafe { libc::isatty(libc::STDOUT_FILENO) } != 1 {
+        return None;
+    }
+    let pager = std::env::var("PAGER").unwrap_or_else(|_| DEFAULT_PAGER.to_owned());
+    let pager = pager.trim();
+
+    if pager.is_empty() || pager == "cat" {
+        return None;
+    }
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(pager)
+        .env(
+            "LESS",
+            std::env::var("LESS").unwrap_or_else(|_| LESS.to_owned()),
+        )
+        .stdin(Stdio::piped())
+        .spawn()
+        .ok()?;
+    let stdin = child.stdin.take()?;
+
+    io::stdout().flush().ok();
+
+    let stdout = unsafe { libc::dup(libc::STDOUT_FILENO) };
+    if stdout >= 0 && unsafe { libc::dup2(stdin.as_raw_fd(), libc::STDOUT_FILENO) } >= 0 {
+        // Standard output now refers to the pipe, so our handle to it can be closed.
+        drop(stdin);
+
+        return Some(Pager { child, stdout });
+    }
+    if stdout >= 0 {
+        unsafe { libc::close(stdout) };
+    }
+    // Closing the pipe makes the pager exit.
+    drop(stdin);
+    child.wait().ok();
+
+    None
+}
+
+/// Whether an error was caused by writing to a pager the user has quit.
+pub fn is_broken_pipe(err: &anyhow::Error) -> bool {
+    err.chain()
+        .filter_map(|e| e.downcast_ref::<io::Error>())
+        .any(|e| e.kind() == io::ErrorKind::BrokenPipe)
+}
@@ -1,7 +1,11 @@
 use radicle_common as common;
+use radicle_common::cobs::Timestamp;
 use radicle_common::git;
 
+pub use radicle_common::markdown::Validation;
+
 use crate as term;
+use crate::format::Indicator;
 
 /// How a comment is to be supplied by the user for a patch or issue on the terminal.
 #[derive(Clone, Debug)]
@@ -15,22 +19,46 @@ pub enum Comment {
 }
 
 impl Comment {
-    /// Get the comment as a string according to the method.
-    pub fn get(self, help: &str) -> String {
-        let comment = match self {
-            Comment::Edit => term::Editor::new()
-                .require_save(true)
-                .trim_newlines(true)
-                .extension(".markdown")
-                .edit(help)
-                .unwrap(),
-            Comment::Blank => None,
-            Comment::Text(c) => Some(c),
-        };
-        let comment = comment.unwrap_or_default().replace(help, "");
-        let comment = comment.trim();
+    /// Get the comment as a string according to the method, and validate it. When the
+    /// comment is written in the editor and isn't valid, the editor is re-opened with
+    /// what the user wrote, until the comment is valid or the user gives up.
+    pub fn get(self, help: &str, validation: Validation) -> anyhow::Result<String> {
+        let mut content = help.to_owned();
+
+        loop {
+            let edited = match &self {
+                Comment::Edit => term::interrupt::deferred(|| {
+                    term::Editor::new()
+                        .require_save(true)
+                        .trim_newlines(true)
+                        .extension(".markdown")
+                        .edit(&content)
+                        .unwrap()
+                }),
+                Comment::Blank => None,
+                Comment::Text(c) => Some(c.clone()),
+            };
+            let edited = edited.unwrap_or_default();
+            let comment = edited.replace(help, "");
+            let comment = comment.trim();
+            let errors = validation.check(comment);
 
-        comment.to_owned()
+            if errors.is_empty() {
+                return Ok(comment.to_owned());
+            }
+            for err in &errors {
+                term::warning(err);
+            }
+            if !matches!(self, Comment::Edit) || !term::confirm("Edit again?") {
+                anyhow::bail!("invalid content: {}", errors.join(", "));
+            }
+            // Keep what was written, and the help text if it was removed.
+            content = if edited.contains(help.trim()) {
+                edited
+            } else {
+                format!("{}\n{}", edited, help)
+            };
+        }
     }
 
     pub fn append(&mut self, arg: &str) {
@@ -48,24 +76,84 @@ impl Default for Comment {
     }
 }
 
-/// List the given commits in a table.
-pub fn list_commits(commits: &[git::Commit]) -> anyhow::Result<()> {
+/// Number of commits listed before the rest are summarized, unless all commits are
+/// asked for.
+pub const COMMIT_LIMIT: usize = 20;
+
+/// List the given commits in a table, with their author, date and signature status,
+/// and with `stat`, their diffstat. When there are more commits than were given, out
+/// of `total`, the rest are summarized in one line.
+pub fn list_commits(
+    repo: &git::Repository,
+    commits: &[git::Commit],
+    total: usize,
+    stat: bool,
+) -> anyhow::Result<()> {
+    let ids = commits.iter().map(|c| c.id()).collect::<Vec<_>>();
+    // Nb. Signatures are only informative here, so failing to check them isn't fatal.
+    let signatures = git::signature_status(repo, &ids).unwrap_or_default();
     let mut table = term::Table::default();
 
     for commit in commits {
         let message = commit
             .summary_bytes()
             .unwrap_or_else(|| commit.message_bytes());
+        let author = commit.author();
+        let time = Timestamp::new(commit.time().seconds().max(0) as u64);
+        let signature = signatures
+            .get(&commit.id())
+            .copied()
+            .unwrap_or(git::SignatureStatus::Unverified);
+        let diffstat = if stat {
+            let parent = commit.parents().next().map(|p| p.tree()).transpose()?;
+            let stats = repo
+                .diff_tree_to_tree(parent.as_ref(), Some(&commit.tree()?), None)?
+                .stats()?;
+
+            format!(
+                "{} {}",
+                term::format::positive(format!("+{}", stats.insertions())),
+                term::format::negative(format!("−{}", stats.deletions())),
+            )
+        } else {
+            String::new()
+        };
+
         table.push([
             term::format::secondary(common::fmt::oid(&commit.id())),
             term::format::italic(String::from_utf8_lossy(message)),
+            term::format::tertiary(String::from_utf8_lossy(author.name_bytes())),
+            term::format::dim(term::format::timeago(time)),
+            pretty_signature(signature),
+            diffstat,
         ]);
     }
     table.render();
 
+    if total > commits.len() {
+        term::info!(
+            "{}",
+            term::format::dim(format!("… and {} more", total - commits.len()))
+        );
+    }
     Ok(())
 }
 
+/// Format the signature status of a commit.
+fn pretty_signature(status: git::SignatureStatus) -> String {
+    match status {
+        git::SignatureStatus::Good => {
+            term::format::positive(term::format::labeled(Indicator::Success, "signed"))
+        }
+        git::SignatureStatus::Untrusted => term::format::yellow("signed, untrusted"),
+        git::SignatureStatus::Bad => {
+            term::format::negative(term::format::labeled(Indicator::Failure, "bad signature"))
+        }
+        git::SignatureStatus::Unverified => term::format::dim("unverified"),
+        git::SignatureStatus::Unsigned => term::format::dim("unsigned"),
+    }
+}
+
 /// Print commits ahead and behind.
 pub fn print_commits_ahead_behind(
     repo: &git::Repository,
@@ -0,0 +1,100 @@
+use dialoguer::console::style;
+use indicatif::{ProgressBar, ProgressFinish, ProgressStyle};
+
+use crate as term;
+
+/// A progress bar, for long-running transfers. Shown as a spinner until the size of
+/// the transfer is known.
+pub struct Progress {
+    progress: ProgressBar,
+    message: String,
+    bar: bool,
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        if !self.progress.is_finished() {
+            self.set_failed();
+        }
+    }
+}
+
+impl Progress {
+    pub fn finish(&self) {
+        self.progress.finish_and_clear();
+        term::success!("{}", &self.message);
+    }
+
+    pub fn failed(mut self) {
+        self.set_failed();
+    }
+
+    pub fn clear(self) {
+        self.progress.finish_and_clear();
+    }
+
+    /// Set the message, and go back to a spinner until the next transfer update.
+    pub fn message(&mut self, msg: impl Into<String>) {
+        let msg = msg.into();
+
+        if self.bar {
+            self.progress.set_style(spinner_style());
+            self.bar = false;
+        }
+        self.progress.set_message(msg.clone());
+        self.message = msg;
+    }
+
+    /// Update the transfer, eg. objects received so far, out of `total`.
+    pub fn transfer(&mut self, stage: &str, done: u64, total: u64, bytes: Option<&str>) {
+        if !self.bar {
+            self.progress.set_style(bar_style());
+            self.bar = true;
+        }
+        self.progress.set_length(total);
+        self.progress.set_position(done);
+        self.progress.set_prefix(match bytes {
+            Some(bytes) => format!("{} ({})", stage, bytes),
+            None => stage.to_owned(),
+        });
+    }
+
+    pub fn set_failed(&mut self) {
+        self.progress.finish_and_clear();
+        term::eprintln(style("!!").red().reverse(), &self.message);
+    }
+}
+
+fn spinner_style() -> ProgressStyle {
+    ProgressStyle::default_spinner()
+        .tick_strings(&[
+            &style("\\ ").yellow().to_string(),
+            &style("| ").yellow().to_string(),
+            &style("/ ").yellow().to_string(),
+            &style("| ").yellow().to_string(),
+        ])
+        .template("{spinner} {msg}")
+        .on_finish(ProgressFinish::AndClear)
+}
+
+fn bar_style() -> ProgressStyle {
+    ProgressStyle::default_bar()
+        .template("{msg}\n  {prefix:.dim} [{bar:30.yellow}] {pos}/{len}")
+        .progress_chars("=> ")
+        .on_finish(ProgressFinish::AndClear)
+}
+
+pub fn progress(message: impl ToString) -> Progress {
+    let message = message.to_string();
+    let progress = ProgressBar::new(!0);
+
+    progress.set_style(spinner_style());
+    progress.enable_steady_tick(99);
+    progress.set_message(message.clone());
+
+    Progress {
+        progress,
+        message,
+        bar: false,
+    }
+}
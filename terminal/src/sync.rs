@@ -1,17 +1,55 @@
 use std::convert::TryInto;
 use std::time;
 
+use anyhow::Context as _;
 use librad::git::Urn;
 
 use radicle_common as common;
+use radicle_common::args::Error;
+use radicle_common::config::{self, Pinning};
+use radicle_common::fingerprint::{self, Check, KnownSeeds};
+use radicle_common::keys;
 use radicle_common::nonempty::NonEmpty;
+use radicle_common::pin::{self, Pins};
 use radicle_common::profile::Profile;
-use radicle_common::signer::ToSigner;
+use radicle_common::proxy;
+use radicle_common::signer::{BoxedSigner, ToSigner};
+use radicle_common::storage::Owner;
+use radicle_common::store::Store;
 use radicle_common::sync;
 use radicle_common::sync::SyncResult;
 
 use crate as term;
+use crate::format::Indicator;
 
+/// Sync options.
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// Number of seeds to sync with at a time.
+    pub jobs: usize,
+    /// Number of times to retry syncing with a seed after a network failure.
+    pub retries: u32,
+    /// Offer to accept a new delegate set of a pinned project, instead of refusing it.
+    pub repin: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            jobs: sync::DEFAULT_JOBS,
+            retries: sync::DEFAULT_RETRIES,
+            repin: false,
+        }
+    }
+}
+
+/// Sync a URN with seeds, showing progress as seeds are synced.
+///
+/// When fetching a project whose delegate set is pinned, an identity update that doesn't
+/// match the pin is refused, see [`radicle_common::pin`].
+///
+/// Seeds synced with by an earlier sync of the same URN and signed refs that was
+/// interrupted are skipped, and reported as such in the results.
 pub fn sync(
     urn: Urn,
     seeds: NonEmpty<sync::Seed<String>>,
@@ -20,18 +58,185 @@ pub fn sync(
     signer: impl ToSigner,
     rt: &common::tokio::runtime::Runtime,
 ) -> anyhow::Result<NonEmpty<SyncResult>> {
+    sync_with(urn, seeds, mode, Options::default(), profile, signer, rt)
+}
+
+/// Like [`sync`], with the given options. Seeds that couldn't be reached are retried
+/// with exponential backoff.
+pub fn sync_with(
+    urn: Urn,
+    seeds: NonEmpty<sync::Seed<String>>,
+    mode: sync::Mode,
+    options: Options,
+    profile: &Profile,
+    signer: impl ToSigner,
+    rt: &common::tokio::runtime::Runtime,
+) -> anyhow::Result<NonEmpty<SyncResult>> {
+    let seeds = verify(seeds, profile)?;
+    let seeds = direct(seeds)?;
+
+    if proxy::is_set() {
+        // Nb. A config that can't be read doesn't allow anything.
+        let direct = config::Config::profile(profile).map_or(false, |c| c.network.allow_direct);
+
+        if !direct {
+            return Err(Error::WithHint {
+                err: anyhow::anyhow!("peer-to-peer sync can't go through the proxy"),
+                hint: "hint: peer-to-peer sync runs over QUIC, ie. UDP; set `network.allow-direct = true` to sync without the proxy",
+            }
+            .into());
+        }
+        term::warning("Peer-to-peer sync can't go through the proxy, connecting to seeds directly");
+    }
     let signer = signer.to_signer(profile)?;
-    let timeout = time::Duration::from_secs(9);
-    let spinner = term::spinner("Syncing...");
+    let pins = Pins::load(profile)?;
+    let fetching = matches!(mode, sync::Mode::Fetch | sync::Mode::All);
+    let snapshot = if fetching && pins.get(&urn).is_some() {
+        let storage = common::profile::read_only(profile)?;
+        Some(pin::Snapshot::take(&storage, &urn)?)
+    } else {
+        None
+    };
+    let timeout = sync::Timeout {
+        connect: time::Duration::from_secs(9),
+        fetch: time::Duration::from_secs(60),
+        push: time::Duration::from_secs(9),
+    };
+    let backoff = sync::Backoff::default();
+    let now = time::SystemTime::now()
+        .duration_since(time::UNIX_EPOCH)?
+        .as_secs();
+    let owner = Owner::current();
+    let refs = signed_refs(profile, &urn)?;
+    let done = sync::Journal::update(profile, |j| j.begin(&urn, mode, refs, &owner, now))?;
+    let (skipped, pending): (Vec<_>, Vec<_>) =
+        seeds.iter().cloned().partition(|s| done.contains(&s.peer));
+    // If every seed was synced with, the interrupted sync was about to finish: start over.
+    let (skipped, pending) = if pending.is_empty() {
+        (Vec::new(), seeds.iter().cloned().collect())
+    } else {
+        (skipped, pending)
+    };
+
+    if !skipped.is_empty() {
+        term::info!(
+            "Resuming interrupted sync: {} of {} seed(s) already synced",
+            skipped.len(),
+            seeds.len()
+        );
+    }
+
+    let mut progress = term::progress("Syncing...");
     let result = rt.block_on(async {
-        let (seeds, _errors) = sync::Seeds::resolve(seeds.iter()).await;
-        let client = sync::client(signer, profile).await?;
-        let result = sync::sync(&client, urn, seeds, mode, timeout).await;
+        let (mut seeds, _errors) = sync::Seeds::resolve(pending.iter()).await;
+        let (client, endpoint) = sync::client(signer.clone(), profile).await?;
+        let mut results = Vec::new();
+
+        for attempt in 0..=options.retries {
+            if attempt > 0 {
+                let delay = backoff.delay(attempt);
+
+                progress.message(format!(
+                    "Couldn't reach {} seed(s), retrying in {:.1}s (attempt {} of {})...",
+                    seeds.0.len(),
+                    delay.as_secs_f32(),
+                    attempt + 1,
+                    options.retries + 1,
+                ));
+                common::tokio::time::sleep(delay).await;
+            }
+            // Seeds currently being synced with, and what is being done.
+            let mut active: Vec<(librad::PeerId, String)> = Vec::new();
+            let status = |active: &[(librad::PeerId, String)]| {
+                let status = match active {
+                    [] => String::from("Syncing..."),
+                    [(_, action)] => action.clone(),
+                    _ => format!("Syncing with {} seeds...", active.len()),
+                };
+                if attempt > 0 {
+                    format!(
+                        "{} (attempt {} of {})",
+                        status,
+                        attempt + 1,
+                        options.retries + 1
+                    )
+                } else {
+                    status
+                }
+            };
+            let attempted = sync::sync(
+                &client,
+                &endpoint,
+                urn.clone(),
+                seeds,
+                mode,
+                timeout,
+                options.jobs,
+                |p| match p {
+                    sync::Progress::Fetching(seed) => {
+                        active.retain(|(peer, _)| *peer != seed.peer);
+                        active.push((
+                            seed.peer,
+                            format!(
+                                "Fetching from {}...",
+                                seed_name(seed.label.as_deref(), &seed.peer)
+                            ),
+                        ));
+                        progress.message(status(&active));
+                    }
+                    sync::Progress::Pushing(seed) => {
+                        active.retain(|(peer, _)| *peer != seed.peer);
+                        active.push((
+                            seed.peer,
+                            format!(
+                                "Pushing to {}...",
+                                seed_name(seed.label.as_deref(), &seed.peer)
+                            ),
+                        ));
+                        progress.message(status(&active));
+                    }
+                    // With several seeds at once, transfers would overwrite each other.
+                    sync::Progress::Transfer(_, t) if active.len() == 1 => {
+                        progress.transfer(&t.stage, t.done, t.total, t.bytes.as_deref())
+                    }
+                    sync::Progress::Transfer(..) => {}
+                    sync::Progress::Done(result) => {
+                        active.retain(|(peer, _)| *peer != result.seed.peer);
+                        progress.message(status(&active));
+
+                        if result.is_ok() {
+                            // Nb. A failure to save only means the sync can't be resumed.
+                            sync::Journal::update(profile, |j| {
+                                j.complete(&urn, mode, &owner, &result.seed.peer)
+                            })
+                            .ok();
+                        }
+                    }
+                },
+            )
+            .await;
+
+            let (retry, done): (Vec<_>, Vec<_>) = attempted
+                .into_iter()
+                .partition(|r| r.is_transient() && attempt < options.retries);
 
-        Ok::<Vec<SyncResult>, anyhow::Error>(result)
-    })?;
+            results.extend(done);
+            if retry.is_empty() {
+                break;
+            }
+            seeds = sync::Seeds(retry.into_iter().map(|r| r.seed).collect());
+        }
 
-    let results = if let Ok(results) = result.try_into() {
+        Ok::<Vec<SyncResult>, anyhow::Error>(results)
+    });
+    // Only interrupted syncs are resumed, failed ones start over.
+    sync::Journal::update(profile, |j| j.finish(&urn, mode, &owner))?;
+
+    let results = result?
+        .into_iter()
+        .chain(skipped.iter().map(SyncResult::skipped))
+        .collect::<Vec<_>>();
+    let results: NonEmpty<SyncResult> = if let Ok(results) = results.try_into() {
         results
     } else {
         return Err(anyhow::anyhow!(
@@ -39,10 +244,248 @@ pub fn sync(
         ));
     };
 
+    // Record what was published, so that `rad ls` can show unpublished changes.
+    if matches!(mode, sync::Mode::Push | sync::Mode::All) && results.iter().any(|r| r.is_ok()) {
+        if let Ok(Some(head)) = published_head(profile, &urn) {
+            common::registry::synced(profile, &urn, head).ok();
+        }
+    }
+
+    progress.message("Syncing...");
     match mode {
-        sync::Mode::Push | sync::Mode::All => spinner.finish(),
-        sync::Mode::Fetch => spinner.clear(),
+        sync::Mode::Push | sync::Mode::All => progress.finish(),
+        sync::Mode::Fetch => progress.clear(),
     }
 
+    if let Some(snapshot) = snapshot {
+        check_pin(&urn, &snapshot, pins, options.repin, profile, signer)?;
+    }
     Ok(results)
 }
+
+/// Check the delegates of a pinned project after a fetch. If they changed, the change is
+/// shown, and the identity update is refused by restoring the previous identity, unless
+/// `repin` is set and the user accepts the new delegate set.
+fn check_pin(
+    urn: &Urn,
+    snapshot: &pin::Snapshot,
+    mut pins: Pins,
+    repin: bool,
+    profile: &Profile,
+    signer: BoxedSigner,
+) -> anyhow::Result<()> {
+    let storage = keys::storage(profile, signer)?;
+    let project = match common::project::get(&storage, urn)? {
+        Some(project) => project,
+        None => return Ok(()),
+    };
+    let (added, removed) = match pins.verify_project(&project) {
+        pin::Verification::Mismatch { added, removed } => (added, removed),
+        pin::Verification::Match | pin::Verification::Unpinned => return Ok(()),
+    };
+
+    term::blank();
+    for peer in &added {
+        term::warning(&format!("Delegate added: {}", peer));
+    }
+    for peer in &removed {
+        term::warning(&format!("Delegate removed: {}", peer));
+    }
+    if repin && term::confirm(format!("Accept the new delegate set of {}?", urn)) {
+        pins.pin(urn, &project.remotes);
+        pins.save(profile)?;
+
+        term::success!(
+            "Pinned {} delegate(s) for {}",
+            term::format::dim(project.remotes.len()),
+            term::format::highlight(&project.name)
+        );
+        return Ok(());
+    }
+    // Refuse the identity update by restoring the previous identity head.
+    snapshot
+        .restore(&storage, &pins)
+        .context("failed to refuse the identity update")?;
+
+    Err(Error::WithHint {
+        err: anyhow::anyhow!(
+            "project delegates of {} do not match the pinned delegate set",
+            urn
+        ),
+        hint: "hint: if this change is expected, run `rad sync --fetch --pin` to review and accept the new delegate set",
+    }
+    .into())
+}
+
+/// Head of the project's default branch in local storage.
+fn published_head(profile: &Profile, urn: &Urn) -> anyhow::Result<Option<common::git::Oid>> {
+    let storage = common::profile::read_only(profile)?;
+    let meta = common::project::get(&storage, urn)?
+        .ok_or_else(|| anyhow::anyhow!("project {} not found in local storage", urn))?;
+
+    common::project::get_local_head(&storage, urn, &meta.default_branch)
+}
+
+/// The `rad/signed_refs` commit of a project in storage, if any.
+fn signed_refs(profile: &Profile, urn: &Urn) -> anyhow::Result<Option<common::git::Oid>> {
+    let repo = common::git::Repository::open_bare(profile.paths().git_dir())?;
+    let name = format!("refs/namespaces/{}/refs/rad/signed_refs", urn.encode_id());
+
+    Ok(repo.refname_to_id(&name).ok())
+}
+
+fn seed_name(label: Option<&str>, peer: &librad::PeerId) -> String {
+    match label {
+        Some(label) => term::format::highlight(label),
+        None => term::format::tertiary(common::fmt::peer(peer)),
+    }
+}
+
+/// Print a summary table of sync results, with one row per seed. Seeds that couldn't
+/// be resolved are included. Returns the number of seeds the sync failed with.
+pub fn print_summary(
+    seeds: &NonEmpty<sync::Seed<String>>,
+    results: &NonEmpty<SyncResult>,
+) -> usize {
+    let mut table = term::Table::default();
+    let mut failed = 0;
+
+    for seed in seeds.iter() {
+        let result = results.iter().find(|r| r.seed.peer == seed.peer);
+        let (ok, status) = match result {
+            None => (
+                false,
+                term::format::negative("unreachable: address did not resolve"),
+            ),
+            Some(result) if result.is_skipped() => (
+                true,
+                term::format::dim("skipped: synced before the sync was interrupted"),
+            ),
+            Some(result) => {
+                let mut ok = true;
+                let mut status = Vec::new();
+
+                match &result.fetch {
+                    Some(Ok(_)) => status.push(term::format::positive("fetched")),
+                    Some(Err(err)) => {
+                        ok = false;
+                        status.push(term::format::negative(format!("fetch failed: {}", err)));
+                    }
+                    None => {}
+                }
+                match &result.push {
+                    Some(Ok(_)) => status.push(term::format::positive("pushed")),
+                    Some(Err(err)) => {
+                        ok = false;
+                        status.push(term::format::negative(format!("push failed: {}", err)));
+                    }
+                    None => {}
+                }
+                (ok, status.join(", "))
+            }
+        };
+        if !ok {
+            failed += 1;
+        }
+        table.push([
+            if ok {
+                term::format::positive(term::format::symbol(Indicator::Success))
+            } else {
+                term::format::negative(term::format::symbol(Indicator::Failure))
+            },
+            term::format::tertiary(common::fmt::peer(&seed.peer)),
+            term::format::dim(seed.label.clone().unwrap_or_else(|| seed.addrs.clone())),
+            status,
+        ]);
+    }
+    table.render();
+
+    failed
+}
+
+/// Keep the seeds that can be connected to directly. Onion seeds, and seeds with a proxy
+/// of their own, are only reachable through that proxy, which peer-to-peer sync can't use.
+fn direct(seeds: NonEmpty<sync::Seed<String>>) -> anyhow::Result<NonEmpty<sync::Seed<String>>> {
+    let mut direct = Vec::new();
+
+    for seed in seeds {
+        let host = fingerprint::host(&seed.addrs);
+
+        if let Some(via) = proxy::for_seed(host) {
+            term::warning(&format!(
+                "Skipping seed {}: it is only reachable through {}, which peer-to-peer sync can't use",
+                host, via
+            ));
+        } else {
+            direct.push(seed);
+        }
+    }
+
+    NonEmpty::from_vec(direct).ok_or_else(|| {
+        Error::WithHint {
+            err: anyhow::anyhow!("no seeds left to sync with: they are only reachable through a proxy"),
+            hint: "hint: onion seeds and seeds with a proxy can still be fetched from with `git fetch`, over their HTTP API",
+        }
+        .into()
+    })
+}
+
+/// Check seeds against their pinned peer ids, pinning seeds we haven't seen before.
+/// Seeds whose peer id changed are reported, and skipped if the policy says so.
+fn verify(
+    seeds: NonEmpty<sync::Seed<String>>,
+    profile: &Profile,
+) -> anyhow::Result<NonEmpty<sync::Seed<String>>> {
+    // Nb. A working copy's config can't relax pinning, so only the profile's is read.
+    let policy = config::Config::profile(profile)
+        .map(|c| c.policy.seed_pinning)
+        .unwrap_or_default();
+    if policy == Pinning::Off {
+        return Ok(seeds);
+    }
+    let mut known = KnownSeeds::load(profile)?;
+    let mut save = false;
+    let mut verified = Vec::new();
+
+    for seed in seeds {
+        let host = fingerprint::host(&seed.addrs);
+
+        match known.check(host, &seed.peer) {
+            Check::New => {
+                save = true;
+                verified.push(seed);
+            }
+            Check::Known => verified.push(seed),
+            Check::Changed { pinned } => {
+                term::warning(&format!(
+                    "The peer id of seed {} has changed! Someone could be impersonating it.",
+                    host
+                ));
+                term::warning(&format!(
+                    "Pinned: {}, got: {}",
+                    common::fmt::peer(&pinned),
+                    common::fmt::peer(&seed.peer)
+                ));
+                if policy == Pinning::Block {
+                    term::warning(&format!(
+                        "Skipping seed. If the change is expected, run `rad seed fingerprint forget {}`.",
+                        host
+                    ));
+                } else {
+                    verified.push(seed);
+                }
+            }
+        }
+    }
+    if save {
+        known.save(profile)?;
+    }
+
+    NonEmpty::from_vec(verified).ok_or_else(|| {
+        Error::WithHint {
+            err: anyhow::anyhow!("no seeds left to sync with: their peer ids have changed"),
+            hint: "hint: if the change is expected, run `rad seed fingerprint forget <host>`",
+        }
+        .into()
+    })
+}
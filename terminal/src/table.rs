@@ -1,10 +1,16 @@
 use std::fmt::Write;
 
+use radicle_common::fmt::fit;
+
 use crate as term;
 
+pub use radicle_common::fmt::MIN_COLUMN_WIDTH;
+
 #[derive(Debug, Default)]
 pub struct TableOptions {
     pub overflow: bool,
+    /// Don't truncate cells to fit the terminal width.
+    pub full: bool,
 }
 
 #[derive(Debug)]
@@ -38,26 +44,49 @@ impl<const W: usize> Table<W> {
         self.rows.push(row);
     }
 
+    /// Render the table. When output is a terminal, the widest columns are truncated
+    /// until rows fit its width, unless the table is `full`.
     pub fn render(self) {
-        let width = term::width(); // Terminal width.
+        let width = if self.opts.full {
+            None
+        } else {
+            term::columns()
+        };
+        let widths = match width {
+            Some(width) if !self.opts.overflow => fit(self.widths, width.saturating_sub(1)),
+            _ => self.widths,
+        };
 
         for row in &self.rows {
             let mut output = String::new();
             let cells = row.len();
 
             for (i, cell) in row.iter().enumerate() {
-                if i == cells - 1 || self.opts.overflow {
+                if self.opts.overflow {
                     write!(output, "{}", cell).ok();
+                } else if i == cells - 1 {
+                    write!(output, "{}", term::format::truncate(cell, widths[i])).ok();
                 } else {
                     write!(
                         output,
                         "{} ",
-                        console::pad_str(cell, self.widths[i], console::Alignment::Left, None)
+                        console::pad_str(
+                            &term::format::truncate(cell, widths[i]),
+                            widths[i],
+                            console::Alignment::Left,
+                            None
+                        )
                     )
                     .ok();
                 }
             }
-            println!("{}", console::truncate_str(&output, width - 1, "…"));
+            match width {
+                Some(width) => println!(
+                    "{}",
+                    term::format::truncate(&output, width.saturating_sub(1))
+                ),
+                None => println!("{}", output),
+            }
         }
     }
 
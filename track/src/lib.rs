@@ -10,9 +10,12 @@ use librad::git::tracking;
 use librad::profile::Profile;
 use librad::PeerId;
 
+use radicle_common::allowlist::Allowlist;
 use radicle_common::args::Help;
+use radicle_common::cobs::Timestamp;
 use radicle_common::nonempty::NonEmpty;
 use radicle_common::project::PeerInfo;
+use radicle_common::store::Store;
 use radicle_common::tokio;
 use radicle_common::Url;
 use radicle_common::{git, keys, project, seed, sync, Urn};
@@ -44,8 +47,9 @@ pub const HELP: Help = Help {
 Usage
 
     rad track           [--local | --remote]
+    rad track --list
     rad track           [--seed <url>]
-    rad track <peer-id> [--seed <url>] [--no-sync] [--no-upstream] [--no-fetch]
+    rad track <peer-id> [--seed <url>] [--no-sync] [--no-upstream] [--no-fetch] [--allow]
 
     If a peer id is supplied, track this peer in the context of the current project. By default,
     a remote is created in the repository and an upstream tracking branch is setup. If a seed
@@ -53,14 +57,23 @@ Usage
 
     If no peer id is supplied, show the local or remote tracking graph of the current project.
 
+    With `--list`, the peers tracked in every project are listed, with the head of the
+    project's default branch last fetched from each peer. This can be run outside of a project.
+
+    With `--allow`, the peer is also added to the project's collaborator allowlist. Once a
+    project has an allowlist, patches and issues from peers that are neither delegates nor
+    on the list are hidden by default, and only the identity of these peers is fetched.
+
 Options
 
     --local                Show the local project tracking graph
     --remote               Show the remote project tracking graph from a seed
+    --list, -l             List tracked peers of all projects, with their last seen head
     --seed <url>           Seed URL to fetch refs from
     --no-upstream          Don't setup a tracking branch for the remote
     --no-sync              Don't sync the peer's refs
     --no-fetch             Don't fetch the peer's refs into the working copy
+    --allow                Add the peer to the project's collaborator allowlist
     --verbose, -v          Verbose output
     --help                 Print help
 "#,
@@ -71,6 +84,9 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
     let signer = term::signer(&profile)?;
     let storage = keys::storage(&profile, signer.clone())?;
 
+    if options.list {
+        return list(storage.read_only());
+    }
     let (urn, repo) =
         project::cwd().context("this command must be run in the context of a project")?;
     let proj = project::get(&storage, &urn)?
@@ -123,6 +139,24 @@ pub fn track(
         if existing { "exists" } else { "established" },
     );
 
+    let mut allowlist = Allowlist::load(&profile)?;
+    if options.allow {
+        allowlist.allow(urn, &peer);
+        allowlist.save(&profile)?;
+
+        term::success!(
+            "Peer {} added to the collaborator allowlist",
+            term::format::tertiary(peer)
+        );
+    }
+    if !allowlist.allows(&project, &peer) {
+        term::warning(&format!(
+            "Peer {} is not on the collaborator allowlist, only its identity will be fetched",
+            term::format::tertiary(peer)
+        ));
+    }
+    allowlist.enforce(&storage, &project)?;
+
     if options.sync {
         let seeds = if let Some(addr) = &options.seed {
             let seed = addr
@@ -285,6 +319,58 @@ pub fn show(
     Ok(())
 }
 
+/// List the tracked peers of every project, with the head of the project's default branch
+/// as last fetched from each peer.
+pub fn list(storage: &ReadOnly) -> anyhow::Result<()> {
+    let monorepo = git::Repository::open_bare(storage.as_ref().path())?;
+    let mut table = term::Table::default();
+
+    let mut count = 0;
+
+    for (urn, project, _) in project::list(storage)? {
+        let default_branch: &str = &project.default_branch;
+        let mut tracked = project::tracked(&project, storage)?
+            .into_iter()
+            .collect::<Vec<_>>();
+        tracked.sort_by_key(|(peer, _)| peer.default_encoding());
+
+        for (peer, info) in tracked {
+            let head = get_peer_branches(storage, peer, &urn)?
+                .into_iter()
+                .find(|(name, _)| name == default_branch)
+                .map(|(_, oid)| oid);
+            let (head, seen) = match head.map(|oid| monorepo.find_commit(oid)) {
+                Some(Ok(commit)) => (
+                    term::format::secondary(radicle_common::fmt::oid(&commit.id())),
+                    term::format::dim(term::format::timeago(Timestamp::new(
+                        commit.time().seconds().max(0) as u64,
+                    ))),
+                ),
+                _ => (term::format::dim("-"), term::format::dim("no refs fetched")),
+            };
+
+            table.push([
+                term::format::bold(&project.name),
+                term::format::tertiary(radicle_common::fmt::peer(&peer)),
+                info.person
+                    .as_ref()
+                    .map(|p| term::format::dim(&p.name))
+                    .unwrap_or_default(),
+                head,
+                seen,
+            ]);
+            count += 1;
+        }
+    }
+    if count == 0 {
+        term::info!("{}", term::format::dim("No tracked peers"));
+    } else {
+        table.render();
+    }
+
+    Ok(())
+}
+
 /// Return the Peer's branches with their Oid's by parsing references in the storage's git
 /// repository.
 fn get_peer_branches(
@@ -19,6 +19,8 @@ pub struct Options {
     pub local: bool,
     pub seed: Option<seed::Address>,
     pub verbose: bool,
+    pub allow: bool,
+    pub list: bool,
 }
 
 impl Args for Options {
@@ -32,6 +34,8 @@ impl Args for Options {
         let mut sync = true;
         let mut fetch = true;
         let mut verbose = false;
+        let mut allow = false;
+        let mut list = false;
         let mut seed = None;
 
         while let Some(arg) = parser.next()? {
@@ -57,6 +61,8 @@ impl Args for Options {
                 Long("no-upstream") => upstream = false,
                 Long("no-sync") => sync = false,
                 Long("no-fetch") => fetch = false,
+                Long("allow") => allow = true,
+                Long("list") | Short('l') => list = true,
 
                 Long("verbose") | Short('v') => verbose = true,
                 Long("help") => {
@@ -77,6 +83,10 @@ impl Args for Options {
             }
         }
 
+        if list && peer.is_some() {
+            anyhow::bail!("`--list` cannot be used with a <peer-id>");
+        }
+
         // If a seed is specified, and `--local` isn't, we assume remote.
         // Otherwise, we assume local.
         let local = if let Some(local) = local {
@@ -94,6 +104,8 @@ impl Args for Options {
                 local,
                 seed,
                 verbose,
+                allow,
+                list,
             },
             vec![],
         ))
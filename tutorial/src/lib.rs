@@ -0,0 +1,340 @@
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context as _};
+
+use radicle_common::args::{Args, Error, Help};
+use radicle_common::cobs::patch::Patch;
+use radicle_common::{cobs, git, json, keys, profile};
+use radicle_terminal as term;
+
+pub const HELP: Help = Help {
+    name: "tutorial",
+    description: env!("CARGO_PKG_DESCRIPTION"),
+    version: env!("CARGO_PKG_VERSION"),
+    usage: r#"
+Usage
+
+    rad tutorial [--keep]
+
+    Walks through the radicle workflow, from creating a project to
+    proposing, reviewing and merging a patch. Everything happens in a
+    temporary sandbox, with its own profile: your own profile and
+    projects are not touched.
+
+Options
+
+    --keep      Don't delete the sandbox when done
+    --help      Print help
+"#,
+};
+
+/// Passphrase of the sandbox profile.
+const PASSPHRASE: &str = "radicle";
+
+#[derive(Default, Debug)]
+pub struct Options {
+    pub keep: bool,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut keep = false;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("keep") => {
+                    keep = true;
+                }
+                Long("help") => {
+                    return Err(Error::Help.into());
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+
+        Ok((Options { keep }, vec![]))
+    }
+}
+
+/// The tutorial sandbox.
+struct Sandbox {
+    root: PathBuf,
+    project: PathBuf,
+    keep: bool,
+}
+
+impl Sandbox {
+    fn new(keep: bool) -> anyhow::Result<Self> {
+        let root = env::temp_dir().join(format!("rad-tutorial-{}", process::id()));
+        let home = root.join("home");
+        let project = root.join("acme");
+
+        fs::create_dir_all(&home)?;
+        fs::create_dir_all(&project)?;
+
+        // Nb. These are inherited by the commands we run, so that they use the sandbox
+        // profile, and never the user's own profile or ssh-agent.
+        env::set_var(profile::RAD_HOME, &home);
+        env::set_var(keys::RAD_PASSPHRASE, PASSPHRASE);
+        env::remove_var("SSH_AUTH_SOCK");
+        for var in ["GIT_AUTHOR_NAME", "GIT_COMMITTER_NAME"] {
+            env::set_var(var, "Tutorial");
+        }
+        for var in ["GIT_AUTHOR_EMAIL", "GIT_COMMITTER_EMAIL"] {
+            env::set_var(var, "tutorial@radicle.local");
+        }
+
+        Ok(Self {
+            root,
+            project,
+            keep,
+        })
+    }
+}
+
+impl Drop for Sandbox {
+    fn drop(&mut self) {
+        if self.keep {
+            term::info!(
+                "The sandbox was kept in {}",
+                term::format::highlight(self.root.display())
+            );
+        } else {
+            fs::remove_dir_all(&self.root).ok();
+        }
+    }
+}
+
+pub fn run(options: Options, _ctx: impl term::Context) -> anyhow::Result<()> {
+    term::headline("Welcome to the 🌱 radicle tutorial!");
+    term::blank();
+    term::info!("We'll create a project, propose a change to it as a patch, then review");
+    term::info!("and merge that patch. Everything happens in a temporary sandbox.");
+
+    let sandbox = Sandbox::new(options.keep)?;
+    let project = sandbox.project.as_path();
+
+    // 1. Identity.
+    step(
+        1,
+        "Create an identity",
+        &[
+            "Everything you publish on radicle is signed by your identity. Normally",
+            "you create one with `rad auth`; here we use a throwaway one.",
+        ],
+        &["auth", "--init", "--name", "tutorial"],
+    )?;
+    rad(project, &["auth", "--init", "--name", "tutorial"])?;
+    verify(
+        profile::default().is_ok(),
+        "Your sandbox identity was created",
+    )?;
+
+    // 2. Project.
+    git::git(project, ["init", "--quiet"])?;
+    fs::write(project.join("README.md"), "# Acme\n")?;
+    git::git(project, ["add", "README.md"])?;
+    git::git(project, ["commit", "--quiet", "-m", "Initial commit"])?;
+
+    let repo = git::Repository::open(project)?;
+    let default_branch = repo
+        .head()?
+        .shorthand()
+        .ok_or_else(|| anyhow!("invalid default branch"))?
+        .to_owned();
+
+    step(
+        2,
+        "Initialize a project",
+        &[
+            "We've made a git repository with a first commit. `rad init` turns it into",
+            "a radicle project, and adds a `rad` remote pointing to your local storage.",
+        ],
+        &["init", "--name", "acme", "--description", "..."],
+    )?;
+    rad(
+        project,
+        &[
+            "init",
+            "--name",
+            "acme",
+            "--description",
+            "A tutorial project",
+            "--no-confirm",
+        ],
+    )?;
+    verify(
+        git::rad_remote(&repo).is_ok(),
+        "The repository is now a radicle project",
+    )?;
+
+    // 3. Patch.
+    git::git(project, ["checkout", "--quiet", "-b", "add-license"])?;
+    fs::write(project.join("LICENSE"), "MIT\n")?;
+    git::git(project, ["add", "LICENSE"])?;
+    git::git(project, ["commit", "--quiet", "-m", "Add a license"])?;
+
+    step(
+        3,
+        "Propose a patch",
+        &[
+            "We've committed a change on the `add-license` branch. `rad patch` proposes",
+            "the current branch as a patch. Answer the prompts to create it.",
+        ],
+        &["patch", "--no-sync"],
+    )?;
+    rad(
+        project,
+        &["patch", "--no-sync", "--message", "Add a license"],
+    )?;
+    let patch_id = patch_id(project)?;
+    verify(
+        patch(&repo, &patch_id).is_ok(),
+        &format!("Patch {} was created", patch_id),
+    )?;
+
+    // 4. Review.
+    step(
+        4,
+        "Review the patch",
+        &[
+            "Anyone can review a patch, accepting or rejecting it, with a comment.",
+            "Here, you'll accept your own patch.",
+        ],
+        &["patch", "review", "<id>", "--accept"],
+    )?;
+    rad(
+        project,
+        &[
+            "patch",
+            "review",
+            &patch_id,
+            "--accept",
+            "--no-sync",
+            "--message",
+            "Looks good!",
+        ],
+    )?;
+    verify(
+        patch(&repo, &patch_id).map_or(false, |p| p.latest().1.review_count().accepted > 0),
+        "The patch was accepted",
+    )?;
+
+    // 5. Merge.
+    git::git(project, ["checkout", "--quiet", &default_branch])?;
+
+    step(
+        5,
+        "Merge the patch",
+        &[
+            "Maintainers merge patches into the default branch, which must be",
+            "checked out. We've switched back to it for you.",
+        ],
+        &["patch", "merge", "<id>"],
+    )?;
+    rad(
+        project,
+        &["patch", "merge", &patch_id, "--no-sync", "--no-confirm"],
+    )?;
+    verify(
+        project.join("LICENSE").exists(),
+        "The patch was merged into the default branch",
+    )?;
+
+    term::blank();
+    term::success!("You've completed the tutorial 🎉");
+    term::tip!(
+        "To get started for real, run `rad auth` and then `rad init` in one of your repositories."
+    );
+    term::blank();
+
+    drop(sandbox);
+
+    Ok(())
+}
+
+/// Explain a step, and wait for the user to be ready.
+fn step(n: usize, title: &str, text: &[&str], command: &[&str]) -> anyhow::Result<()> {
+    term::blank();
+    term::headline(&format!("{}. {}", n, title));
+    term::blank();
+    for line in text {
+        term::indented(line);
+    }
+    term::blank();
+    term::subcommand(format!("rad {}", command.join(" ")));
+    term::blank();
+
+    if !term::ask("Ready?", true) {
+        anyhow::bail!("tutorial aborted by user");
+    }
+    Ok(())
+}
+
+/// Check that a step was successful.
+fn verify(ok: bool, msg: &str) -> anyhow::Result<()> {
+    if !ok {
+        anyhow::bail!("something went wrong, this step could not be verified");
+    }
+    term::blank();
+    term::success!("{}", msg);
+
+    Ok(())
+}
+
+/// Run a `rad` command in the sandbox.
+fn rad(cwd: &Path, args: &[&str]) -> anyhow::Result<()> {
+    let exe = env::current_exe()?;
+    let status = process::Command::new(exe)
+        .current_dir(cwd)
+        .args(args)
+        .status()
+        .context("failed to run `rad`")?;
+
+    if !status.success() {
+        anyhow::bail!("`rad {}` failed", args.join(" "));
+    }
+    Ok(())
+}
+
+/// Load a patch of the sandbox project from storage.
+fn patch(repo: &git::Repository, id: &str) -> anyhow::Result<Patch> {
+    let urn = git::rad_remote(repo)?.url.urn;
+    let profile = profile::default()?;
+    let signer = term::signer(&profile)?;
+    let storage = keys::storage(&profile, signer)?;
+    let cobs = cobs::store(&profile, &storage)?;
+    let id = cobs::Identifier::from_str(id)?;
+
+    let (_, patch) = cobs
+        .patches()
+        .resolve::<Patch>(&urn, &id)?
+        .ok_or_else(|| anyhow!("patch {} was not found", id))?;
+
+    Ok(patch)
+}
+
+/// Get the id of the patch proposed in the sandbox.
+fn patch_id(cwd: &Path) -> anyhow::Result<String> {
+    let exe = env::current_exe()?;
+    let output = process::Command::new(exe)
+        .current_dir(cwd)
+        .args(["patch", "--list", "--json", "--no-sync"])
+        .output()?;
+    let patches: json::Value = json::from_slice(&output.stdout)?;
+
+    patches
+        .get(0)
+        .and_then(|p| p.get("id"))
+        .and_then(|id| id.as_str())
+        .map(|id| id.to_owned())
+        .ok_or_else(|| anyhow!("no patch was found"))
+}
@@ -8,8 +8,10 @@ use librad::git::tracking::git::tracking;
 use librad::git::Urn;
 use librad::PeerId;
 
+use radicle_common::allowlist::Allowlist;
 use radicle_common::args::{Args, Error, Help};
 use radicle_common::profile::Profile;
+use radicle_common::store::Store;
 use radicle_common::{fmt, keys, project};
 use radicle_terminal as term;
 
@@ -105,6 +107,16 @@ pub fn execute(urn: &Urn, options: Options, profile: &Profile) -> anyhow::Result
             term::format::dim(fmt::peer(&peer)),
             term::format::highlight(urn)
         );
+
+        let mut allowlist = Allowlist::load(profile)?;
+        if allowlist.disallow(urn, &peer) {
+            allowlist.save(profile)?;
+
+            term::success!(
+                "Peer {} removed from the collaborator allowlist",
+                term::format::dim(fmt::peer(&peer))
+            );
+        }
     } else {
         tracking::untrack_all(
             &storage,